@@ -73,6 +73,8 @@ pub enum Method {
 	Health,
 	/// Imports a database
 	Import,
+	/// Imports a machine learning model
+	ImportMl,
 	/// Invalidates a session
 	Invalidate,
 	/// Kills a live query
@@ -547,7 +547,7 @@ async fn router(
 			Ok(DbResponse::Query(response))
 		}
 		#[cfg(target_arch = "wasm32")]
-		Method::Export | Method::Import => unreachable!(),
+		Method::Export | Method::Import | Method::ImportMl => unreachable!(),
 		#[cfg(not(target_arch = "wasm32"))]
 		Method::Export => {
 			let ns = session.ns.clone().unwrap_or_default();
@@ -648,6 +648,30 @@ async fn router(
 			}
 			Ok(DbResponse::Other(Value::None))
 		}
+		#[cfg(not(target_arch = "wasm32"))]
+		Method::ImportMl => {
+			let path = param.file.expect("file to import from");
+			let mut file = match OpenOptions::new().read(true).open(&path).await {
+				Ok(path) => path,
+				Err(error) => {
+					return Err(Error::FileOpen {
+						path,
+						error,
+					}
+					.into());
+				}
+			};
+			let mut data = Vec::new();
+			if let Err(error) = file.read_to_end(&mut data).await {
+				return Err(Error::FileRead {
+					path,
+					error,
+				}
+				.into());
+			}
+			kvs.import_ml(&*session, &data).await?;
+			Ok(DbResponse::Other(Value::None))
+		}
 		Method::Health => Ok(DbResponse::Other(Value::None)),
 		Method::Version => Ok(DbResponse::Other(crate::env::VERSION.into())),
 		Method::Set => {
@@ -668,15 +692,18 @@ async fn router(
 			Ok(DbResponse::Other(Value::None))
 		}
 		Method::Live => {
-			let table = match &mut params[..] {
-				[value] => mem::take(value),
+			let (table, diff) = match &mut params[..] {
+				[value] => (mem::take(value), false),
+				[value, diff] => (mem::take(value), diff.is_true()),
 				_ => unreachable!(),
 			};
+			let sql = match diff {
+				true => "LIVE SELECT DIFF FROM type::table($table)",
+				false => "LIVE SELECT * FROM type::table($table)",
+			};
 			let mut vars = BTreeMap::new();
 			vars.insert("table".to_owned(), table);
-			let response = kvs
-				.execute("LIVE SELECT * FROM type::table($table)", &*session, Some(vars))
-				.await?;
+			let response = kvs.execute(sql, &*session, Some(vars)).await?;
 			let value = take(true, response).await?;
 			Ok(DbResponse::Other(value))
 		}
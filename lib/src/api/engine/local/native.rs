@@ -102,7 +102,8 @@ pub(crate) fn router(
 			_ => None,
 		};
 
-		let kvs = match Datastore::new(&address.path).await {
+		let options = &address.config.rocksdb;
+		let kvs = match Datastore::new_with_options(&address.path, None, options).await {
 			Ok(kvs) => {
 				// If a root user is specified, setup the initial datastore credentials
 				if let Some(root) = configured_root {
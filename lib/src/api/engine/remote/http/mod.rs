@@ -493,7 +493,7 @@ async fn router(
 			Ok(DbResponse::Query(values))
 		}
 		#[cfg(target_arch = "wasm32")]
-		Method::Export | Method::Import => unreachable!(),
+		Method::Export | Method::Import | Method::ImportMl => unreachable!(),
 		#[cfg(not(target_arch = "wasm32"))]
 		Method::Export => {
 			let path = base_url.join(Method::Export.as_str())?;
@@ -506,8 +506,8 @@ async fn router(
 			Ok(DbResponse::Other(value))
 		}
 		#[cfg(not(target_arch = "wasm32"))]
-		Method::Import => {
-			let path = base_url.join(Method::Import.as_str())?;
+		Method::Import | Method::ImportMl => {
+			let path = base_url.join(method.as_str())?;
 			let file = param.file.expect("file to import from");
 			let request = client
 				.post(path)
@@ -553,16 +553,21 @@ async fn router(
 		}
 		Method::Live => {
 			let path = base_url.join(SQL_PATH)?;
-			let table = match &params[..] {
-				[table] => table.to_string(),
+			let (table, diff) = match &params[..] {
+				[table] => (table.to_string(), false),
+				[table, diff] => (table.to_string(), diff.is_true()),
 				_ => unreachable!(),
 			};
+			let sql = match diff {
+				true => "LIVE SELECT DIFF FROM type::table($table)",
+				false => "LIVE SELECT * FROM type::table($table)",
+			};
 			let request = client
 				.post(path)
 				.headers(headers.clone())
 				.auth(auth)
 				.query(&[("table", table)])
-				.body("LIVE SELECT * FROM type::table($table)");
+				.body(sql);
 			let value = take(true, request).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -72,6 +72,10 @@ pub enum Error {
 	#[error("Invalid request: {0}")]
 	InvalidRequest(String),
 
+	/// Invalid pagination cursor
+	#[error("Invalid cursor: {0}")]
+	InvalidCursor(String),
+
 	/// Invalid params
 	#[error("Invalid params: {0}")]
 	InvalidParams(String),
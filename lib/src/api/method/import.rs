@@ -16,6 +16,21 @@ use std::pin::Pin;
 pub struct Import<'r, C: Connection> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) file: PathBuf,
+	pub(super) is_ml: bool,
+}
+
+impl<'r, C> Import<'r, C>
+where
+	C: Connection,
+{
+	/// Imports a machine learning model file instead of a database backup
+	///
+	/// The model is defined in the selected database, and can be evaluated
+	/// in queries with `ml::name<version>(...)`.
+	pub fn ml(mut self) -> Self {
+		self.is_ml = true;
+		self
+	}
 }
 
 impl<'r, Client> IntoFuture for Import<'r, Client>
@@ -31,7 +46,10 @@ where
 			if !router.features.contains(&ExtraFeatures::Backup) {
 				return Err(Error::BackupsNotSupported.into());
 			}
-			let mut conn = Client::new(Method::Import);
+			let mut conn = Client::new(match self.is_ml {
+				true => Method::ImportMl,
+				false => Method::Import,
+			});
 			conn.execute_unit(router, Param::file(self.file)).await
 		})
 	}
@@ -16,6 +16,19 @@ use std::pin::Pin;
 pub struct Live<'r, C: Connection> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) table_name: String,
+	pub(super) diff: bool,
+}
+
+impl<'r, C> Live<'r, C>
+where
+	C: Connection,
+{
+	/// Receives RFC 6902 JSON Patch operations, describing the changes
+	/// made to each record, instead of the whole record
+	pub fn diff(mut self) -> Self {
+		self.diff = true;
+		self
+	}
 }
 
 impl<'r, Client> IntoFuture for Live<'r, Client>
@@ -28,7 +41,11 @@ where
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
 			let mut conn = Client::new(Method::Live);
-			conn.execute(self.router?, Param::new(vec![Value::Table(Table(self.table_name))])).await
+			let mut params = vec![Value::Table(Table(self.table_name))];
+			if self.diff {
+				params.push(Value::Bool(true));
+			}
+			conn.execute(self.router?, Param::new(params)).await
 		})
 	}
 }
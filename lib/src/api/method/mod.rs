@@ -54,6 +54,7 @@ pub use live::Live;
 pub use merge::Merge;
 pub use patch::Patch;
 pub use query::Query;
+pub use select::Pages;
 pub use select::Select;
 pub use set::Set;
 pub use signin::Signin;
@@ -94,6 +95,7 @@ impl Method {
 			Method::Export => "export",
 			Method::Health => "health",
 			Method::Import => "import",
+			Method::ImportMl => "ml/import",
 			Method::Invalidate => "invalidate",
 			Method::Kill => "kill",
 			Method::Live => "live",
@@ -954,6 +956,7 @@ where
 		Live {
 			router: self.router.extract(),
 			table_name: table_name.into(),
+			diff: false,
 		}
 	}
 
@@ -1025,6 +1028,7 @@ where
 		Import {
 			router: self.router.extract(),
 			file: file.as_ref().to_owned(),
+			is_ml: false,
 		}
 	}
 }
@@ -1,17 +1,34 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::opt::Cursor;
 use crate::api::opt::Range;
 use crate::api::opt::Resource;
 use crate::api::Connection;
 use crate::api::Result;
+use crate::opt::from_value;
+use crate::sql;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Array;
+use crate::sql::Fields;
 use crate::sql::Id;
+use crate::sql::Limit;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Table;
 use crate::sql::Value;
+use futures::ready;
+use futures::Stream;
 use serde::de::DeserializeOwned;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
+use std::mem;
+use std::ops::Bound;
 use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
 
 /// A select future
 #[derive(Debug)]
@@ -76,6 +93,30 @@ where
 	into_future! {execute_vec}
 }
 
+impl<C, R> Select<'_, C, R>
+where
+	C: Connection,
+{
+	fn after(mut self, cursor: Cursor) -> Self {
+		// A cursor only continues the pages of its own table
+		if let Ok(Resource::Table(Table(table))) = &self.resource {
+			if *table != cursor.0.tb {
+				self.resource = Err(Error::InvalidCursor(cursor.to_string()).into());
+				return self;
+			}
+		}
+		let end = match self.range.take() {
+			Some(range) => range.end,
+			None => Bound::Unbounded,
+		};
+		self.range = Some(Range {
+			start: Bound::Excluded(cursor.into_id()),
+			end,
+		});
+		self
+	}
+}
+
 impl<C> Select<'_, C, Value>
 where
 	C: Connection,
@@ -85,9 +126,14 @@ where
 		self.range = Some(bounds.into());
 		self
 	}
+
+	/// Restricts the records selected to those after the specified cursor
+	pub fn cursor(self, cursor: Cursor) -> Self {
+		self.after(cursor)
+	}
 }
 
-impl<C, R> Select<'_, C, Vec<R>>
+impl<'r, C, R> Select<'r, C, Vec<R>>
 where
 	C: Connection,
 {
@@ -96,4 +142,173 @@ where
 		self.range = Some(bounds.into());
 		self
 	}
+
+	/// Restricts the records selected to those after the specified cursor
+	pub fn cursor(self, cursor: Cursor) -> Self {
+		self.after(cursor)
+	}
+
+	/// Selects the records page by page, in the order of their record IDs
+	///
+	/// Each page continues from the last record of the previous page, so records
+	/// are neither skipped nor repeated when other records are created in between.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use futures::StreamExt;
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// #
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// let mut pages = db.select("person").pages(100);
+	/// while let Some(page) = pages.next().await {
+	///     let people: Vec<Person> = page?;
+	/// }
+	///
+	/// // Resume from where the stream stopped
+	/// if let Some(cursor) = pages.cursor().cloned() {
+	///     let people: Vec<Person> = db.select("person").cursor(cursor).await?;
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn pages(self, limit: usize) -> Pages<'r, C, R> {
+		let Select {
+			router,
+			resource,
+			range,
+			..
+		} = self;
+		let range = range.unwrap_or(Range {
+			start: Bound::Unbounded,
+			end: Bound::Unbounded,
+		});
+		let state = match router {
+			Ok(router) => match resource.and_then(|resource| resource.with_range(range)) {
+				Ok(Value::Range(range)) => Ok((router, *range)),
+				Ok(value) => Err(Error::InvalidParams(format!(
+					"Expected a range of records to select page by page, but found `{value}`"
+				))
+				.into()),
+				Err(error) => Err(error),
+			},
+			Err(error) => Err(error),
+		};
+		Pages {
+			state,
+			limit,
+			cursor: None,
+			future: None,
+			done: false,
+			response_type: PhantomData,
+		}
+	}
+}
+
+/// A stream of pages of records, selected using a continuation cursor
+#[must_use = "streams do nothing unless you poll them"]
+pub struct Pages<'r, C: Connection, R> {
+	state: Result<(&'r Router<C>, sql::Range)>,
+	limit: usize,
+	cursor: Option<Cursor>,
+	future: Option<Pin<Box<dyn Future<Output = Result<Value>> + Send + Sync + 'r>>>,
+	done: bool,
+	response_type: PhantomData<R>,
+}
+
+impl<C, R> Unpin for Pages<'_, C, R> where C: Connection {}
+
+impl<C, R> Pages<'_, C, R>
+where
+	C: Connection,
+{
+	/// The cursor pointing at the last record returned so far
+	pub fn cursor(&self) -> Option<&Cursor> {
+		self.cursor.as_ref()
+	}
+}
+
+impl<'r, Client, R> Pages<'r, Client, R>
+where
+	Client: Connection,
+	R: DeserializeOwned,
+{
+	fn next_page(&mut self, result: Result<Value>) -> Option<Result<Vec<R>>> {
+		let records = match result {
+			Ok(Value::Array(records)) => records,
+			Ok(Value::None | Value::Null) => Array::new(),
+			Ok(value) => vec![value].into(),
+			Err(error) => {
+				self.done = true;
+				return Some(Err(error));
+			}
+		};
+		// A page which is not full is the last one
+		if records.len() < self.limit {
+			self.done = true;
+		}
+		match (records.last().and_then(Cursor::from_record), &mut self.state) {
+			(Some(cursor), Ok((_, range))) => {
+				range.beg = Bound::Excluded(cursor.0.id.clone());
+				self.cursor = Some(cursor);
+			}
+			_ => {
+				self.done = true;
+				// Only the first page is returned when empty
+				if self.cursor.is_some() {
+					return None;
+				}
+			}
+		}
+		Some(from_value(Value::Array(records)).map_err(Into::into))
+	}
+}
+
+impl<'r, Client, R> Stream for Pages<'r, Client, R>
+where
+	Client: Connection,
+	R: DeserializeOwned,
+{
+	type Item = Result<Vec<R>>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = &mut *self;
+		if this.future.is_none() {
+			if this.done {
+				return Poll::Ready(None);
+			}
+			let (router, range) = match &this.state {
+				Ok((router, range)) => (*router, range.clone()),
+				Err(_) => {
+					this.done = true;
+					let state =
+						mem::replace(&mut this.state, Err(Error::ConnectionUninitialised.into()));
+					return Poll::Ready(state.err().map(Err));
+				}
+			};
+			let statement = SelectStatement {
+				expr: Fields::all(),
+				what: sql::Values(vec![range.into()]),
+				limit: Some(Limit(this.limit.into())),
+				..Default::default()
+			};
+			let query = sql::Query(Statements(vec![Statement::Select(statement)]));
+			this.future = Some(Box::pin(async move {
+				let mut conn = Client::new(Method::Query);
+				let param = Param::query(query, Default::default());
+				conn.execute_query(router, param).await?.take(0)
+			}));
+		}
+		let Some(future) = this.future.as_mut() else {
+			unreachable!();
+		};
+		let result = ready!(future.as_mut().poll(cx));
+		this.future = None;
+		Poll::Ready(this.next_page(result))
+	}
 }
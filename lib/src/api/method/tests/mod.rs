@@ -159,6 +159,7 @@ async fn api() {
 
 	// import
 	let _: () = DB.import("backup.sql").await.unwrap();
+	let _: () = DB.import("model.json").ml().await.unwrap();
 
 	// version
 	let _: Version = DB.version().await.unwrap();
@@ -31,7 +31,7 @@ pub(super) fn mock(route_rx: Receiver<Option<Route>>) {
 					_ => unreachable!(),
 				},
 				Method::Live => match &params[..] {
-					[_] => Ok(DbResponse::Other(
+					[_] | [_, _] => Ok(DbResponse::Other(
 						"c6c0e36c-e2cf-42cb-b2d5-75415249b261".to_owned().into(),
 					)),
 					_ => unreachable!(),
@@ -78,7 +78,7 @@ pub(super) fn mock(route_rx: Receiver<Option<Route>>) {
 					}
 					_ => unreachable!(),
 				},
-				Method::Export | Method::Import => match param.file {
+				Method::Export | Method::Import | Method::ImportMl => match param.file {
 					Some(_) => Ok(DbResponse::Other(Value::None)),
 					_ => unreachable!(),
 				},
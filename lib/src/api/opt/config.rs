@@ -1,4 +1,4 @@
-use crate::{dbs::Capabilities, iam::Level};
+use crate::{dbs::Capabilities, iam::Level, kvs::RocksDbOptions};
 use std::time::Duration;
 
 /// Configuration for server connection, including: strictness, notifications, query_timeout, transaction_timeout
@@ -17,6 +17,7 @@ pub struct Config {
 	pub(crate) password: String,
 	pub(crate) tick_interval: Option<Duration>,
 	pub(crate) capabilities: Capabilities,
+	pub(crate) rocksdb: RocksDbOptions,
 }
 
 impl Config {
@@ -96,4 +97,10 @@ impl Config {
 		self.capabilities = capabilities;
 		self
 	}
+
+	/// Set the options used to open the `rocksdb` and `speedb` storage engines
+	pub fn rocksdb(mut self, options: RocksDbOptions) -> Self {
+		self.rocksdb = options;
+		self
+	}
 }
@@ -0,0 +1,103 @@
+use crate::api::err::Error;
+use crate::sql;
+use crate::sql::Id;
+use crate::sql::Thing;
+use crate::sql::Value;
+use base64_lib::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64_lib::Engine;
+use std::fmt;
+use std::str::FromStr;
+
+/// An opaque continuation cursor, pointing at the last record of a page of results
+///
+/// Records are returned in the order of their IDs, so the next page starts with
+/// the first record whose ID comes after the cursor, even when other records are
+/// created or deleted between requests. A cursor only continues the pages of the
+/// table of its record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor(pub(crate) Thing);
+
+impl Cursor {
+	/// Creates a cursor pointing at the specified record
+	///
+	/// Returns `None` if the record does not have a record ID.
+	pub fn from_record(record: &Value) -> Option<Self> {
+		match record {
+			Value::Thing(thing) => Some(Self(thing.clone())),
+			record => match record.rid() {
+				Value::Thing(thing) => Some(Self(thing)),
+				_ => None,
+			},
+		}
+	}
+
+	/// Returns the table of the record this cursor points at
+	pub fn table(&self) -> &str {
+		&self.0.tb
+	}
+
+	/// Returns the ID of the record this cursor points at
+	pub fn into_id(self) -> Id {
+		self.0.id
+	}
+}
+
+impl From<Thing> for Cursor {
+	fn from(thing: Thing) -> Self {
+		Self(thing)
+	}
+}
+
+impl fmt::Display for Cursor {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&URL_SAFE_NO_PAD.encode(self.0.to_string()))
+	}
+}
+
+impl FromStr for Cursor {
+	type Err = crate::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || Error::InvalidCursor(s.to_owned());
+		let bytes = URL_SAFE_NO_PAD.decode(s).map_err(|_| invalid())?;
+		let thing = String::from_utf8(bytes).map_err(|_| invalid())?;
+		match sql::thing(&thing) {
+			Ok(thing) => Ok(Self(thing)),
+			Err(_) => Err(invalid().into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cursor_roundtrip() {
+		for id in ["1", "⟨1⟩", "john", "⟨john doe⟩", "[1, 'a']", "{ a: 1 }"] {
+			let thing = sql::thing(&format!("person:{id}")).unwrap();
+			let cursor = Cursor(thing);
+			let parsed: Cursor = cursor.to_string().parse().unwrap();
+			assert_eq!(parsed, cursor);
+			assert_eq!(parsed.table(), "person");
+		}
+	}
+
+	#[test]
+	fn cursor_invalid() {
+		assert!("!".parse::<Cursor>().is_err());
+		assert!(URL_SAFE_NO_PAD.encode("a b").parse::<Cursor>().is_err());
+		// A cursor points at a record of a table
+		assert!(URL_SAFE_NO_PAD.encode("john").parse::<Cursor>().is_err());
+	}
+
+	#[test]
+	fn cursor_from_record() {
+		let record = sql::value("{ id: person:john, name: 'John' }").unwrap();
+		let john = Thing::from(("person", "john"));
+		assert_eq!(Cursor::from_record(&record), Some(Cursor(john.clone())));
+		assert_eq!(Cursor::from_record(&Value::from(john.clone())), Some(Cursor(john)));
+		let record = sql::value("{ name: 'John' }").unwrap();
+		assert_eq!(Cursor::from_record(&record), None);
+	}
+}
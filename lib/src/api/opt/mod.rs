@@ -4,6 +4,7 @@ pub mod auth;
 pub mod capabilities;
 
 mod config;
+mod cursor;
 mod endpoint;
 mod export;
 mod query;
@@ -24,6 +25,7 @@ use serde_json::Map;
 use serde_json::Value as JsonValue;
 
 pub use config::*;
+pub use cursor::*;
 pub use endpoint::*;
 pub use export::*;
 pub use query::*;
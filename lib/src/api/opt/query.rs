@@ -151,6 +151,12 @@ impl IntoQuery for RemoveStatement {
 	}
 }
 
+impl IntoQuery for RebuildStatement {
+	fn into_query(self) -> Result<Vec<Statement>> {
+		Ok(vec![Statement::Rebuild(self)])
+	}
+}
+
 impl IntoQuery for OptionStatement {
 	fn into_query(self) -> Result<Vec<Statement>> {
 		Ok(vec![Statement::Option(self)])
@@ -0,0 +1,243 @@
+use crate::cf::{TableMutation, TableMutations};
+use crate::cnf::PROCESSOR_BATCH_SIZE;
+use crate::err::Error;
+use crate::key::{change, thing};
+use crate::kvs::{Key, Transaction};
+use crate::sql::{Datetime, Thing, Value};
+use crate::vs::Versionstamp;
+use chrono::Utc;
+use std::collections::BTreeMap;
+
+/// Returns the versionstamp of the database at the given time.
+///
+/// The history of a table is kept by its change feed, or by the change feed of its database.
+/// This function checks that such a change feed exists, and that it still retains the changes
+/// recorded since the given time.
+///
+/// The precision of the returned versionstamp depends on how often the datastore
+/// correlates timestamps with versionstamps (see Datastore::save_timestamp_for_versionstamp).
+pub(crate) async fn versionstamp_at(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	at: &Datetime,
+) -> Result<Versionstamp, Error> {
+	// A database or a table which is not defined has no change feed
+	let db_cf = match tx.get_and_cache_db(ns, db).await {
+		Ok(v) => v.changefeed.clone(),
+		Err(Error::DbNotFound {
+			..
+		}) => None,
+		Err(e) => return Err(e),
+	};
+	let tb_cf = match tx.get_and_cache_tb(ns, db, tb).await {
+		Ok(v) => v.changefeed.clone(),
+		Err(Error::TbNotFound {
+			..
+		}) => None,
+		Err(e) => return Err(e),
+	};
+	// Get the longest retention of the change feeds covering the table
+	let expiry =
+		[db_cf, tb_cf].into_iter().flatten().map(|cf| cf.expiry).max().ok_or_else(|| {
+			Error::TbVersionUnavailable {
+				table: tb.to_owned(),
+			}
+		})?;
+	let ts = at.0.timestamp();
+	if ts < 0 {
+		return Err(Error::VersionNotFound {
+			value: at.to_raw(),
+		});
+	}
+	// Check that the changes have not been garbage collected
+	let age = (Utc::now().timestamp() - ts).max(0) as u64;
+	if age > expiry.as_secs() {
+		return Err(Error::VersionExpired {
+			table: tb.to_owned(),
+			value: at.to_raw(),
+		});
+	}
+	// Get the versionstamp associated to the timestamp
+	match tx.get_versionstamp_from_timestamp(ts as u64, ns, db, false).await? {
+		Some(vs) => Ok(vs),
+		None => Err(Error::VersionNotFound {
+			value: at.to_raw(),
+		}),
+	}
+}
+
+/// Reads the records of a table as they were before a versionstamp, batch after batch,
+/// by combining the current records of the table with its change feed:
+///
+/// - a record which has not changed since the versionstamp has its current value,
+/// - a record which has changed since the versionstamp has the value of the latest change
+///   recorded before the versionstamp, if any,
+/// - a record without any change recorded before the versionstamp did not exist yet.
+///
+/// Only the records whose keys are between beg (included) and end (excluded) are read,
+/// and only the changes of these records are kept in memory.
+pub(crate) struct VersionScan {
+	/// The key of the next current record to read
+	nxt: Key,
+	/// The end of the range of records
+	end: Key,
+	/// The records which changed since the versionstamp, with their value before it
+	changed: BTreeMap<Key, Option<(Thing, Value)>>,
+	/// All the current records have been read
+	done: bool,
+}
+
+impl VersionScan {
+	pub(crate) async fn new(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		vs: Versionstamp,
+		beg: &[u8],
+		end: &[u8],
+	) -> Result<Self, Error> {
+		let mut changed = BTreeMap::new();
+		// Collect the records which changed after the versionstamp
+		let mut scan = MutationScan::new(change::prefix_ts(ns, db, vs), change::suffix(ns, db));
+		while let Some(muts) = scan.next_batch(tx, tb).await? {
+			for m in muts {
+				if let Some((key, _)) = record(ns, db, m, beg, end)? {
+					changed.insert(key, None);
+				}
+			}
+		}
+		// Collect the latest change of these records before the versionstamp
+		if !changed.is_empty() {
+			let mut scan = MutationScan::new(change::prefix(ns, db), change::prefix_ts(ns, db, vs));
+			while let Some(muts) = scan.next_batch(tx, tb).await? {
+				for m in muts {
+					if let Some((key, val)) = record(ns, db, m, beg, end)? {
+						if let Some(v) = changed.get_mut(&key) {
+							*v = val;
+						}
+					}
+				}
+			}
+		}
+		Ok(Self {
+			nxt: beg.to_vec(),
+			end: end.to_vec(),
+			changed,
+			done: false,
+		})
+	}
+
+	/// Returns the next records of the table, in the order of their keys
+	pub(crate) async fn next_batch(
+		&mut self,
+		tx: &mut Transaction,
+	) -> Result<Vec<(Thing, Value)>, Error> {
+		if self.done {
+			return Ok(vec![]);
+		}
+		let beg = self.nxt.clone();
+		let batch = tx.scan(beg.clone()..self.end.clone(), PROCESSOR_BATCH_SIZE).await?;
+		// The batch covers the keys up to its last key, or the rest of the range
+		let upto = match batch.last() {
+			Some((k, _)) if batch.len() == PROCESSOR_BATCH_SIZE as usize => {
+				let mut nxt = k.clone();
+				nxt.push(0x00);
+				nxt
+			}
+			_ => {
+				self.done = true;
+				self.end.clone()
+			}
+		};
+		// Keep the current records which did not change since the versionstamp
+		let mut res = BTreeMap::new();
+		for (k, v) in batch {
+			if self.changed.contains_key(&k) {
+				continue;
+			}
+			let key: thing::Thing = (&k).into();
+			let rid = Thing::from((key.tb, key.id));
+			res.insert(k, (rid, (&v).into()));
+		}
+		// Restore the records which changed since the versionstamp
+		for (k, v) in self.changed.range(beg..upto.clone()) {
+			if let Some(v) = v {
+				res.insert(k.clone(), v.clone());
+			}
+		}
+		self.nxt = upto;
+		Ok(res.into_values().collect())
+	}
+}
+
+/// Returns the key of the record changed by a mutation, and its value after the mutation,
+/// if the record is between beg (included) and end (excluded)
+#[allow(clippy::type_complexity)]
+fn record(
+	ns: &str,
+	db: &str,
+	m: TableMutation,
+	beg: &[u8],
+	end: &[u8],
+) -> Result<Option<(Key, Option<(Thing, Value)>)>, Error> {
+	let (id, val) = match m {
+		TableMutation::Set(id, v) => (id, Some(v)),
+		TableMutation::Del(id) => (id, None),
+		TableMutation::Def(_) => return Ok(None),
+	};
+	let key = thing::new(ns, db, &id.tb, &id.id).encode()?;
+	if key.as_slice() < beg || key.as_slice() >= end {
+		return Ok(None);
+	}
+	Ok(Some((key, val.map(|v| (id, v)))))
+}
+
+/// Reads, in order and batch after batch, the mutations of a table recorded in a change feed range
+struct MutationScan {
+	nxt: Key,
+	end: Key,
+	done: bool,
+}
+
+impl MutationScan {
+	fn new(beg: Key, end: Key) -> Self {
+		Self {
+			nxt: beg,
+			end,
+			done: false,
+		}
+	}
+
+	/// Returns the mutations of the table found in the next batch of change feed entries,
+	/// or none once the whole range has been read
+	async fn next_batch(
+		&mut self,
+		tx: &mut Transaction,
+		tb: &str,
+	) -> Result<Option<Vec<TableMutation>>, Error> {
+		if self.done {
+			return Ok(None);
+		}
+		let batch = tx.scan(self.nxt.clone()..self.end.clone(), PROCESSOR_BATCH_SIZE).await?;
+		if batch.len() < PROCESSOR_BATCH_SIZE as usize {
+			self.done = true;
+		}
+		if let Some((k, _)) = batch.last() {
+			self.nxt = k.clone();
+			self.nxt.push(0x00);
+		}
+		let mut res = Vec::new();
+		for (k, v) in batch {
+			// The values of the changes of the other tables are not decoded
+			if change::Cf::decode(&k)?.tb != tb {
+				continue;
+			}
+			let muts: TableMutations = v.into();
+			res.extend(muts.1);
+		}
+		Ok(Some(res))
+	}
+}
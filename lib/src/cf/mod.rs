@@ -1,9 +1,11 @@
 pub(crate) mod gc;
+pub(crate) mod history;
 pub(crate) mod mutations;
 pub(crate) mod reader;
 pub(crate) mod writer;
 
 pub use self::gc::*;
+pub(crate) use self::history::{versionstamp_at, VersionScan};
 pub use self::mutations::*;
 pub use self::reader::read;
 pub use self::writer::Writer;
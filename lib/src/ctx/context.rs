@@ -45,6 +45,8 @@ pub struct Context<'a> {
 	query_planner: Option<&'a QueryPlanner<'a>>,
 	// Capabilities
 	capabilities: Arc<Capabilities>,
+	// An optional memory budget for buffering query results
+	memory_budget: Option<usize>,
 }
 
 impl<'a> Default for Context<'a> {
@@ -75,6 +77,7 @@ impl<'a> Context<'a> {
 			notifications: None,
 			query_planner: None,
 			capabilities: Arc::new(Capabilities::default()),
+			memory_budget: None,
 		}
 	}
 
@@ -88,6 +91,7 @@ impl<'a> Context<'a> {
 			notifications: parent.notifications.clone(),
 			query_planner: parent.query_planner,
 			capabilities: parent.capabilities.clone(),
+			memory_budget: parent.memory_budget,
 		}
 	}
 
@@ -129,6 +133,12 @@ impl<'a> Context<'a> {
 		self.notifications = chn.cloned()
 	}
 
+	/// Add a memory budget to the context. Once the results buffered by a
+	/// query exceed this number of bytes, they are moved to temporary files.
+	pub fn add_memory_budget(&mut self, bytes: usize) {
+		self.memory_budget = Some(bytes)
+	}
+
 	/// Set the query planner
 	pub(crate) fn set_query_planner(&mut self, qp: &'a QueryPlanner) {
 		self.query_planner = Some(qp);
@@ -148,6 +158,15 @@ impl<'a> Context<'a> {
 		self.query_planner
 	}
 
+	/// Get the memory budget for buffering query results, if any
+	pub(crate) fn memory_budget(&self) -> Option<usize> {
+		// There is no filesystem to move query results to in WebAssembly
+		if cfg!(target_arch = "wasm32") {
+			return None;
+		}
+		self.memory_budget
+	}
+
 	/// Check if the context is done. If it returns `None` the operation may
 	/// proceed, otherwise the operation should be stopped.
 	pub fn done(&self) -> Option<Reason> {
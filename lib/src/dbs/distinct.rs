@@ -1,26 +1,32 @@
 use crate::ctx::Context;
+use crate::dbs::spill::KeySpill;
 use crate::dbs::{Iterable, Processed};
+use crate::err::Error;
 use crate::kvs::Key;
-use radix_trie::Trie;
+use radix_trie::{Trie, TrieCommon};
 use std::default::Default;
 #[cfg(not(target_arch = "wasm32"))]
 use std::sync::Arc;
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::sync::Mutex;
 
-// TODO: This is currently processed in memory. In the future is should be on disk (mmap?)
+// This is processed in memory, until the memory budget of the query is exceeded
 type Distinct = Trie<Key, bool>;
 
 #[derive(Default)]
 pub(crate) struct SyncDistinct {
 	processed: Distinct,
+	spill: Option<KeySpill>,
 }
 
 impl SyncDistinct {
 	pub(super) fn new(ctx: &Context<'_>) -> Option<Self> {
 		if let Some(pla) = ctx.get_query_planner() {
 			if pla.requires_distinct() {
-				return Some(Self::default());
+				return Some(Self {
+					processed: Distinct::default(),
+					spill: ctx.memory_budget().map(KeySpill::new),
+				});
 			}
 		}
 		None
@@ -49,17 +55,33 @@ impl SyncDistinct {
 		}
 	}
 
-	pub(super) fn check_already_processed(&mut self, pro: &Processed) -> bool {
+	pub(super) fn check_already_processed(&mut self, pro: &Processed) -> Result<bool, Error> {
 		if let Some(key) = pro.rid.as_ref().map(std::convert::Into::<Vec<u8>>::into) {
 			if self.processed.get(&key).is_some() {
-				true
-			} else {
-				self.processed.insert(key, true);
-				false
+				return Ok(true);
 			}
-		} else {
-			false
+			if let Some(spill) = &mut self.spill {
+				// Check the keys which were moved to disk
+				if spill.contains(&key)? {
+					return Ok(true);
+				}
+				// Move the keys to disk once over budget
+				if spill.track(&key) {
+					let mut keys: Vec<Key> = self.processed.keys().cloned().collect();
+					keys.push(key);
+					spill.spill(keys)?;
+					self.processed = Distinct::default();
+					return Ok(false);
+				}
+			}
+			self.processed.insert(key, true);
 		}
+		Ok(false)
+	}
+
+	/// The number of runs of keys which were moved to disk
+	pub(super) fn spilled_runs(&self) -> usize {
+		self.spill.as_ref().map(KeySpill::runs).unwrap_or(0)
 	}
 }
 
@@ -72,12 +94,9 @@ pub(crate) struct AsyncDistinct {
 #[cfg(not(target_arch = "wasm32"))]
 impl AsyncDistinct {
 	pub(super) fn new(ctx: &Context<'_>) -> Option<Self> {
-		if let Some(pla) = ctx.get_query_planner() {
-			if pla.requires_distinct() {
-				return Some(Self::default());
-			}
-		}
-		None
+		SyncDistinct::new(ctx).map(|d| Self {
+			processed: Arc::new(Mutex::new(d)),
+		})
 	}
 
 	pub(super) fn requires_distinct(
@@ -93,7 +112,12 @@ impl AsyncDistinct {
 		None
 	}
 
-	pub(super) async fn check_already_processed(&self, pro: &Processed) -> bool {
+	pub(super) async fn check_already_processed(&self, pro: &Processed) -> Result<bool, Error> {
 		self.processed.lock().await.check_already_processed(pro)
 	}
+
+	/// The number of runs of keys which were moved to disk
+	pub(super) async fn spilled_runs(&self) -> usize {
+		self.processed.lock().await.spilled_runs()
+	}
 }
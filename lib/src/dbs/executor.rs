@@ -200,6 +200,10 @@ impl<'a> Executor<'a> {
 			let is_stm_kill = matches!(stm, Statement::Kill(_));
 			// Check if this is a RETURN statement
 			let is_stm_output = matches!(stm, Statement::Output(_));
+			// Get the type of statement
+			let kind = stm.kind();
+			// The number of keys read by the statement
+			let mut scanned = 0;
 			// Process a single statement
 			let res = match stm {
 				// Specify runtime options
@@ -246,7 +250,7 @@ impl<'a> Executor<'a> {
 					continue;
 				}
 				// Switch to a different NS or DB
-				Statement::Use(stm) => {
+				Statement::Use(ref stm) => {
 					if let Some(ref ns) = stm.ns {
 						self.set_ns(&mut ctx, &mut opt, ns).await;
 					}
@@ -256,7 +260,7 @@ impl<'a> Executor<'a> {
 					Ok(Value::None)
 				}
 				// Process param definition statements
-				Statement::Set(stm) => {
+				Statement::Set(ref stm) => {
 					// Create a transaction
 					let loc = self.begin(stm.writeable()).await;
 					// Check the transaction
@@ -265,13 +269,18 @@ impl<'a> Executor<'a> {
 						true => Err(Error::TxFailure),
 						// The transaction began successfully
 						false => {
-							// Check the statement
-							match stm.compute(&ctx, &opt, &self.txn(), None).await {
+							// Resolve the custom roles of the actor
+							let res = match opt.with_custom_roles(&self.txn()).await {
+								// Check the statement
+								Ok(opt) => stm.compute(&ctx, &opt, &self.txn(), None).await,
+								Err(e) => Err(e),
+							};
+							match res {
 								Ok(val) => {
 									// Check if writeable
 									let writeable = stm.writeable();
 									// Set the parameter
-									ctx.add_value(stm.name, val);
+									ctx.add_value(stm.name.clone(), val);
 									// Finalise transaction, returning nothing unless it couldn't commit
 									if writeable {
 										match self.commit(loc).await {
@@ -319,28 +328,37 @@ impl<'a> Executor<'a> {
 							// The transaction began successfully
 							false => {
 								let mut ctx = Context::new(&ctx);
-								// Process the statement
-								let res = match stm.timeout() {
-									// There is a timeout clause
-									Some(timeout) => {
-										// Set statement timeout
-										ctx.add_timeout(timeout);
-										// Process the statement
-										let res = stm.compute(&ctx, &opt, &self.txn(), None).await;
-										// Catch statement timeout
-										match ctx.is_timedout() {
-											true => Err(Error::QueryTimedout),
-											false => res,
+								// Get the number of keys read so far
+								let reads = self.txn().lock().await.reads();
+								// Resolve the custom roles of the actor
+								let res = match opt.with_custom_roles(&self.txn()).await {
+									// Process the statement
+									Ok(opt) => match stm.timeout() {
+										// There is a timeout clause
+										Some(timeout) => {
+											// Set statement timeout
+											ctx.add_timeout(timeout);
+											// Process the statement
+											let res =
+												stm.compute(&ctx, &opt, &self.txn(), None).await;
+											// Catch statement timeout
+											match ctx.is_timedout() {
+												true => Err(Error::QueryTimedout),
+												false => res,
+											}
 										}
-									}
-									// There is no timeout clause
-									None => stm.compute(&ctx, &opt, &self.txn(), None).await,
+										// There is no timeout clause
+										None => stm.compute(&ctx, &opt, &self.txn(), None).await,
+									},
+									Err(e) => Err(e),
 								};
 								// Catch global timeout
 								let res = match ctx.is_timedout() {
 									true => Err(Error::QueryTimedout),
 									false => res,
 								};
+								// Get the number of keys read by the statement
+								scanned = self.txn().lock().await.reads() - reads;
 								// Finalise transaction and return the result.
 								if res.is_ok() && stm.writeable() {
 									if let Err(e) = self.commit(loc).await {
@@ -368,10 +386,34 @@ impl<'a> Executor<'a> {
 					}
 				},
 			};
+			// Get the statement end time
+			let time = now.elapsed();
+			// Record the statement duration
+			self.kvs.metrics().observe_statement(kind, time);
+			// Log the statement if it was slow
+			if let Some(threshold) = self.kvs.slow_query_threshold() {
+				if time >= threshold {
+					let returned = match &res {
+						Ok(Value::Array(v)) => v.len(),
+						Ok(Value::None) | Err(_) => 0,
+						Ok(_) => 1,
+					};
+					let session = ctx.value("session").unwrap_or(&Value::None);
+					warn!(
+						"Slow query: ns={} db={} user={} duration={:?} scanned={} returned={}: {}",
+						session.pick(NS.as_ref()).as_raw_string(),
+						session.pick(DB.as_ref()).as_raw_string(),
+						opt.auth.id(),
+						time,
+						scanned,
+						returned,
+						redact(&stm.to_string())
+					);
+				}
+			}
 			// Produce the response
 			let res = Response {
-				// Get the statement end time
-				time: now.elapsed(),
+				time,
 				// TODO: Replace with `inspect_err` once stable.
 				result: res.map_err(|e| {
 					// Mark the error.
@@ -399,10 +441,47 @@ impl<'a> Executor<'a> {
 	}
 }
 
+/// Replaces the contents of the string literals in a rendered statement with `?`,
+/// so that the values used in a slow query do not end up in the server logs.
+fn redact(sql: &str) -> String {
+	let mut out = String::with_capacity(sql.len());
+	let mut chars = sql.chars();
+	while let Some(c) = chars.next() {
+		out.push(c);
+		if c == '\'' || c == '"' {
+			// Skip to the closing quote, ignoring escaped characters
+			while let Some(n) = chars.next() {
+				match n {
+					'\\' => {
+						chars.next();
+					}
+					n if n == c => break,
+					_ => continue,
+				}
+			}
+			out.push('?');
+			out.push(c);
+		}
+	}
+	out
+}
+
 #[cfg(test)]
 mod tests {
+	use super::redact;
 	use crate::{dbs::Session, iam::Role, kvs::Datastore};
 
+	#[test]
+	fn redact_string_literals() {
+		let sql =
+			r#"SELECT * FROM person WHERE email = 'tobie@surrealdb.com' AND bio = "say \"hi\"""#;
+		assert_eq!(redact(sql), r#"SELECT * FROM person WHERE email = '?' AND bio = "?""#);
+		let sql = r"RETURN 'it\'s' + 1";
+		assert_eq!(redact(sql), "RETURN '?' + 1");
+		let sql = "SELECT * FROM person:tobie LIMIT 10";
+		assert_eq!(redact(sql), sql);
+	}
+
 	#[tokio::test]
 	async fn check_execute_option_permissions() {
 		let tests = vec![
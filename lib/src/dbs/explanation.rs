@@ -2,6 +2,17 @@ use crate::ctx::Context;
 use crate::dbs::Iterable;
 use crate::sql::{Explain, Object, Value};
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// The actual work done by an iterator, measured by EXPLAIN ANALYZE
+pub(super) struct Analysis {
+	/// The time spent iterating
+	pub(super) time: Duration,
+	/// The number of records produced
+	pub(super) rows: usize,
+	/// The number of keys read from the datastore
+	pub(super) scanned: u64,
+}
 
 #[derive(Default)]
 pub(super) struct Explanation(Vec<ExplainItem>);
@@ -17,26 +28,40 @@ impl Explanation {
 			Some(e) => {
 				let mut exp = Self::default();
 				for i in iterables {
-					exp.add_iter(ctx, i);
+					exp.add_iter(ctx, i, e.full);
 				}
 				if let Some(qp) = ctx.get_query_planner() {
 					for reason in qp.fallbacks() {
 						exp.add_fallback(reason.to_string());
 					}
 				}
-				(e.0, Some(exp))
+				(e.full || e.analyze, Some(exp))
 			}
 		}
 	}
 
-	fn add_iter(&mut self, ctx: &Context<'_>, iter: &Iterable) {
-		self.0.push(ExplainItem::new_iter(ctx, iter));
+	fn add_iter(&mut self, ctx: &Context<'_>, iter: &Iterable, full: bool) {
+		self.0.push(ExplainItem::new_iter(ctx, iter, full));
+	}
+
+	/// Adds the actual time, rows and scanned keys next to the plan of each iterator
+	pub(super) fn add_analysis(&mut self, analysis: Vec<Analysis>) {
+		// The iterators are explained first, in the order they are iterated
+		for (item, a) in self.0.iter_mut().zip(analysis) {
+			item.details.push(("time", Value::Duration(a.time.into())));
+			item.details.push(("rows", a.rows.into()));
+			item.details.push(("scanned", a.scanned.into()));
+		}
 	}
 
 	pub(super) fn add_fetch(&mut self, count: usize) {
 		self.0.push(ExplainItem::new_fetch(count));
 	}
 
+	pub(super) fn add_spill(&mut self, source: &'static str, runs: usize) {
+		self.0.push(ExplainItem::new_spill(source, runs));
+	}
+
 	fn add_fallback(&mut self, reason: String) {
 		self.0.push(ExplainItem::new_fallback(reason));
 	}
@@ -61,6 +86,13 @@ impl ExplainItem {
 		}
 	}
 
+	fn new_spill(source: &'static str, runs: usize) -> Self {
+		Self {
+			name: "Spill".into(),
+			details: vec![("source", source.into()), ("runs", runs.into())],
+		}
+	}
+
 	fn new_fallback(reason: String) -> Self {
 		Self {
 			name: "Fallback".into(),
@@ -68,16 +100,29 @@ impl ExplainItem {
 		}
 	}
 
-	fn new_iter(ctx: &Context<'_>, iter: &Iterable) -> Self {
+	fn new_iter(ctx: &Context<'_>, iter: &Iterable, full: bool) -> Self {
 		match iter {
 			Iterable::Value(v) => Self {
 				name: "Iterate Value".into(),
 				details: vec![("value", v.to_owned())],
 			},
-			Iterable::Table(t) => Self {
-				name: "Iterate Table".into(),
-				details: vec![("table", Value::from(t.0.to_owned()))],
-			},
+			Iterable::Table(t) => {
+				let mut details = vec![("table", Value::from(t.0.to_owned()))];
+				// The estimate is only known when the indexes have been analyzed
+				if full {
+					if let Some(qp) = ctx.get_query_planner() {
+						if let Some(est) =
+							qp.get_query_executor(&t.0).and_then(|exe| exe.table_estimate())
+						{
+							details.push(("estimate", est));
+						}
+					}
+				}
+				Self {
+					name: "Iterate Table".into(),
+					details,
+				}
+			}
 			Iterable::Thing(t) => Self {
 				name: "Iterate Thing".into(),
 				details: vec![("thing", Value::Thing(t.to_owned()))],
@@ -107,6 +152,11 @@ impl ExplainItem {
 				if let Some(qp) = ctx.get_query_planner() {
 					if let Some(exe) = qp.get_query_executor(&t.0) {
 						details.push(("plan", exe.explain(*ir)));
+						if full {
+							if let Some(est) = exe.estimate(*ir) {
+								details.push(("estimate", est));
+							}
+						}
 					}
 				}
 				Self {
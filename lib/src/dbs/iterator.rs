@@ -3,7 +3,8 @@ use crate::ctx::Context;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::dbs::distinct::AsyncDistinct;
 use crate::dbs::distinct::SyncDistinct;
-use crate::dbs::explanation::Explanation;
+use crate::dbs::explanation::{Analysis, Explanation};
+use crate::dbs::spill::Spill;
 use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
@@ -12,7 +13,9 @@ use crate::idx::docids::DocId;
 use crate::idx::planner::executor::IteratorRef;
 use crate::sql::array::Array;
 use crate::sql::edges::Edges;
-use crate::sql::field::Field;
+use crate::sql::field::{Field, Fields};
+use crate::sql::group::Groups;
+use crate::sql::order::Orders;
 use crate::sql::range::Range;
 use crate::sql::table::Table;
 use crate::sql::thing::Thing;
@@ -22,6 +25,7 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::mem;
+use trice::Instant;
 
 pub(crate) enum Iterable {
 	Value(Value),
@@ -64,10 +68,19 @@ pub(crate) struct Iterator {
 	// Iterator runtime error
 	error: Option<Error>,
 	// Iterator output results
-	// TODO: Should be stored on disk / (mmap?)
 	results: Vec<Value>,
+	// Iterator results moved to disk
+	spill: Option<Spill>,
+	// Iterator distinct runs moved to disk
+	distinct_spill: usize,
+	// Iterator results already ordered by an index
+	ordered: bool,
 	// Iterator input values
 	entries: Vec<Iterable>,
+	// Iterator number of records produced
+	count: usize,
+	// Iterator work done by each input value, when analyzing
+	analysis: Option<Vec<Analysis>>,
 }
 
 impl Iterator {
@@ -276,8 +289,16 @@ impl Iterator {
 		self.setup_limit(&cancel_ctx, opt, txn, stm).await?;
 		// Process the query START clause
 		self.setup_start(&cancel_ctx, opt, txn, stm).await?;
-		// Extract the expected behaviour depending on the presence of EXPLAIN with or without FULL
+		// Check if an index returns the records in order
+		self.setup_ordered(&cancel_ctx);
+		// Process the query memory budget
+		self.setup_spill(&cancel_ctx, stm);
+		// Extract the expected behaviour depending on the presence of EXPLAIN with or without ANALYZE or FULL
 		let (do_iterate, mut explanation) = Explanation::new(ctx, stm.explain(), &self.entries);
+		// Measure each input value if this is an EXPLAIN ANALYZE
+		if stm.explain().is_some_and(|e| e.analyze) {
+			self.analysis = Some(vec![]);
+		}
 
 		if do_iterate {
 			// Process prepared values
@@ -298,7 +319,16 @@ impl Iterator {
 			self.output_limit(ctx, opt, txn, stm).await?;
 
 			if let Some(e) = &mut explanation {
+				if let Some(a) = self.analysis.take() {
+					e.add_analysis(a);
+				}
 				e.add_fetch(self.results.len());
+				if let Some(s) = self.spill.as_ref().filter(|s| s.runs() > 0) {
+					e.add_spill("results", s.runs());
+				}
+				if self.distinct_spill > 0 {
+					e.add_spill("distinct", self.distinct_spill);
+				}
 				self.results.clear();
 			} else {
 				// Process any FETCH clause
@@ -343,6 +373,44 @@ impl Iterator {
 		Ok(())
 	}
 
+	#[inline]
+	fn setup_ordered(&mut self, ctx: &Context<'_>) {
+		if let [Iterable::Index(t, _)] = self.entries.as_slice() {
+			if let Some(qp) = ctx.get_query_planner() {
+				self.ordered = qp.get_query_executor(&t.0).is_some() && qp.is_ordered();
+			}
+		}
+	}
+
+	#[inline]
+	fn setup_spill(&mut self, ctx: &Context<'_>, stm: &Statement<'_>) {
+		if let Some(budget) = ctx.memory_budget() {
+			// Only results which are grouped or sorted can be moved to disk
+			let sorted = match stm.order() {
+				Some(orders) => !self.ordered && !orders.iter().any(|o| o.random),
+				None => false,
+			};
+			if stm.split().is_none() && (stm.group().is_some() || sorted) {
+				self.spill = Some(Spill::new(budget));
+			}
+		}
+	}
+
+	/// Moves the buffered results to disk, sorted by the GROUP or ORDER clause
+	fn spill_results(&mut self, stm: &Statement<'_>) -> Result<(), Error> {
+		if let Some(spill) = &mut self.spill {
+			let res = mem::take(&mut self.results);
+			if let Some(groups) = stm.group() {
+				// The group key of each result is only computed once
+				let res = res.into_iter().map(|v| keyed(groups, v)).collect();
+				spill.spill(res, keyed_cmp)?;
+			} else if let Some(orders) = stm.order() {
+				spill.spill(res, |a, b| order_cmp(orders, a, b))?;
+			}
+		}
+		Ok(())
+	}
+
 	#[inline]
 	async fn output_split(
 		&mut self,
@@ -397,21 +465,40 @@ impl Iterator {
 	) -> Result<(), Error> {
 		if let Some(fields) = stm.expr() {
 			if let Some(groups) = stm.group() {
+				// Check if any results were moved to disk
+				if let Some(spill) = self.spill.as_mut().filter(|s| s.is_spilled()) {
+					// Merge the results, which are sorted by group
+					let res = mem::take(&mut self.results);
+					let res = res.into_iter().map(|v| keyed(groups, v)).collect();
+					let mut merge = spill.merge(res, keyed_cmp)?;
+					let mut key: Option<Value> = None;
+					let mut vals = Array::new();
+					// Process each group once all of its values are merged
+					while let Some(v) = merge.next()? {
+						let (arr, obj) = unkeyed(v);
+						if key.as_ref().is_some_and(|k| k != &arr) {
+							let vals = mem::take(&mut vals);
+							let obj =
+								Self::output_group_values(ctx, opt, txn, fields, vals).await?;
+							self.results.push(obj);
+						}
+						key = Some(arr);
+						vals.push(obj);
+					}
+					if key.is_some() {
+						let obj = Self::output_group_values(ctx, opt, txn, fields, vals).await?;
+						self.results.push(obj);
+					}
+					return Ok(());
+				}
 				// Create the new grouped collection
 				let mut grp: BTreeMap<Array, Array> = BTreeMap::new();
 				// Get the query result
 				let res = mem::take(&mut self.results);
 				// Loop over each value
 				for obj in res {
-					// Create a new column set
-					let mut arr = Array::with_capacity(groups.len());
-					// Loop over each group clause
-					for group in groups.iter() {
-						// Get the value at the path
-						let val = obj.pick(group);
-						// Set the value at the path
-						arr.push(val);
-					}
+					// Get the values at the group paths
+					let arr = group_key(groups, &obj);
 					// Add to grouped collection
 					match grp.get_mut(&arr) {
 						Some(v) => v.push(obj),
@@ -422,43 +509,8 @@ impl Iterator {
 				}
 				// Loop over each grouped collection
 				for (_, vals) in grp {
-					// Create a new value
-					let mut obj = Value::base();
-					// Save the collected values
-					let vals = Value::from(vals);
-					// Loop over each group clause
-					for field in fields.other() {
-						// Process the field
-						if let Field::Single {
-							expr,
-							alias,
-						} = field
-						{
-							let idiom = alias
-								.as_ref()
-								.map(Cow::Borrowed)
-								.unwrap_or_else(|| Cow::Owned(expr.to_idiom()));
-							match expr {
-								Value::Function(f) if f.is_aggregate() => {
-									let x =
-										vals.all().get(ctx, opt, txn, None, idiom.as_ref()).await?;
-									let x = f.aggregate(x).compute(ctx, opt, txn, None).await?;
-									obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
-								}
-								_ => {
-									let x = vals.first();
-									let x = if let Some(alias) = alias {
-										let cur = (&x).into();
-										alias.compute(ctx, opt, txn, Some(&cur)).await?
-									} else {
-										let cur = (&x).into();
-										expr.compute(ctx, opt, txn, Some(&cur)).await?
-									};
-									obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
-								}
-							}
-						}
-					}
+					// Process the grouped values
+					let obj = Self::output_group_values(ctx, opt, txn, fields, vals).await?;
 					// Add the object to the results
 					self.results.push(obj);
 				}
@@ -467,6 +519,53 @@ impl Iterator {
 		Ok(())
 	}
 
+	/// Processes the fields of a single group of values
+	async fn output_group_values(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		fields: &Fields,
+		vals: Array,
+	) -> Result<Value, Error> {
+		// Create a new value
+		let mut obj = Value::base();
+		// Save the collected values
+		let vals = Value::from(vals);
+		// Loop over each group clause
+		for field in fields.other() {
+			// Process the field
+			if let Field::Single {
+				expr,
+				alias,
+			} = field
+			{
+				let idiom = alias
+					.as_ref()
+					.map(Cow::Borrowed)
+					.unwrap_or_else(|| Cow::Owned(expr.to_idiom()));
+				match expr {
+					Value::Function(f) if f.is_aggregate() => {
+						let x = vals.all().get(ctx, opt, txn, None, idiom.as_ref()).await?;
+						let x = f.aggregate(x).compute(ctx, opt, txn, None).await?;
+						obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
+					}
+					_ => {
+						let x = vals.first();
+						let x = if let Some(alias) = alias {
+							let cur = (&x).into();
+							alias.compute(ctx, opt, txn, Some(&cur)).await?
+						} else {
+							let cur = (&x).into();
+							expr.compute(ctx, opt, txn, Some(&cur)).await?
+						};
+						obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
+					}
+				}
+			}
+		}
+		Ok(obj)
+	}
+
 	#[inline]
 	async fn output_order(
 		&mut self,
@@ -475,33 +574,25 @@ impl Iterator {
 		_txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
-		if let Some(orders) = stm.order() {
-			// Sort the full result set
-			self.results.sort_by(|a, b| {
-				// Loop over each order clause
-				for order in orders.iter() {
-					// Reverse the ordering if DESC
-					let o = match order.random {
-						true => {
-							let a = rand::random::<f64>();
-							let b = rand::random::<f64>();
-							a.partial_cmp(&b)
+		// The results may already be ordered by an index
+		if let Some(orders) = stm.order().filter(|_| !self.ordered) {
+			let cmp = |a: &Value, b: &Value| order_cmp(orders, a, b);
+			match self.spill.as_mut() {
+				// Merge the sorted results which were moved to disk
+				Some(spill) if spill.is_spilled() => {
+					let mut merge = spill.merge(mem::take(&mut self.results), cmp)?;
+					// Only the results before the LIMIT need to be merged
+					let max = self.limit.map(|l| l + self.start.unwrap_or(0));
+					while max.map_or(true, |m| self.results.len() < m) {
+						match merge.next()? {
+							Some(v) => self.results.push(v),
+							None => break,
 						}
-						false => match order.direction {
-							true => a.compare(b, order, order.collate, order.numeric),
-							false => b.compare(a, order, order.collate, order.numeric),
-						},
-					};
-					//
-					match o {
-						Some(Ordering::Greater) => return Ordering::Greater,
-						Some(Ordering::Equal) => continue,
-						Some(Ordering::Less) => return Ordering::Less,
-						None => continue,
 					}
 				}
-				Ordering::Equal
-			})
+				// Sort the full result set
+				_ => self.results.sort_by(cmp),
+			}
 		}
 		Ok(())
 	}
@@ -571,8 +662,10 @@ impl Iterator {
 		for v in mem::take(&mut self.entries) {
 			// Distinct is passed only for iterators that really requires it
 			let dis = SyncDistinct::requires_distinct(ctx, distinct.as_mut(), &v);
-			v.iterate(ctx, opt, txn, stm, self, dis).await?;
+			self.iterate_one(ctx, opt, txn, stm, v, dis).await?;
 		}
+		// Record any distinct runs moved to disk
+		self.distinct_spill = distinct.as_ref().map_or(0, SyncDistinct::spilled_runs);
 		// Everything processed ok
 		Ok(())
 	}
@@ -588,8 +681,8 @@ impl Iterator {
 	) -> Result<(), Error> {
 		// Prevent deep recursion
 		let opt = &opt.dive(4)?;
-		// Check if iterating in parallel
-		match stm.parallel() {
+		// Check if iterating in parallel, which can not be analyzed
+		match stm.parallel() && self.analysis.is_none() {
 			// Run statements sequentially
 			false => {
 				// If any iterator requires distinct, we new to create a global distinct instance
@@ -598,8 +691,10 @@ impl Iterator {
 				for v in mem::take(&mut self.entries) {
 					// Distinct is passed only for iterators that really requires it
 					let dis = SyncDistinct::requires_distinct(ctx, distinct.as_mut(), &v);
-					v.iterate(ctx, opt, txn, stm, self, dis).await?;
+					self.iterate_one(ctx, opt, txn, stm, v, dis).await?;
 				}
+				// Record any distinct runs moved to disk
+				self.distinct_spill = distinct.as_ref().map_or(0, SyncDistinct::spilled_runs);
 				// Everything processed ok
 				Ok(())
 			}
@@ -656,12 +751,44 @@ impl Iterator {
 				let res = futures::join!(adocs, avals, aproc, fut);
 				// Consume executor error
 				let _ = res.3;
+				// Record any distinct runs moved to disk
+				if let Some(d) = &distinct {
+					self.distinct_spill = d.spilled_runs().await;
+				}
 				// Everything processed ok
 				Ok(())
 			}
 		}
 	}
 
+	/// Processes a single input value, measuring it when analyzing
+	async fn iterate_one(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		v: Iterable,
+		dis: Option<&mut SyncDistinct>,
+	) -> Result<(), Error> {
+		if self.analysis.is_none() {
+			return v.iterate(ctx, opt, txn, stm, self, dis).await;
+		}
+		let now = Instant::now();
+		let count = self.count;
+		let reads = txn.lock().await.reads();
+		v.iterate(ctx, opt, txn, stm, self, dis).await?;
+		let analysis = Analysis {
+			time: now.elapsed(),
+			rows: self.count - count,
+			scanned: txn.lock().await.reads() - reads,
+		};
+		if let Some(a) = &mut self.analysis {
+			a.push(analysis);
+		}
+		Ok(())
+	}
+
 	/// Process a new record Thing and Value
 	pub async fn process(
 		&mut self,
@@ -689,10 +816,22 @@ impl Iterator {
 				self.run.cancel();
 				return;
 			}
-			Ok(v) => self.results.push(v),
+			Ok(v) => {
+				self.count += 1;
+				// Check if the memory budget is exceeded
+				let over = self.spill.as_mut().is_some_and(|s| s.track(&v));
+				self.results.push(v);
+				if over {
+					if let Err(e) = self.spill_results(stm) {
+						self.error = Some(e);
+						self.run.cancel();
+						return;
+					}
+				}
+			}
 		}
 		// Check if we can exit
-		if stm.group().is_none() && stm.order().is_none() {
+		if stm.group().is_none() && (stm.order().is_none() || self.ordered) {
 			if let Some(l) = self.limit {
 				if let Some(s) = self.start {
 					if self.results.len() == l + s {
@@ -705,3 +844,63 @@ impl Iterator {
 		}
 	}
 }
+
+/// Returns the values of a result at each of the GROUP BY paths
+fn group_key(groups: &Groups, obj: &Value) -> Array {
+	let mut arr = Array::with_capacity(groups.len());
+	for group in groups.iter() {
+		arr.push(obj.pick(group));
+	}
+	arr
+}
+
+/// Pairs a result with the values at each of the GROUP BY paths
+fn keyed(groups: &Groups, obj: Value) -> Value {
+	Value::from(vec![Value::from(group_key(groups, &obj)), obj])
+}
+
+/// Splits a result paired with the values at each of the GROUP BY paths
+fn unkeyed(v: Value) -> (Value, Value) {
+	match v {
+		Value::Array(Array(v)) => match <[Value; 2]>::try_from(v) {
+			Ok([key, obj]) => (key, obj),
+			Err(v) => (Value::None, v.into()),
+		},
+		v => (Value::None, v),
+	}
+}
+
+/// Compares two results paired with the values at each of the GROUP BY paths
+fn keyed_cmp(a: &Value, b: &Value) -> Ordering {
+	match (a, b) {
+		(Value::Array(a), Value::Array(b)) => a.0.first().cmp(&b.0.first()),
+		_ => Ordering::Equal,
+	}
+}
+
+/// Compares two results by each of the ORDER BY clauses
+fn order_cmp(orders: &Orders, a: &Value, b: &Value) -> Ordering {
+	// Loop over each order clause
+	for order in orders.iter() {
+		// Reverse the ordering if DESC
+		let o = match order.random {
+			true => {
+				let a = rand::random::<f64>();
+				let b = rand::random::<f64>();
+				a.partial_cmp(&b)
+			}
+			false => match order.direction {
+				true => a.compare(b, order, order.collate, order.numeric),
+				false => b.compare(a, order, order.collate, order.numeric),
+			},
+		};
+		//
+		match o {
+			Some(Ordering::Greater) => return Ordering::Greater,
+			Some(Ordering::Equal) => continue,
+			Some(Ordering::Less) => return Ordering::Less,
+			None => continue,
+		}
+	}
+	Ordering::Equal
+}
@@ -10,6 +10,7 @@ mod notification;
 mod options;
 mod response;
 mod session;
+mod spill;
 mod statement;
 mod transaction;
 mod variables;
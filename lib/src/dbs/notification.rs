@@ -1,9 +1,12 @@
 use crate::sql::{Object, Uuid, Value};
+use derive::Store;
+use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Debug, Display};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Store)]
 #[serde(rename_all = "UPPERCASE")]
+#[revisioned(revision = 1)]
 pub enum Action {
 	Create,
 	Update,
@@ -20,10 +23,16 @@ impl Display for Action {
 	}
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Store)]
+#[revisioned(revision = 1)]
 pub struct Notification {
+	/// The id of the LIVE query which sent this notification
 	pub id: Uuid,
+	/// The action which caused this notification
 	pub action: Action,
+	/// The record id for a DELETE, otherwise the selected fields
+	/// of the record, or the RFC 6902 JSON Patch operations which
+	/// were applied to the record, for a `LIVE SELECT DIFF` query
 	pub result: Value,
 }
 
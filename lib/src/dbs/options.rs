@@ -1,8 +1,8 @@
 use super::capabilities::Capabilities;
 use crate::cnf;
-use crate::dbs::Notification;
+use crate::dbs::{Notification, Transaction};
 use crate::err::Error;
-use crate::iam::{Action, Auth, ResourceKind, Role};
+use crate::iam::{Action, Auth, Level, ResourceKind, Role};
 use crate::sql::Base;
 use channel::Sender;
 use std::sync::Arc;
@@ -146,6 +146,26 @@ impl Options {
 		self
 	}
 
+	/// Resolve the current definitions of the custom roles of
+	/// the actor, for the code which uses this `Options`.
+	///
+	/// Custom roles are resolved in the transaction of each statement, rather
+	/// than when the actor signs in, so that a role which is removed or
+	/// redefined applies to the following statements. Custom roles are defined
+	/// on the same level as the actor, and a role which does not exist grants nothing.
+	pub(crate) async fn with_custom_roles(&self, txn: &Transaction) -> Result<Self, Error> {
+		if !self.auth.has_custom_roles() {
+			return Ok(self.clone());
+		}
+		let mut run = txn.lock().await;
+		let roles = match self.auth.level() {
+			Level::Namespace(ns) => run.all_ns_roles(ns).await?.to_vec(),
+			Level::Database(ns, db) => run.all_db_roles(ns, db).await?.to_vec(),
+			_ => vec![],
+		};
+		Ok(self.clone().with_auth(Arc::new(self.auth.with_custom_roles(roles))))
+	}
+
 	/// Specify whether live queries are supported for
 	/// code which uses this `Options`, with chaining.
 	pub fn with_live(mut self, live: bool) -> Self {
@@ -482,6 +502,15 @@ impl Options {
 	/// TODO: This method is called a lot during data operations, so we decided to bypass the system's authorization mechanism.
 	/// This is a temporary solution, until we optimize the new authorization system.
 	pub fn check_perms(&self, action: Action) -> bool {
+		self.check_perms_on(action, None)
+	}
+
+	/// Whether or not to check the permissions of a table, on which custom roles can grant actions by name
+	pub fn check_table_perms(&self, action: Action, tb: &str) -> bool {
+		self.check_perms_on(action, Some(tb))
+	}
+
+	fn check_perms_on(&self, action: Action, tb: Option<&str>) -> bool {
 		// If permissions are disabled, don't check permissions
 		if !self.perms {
 			return false;
@@ -494,9 +523,11 @@ impl Options {
 
 		// Is the actor allowed to view?
 		let can_view =
-			[Role::Viewer, Role::Editor, Role::Owner].iter().any(|r| self.auth.has_role(r));
+			[Role::Viewer, Role::Editor, Role::Owner].iter().any(|r| self.auth.has_role(r))
+				|| self.auth.has_grant(&Action::View, &ResourceKind::Table, tb);
 		// Is the actor allowed to edit?
-		let can_edit = [Role::Editor, Role::Owner].iter().any(|r| self.auth.has_role(r));
+		let can_edit = [Role::Editor, Role::Owner].iter().any(|r| self.auth.has_role(r))
+			|| self.auth.has_grant(&Action::Edit, &ResourceKind::Table, tb);
 		// Is the target database in the actor's level?
 		let db_in_actor_level = self.auth.is_root()
 			|| self.auth.is_ns() && self.auth.level().ns().unwrap() == self.ns()
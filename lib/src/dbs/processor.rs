@@ -1,3 +1,4 @@
+use crate::cf;
 use crate::cnf::PROCESSOR_BATCH_SIZE;
 use crate::ctx::Context;
 #[cfg(not(target_arch = "wasm32"))]
@@ -7,8 +8,9 @@ use crate::dbs::{Iterable, Iterator, Operable, Options, Processed, Statement, Tr
 use crate::err::Error;
 use crate::idx::planner::executor::IteratorRef;
 use crate::key::{graph, thing};
+use crate::kvs;
 use crate::sql::dir::Dir;
-use crate::sql::{Edges, Range, Table, Thing, Value};
+use crate::sql::{Datetime, Edges, Range, Table, Thing, Value};
 #[cfg(not(target_arch = "wasm32"))]
 use channel::Sender;
 use std::ops::Bound;
@@ -58,7 +60,7 @@ impl<'a> Processor<'a> {
 		match self {
 			Processor::Iterator(distinct, ite) => {
 				let is_processed = if let Some(d) = distinct {
-					d.check_already_processed(&pro)
+					d.check_already_processed(&pro)?
 				} else {
 					false
 				};
@@ -69,7 +71,7 @@ impl<'a> Processor<'a> {
 			#[cfg(not(target_arch = "wasm32"))]
 			Processor::Channel(distinct, chn) => {
 				let is_processed = if let Some(d) = distinct {
-					d.check_already_processed(&pro).await
+					d.check_already_processed(&pro).await?
 				} else {
 					false
 				};
@@ -137,6 +139,26 @@ impl<'a> Processor<'a> {
 	) -> Result<(), Error> {
 		// Check that the table exists
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
+		// Fetch the data as it was at the specified version
+		if let Some(version) = stm.version() {
+			let beg = thing::new(opt.ns(), opt.db(), &v.tb, &v.id).encode()?;
+			let mut end = beg.clone();
+			end.push(0x00);
+			let mut run = txn.lock().await;
+			let mut scan = self.scan_version(&mut run, opt, &v.tb, &version.0, &beg, &end).await?;
+			let val = match scan.next_batch(&mut run).await?.pop() {
+				Some((_, v)) => v,
+				None => Value::None,
+			};
+			drop(run);
+			let pro = Processed {
+				ir: None,
+				rid: Some(v),
+				doc_id: None,
+				val: Operable::Value(val),
+			};
+			return self.process(ctx, opt, txn, stm, pro).await;
+		}
 		// Fetch the data from the store
 		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
 		let val = txn.clone().lock().await.get(key).await?;
@@ -238,6 +260,10 @@ impl<'a> Processor<'a> {
 		// Prepare the start and end keys
 		let beg = thing::prefix(opt.ns(), opt.db(), &v);
 		let end = thing::suffix(opt.ns(), opt.db(), &v);
+		// Process the records as they were at the specified version
+		if let Some(version) = stm.version() {
+			return self.process_version(ctx, opt, txn, stm, &v, &version.0, &beg, &end).await;
+		}
 		// Prepare the next holder key
 		let mut nxt: Option<Vec<u8>> = None;
 		// Loop until no more keys
@@ -327,6 +353,10 @@ impl<'a> Processor<'a> {
 				key
 			}
 		};
+		// Process the records as they were at the specified version
+		if let Some(version) = stm.version() {
+			return self.process_version(ctx, opt, txn, stm, &v.tb, &version.0, &beg, &end).await;
+		}
 		// Prepare the next holder key
 		let mut nxt: Option<Vec<u8>> = None;
 		// Loop until no more keys
@@ -394,6 +424,12 @@ impl<'a> Processor<'a> {
 		stm: &Statement<'_>,
 		e: Edges,
 	) -> Result<(), Error> {
+		// Graph edges are not kept in the change feeds
+		if stm.version().is_some() {
+			return Err(Error::VersionUnsupported {
+				value: e.to_string(),
+			});
+		}
 		// Pull out options
 		let ns = opt.ns();
 		let db = opt.db();
@@ -533,6 +569,67 @@ impl<'a> Processor<'a> {
 		Ok(())
 	}
 
+	#[allow(clippy::too_many_arguments)]
+	async fn process_version(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		tb: &str,
+		at: &Datetime,
+		beg: &[u8],
+		end: &[u8],
+	) -> Result<(), Error> {
+		// Reconstruct the records from the change feed
+		let mut scan = {
+			let mut run = txn.lock().await;
+			self.scan_version(&mut run, opt, tb, at, beg, end).await?
+		};
+		loop {
+			// Check if the context is finished
+			if ctx.is_done() {
+				break;
+			}
+			// Get the next batch of records
+			let res = scan.next_batch(&mut *txn.lock().await).await?;
+			// Exit when settled
+			if res.is_empty() {
+				break;
+			}
+			// Loop over results
+			for (rid, val) in res {
+				// Check the context
+				if ctx.is_done() {
+					break;
+				}
+				// Process the record
+				let pro = Processed {
+					ir: None,
+					rid: Some(rid),
+					doc_id: None,
+					val: Operable::Value(val),
+				};
+				self.process(ctx, opt, txn, stm, pro).await?;
+			}
+		}
+		// Everything ok
+		Ok(())
+	}
+
+	async fn scan_version(
+		&self,
+		run: &mut kvs::Transaction,
+		opt: &Options,
+		tb: &str,
+		at: &Datetime,
+		beg: &[u8],
+		end: &[u8],
+	) -> Result<cf::VersionScan, Error> {
+		let vs = cf::versionstamp_at(run, opt.ns(), opt.db(), tb, at).await?;
+		cf::VersionScan::new(run, opt.ns(), opt.db(), tb, vs, beg, end).await
+	}
+
 	#[allow(clippy::too_many_arguments)]
 	async fn process_index(
 		&mut self,
@@ -0,0 +1,675 @@
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::sql::value::Value;
+use revision::Revisioned;
+use std::cmp::{Ordering, Reverse};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::path::PathBuf;
+use std::vec::IntoIter;
+#[cfg(not(target_arch = "wasm32"))]
+use uuid::Uuid;
+
+/// The number of keys between each entry of the sparse index of a key run
+const KEY_RUN_INDEX_INTERVAL: usize = 64;
+
+/// The number of bits of the bloom filter of a key run, for each of its keys
+const KEY_RUN_BLOOM_BITS: usize = 10;
+
+/// The number of hashes of each key in the bloom filter of a key run
+const KEY_RUN_BLOOM_HASHES: u64 = 7;
+
+/// The minimum number of values or keys in a run, so that
+/// a small memory budget does not create a file for each of them
+const MIN_RUN_LEN: usize = 64;
+
+/// The maximum number of runs which are merged together,
+/// which bounds the number of files open at the same time
+const MERGE_FAN_IN: usize = 16;
+
+/// Counts the bytes written, without storing them
+struct Counter(usize);
+
+impl Write for Counter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0 += buf.len();
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Returns the number of bytes used by a value, once serialized
+fn size(v: &Value) -> usize {
+	let mut c = Counter(0);
+	match v.serialize_revisioned(&mut c) {
+		Ok(_) => c.0,
+		Err(_) => 0,
+	}
+}
+
+/// A temporary file, which is removed once dropped
+struct TempFile {
+	path: PathBuf,
+}
+
+impl TempFile {
+	/// Creates a new temporary file, and opens it for writing
+	#[cfg(not(target_arch = "wasm32"))]
+	fn create() -> Result<(Self, File), Error> {
+		let path = std::env::temp_dir().join(format!("surrealdb-{}.spill", Uuid::new_v4()));
+		let file = File::options().read(true).write(true).create_new(true).open(&path)?;
+		Ok((
+			Self {
+				path,
+			},
+			file,
+		))
+	}
+
+	/// There is no filesystem to move the results to in WebAssembly
+	#[cfg(target_arch = "wasm32")]
+	fn create() -> Result<(Self, File), Error> {
+		Err(Error::Unimplemented(
+			"Moving query results to disk is not supported in WebAssembly".into(),
+		))
+	}
+
+	/// Opens the temporary file for reading, from its start
+	fn open(&self) -> Result<File, Error> {
+		Ok(File::open(&self.path)?)
+	}
+}
+
+impl Drop for TempFile {
+	fn drop(&mut self) {
+		if let Err(e) = fs::remove_file(&self.path) {
+			warn!("Unable to remove temporary file {}: {e}", self.path.display());
+		}
+	}
+}
+
+/// A sorted run of values, stored in a temporary file. The
+/// file is only open while the run is being written or read.
+struct Run {
+	file: TempFile,
+	len: usize,
+	/// The number of merges the values of the run went through
+	level: usize,
+}
+
+impl Run {
+	fn write<I>(vals: I, level: usize) -> Result<Self, Error>
+	where
+		I: IntoIterator<Item = Result<Value, Error>>,
+	{
+		let (file, f) = TempFile::create()?;
+		let mut w = BufWriter::new(f);
+		let mut len = 0;
+		for v in vals {
+			v?.serialize_revisioned(&mut w)?;
+			len += 1;
+		}
+		w.flush()?;
+		Ok(Self {
+			file,
+			len,
+			level,
+		})
+	}
+
+	/// Merges consecutive runs into a single run
+	fn merge<F>(runs: Vec<Run>, level: usize, cmp: F) -> Result<Self, Error>
+	where
+		F: FnMut(&Value, &Value) -> Ordering,
+	{
+		let mut srcs = Vec::with_capacity(runs.len());
+		for run in runs {
+			srcs.push(Source::Run(run.into_reader()?));
+		}
+		let mut merge = Merge::new(srcs, cmp)?;
+		Self::write(std::iter::from_fn(|| merge.next().transpose()), level)
+	}
+
+	fn into_reader(self) -> Result<RunReader, Error> {
+		Ok(RunReader {
+			rdr: BufReader::new(self.file.open()?),
+			left: self.len,
+			_file: self.file,
+		})
+	}
+}
+
+/// Reads the values of a run, in order
+struct RunReader {
+	rdr: BufReader<File>,
+	left: usize,
+	_file: TempFile,
+}
+
+impl RunReader {
+	fn next(&mut self) -> Result<Option<Value>, Error> {
+		if self.left == 0 {
+			return Ok(None);
+		}
+		self.left -= 1;
+		Ok(Some(Value::deserialize_revisioned(&mut self.rdr)?))
+	}
+}
+
+enum Source {
+	Memory(IntoIter<Value>),
+	Run(RunReader),
+}
+
+impl Source {
+	fn next(&mut self) -> Result<Option<Value>, Error> {
+		match self {
+			Source::Memory(i) => Ok(i.next()),
+			Source::Run(r) => r.next(),
+		}
+	}
+}
+
+/// Tracks the size of the results buffered by a query, and moves sorted
+/// runs of these results to temporary files, once the memory budget of
+/// the query has been exceeded. Once enough runs of the same level are
+/// stored, they are merged into a single run of the next level, so that
+/// the runs can always be merged back together with a few open files.
+pub(super) struct Spill {
+	budget: usize,
+	size: usize,
+	len: usize,
+	runs: Vec<Run>,
+	spilled: usize,
+}
+
+impl Spill {
+	pub(super) fn new(budget: usize) -> Self {
+		Self {
+			budget,
+			size: 0,
+			len: 0,
+			runs: vec![],
+			spilled: 0,
+		}
+	}
+
+	/// Tracks a value buffered in memory, and returns true once the
+	/// memory budget has been exceeded by enough values to fill a run
+	pub(super) fn track(&mut self, v: &Value) -> bool {
+		self.size += size(v);
+		self.len += 1;
+		self.size > self.budget && self.len >= MIN_RUN_LEN
+	}
+
+	/// Sorts the values buffered in memory, and moves them to a new run
+	pub(super) fn spill<F>(&mut self, mut vals: Vec<Value>, mut cmp: F) -> Result<(), Error>
+	where
+		F: FnMut(&Value, &Value) -> Ordering,
+	{
+		vals.sort_by(&mut cmp);
+		self.spilled += 1;
+		self.runs.push(Run::write(vals.into_iter().map(Ok), 0)?);
+		self.size = 0;
+		self.len = 0;
+		// Merge the last runs, once enough of them have the same level
+		while self.runs.len() >= MERGE_FAN_IN {
+			let at = self.runs.len() - MERGE_FAN_IN;
+			let level = self.runs[at].level;
+			if self.runs[at..].iter().any(|r| r.level != level) {
+				break;
+			}
+			let runs = self.runs.split_off(at);
+			self.runs.push(Run::merge(runs, level + 1, &mut cmp)?);
+		}
+		Ok(())
+	}
+
+	/// Checks if any values are currently stored on disk
+	pub(super) fn is_spilled(&self) -> bool {
+		!self.runs.is_empty()
+	}
+
+	/// The number of runs which were written to disk from memory
+	pub(super) fn runs(&self) -> usize {
+		self.spilled
+	}
+
+	/// Merges the runs on disk with the values still buffered in memory
+	pub(super) fn merge<F>(&mut self, mut vals: Vec<Value>, mut cmp: F) -> Result<Merge<F>, Error>
+	where
+		F: FnMut(&Value, &Value) -> Ordering,
+	{
+		vals.sort_by(&mut cmp);
+		self.size = 0;
+		self.len = 0;
+		// Merge the last runs until the remaining runs can all be open at once
+		while self.runs.len() >= MERGE_FAN_IN {
+			let at = self.runs.len() - MERGE_FAN_IN;
+			let runs = self.runs.split_off(at);
+			let level = runs[0].level + 1;
+			self.runs.push(Run::merge(runs, level, &mut cmp)?);
+		}
+		// Earlier runs come first, so that the merge is stable
+		let mut srcs = Vec::with_capacity(self.runs.len() + 1);
+		for run in self.runs.drain(..) {
+			srcs.push(Source::Run(run.into_reader()?));
+		}
+		srcs.push(Source::Memory(vals.into_iter()));
+		Merge::new(srcs, cmp)
+	}
+}
+
+/// Merges sorted sources into a single sorted sequence of values
+pub(super) struct Merge<F> {
+	srcs: Vec<Source>,
+	heads: Vec<Option<Value>>,
+	/// A binary heap of the sources which are not exhausted,
+	/// with the source of the smallest head at the top
+	heap: Vec<usize>,
+	cmp: F,
+}
+
+impl<F> Merge<F>
+where
+	F: FnMut(&Value, &Value) -> Ordering,
+{
+	fn new(mut srcs: Vec<Source>, cmp: F) -> Result<Self, Error> {
+		let mut heads = Vec::with_capacity(srcs.len());
+		for src in srcs.iter_mut() {
+			heads.push(src.next()?);
+		}
+		let heap = (0..heads.len()).filter(|i| heads[*i].is_some()).collect::<Vec<_>>();
+		let mut merge = Self {
+			srcs,
+			heads,
+			heap,
+			cmp,
+		};
+		for i in (0..merge.heap.len() / 2).rev() {
+			merge.sift_down(i);
+		}
+		Ok(merge)
+	}
+
+	/// Returns the next value in order, or None once all sources are exhausted
+	pub(super) fn next(&mut self) -> Result<Option<Value>, Error> {
+		let i = match self.heap.first() {
+			Some(i) => *i,
+			None => return Ok(None),
+		};
+		let next = self.srcs[i].next()?;
+		let head = mem::replace(&mut self.heads[i], next);
+		if self.heads[i].is_none() {
+			self.heap.swap_remove(0);
+		}
+		self.sift_down(0);
+		Ok(head)
+	}
+
+	/// Checks if the head of a source comes before the head of another
+	/// source. Equal heads are taken from the earlier source first.
+	fn less(&mut self, a: usize, b: usize) -> bool {
+		match (&self.heads[a], &self.heads[b]) {
+			(Some(x), Some(y)) => match (self.cmp)(x, y) {
+				Ordering::Less => true,
+				Ordering::Greater => false,
+				Ordering::Equal => a < b,
+			},
+			_ => false,
+		}
+	}
+
+	fn sift_down(&mut self, mut pos: usize) {
+		loop {
+			let mut min = pos;
+			for child in [2 * pos + 1, 2 * pos + 2] {
+				if child < self.heap.len() && self.less(self.heap[child], self.heap[min]) {
+					min = child;
+				}
+			}
+			if min == pos {
+				return;
+			}
+			self.heap.swap(pos, min);
+			pos = min;
+		}
+	}
+}
+
+/// A bloom filter of the keys of a run, which
+/// rules out most of the keys missing from the run
+struct Bloom {
+	bits: Vec<u64>,
+}
+
+impl Bloom {
+	fn new(len: usize) -> Self {
+		Self {
+			bits: vec![0; len * KEY_RUN_BLOOM_BITS / 64 + 1],
+		}
+	}
+
+	/// Returns the positions of the bits of a key
+	fn positions(&self, key: &[u8]) -> impl Iterator<Item = usize> {
+		let mut h = DefaultHasher::new();
+		h.write(key);
+		let h = h.finish();
+		let (a, b) = (h & 0xffff_ffff, h >> 32);
+		let m = (self.bits.len() * 64) as u64;
+		(0..KEY_RUN_BLOOM_HASHES).map(move |i| (a.wrapping_add(i.wrapping_mul(b)) % m) as usize)
+	}
+
+	fn insert(&mut self, key: &[u8]) {
+		for p in self.positions(key) {
+			self.bits[p / 64] |= 1 << (p % 64);
+		}
+	}
+
+	fn may_contain(&self, key: &[u8]) -> bool {
+		self.positions(key).all(|p| self.bits[p / 64] & (1 << (p % 64)) != 0)
+	}
+}
+
+/// A sorted run of keys, stored in a temporary file, with a sparse
+/// index of the keys and their offsets, and a bloom filter, in memory
+struct KeyRun {
+	file: TempFile,
+	handle: File,
+	len: usize,
+	index: Vec<(Key, u64)>,
+	last: Key,
+	bloom: Bloom,
+	/// The number of merges the keys of the run went through
+	level: usize,
+}
+
+impl KeyRun {
+	fn write<I>(keys: I, len: usize, level: usize) -> Result<Self, Error>
+	where
+		I: IntoIterator<Item = Result<Key, Error>>,
+	{
+		let (file, handle) = TempFile::create()?;
+		let mut index = Vec::with_capacity(len / KEY_RUN_INDEX_INTERVAL + 1);
+		let mut bloom = Bloom::new(len);
+		let mut last = Key::new();
+		let mut w = BufWriter::new(&handle);
+		let mut offset = 0u64;
+		for (i, key) in keys.into_iter().enumerate() {
+			let key = key?;
+			let size = key.len() as u32;
+			w.write_all(&size.to_be_bytes())?;
+			w.write_all(&key)?;
+			bloom.insert(&key);
+			if i % KEY_RUN_INDEX_INTERVAL == 0 {
+				index.push((key.clone(), offset));
+			}
+			offset += 4 + size as u64;
+			last = key;
+		}
+		w.flush()?;
+		drop(w);
+		Ok(Self {
+			file,
+			handle,
+			len,
+			index,
+			last,
+			bloom,
+			level,
+		})
+	}
+
+	/// Merges runs of distinct keys into a single run
+	fn merge(runs: Vec<KeyRun>, level: usize) -> Result<Self, Error> {
+		let len = runs.iter().map(|r| r.len).sum();
+		let mut rdrs = Vec::with_capacity(runs.len());
+		let mut heap = BinaryHeap::with_capacity(runs.len());
+		for (i, run) in runs.iter().enumerate() {
+			let mut rdr = KeyRunReader::new(run)?;
+			if let Some(key) = rdr.next()? {
+				heap.push(Reverse((key, i)));
+			}
+			rdrs.push(rdr);
+		}
+		let keys = std::iter::from_fn(|| {
+			let Reverse((key, i)) = heap.pop()?;
+			match rdrs[i].next() {
+				Ok(Some(next)) => heap.push(Reverse((next, i))),
+				Ok(None) => {}
+				Err(e) => return Some(Err(e)),
+			}
+			Some(Ok(key))
+		});
+		Self::write(keys, len, level)
+	}
+
+	fn contains(&self, key: &[u8]) -> Result<bool, Error> {
+		// Rule out the keys outside of the run, and most of the keys missing from it
+		if key > self.last.as_slice() || !self.bloom.may_contain(key) {
+			return Ok(false);
+		}
+		// Find the last indexed key which is not greater than the key
+		let pos = self.index.partition_point(|(k, _)| k.as_slice() <= key);
+		if pos == 0 {
+			return Ok(false);
+		}
+		let (first, offset) = &self.index[pos - 1];
+		if first.as_slice() == key {
+			return Ok(true);
+		}
+		// Scan the keys which follow the indexed key
+		let mut f = &self.handle;
+		f.seek(SeekFrom::Start(*offset))?;
+		let mut rdr = BufReader::new(f);
+		let mut len = [0u8; 4];
+		let mut buf = Vec::new();
+		for _ in 0..KEY_RUN_INDEX_INTERVAL {
+			match rdr.read_exact(&mut len) {
+				Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+				r => r?,
+			}
+			buf.resize(u32::from_be_bytes(len) as usize, 0);
+			rdr.read_exact(&mut buf)?;
+			match buf.as_slice().cmp(key) {
+				Ordering::Less => continue,
+				Ordering::Equal => return Ok(true),
+				Ordering::Greater => break,
+			}
+		}
+		Ok(false)
+	}
+}
+
+/// Reads the keys of a key run, in order
+struct KeyRunReader {
+	rdr: BufReader<File>,
+	left: usize,
+}
+
+impl KeyRunReader {
+	fn new(run: &KeyRun) -> Result<Self, Error> {
+		Ok(Self {
+			rdr: BufReader::new(run.file.open()?),
+			left: run.len,
+		})
+	}
+
+	fn next(&mut self) -> Result<Option<Key>, Error> {
+		if self.left == 0 {
+			return Ok(None);
+		}
+		self.left -= 1;
+		let mut len = [0u8; 4];
+		self.rdr.read_exact(&mut len)?;
+		let mut key = vec![0; u32::from_be_bytes(len) as usize];
+		self.rdr.read_exact(&mut key)?;
+		Ok(Some(key))
+	}
+}
+
+/// Tracks the size of a set of keys, and moves sorted runs of the keys
+/// to temporary files, once the memory budget of the query has been
+/// exceeded. Each run can then be searched using its bloom filter and
+/// its sparse index. Once enough runs of the same level are stored,
+/// they are merged into a single run of the next level, so that only
+/// a few runs need to be searched, with a few open files.
+pub(super) struct KeySpill {
+	budget: usize,
+	size: usize,
+	len: usize,
+	runs: Vec<KeyRun>,
+	spilled: usize,
+}
+
+impl KeySpill {
+	pub(super) fn new(budget: usize) -> Self {
+		Self {
+			budget,
+			size: 0,
+			len: 0,
+			runs: vec![],
+			spilled: 0,
+		}
+	}
+
+	/// Tracks a key buffered in memory, and returns true once the
+	/// memory budget has been exceeded by enough keys to fill a run
+	pub(super) fn track(&mut self, key: &Key) -> bool {
+		self.size += key.len();
+		self.len += 1;
+		self.size > self.budget && self.len >= MIN_RUN_LEN
+	}
+
+	/// Sorts the keys buffered in memory, and moves them to a new run
+	pub(super) fn spill(&mut self, mut keys: Vec<Key>) -> Result<(), Error> {
+		keys.sort();
+		let len = keys.len();
+		self.spilled += 1;
+		self.runs.push(KeyRun::write(keys.into_iter().map(Ok), len, 0)?);
+		self.size = 0;
+		self.len = 0;
+		// Merge the last runs, once enough of them have the same level
+		while self.runs.len() >= MERGE_FAN_IN {
+			let at = self.runs.len() - MERGE_FAN_IN;
+			let level = self.runs[at].level;
+			if self.runs[at..].iter().any(|r| r.level != level) {
+				break;
+			}
+			let runs = self.runs.split_off(at);
+			self.runs.push(KeyRun::merge(runs, level + 1)?);
+		}
+		Ok(())
+	}
+
+	/// Checks if a key has been moved to any of the runs
+	pub(super) fn contains(&self, key: &[u8]) -> Result<bool, Error> {
+		for run in self.runs.iter() {
+			if run.contains(key)? {
+				return Ok(true);
+			}
+		}
+		Ok(false)
+	}
+
+	/// The number of runs which were written to disk from memory
+	pub(super) fn runs(&self) -> usize {
+		self.spilled
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn cmp(a: &Value, b: &Value) -> Ordering {
+		a.partial_cmp(b).unwrap_or(Ordering::Equal)
+	}
+
+	#[test]
+	fn spill_merge_sorted_runs() {
+		let mut spill = Spill::new(0);
+		let vals: Vec<Value> = (0..MIN_RUN_LEN as i64).rev().map(|i| Value::from(i * 3)).collect();
+		for (i, v) in vals.iter().enumerate() {
+			assert_eq!(spill.track(v), i + 1 == MIN_RUN_LEN);
+		}
+		spill.spill(vals, cmp).unwrap();
+		let vals: Vec<Value> = (0..MIN_RUN_LEN as i64).map(|i| Value::from(i * 3 + 1)).collect();
+		spill.spill(vals, cmp).unwrap();
+		assert!(spill.is_spilled());
+		assert_eq!(spill.runs(), 2);
+		let vals: Vec<Value> = (0..MIN_RUN_LEN as i64).map(|i| Value::from(i * 3 + 2)).collect();
+		let mut merge = spill.merge(vals, cmp).unwrap();
+		let mut res = vec![];
+		while let Some(v) = merge.next().unwrap() {
+			res.push(v);
+		}
+		assert!(!spill.is_spilled());
+		let exp: Vec<Value> = (0..MIN_RUN_LEN as i64 * 3).map(Value::from).collect();
+		assert_eq!(res, exp);
+	}
+
+	#[test]
+	fn spill_merge_runs_in_levels() {
+		let mut spill = Spill::new(0);
+		// Sort by the first value only, to check that the merge is stable
+		let cmp = |a: &Value, b: &Value| match (a, b) {
+			(Value::Array(a), Value::Array(b)) => a.first().cmp(&b.first()),
+			_ => Ordering::Equal,
+		};
+		for run in 0..100i64 {
+			let vals = (0..4i64).map(|i| Value::from(vec![Value::from(i), Value::from(run)]));
+			spill.spill(vals.collect(), cmp).unwrap();
+			assert!(spill.runs.len() < MERGE_FAN_IN * 2);
+		}
+		assert_eq!(spill.runs(), 100);
+		assert!(spill.runs.iter().any(|r| r.level == 1));
+		let vals = (0..4i64).map(|i| Value::from(vec![Value::from(i), Value::from(100)]));
+		let mut merge = spill.merge(vals.collect(), cmp).unwrap();
+		assert!(merge.srcs.len() <= MERGE_FAN_IN);
+		let mut res = vec![];
+		while let Some(v) = merge.next().unwrap() {
+			res.push(v);
+		}
+		let exp: Vec<Value> = (0..4i64)
+			.flat_map(|i| (0..=100i64).map(move |run| vec![Value::from(i), Value::from(run)]))
+			.map(Value::from)
+			.collect();
+		assert_eq!(res, exp);
+	}
+
+	#[test]
+	fn key_spill_contains() {
+		let mut spill = KeySpill::new(0);
+		let keys: Vec<Key> = (0..500u32).map(|i| (i * 2).to_be_bytes().to_vec()).collect();
+		spill.spill(keys).unwrap();
+		spill.spill(vec![b"a".to_vec(), b"b".to_vec()]).unwrap();
+		assert_eq!(spill.runs(), 2);
+		for i in 0..1000u32 {
+			assert_eq!(spill.contains(&i.to_be_bytes()).unwrap(), i % 2 == 0, "{i}");
+		}
+		assert!(spill.contains(b"b").unwrap());
+		assert!(!spill.contains(b"c").unwrap());
+	}
+
+	#[test]
+	fn key_spill_merge_runs_in_levels() {
+		let mut spill = KeySpill::new(0);
+		for run in 0..40u32 {
+			let keys = (0..100u32).map(|i| (i * 80 + run * 2).to_be_bytes().to_vec());
+			spill.spill(keys.collect()).unwrap();
+		}
+		assert_eq!(spill.runs(), 40);
+		assert!(spill.runs.len() < MERGE_FAN_IN);
+		for i in 0..8000u32 {
+			assert_eq!(spill.contains(&i.to_be_bytes()).unwrap(), i % 2 == 0, "{i}");
+		}
+	}
+}
@@ -14,9 +14,9 @@ impl<'a> Document<'a> {
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
 		// Check if this record exists
-		if self.id.is_some() {
+		if let Some(rid) = self.id {
 			// Should we run permissions checks?
-			if opt.check_perms(stm.into()) {
+			if opt.check_table_perms(stm.into(), &rid.tb) {
 				// Get the table
 				let tb = self.tb(opt, txn).await?;
 				// Get the permission clause
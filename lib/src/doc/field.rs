@@ -116,7 +116,7 @@ impl<'a> Document<'a> {
 					}
 				}
 				// Check for a PERMISSIONS clause
-				if opt.check_perms(Action::Edit) {
+				if opt.check_table_perms(Action::Edit, &rid.tb) {
 					// Get the permission clause
 					let perms = if self.is_new() {
 						&fd.permissions.create
@@ -4,6 +4,8 @@ use crate::dbs::{Options, Transaction};
 use crate::doc::{CursorDoc, Document};
 use crate::err::Error;
 use crate::idx::ft::FtIndex;
+use crate::idx::planner::stats::IndexStatistics;
+use crate::idx::spatial::SpatialIndex;
 use crate::idx::trees::mtree::MTreeIndex;
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::IndexKeyBase;
@@ -48,21 +50,56 @@ impl<'a> Document<'a> {
 				// Claim transaction
 				let mut run = txn.lock().await;
 
+				// Keep the entries of an index which is being rebuilt up to date
+				if let Some(ix) = ix.shadow() {
+					let mut ic = IndexOperation::new(opt, &ix, o.clone(), n.clone(), rid);
+					ic.compute(&mut run).await?;
+				}
+
 				// Store all the variable and parameters required by the index operation
 				let mut ic = IndexOperation::new(opt, ix, o, n, rid);
 
 				// Index operation dispatching
-				match &ix.index {
-					Index::Uniq => ic.index_unique(&mut run).await?,
-					Index::Idx => ic.index_non_unique(&mut run).await?,
-					Index::Search(p) => ic.index_full_text(&mut run, p).await?,
-					Index::MTree(p) => ic.index_mtree(&mut run, p).await?,
-				};
+				ic.compute(&mut run).await?;
+
+				// Record the entries added to, or removed from, an analyzed index
+				if ic.delta != 0 {
+					IndexStatistics::record(
+						&mut run,
+						opt.ns(),
+						opt.db(),
+						&ix.what,
+						&ix.name,
+						ic.delta,
+					)
+					.await?;
+				}
 			}
 		}
 		// Carry on
 		Ok(())
 	}
+
+	/// Indexes the current version of the document in a single index.
+	///
+	/// The index entries are computed as if the document was forcefully
+	/// updated, so indexing a document which is already indexed is a no-op.
+	pub(crate) async fn build_index(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Calculate the values
+		let n = build_opt_values(ctx, opt, txn, ix, &self.current).await?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Replace the index entries of the document
+		IndexOperation::new(opt, ix, n.clone(), n, rid).compute(&mut run).await
+	}
 }
 
 /// Extract from the given document, the values required by the index and put then in an array.
@@ -216,6 +253,8 @@ struct IndexOperation<'a> {
 	/// The new values (if existing)
 	n: Option<Vec<Value>>,
 	rid: &'a Thing,
+	/// The number of entries added to a standard or unique index, minus the entries removed
+	delta: i64,
 }
 
 impl<'a> IndexOperation<'a> {
@@ -232,6 +271,18 @@ impl<'a> IndexOperation<'a> {
 			o,
 			n,
 			rid,
+			delta: 0,
+		}
+	}
+
+	async fn compute(&mut self, run: &mut kvs::Transaction) -> Result<(), Error> {
+		let ix = self.ix;
+		match &ix.index {
+			Index::Uniq => self.index_unique(run).await,
+			Index::Idx => self.index_non_unique(run).await,
+			Index::Search(p) => self.index_full_text(run, p).await,
+			Index::MTree(p) => self.index_mtree(run, p).await,
+			Index::Spatial => self.index_spatial(run).await,
 		}
 	}
 
@@ -240,7 +291,7 @@ impl<'a> IndexOperation<'a> {
 			self.opt.ns(),
 			self.opt.db(),
 			&self.ix.what,
-			&self.ix.name,
+			self.ix.storage(),
 			v,
 			None,
 		)
@@ -251,7 +302,7 @@ impl<'a> IndexOperation<'a> {
 			self.opt.ns(),
 			self.opt.db(),
 			&self.ix.what,
-			&self.ix.name,
+			self.ix.storage(),
 			v,
 			Some(&self.rid.id),
 		)
@@ -266,7 +317,10 @@ impl<'a> IndexOperation<'a> {
 				match run.delc(key, Some(self.rid)).await {
 					Err(Error::TxConditionNotMet) => Ok(()),
 					Err(e) => Err(e),
-					Ok(v) => Ok(v),
+					Ok(v) => {
+						self.delta -= 1;
+						Ok(v)
+					}
 				}?
 			}
 		}
@@ -282,6 +336,7 @@ impl<'a> IndexOperation<'a> {
 						let rid: Thing = val.into();
 						return self.err_index_exists(rid, n);
 					}
+					self.delta += 1;
 				}
 			}
 		}
@@ -297,7 +352,10 @@ impl<'a> IndexOperation<'a> {
 				match run.delc(key, Some(self.rid)).await {
 					Err(Error::TxConditionNotMet) => Ok(()),
 					Err(e) => Err(e),
-					Ok(v) => Ok(v),
+					Ok(v) => {
+						self.delta -= 1;
+						Ok(v)
+					}
 				}?
 			}
 		}
@@ -312,6 +370,7 @@ impl<'a> IndexOperation<'a> {
 					let rid: Thing = val.into();
 					return self.err_index_exists(rid, n);
 				}
+				self.delta += 1;
 			}
 		}
 		Ok(())
@@ -361,4 +420,10 @@ impl<'a> IndexOperation<'a> {
 		}
 		mt.finish(run).await
 	}
+
+	async fn index_spatial(&mut self, run: &mut kvs::Transaction) -> Result<(), Error> {
+		let ikb = IndexKeyBase::new(self.opt, self.ix);
+		let si = SpatialIndex::new(ikb);
+		si.index_document(run, self.rid, self.o.take(), self.n.take()).await
+	}
 }
@@ -49,6 +49,10 @@ impl<'a> Document<'a> {
 					true => &self.initial,
 					false => &self.current,
 				};
+				// Archived LIVE queries belong to a dead node
+				if lv.archived.is_some() {
+					continue;
+				}
 				// Ensure that a session exists on the LIVE query
 				let sess = match lv.session.as_ref() {
 					Some(v) => v,
@@ -73,6 +77,9 @@ impl<'a> Document<'a> {
 				// This ensures that we are using the auth data
 				// of the user who created the LIVE query.
 				let lqopt = opt.new_with_perms(true).with_auth(Arc::from(auth));
+				// The custom roles of the user are resolved when the
+				// notification is sent, as they may have changed since.
+				let lqopt = lqopt.with_custom_roles(txn).await?;
 				// Add $before, $after, $value, and $event params
 				// to this LIVE query so that user can use these
 				// within field projections and WHERE clauses.
@@ -98,46 +105,40 @@ impl<'a> Document<'a> {
 					Ok(_) => (),
 				}
 				// Finally, let's check what type of statement
-				// caused this LIVE query to run, and send the
+				// caused this LIVE query to run, and create the
 				// relevant notification based on the statement.
-				if stm.is_delete() {
-					// Send a DELETE notification
-					if opt.id()? == lv.node.0 {
-						let thing = (*rid).clone();
-						chn.send(Notification {
-							id: lv.id.clone(),
-							action: Action::Delete,
-							result: Value::Thing(thing),
-						})
-						.await?;
-					} else {
-						// TODO: Send to storage
+				let notification = if stm.is_delete() {
+					// Create a DELETE notification
+					let thing = (*rid).clone();
+					Notification {
+						id: lv.id.clone(),
+						action: Action::Delete,
+						result: Value::Thing(thing),
 					}
 				} else if self.is_new() {
-					// Send a CREATE notification
-					if opt.id()? == lv.node.0 {
-						chn.send(Notification {
-							id: lv.id.clone(),
-							action: Action::Create,
-							result: self.pluck(&lqctx, &lqopt, txn, &lq).await?,
-						})
-						.await?;
-					} else {
-						// TODO: Send to storage
+					// Create a CREATE notification
+					Notification {
+						id: lv.id.clone(),
+						action: Action::Create,
+						result: self.pluck(&lqctx, &lqopt, txn, &lq).await?,
 					}
 				} else {
-					// Send a UPDATE notification
-					if opt.id()? == lv.node.0 {
-						chn.send(Notification {
-							id: lv.id.clone(),
-							action: Action::Update,
-							result: self.pluck(&lqctx, &lqopt, txn, &lq).await?,
-						})
-						.await?;
-					} else {
-						// TODO: Send to storage
+					// Create a UPDATE notification
+					Notification {
+						id: lv.id.clone(),
+						action: Action::Update,
+						result: self.pluck(&lqctx, &lqopt, txn, &lq).await?,
 					}
 				};
+				if opt.id()? == lv.node.0 {
+					// Send the notification to the subscriber
+					chn.send(notification).await?;
+				} else {
+					// Queue the notification for the node which owns
+					// the LIVE query, within the same transaction, so
+					// that it is only delivered if the change is committed
+					txn.lock().await.set_ndnt(lv.node.0, notification).await?;
+				}
 			}
 		}
 		// Carry on
@@ -173,7 +174,7 @@ impl<'a> Document<'a> {
 		doc: &CursorDoc<'_>,
 	) -> Result<(), Error> {
 		// Should we run permissions checks?
-		if opt.check_perms(stm.into()) {
+		if opt.check_table_perms(stm.into(), &self.id.unwrap().tb) {
 			// Get the table
 			let tb = self.tb(opt, txn).await?;
 			// Process the table permissions
@@ -1,6 +1,7 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
 use crate::doc::Document;
 use crate::err::Error;
 use crate::iam::Action;
@@ -47,10 +48,21 @@ impl<'a> Document<'a> {
 				}
 			},
 			None => match stm {
-				Statement::Live(s) => match s.expr.len() {
-					0 => Ok(self.initial.doc.diff(&self.current.doc, Idiom::default()).into()),
-					_ => s.expr.compute(ctx, opt, txn, Some(&self.current), false).await,
-				},
+				Statement::Live(s) if s.is_diff() => {
+					// Get the documents before and after the changes
+					let mut before =
+						self.initial.doc.compute(ctx, opt, txn, Some(&self.initial)).await?;
+					let mut after =
+						self.current.doc.compute(ctx, opt, txn, Some(&self.current)).await?;
+					// Remove any fields which can not be viewed
+					self.pluck_view(ctx, opt, txn, stm, &self.initial, &mut before).await?;
+					self.pluck_view(ctx, opt, txn, stm, &self.current, &mut after).await?;
+					// Output a JSON Patch of any changes applied to the document
+					return Ok(before.json_patch(&after, Idiom::default()).into());
+				}
+				Statement::Live(s) => {
+					s.expr.compute(ctx, opt, txn, Some(&self.current), false).await
+				}
 				Statement::Select(s) => {
 					s.expr.compute(ctx, opt, txn, Some(&self.current), s.group.is_some()).await
 				}
@@ -69,10 +81,26 @@ impl<'a> Document<'a> {
 				_ => Err(Error::Ignore),
 			},
 		}?;
+		// Remove any fields which can not be viewed
+		self.pluck_view(ctx, opt, txn, stm, &self.current, &mut out).await?;
+		// Output result
+		Ok(out)
+	}
+	/// Remove any fields, from the output of a document,
+	/// which are omitted, or which can not be viewed
+	async fn pluck_view(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		doc: &CursorDoc<'_>,
+		out: &mut Value,
+	) -> Result<(), Error> {
 		// Check if this record exists
-		if self.id.is_some() {
+		if let Some(rid) = self.id {
 			// Should we run permissions checks?
-			if opt.check_perms(Action::View) {
+			if opt.check_table_perms(Action::View, &rid.tb) {
 				// Loop through all field statements
 				for fd in self.fd(opt, txn).await?.iter() {
 					// Loop over each field in document
@@ -85,16 +113,12 @@ impl<'a> Document<'a> {
 								// Disable permissions
 								let opt = &opt.new_with_perms(false);
 								// Get the current value
-								let val = self.current.doc.pick(k);
+								let val = doc.doc.pick(k);
 								// Configure the context
 								let mut ctx = Context::new(ctx);
 								ctx.add_value("value", &val);
 								// Process the PERMISSION clause
-								if !e
-									.compute(&ctx, opt, txn, Some(&self.current))
-									.await?
-									.is_truthy()
-								{
+								if !e.compute(&ctx, opt, txn, Some(doc)).await?.is_truthy() {
 									out.del(&ctx, opt, txn, k).await?
 								}
 							}
@@ -111,7 +135,7 @@ impl<'a> Document<'a> {
 		}
 		// Remove metadata fields on output
 		out.del(ctx, opt, txn, &*META).await?;
-		// Output result
-		Ok(out)
+		// Carry on
+		Ok(())
 	}
 }
@@ -0,0 +1,415 @@
+//! The progress of the foreign tables which are rebuilt with `REBUILD TABLE`.
+//!
+//! The records of the view are rebuilt into a separate table, one batch of records
+//! of the foreign tables per transaction. The writes to the records which have been
+//! processed in the meantime keep this table up to date, alongside the view itself.
+//!
+//! Once all the records have been processed, the records of the view are replaced by
+//! the rebuilt records, one batch per transaction, so that each record of the view is
+//! switched at once. The view is then switched back to being maintained on its own,
+//! and the rebuilt table is removed in the background, batch by batch.
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction, Workable};
+use crate::doc::Document;
+use crate::err::Error;
+use crate::key::thing;
+use crate::kvs;
+use crate::kvs::Key;
+use crate::sql::data::Data;
+use crate::sql::paths::ID;
+use crate::sql::statements::delete::DeleteStatement;
+use crate::sql::statements::update::UpdateStatement;
+use crate::sql::statements::DefineTableStatement;
+use crate::sql::thing::Thing;
+use crate::sql::value::{Value, Values};
+use crate::sql::view::View;
+use derive::Store;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The number of records processed in each transaction
+const BUILD_BATCH_SIZE: u32 = 1000;
+
+/// The number of batches processed on each tick of the datastore
+pub(crate) const REBUILD_BATCHES_PER_TICK: u32 = 10;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(crate) enum Stage {
+	/// The records of the foreign tables are aggregated into the rebuilt table
+	#[default]
+	Build,
+	/// The records of the view are replaced by the rebuilt records
+	Promote,
+	/// The records of the view which were not rebuilt are removed
+	Prune,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+#[revisioned(revision = 1)]
+pub(crate) struct ViewBuilding {
+	/// The table into which the view is rebuilt
+	pub(crate) shadow: Option<String>,
+	/// The stage of the rebuild
+	pub(crate) stage: Stage,
+	/// The position, in the tables of the view, of the table which is processed
+	pub(crate) source: u32,
+	/// The key of the last record which has been processed
+	pub(crate) cursor: Option<Key>,
+	/// The number of records which have been processed
+	pub(crate) count: u64,
+	/// The error which stopped the rebuild, if any
+	pub(crate) error: Option<String>,
+	/// The tables of the previous rebuilds, which remain to be removed
+	pub(crate) purge: Vec<String>,
+}
+
+impl ViewBuilding {
+	/// Retrieves the progress of a view, if it is still being rebuilt
+	pub(crate) async fn get(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Option<Self>, Error> {
+		let key = crate::key::root::vb::new(ns, db, tb);
+		Ok(tx.get(key).await?.map(Self::from))
+	}
+
+	/// Persists the progress of a view
+	async fn set(
+		&self,
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<(), Error> {
+		let key = crate::key::root::vb::new(ns, db, tb);
+		tx.set(key, self).await
+	}
+
+	/// Stops the rebuild of a view, recording the error which prevents it from being rebuilt
+	pub(crate) async fn fail(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		error: &Error,
+	) -> Result<(), Error> {
+		if let Some(mut state) = Self::get(tx, ns, db, tb).await? {
+			state.error = Some(error.to_string());
+			state.set(tx, ns, db, tb).await?;
+		}
+		Ok(())
+	}
+
+	/// Lists the keys of the views which are being rebuilt, leaving out the rebuilds which failed
+	pub(crate) async fn pending(tx: &mut kvs::Transaction) -> Result<Vec<Key>, Error> {
+		let beg = crate::key::root::vb::prefix();
+		let end = crate::key::root::vb::suffix();
+		let mut res = vec![];
+		for (k, v) in tx.scan(beg..end, u32::MAX).await? {
+			if Self::from(v).error.is_none() {
+				res.push(k);
+			}
+		}
+		Ok(res)
+	}
+
+	/// Checks if the record with the given key has been aggregated into the rebuilt table
+	pub(crate) fn covers(&self, view: &View, key: &[u8]) -> bool {
+		if self.stage != Stage::Build {
+			return true;
+		}
+		let Ok(rid) = thing::Thing::decode(key) else {
+			return false;
+		};
+		match view.what.0.iter().position(|v| v.0 == rid.tb) {
+			Some(pos) if (pos as u32) < self.source => true,
+			Some(pos) if pos as u32 == self.source => match &self.cursor {
+				Some(cursor) => key <= cursor.as_slice(),
+				None => false,
+			},
+			_ => false,
+		}
+	}
+
+	/// Starts rebuilding a view in the background.
+	///
+	/// A rebuild which is already in progress is started again, into a new table.
+	pub(crate) async fn rebuild(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &DefineTableStatement,
+	) -> Result<(), Error> {
+		let mut purge = vec![];
+		if let Some(state) = Self::get(tx, ns, db, &tb.name).await? {
+			purge.extend(state.purge);
+			purge.extend(state.shadow);
+		}
+		if let Some(rebuild) = &tb.rebuild {
+			if !purge.contains(&rebuild.0) {
+				purge.push(rebuild.to_raw());
+			}
+		}
+		// Define the rebuilt table, so that its records can be written in strict mode
+		let shadow = format!("{}#{}", tb.name.as_str(), Uuid::new_v4().simple());
+		let key = crate::key::database::tb::new(ns, db, &shadow);
+		let def = DefineTableStatement {
+			name: shadow.clone().into(),
+			..Default::default()
+		};
+		tx.set(key, &def).await?;
+		// Keep the rebuilt table up to date with the writes to the foreign tables
+		let def = DefineTableStatement {
+			rebuild: Some(shadow.clone().into()),
+			..tb.clone()
+		};
+		Self::define(tx, ns, db, &def).await?;
+		// Process the records of the foreign tables from the first record
+		let state = Self {
+			shadow: Some(shadow),
+			purge,
+			..Self::default()
+		};
+		state.set(tx, ns, db, &tb.name).await
+	}
+
+	/// Removes the tables into which a view is rebuilt, and stops its rebuild
+	pub(crate) async fn remove(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<(), Error> {
+		if let Some(state) = Self::get(tx, ns, db, tb).await? {
+			for shadow in state.shadow.iter().chain(state.purge.iter()) {
+				let key = crate::key::database::tb::new(ns, db, shadow);
+				tx.del(key).await?;
+				let key = crate::key::table::all::new(ns, db, shadow);
+				tx.delp(key, u32::MAX).await?;
+			}
+		}
+		let key = crate::key::root::vb::new(ns, db, tb);
+		tx.del(key).await
+	}
+
+	/// Stores the definition of a view, along with its copies on each of the foreign tables
+	async fn define(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &DefineTableStatement,
+	) -> Result<(), Error> {
+		let key = crate::key::database::tb::new(ns, db, &tb.name);
+		tx.set(key, tb).await?;
+		if let Some(view) = &tb.view {
+			for v in view.what.0.iter() {
+				let key = crate::key::table::ft::new(ns, db, v, &tb.name);
+				tx.set(key, tb).await?;
+				// Clear the cache
+				let key = crate::key::table::ft::prefix(ns, db, v);
+				tx.clr(key).await?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Processes the next batch of records of the view, returning true once the view is rebuilt.
+	///
+	/// Every record of the foreign tables which is aggregated is written again, so that a
+	/// concurrent update of one of these records conflicts with the batch, instead of being
+	/// aggregated twice, or not at all. The batch is then retried by the next build.
+	pub(crate) async fn next_batch(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		tb: &str,
+	) -> Result<bool, Error> {
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Check that the view is still being rebuilt
+		let mut state = match Self::get(&mut run, opt.ns(), opt.db(), tb).await? {
+			Some(state) if state.error.is_none() => state,
+			_ => return Ok(true),
+		};
+		// Remove a batch of the records of a table which a rebuild replaced
+		if let Some(shadow) = state.purge.first() {
+			let beg = thing::prefix(opt.ns(), opt.db(), shadow);
+			let end = thing::suffix(opt.ns(), opt.db(), shadow);
+			run.delr(beg.clone()..end.clone(), BUILD_BATCH_SIZE).await?;
+			if run.scan(beg..end, 1).await?.is_empty() {
+				let key = crate::key::database::tb::new(opt.ns(), opt.db(), shadow);
+				run.del(key).await?;
+				let key = crate::key::table::all::new(opt.ns(), opt.db(), shadow);
+				run.delp(key, u32::MAX).await?;
+				state.purge.remove(0);
+			}
+		}
+		if !state.purge.is_empty() {
+			state.set(&mut run, opt.ns(), opt.db(), tb).await?;
+			return Ok(false);
+		}
+		// The view is done once there is nothing left to rebuild
+		let Some(shadow) = state.shadow.clone() else {
+			let key = crate::key::root::vb::new(opt.ns(), opt.db(), tb);
+			run.del(key).await?;
+			return Ok(true);
+		};
+		// Check that the view is still rebuilt into this table
+		let ft = run.get_tb(opt.ns(), opt.db(), tb).await?;
+		let view = match &ft.view {
+			Some(view) if ft.rebuild.as_deref() == Some(&shadow) => view.clone(),
+			_ => {
+				state.purge.push(shadow);
+				state.shadow = None;
+				state.set(&mut run, opt.ns(), opt.db(), tb).await?;
+				return Ok(false);
+			}
+		};
+		match state.stage {
+			Stage::Build => {
+				// Move on once all the foreign tables have been processed
+				let Some(src) = view.what.0.get(state.source as usize) else {
+					state.stage = Stage::Promote;
+					state.cursor = None;
+					state.set(&mut run, opt.ns(), opt.db(), tb).await?;
+					return Ok(false);
+				};
+				// Get the next batch of records
+				let beg = match state.cursor.take() {
+					Some(mut key) => {
+						key.push(0x00);
+						key
+					}
+					None => thing::prefix(opt.ns(), opt.db(), src),
+				};
+				let end = thing::suffix(opt.ns(), opt.db(), src);
+				let res = run.scan(beg..end, BUILD_BATCH_SIZE).await?;
+				// Release the transaction
+				drop(run);
+				// Force queries to run
+				let opt = &opt.new_with_force(true);
+				// Don't process field queries
+				let opt = &opt.new_with_fields(false);
+				// Don't process event queries
+				let opt = &opt.new_with_events(false);
+				// Don't process index queries
+				let opt = &opt.new_with_indexes(false);
+				// Aggregate the records into the rebuilt table
+				for (k, v) in res.iter() {
+					let key: thing::Thing = k.into();
+					let val: Value = v.into();
+					let rid = Thing::from((key.tb, key.id));
+					txn.lock().await.set(k.clone(), v.clone()).await?;
+					let doc = Document::new(None, Some(&rid), None, &val, Workable::Normal);
+					doc.build_view(ctx, opt, txn, &ft, &shadow, &state).await?;
+				}
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Store the progress
+				state.count += res.len() as u64;
+				match res.last() {
+					Some((k, _)) if res.len() == BUILD_BATCH_SIZE as usize => {
+						state.cursor = Some(k.clone());
+					}
+					_ => state.source += 1,
+				}
+				state.set(&mut run, opt.ns(), opt.db(), tb).await?;
+				Ok(false)
+			}
+			Stage::Promote => {
+				// Get the next batch of rebuilt records
+				let beg = match state.cursor.take() {
+					Some(mut key) => {
+						key.push(0x00);
+						key
+					}
+					None => thing::prefix(opt.ns(), opt.db(), &shadow),
+				};
+				let end = thing::suffix(opt.ns(), opt.db(), &shadow);
+				let res = run.scan(beg..end, BUILD_BATCH_SIZE).await?;
+				// Release the transaction
+				drop(run);
+				// Don't process field queries
+				let opt = &opt.new_with_fields(false);
+				// Don't process event queries
+				let opt = &opt.new_with_events(false);
+				// Replace the records of the view
+				for (k, v) in res.iter() {
+					let key: thing::Thing = k.into();
+					let mut val: Value = v.into();
+					val.cut(ID.as_ref());
+					let stm = UpdateStatement {
+						what: Values(vec![Value::from(Thing::from((tb.to_owned(), key.id)))]),
+						data: Some(Data::ReplaceExpression(val)),
+						..UpdateStatement::default()
+					};
+					stm.compute(ctx, opt, txn, None).await?;
+				}
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Store the progress
+				match res.last() {
+					Some((k, _)) if res.len() == BUILD_BATCH_SIZE as usize => {
+						state.cursor = Some(k.clone());
+					}
+					_ => state.stage = Stage::Prune,
+				}
+				state.set(&mut run, opt.ns(), opt.db(), tb).await?;
+				Ok(false)
+			}
+			Stage::Prune => {
+				// Get the next batch of records of the view
+				let beg = match state.cursor.take() {
+					Some(mut key) => {
+						key.push(0x00);
+						key
+					}
+					None => thing::prefix(opt.ns(), opt.db(), tb),
+				};
+				let end = thing::suffix(opt.ns(), opt.db(), tb);
+				let res = run.scan(beg..end, BUILD_BATCH_SIZE).await?;
+				// Find the records which were not rebuilt
+				let mut stale = vec![];
+				for (k, _) in res.iter() {
+					let key: thing::Thing = k.into();
+					if !run.exi(thing::new(opt.ns(), opt.db(), &shadow, &key.id)).await? {
+						stale.push(Value::from(Thing::from((tb.to_owned(), key.id))));
+					}
+				}
+				// Release the transaction
+				drop(run);
+				// Remove the records of the view which were not rebuilt
+				if !stale.is_empty() {
+					let stm = DeleteStatement {
+						what: Values(stale),
+						..DeleteStatement::default()
+					};
+					stm.compute(ctx, opt, txn, None).await?;
+				}
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Store the progress
+				if res.len() == BUILD_BATCH_SIZE as usize {
+					state.cursor = res.last().map(|(k, _)| k.clone());
+					state.set(&mut run, opt.ns(), opt.db(), tb).await?;
+					return Ok(false);
+				}
+				// Switch the view back to being maintained on its own
+				let def = DefineTableStatement {
+					rebuild: None,
+					..ft
+				};
+				Self::define(&mut run, opt.ns(), opt.db(), &def).await?;
+				// Remove the rebuilt table afterwards
+				state.purge.push(shadow);
+				state.shadow = None;
+				state.set(&mut run, opt.ns(), opt.db(), tb).await?;
+				Ok(false)
+			}
+		}
+	}
+}
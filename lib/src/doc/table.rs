@@ -1,11 +1,15 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
-use crate::doc::Document;
+use crate::doc::rebuild::ViewBuilding;
+use crate::doc::{CursorDoc, Document};
 use crate::err::Error;
+use crate::fnc::util::hll::HyperLogLog;
+use crate::key::thing;
 use crate::sql::data::Data;
 use crate::sql::expression::Expression;
 use crate::sql::field::{Field, Fields};
+use crate::sql::id::Id;
 use crate::sql::idiom::Idiom;
 use crate::sql::number::Number;
 use crate::sql::operator::Operator;
@@ -13,15 +17,67 @@ use crate::sql::part::Part;
 use crate::sql::paths::ID;
 use crate::sql::statement::Statement as Query;
 use crate::sql::statements::delete::DeleteStatement;
-use crate::sql::statements::ifelse::IfelseStatement;
 use crate::sql::statements::update::UpdateStatement;
+use crate::sql::statements::DefineTableStatement;
 use crate::sql::subquery::Subquery;
 use crate::sql::thing::Thing;
 use crate::sql::value::{Value, Values};
+use crate::sql::view::View;
 use futures::future::try_join_all;
+use std::cmp::Ordering;
 
 type Ops = Vec<(Idiom, Operator, Value)>;
 
+/// The number of records read at once when the records of a group are scanned
+const RESCAN_BATCH_SIZE: u32 = 1000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Extreme {
+	Min,
+	Max,
+}
+
+impl Extreme {
+	/// Compares two values, a value being greater when it is closer to the extreme
+	fn cmp(&self, a: &Value, b: &Value) -> Ordering {
+		match self {
+			Extreme::Min => b.cmp(a),
+			Extreme::Max => a.cmp(b),
+		}
+	}
+}
+
+/// The table into which the records are aggregated
+struct Target<'a> {
+	/// The name of the table
+	name: String,
+	/// The definition of the view
+	view: &'a View,
+	/// The progress of the rebuild, when the records are aggregated into a rebuilt table
+	build: Option<&'a ViewBuilding>,
+}
+
+impl<'a> Target<'a> {
+	fn new(ft: &'a DefineTableStatement) -> Self {
+		Self {
+			name: ft.name.to_raw(),
+			view: ft.view.as_ref().unwrap(),
+			build: None,
+		}
+	}
+	fn rebuild(ft: &'a DefineTableStatement, shadow: &str, state: &'a ViewBuilding) -> Self {
+		Self {
+			name: shadow.to_owned(),
+			view: ft.view.as_ref().unwrap(),
+			build: Some(state),
+		}
+	}
+	/// Checks if the record with the given key is aggregated into the table
+	fn covers(&self, key: &[u8]) -> bool {
+		self.build.map_or(true, |build| build.covers(self.view, key))
+	}
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum Action {
 	Create,
@@ -47,8 +103,6 @@ impl<'a> Document<'a> {
 		}
 		// Don't run permissions
 		let opt = &opt.new_with_perms(false);
-		// Get the record id
-		let rid = self.id.as_ref().unwrap();
 		// Get the query action
 		let act = if stm.is_delete() {
 			Action::Delete
@@ -59,176 +113,182 @@ impl<'a> Document<'a> {
 		};
 		// Loop through all foreign table statements
 		for ft in self.ft(opt, txn).await?.iter() {
-			// Get the table definition
-			let tb = ft.view.as_ref().unwrap();
-			// Check if there is a GROUP BY clause
-			match &tb.group {
-				// There is a GROUP BY clause specified
-				Some(group) => {
-					// Set the previous record id
-					let old = Thing {
-						tb: ft.name.to_raw(),
-						id: try_join_all(
-							group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.initial))),
-						)
-						.await?
-						.into_iter()
-						.collect::<Vec<_>>()
-						.into(),
+			self.view(ctx, opt, txn, &act, &Target::new(ft)).await?;
+			// Keep the table into which the view is rebuilt up to date
+			if let Some(shadow) = &ft.rebuild {
+				let state = {
+					let mut run = txn.lock().await;
+					ViewBuilding::get(&mut run, opt.ns(), opt.db(), &ft.name).await?
+				};
+				if let Some(state) = state.filter(|v| v.shadow.as_deref() == Some(shadow.as_str()))
+				{
+					let tgt = Target::rebuild(ft, shadow, &state);
+					let rid = self.id.as_ref().unwrap();
+					let key = thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id).encode()?;
+					// Only the records which have been processed by the rebuild are aggregated
+					if tgt.covers(&key) {
+						self.view(ctx, opt, txn, &act, &tgt).await?;
+					}
+				}
+			}
+		}
+		// Carry on
+		Ok(())
+	}
+	/// Processes the record into the table into which a foreign table is rebuilt
+	pub(crate) async fn build_view(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		ft: &DefineTableStatement,
+		shadow: &str,
+		state: &ViewBuilding,
+	) -> Result<(), Error> {
+		// Don't run permissions
+		let opt = &opt.new_with_perms(false);
+		// Process the record as if it was new
+		self.view(ctx, opt, txn, &Action::Create, &Target::rebuild(ft, shadow, state)).await
+	}
+	//
+	async fn view(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		tgt: &Target<'_>,
+	) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Get the table definition
+		let tb = tgt.view;
+		// Check if there is a GROUP BY clause
+		match &tb.group {
+			// There is a GROUP BY clause specified
+			Some(group) => {
+				// Set the previous record id
+				let old = Thing {
+					tb: tgt.name.clone(),
+					id: try_join_all(
+						group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.initial))),
+					)
+					.await?
+					.into_iter()
+					.collect::<Vec<_>>()
+					.into(),
+				};
+				// Set the current record id
+				let rid = Thing {
+					tb: tgt.name.clone(),
+					id: try_join_all(
+						group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.current))),
+					)
+					.await?
+					.into_iter()
+					.collect::<Vec<_>>()
+					.into(),
+				};
+				// Check if the previous record was aggregated in the view
+				let retract = match &tb.cond {
+					_ if opt.force || act == &Action::Create => false,
+					Some(cond) => {
+						cond.compute(ctx, opt, txn, Some(&self.initial)).await?.is_truthy()
+					}
+					None => true,
+				};
+				// Check if the current record is aggregated in the view
+				let aggregate = match &tb.cond {
+					_ if act == &Action::Delete => false,
+					Some(cond) => {
+						cond.compute(ctx, opt, txn, Some(&self.current)).await?.is_truthy()
+					}
+					None => true,
+				};
+				if retract {
+					// Delete the old value
+					let act = Action::Delete;
+					// Modify the value in the table
+					let stm = UpdateStatement {
+						what: Values(vec![Value::from(old.clone())]),
+						data: Some(self.data(ctx, opt, txn, act, &old, tgt).await?),
+						..UpdateStatement::default()
 					};
-					// Set the current record id
-					let rid = Thing {
-						tb: ft.name.to_raw(),
-						id: try_join_all(
-							group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.current))),
-						)
-						.await?
-						.into_iter()
-						.collect::<Vec<_>>()
-						.into(),
+					// Execute the statement
+					stm.compute(ctx, opt, txn, None).await?;
+				}
+				if aggregate {
+					// Update the new value
+					let act = Action::Update;
+					// Modify the value in the table
+					let stm = UpdateStatement {
+						what: Values(vec![Value::from(rid.clone())]),
+						data: Some(self.data(ctx, opt, txn, act, &rid, tgt).await?),
+						..UpdateStatement::default()
 					};
-					// Check if a WHERE clause is specified
-					match &tb.cond {
-						// There is a WHERE clause specified
-						Some(cond) => {
-							match cond.compute(ctx, opt, txn, Some(&self.current)).await? {
-								v if v.is_truthy() => {
-									if !opt.force && act != Action::Create {
-										// Delete the old value
-										let act = Action::Delete;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(old)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-									if act != Action::Delete {
-										// Update the new value
-										let act = Action::Update;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(rid)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-								}
-								_ => {
-									if !opt.force && act != Action::Create {
-										// Update the new value
-										let act = Action::Update;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(old)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-								}
-							}
-						}
-						// No WHERE clause is specified
-						None => {
-							if !opt.force && act != Action::Create {
-								// Delete the old value
-								let act = Action::Delete;
-								// Modify the value in the table
-								let stm = UpdateStatement {
-									what: Values(vec![Value::from(old)]),
-									data: Some(self.data(ctx, opt, txn, act, &tb.expr).await?),
-									..UpdateStatement::default()
+					// Execute the statement
+					stm.compute(ctx, opt, txn, None).await?;
+				}
+			}
+			// No GROUP BY clause is specified
+			None => {
+				// Set the current record id
+				let rid = Thing {
+					tb: tgt.name.clone(),
+					id: rid.id.clone(),
+				};
+				// Check if a WHERE clause is specified
+				match &tb.cond {
+					// There is a WHERE clause specified
+					Some(cond) => {
+						match cond.compute(ctx, opt, txn, Some(&self.current)).await? {
+							v if v.is_truthy() => {
+								// Define the statement
+								let stm = match act {
+									// Delete the value in the table
+									Action::Delete => Query::Delete(DeleteStatement {
+										what: Values(vec![Value::from(rid)]),
+										..DeleteStatement::default()
+									}),
+									// Update the value in the table
+									_ => Query::Update(UpdateStatement {
+										what: Values(vec![Value::from(rid)]),
+										data: Some(self.full(ctx, opt, txn, &tb.expr).await?),
+										..UpdateStatement::default()
+									}),
 								};
 								// Execute the statement
 								stm.compute(ctx, opt, txn, None).await?;
 							}
-							if act != Action::Delete {
-								// Update the new value
-								let act = Action::Update;
-								// Modify the value in the table
-								let stm = UpdateStatement {
+							_ => {
+								// Delete the value in the table
+								let stm = DeleteStatement {
 									what: Values(vec![Value::from(rid)]),
-									data: Some(self.data(ctx, opt, txn, act, &tb.expr).await?),
-									..UpdateStatement::default()
+									..DeleteStatement::default()
 								};
 								// Execute the statement
 								stm.compute(ctx, opt, txn, None).await?;
 							}
 						}
 					}
-				}
-				// No GROUP BY clause is specified
-				None => {
-					// Set the current record id
-					let rid = Thing {
-						tb: ft.name.to_raw(),
-						id: rid.id.clone(),
-					};
-					// Check if a WHERE clause is specified
-					match &tb.cond {
-						// There is a WHERE clause specified
-						Some(cond) => {
-							match cond.compute(ctx, opt, txn, Some(&self.current)).await? {
-								v if v.is_truthy() => {
-									// Define the statement
-									let stm = match act {
-										// Delete the value in the table
-										Action::Delete => Query::Delete(DeleteStatement {
-											what: Values(vec![Value::from(rid)]),
-											..DeleteStatement::default()
-										}),
-										// Update the value in the table
-										_ => Query::Update(UpdateStatement {
-											what: Values(vec![Value::from(rid)]),
-											data: Some(self.full(ctx, opt, txn, &tb.expr).await?),
-											..UpdateStatement::default()
-										}),
-									};
-									// Execute the statement
-									stm.compute(ctx, opt, txn, None).await?;
-								}
-								_ => {
-									// Delete the value in the table
-									let stm = DeleteStatement {
-										what: Values(vec![Value::from(rid)]),
-										..DeleteStatement::default()
-									};
-									// Execute the statement
-									stm.compute(ctx, opt, txn, None).await?;
-								}
-							}
-						}
-						// No WHERE clause is specified
-						None => {
-							// Define the statement
-							let stm = match act {
-								// Delete the value in the table
-								Action::Delete => Query::Delete(DeleteStatement {
-									what: Values(vec![Value::from(rid)]),
-									..DeleteStatement::default()
-								}),
-								// Update the value in the table
-								_ => Query::Update(UpdateStatement {
-									what: Values(vec![Value::from(rid)]),
-									data: Some(self.full(ctx, opt, txn, &tb.expr).await?),
-									..UpdateStatement::default()
-								}),
-							};
-							// Execute the statement
-							stm.compute(ctx, opt, txn, None).await?;
-						}
+					// No WHERE clause is specified
+					None => {
+						// Define the statement
+						let stm = match act {
+							// Delete the value in the table
+							Action::Delete => Query::Delete(DeleteStatement {
+								what: Values(vec![Value::from(rid)]),
+								..DeleteStatement::default()
+							}),
+							// Update the value in the table
+							_ => Query::Update(UpdateStatement {
+								what: Values(vec![Value::from(rid)]),
+								data: Some(self.full(ctx, opt, txn, &tb.expr).await?),
+								..UpdateStatement::default()
+							}),
+						};
+						// Execute the statement
+						stm.compute(ctx, opt, txn, None).await?;
 					}
 				}
 			}
@@ -255,7 +315,8 @@ impl<'a> Document<'a> {
 		opt: &Options,
 		txn: &Transaction,
 		act: Action,
-		exp: &Fields,
+		rid: &Thing,
+		tgt: &Target<'_>,
 	) -> Result<Data, Error> {
 		//
 		let mut ops: Ops = vec![];
@@ -265,8 +326,16 @@ impl<'a> Document<'a> {
 			Action::Update => Some(&self.current),
 			_ => unreachable!(),
 		};
+		// Fetch the aggregated record, which holds the auxiliary state
+		let cur: Value = {
+			let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+			match txn.lock().await.get(key).await? {
+				Some(v) => (&v).into(),
+				None => Value::None,
+			}
+		};
 		//
-		for field in exp.other() {
+		for field in tgt.view.expr.other() {
 			// Process the field
 			if let Field::Single {
 				expr,
@@ -292,17 +361,47 @@ impl<'a> Document<'a> {
 						}
 						Some("math::min") | Some("time::min") => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
-							self.min(&mut ops, &act, idiom, val);
+							let ext = Extreme::Min;
+							let arg = &f.args()[0];
+							self.extreme(
+								ctx, opt, txn, &mut ops, &cur, &act, idiom, val, rid, tgt, arg, ext,
+							)
+							.await?;
 						}
 						Some("math::max") | Some("time::max") => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
-							self.max(&mut ops, &act, idiom, val);
+							let ext = Extreme::Max;
+							let arg = &f.args()[0];
+							self.extreme(
+								ctx, opt, txn, &mut ops, &cur, &act, idiom, val, rid, tgt, arg, ext,
+							)
+							.await?;
 						}
 						Some("math::mean") => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
 							self.mean(&mut ops, &act, idiom, val);
 						}
-						_ => unreachable!(),
+						Some("math::stddev") => {
+							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
+							self.variance(&mut ops, &cur, &act, idiom, val, true);
+						}
+						Some("math::variance") => {
+							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
+							self.variance(&mut ops, &cur, &act, idiom, val, false);
+						}
+						Some("array::distinct") => {
+							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
+							self.distinct(&mut ops, &cur, &act, idiom, val);
+						}
+						Some("array::approx_distinct") => {
+							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
+							self.approx_distinct(&mut ops, &cur, &act, idiom, val);
+						}
+						_ => {
+							return Err(Error::InvalidViewAggregate {
+								name: f.to_string(),
+							})
+						}
 					},
 					_ => {
 						let val = expr.compute(ctx, opt, txn, doc).await?;
@@ -314,6 +413,58 @@ impl<'a> Document<'a> {
 		//
 		Ok(Data::SetExpression(ops))
 	}
+	/// Get the path of the auxiliary state of the field in the foreign table
+	fn aux(key: &Idiom, name: &str) -> Idiom {
+		let mut aux = Idiom::from(vec![Part::from("__")]);
+		aux.0.push(Part::from(key.to_hash()));
+		aux.0.push(Part::from(name));
+		aux
+	}
+	/// Add or remove the value from the sorted tally of values of the field in the foreign
+	/// table, returning the distinct values which remain.
+	///
+	/// The tally holds one entry for each distinct value of the group, so it grows with
+	/// the number of distinct values, and each write rewrites the whole tally.
+	fn tally(
+		&self,
+		ops: &mut Ops,
+		cur: &Value,
+		act: &Action,
+		key: &Idiom,
+		val: Value,
+	) -> Vec<Value> {
+		let key_v = Self::aux(key, "v");
+		// Each entry of the tally is a value and its number of occurrences
+		let mut tally = match cur.pick(&key_v) {
+			Value::Array(v) => v.0,
+			_ => vec![],
+		};
+		let pos = tally.binary_search_by(|v| v.pick(&[Part::Index(0.into())]).cmp(&val));
+		match (act, pos) {
+			(Action::Delete, Ok(i)) => match tally[i].pick(&[Part::Index(1.into())]) {
+				Value::Number(c) if c > Number::Int(1) => {
+					tally[i] = Value::from(vec![val, Value::from(c - Number::Int(1))]);
+				}
+				_ => {
+					tally.remove(i);
+				}
+			},
+			(Action::Delete, Err(_)) => (),
+			(_, Ok(i)) => {
+				let c = match tally[i].pick(&[Part::Index(1.into())]) {
+					Value::Number(c) => c,
+					_ => Number::Int(0),
+				};
+				tally[i] = Value::from(vec![val, Value::from(c + Number::Int(1))]);
+			}
+			(_, Err(i)) => {
+				tally.insert(i, Value::from(vec![val, Value::from(1)]));
+			}
+		}
+		let vals = tally.iter().map(|v| v.pick(&[Part::Index(0.into())])).collect();
+		ops.push((key_v, Operator::Equal, Value::from(tally)));
+		vals
+	}
 	/// Set the field in the foreign table
 	fn set(&self, ops: &mut Ops, key: Idiom, val: Value) {
 		ops.push((key, Operator::Equal, val));
@@ -330,52 +481,209 @@ impl<'a> Document<'a> {
 			val,
 		));
 	}
-	/// Set the new minimum value for the field in the foreign table
-	fn min(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
-		if act == &Action::Update {
-			ops.push((
-				key.clone(),
-				Operator::Equal,
-				Value::Subquery(Box::new(Subquery::Ifelse(IfelseStatement {
-					exprs: vec![(
-						Value::Expression(Box::new(Expression::Binary {
-							l: Value::Idiom(key.clone()),
-							o: Operator::MoreThan,
-							r: val.clone(),
-						})),
-						val,
-					)],
-					close: Some(Value::Idiom(key)),
-				}))),
-			));
+	/// Set the new minimum, or maximum, value for the field in the foreign table.
+	///
+	/// The number of records which hold the extreme value is kept alongside it, so a
+	/// write only compares the value with the current extreme. The records of the group
+	/// are only scanned again once the last record holding the extreme is retracted.
+	#[allow(clippy::too_many_arguments)]
+	async fn extreme(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		ops: &mut Ops,
+		cur: &Value,
+		act: &Action,
+		key: Idiom,
+		val: Value,
+		rid: &Thing,
+		tgt: &Target<'_>,
+		arg: &Value,
+		ext: Extreme,
+	) -> Result<(), Error> {
+		if val.is_none_or_null() {
+			return Ok(());
+		}
+		let key_n = Self::aux(&key, "n");
+		let old = cur.pick(&key);
+		let n = match cur.pick(&key_n) {
+			Value::Number(n) => n,
+			_ => Number::Int(0),
+		};
+		let (old, n) = match (act, ext.cmp(&val, &old)) {
+			// The value replaces the extreme
+			(Action::Update, Ordering::Greater) => (val, Number::Int(1)),
+			_ if old.is_none_or_null() => match act {
+				Action::Delete => return Ok(()),
+				_ => (val, Number::Int(1)),
+			},
+			// Another record holds the extreme
+			(_, Ordering::Equal) => match act {
+				Action::Delete if n > Number::Int(1) => (old, n - Number::Int(1)),
+				Action::Delete => self.rescan(ctx, opt, txn, rid, tgt, arg, ext).await?,
+				_ => (old, n + Number::Int(1)),
+			},
+			// The extreme is unchanged
+			_ => return Ok(()),
+		};
+		ops.push((key, Operator::Equal, old));
+		ops.push((key_n, Operator::Equal, n.into()));
+		Ok(())
+	}
+	/// Scan the records of the group to find the extreme value, and the number of records
+	/// which hold it, once the last record holding the previous extreme has been retracted
+	#[allow(clippy::too_many_arguments)]
+	async fn rescan(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		rid: &Thing,
+		tgt: &Target<'_>,
+		arg: &Value,
+		ext: Extreme,
+	) -> Result<(Value, Number), Error> {
+		let view = tgt.view;
+		let mut res = (Value::None, Number::Int(0));
+		let Some(group) = &view.group else {
+			return Ok(res);
+		};
+		for tb in view.what.0.iter() {
+			let mut beg = thing::prefix(opt.ns(), opt.db(), tb);
+			let end = thing::suffix(opt.ns(), opt.db(), tb);
+			loop {
+				let batch =
+					txn.lock().await.scan(beg.clone()..end.clone(), RESCAN_BATCH_SIZE).await?;
+				for (k, v) in batch.iter() {
+					let key: thing::Thing = k.into();
+					let id = Thing::from((key.tb, key.id));
+					// The record being processed is aggregated separately
+					if self.id == Some(&id) {
+						continue;
+					}
+					// A rebuilt table only aggregates the records which have been processed
+					if !tgt.covers(k) {
+						continue;
+					}
+					let val: Value = v.into();
+					let doc = CursorDoc::new(None, Some(&id), None, &val);
+					if let Some(cond) = &view.cond {
+						if !cond.compute(ctx, opt, txn, Some(&doc)).await?.is_truthy() {
+							continue;
+						}
+					}
+					let grp: Id =
+						try_join_all(group.iter().map(|v| v.compute(ctx, opt, txn, Some(&doc))))
+							.await?
+							.into_iter()
+							.collect::<Vec<_>>()
+							.into();
+					if grp != rid.id {
+						continue;
+					}
+					let val = arg.compute(ctx, opt, txn, Some(&doc)).await?;
+					if val.is_none_or_null() {
+						continue;
+					}
+					match ext.cmp(&val, &res.0) {
+						_ if res.0.is_none() => res = (val, Number::Int(1)),
+						Ordering::Greater => res = (val, Number::Int(1)),
+						Ordering::Equal => res.1 = res.1.clone() + Number::Int(1),
+						Ordering::Less => (),
+					}
+				}
+				match batch.last() {
+					Some((k, _)) if batch.len() == RESCAN_BATCH_SIZE as usize => {
+						beg = k.clone();
+						beg.push(0x00);
+					}
+					_ => break,
+				}
+			}
+		}
+		Ok(res)
+	}
+	/// Set the new distinct values for the field in the foreign table
+	fn distinct(&self, ops: &mut Ops, cur: &Value, act: &Action, key: Idiom, val: Value) {
+		if val.is_none() {
+			return;
 		}
+		let vals = self.tally(ops, cur, act, &key, val);
+		ops.push((key, Operator::Equal, Value::from(vals)));
 	}
-	/// Set the new maximum value for the field in the foreign table
-	fn max(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
-		if act == &Action::Update {
-			ops.push((
-				key.clone(),
-				Operator::Equal,
-				Value::Subquery(Box::new(Subquery::Ifelse(IfelseStatement {
-					exprs: vec![(
-						Value::Expression(Box::new(Expression::Binary {
-							l: Value::Idiom(key.clone()),
-							o: Operator::LessThan,
-							r: val.clone(),
-						})),
-						val,
-					)],
-					close: Some(Value::Idiom(key)),
-				}))),
-			));
+	/// Set the new approximate number of distinct values for the field in the foreign table.
+	///
+	/// The sketch of the distinct values can not forget a value, so a value which
+	/// is deleted is still counted, and the estimate is then an upper bound.
+	fn approx_distinct(&self, ops: &mut Ops, cur: &Value, act: &Action, key: Idiom, val: Value) {
+		if act == &Action::Delete || val.is_none() {
+			return;
 		}
+		let key_r = Self::aux(&key, "r");
+		let mut hll = match cur.pick(&key_r) {
+			Value::Bytes(v) => HyperLogLog::from(v.into_inner()),
+			_ => HyperLogLog::default(),
+		};
+		hll.insert(&val);
+		ops.push((key, Operator::Equal, Value::from(hll.estimate())));
+		ops.push((key_r, Operator::Equal, Value::Bytes(Vec::from(hll).into())));
+	}
+	/// Set the new variance, or standard deviation, of the values of the field in the foreign table
+	fn variance(
+		&self,
+		ops: &mut Ops,
+		cur: &Value,
+		act: &Action,
+		key: Idiom,
+		val: Value,
+		stddev: bool,
+	) {
+		let Value::Number(val) = val else {
+			return;
+		};
+		// The count, sum, and sum of squares of the values
+		let (key_c, key_s, key_q) =
+			(Self::aux(&key, "c"), Self::aux(&key, "s"), Self::aux(&key, "q"));
+		let get = |key: &Idiom| match cur.pick(key) {
+			Value::Number(v) => v,
+			_ => Number::Int(0),
+		};
+		let (c, s, q) = match act {
+			Action::Delete => (
+				get(&key_c) - Number::Int(1),
+				get(&key_s) - val.clone(),
+				get(&key_q) - val.clone() * val,
+			),
+			_ => (
+				get(&key_c) + Number::Int(1),
+				get(&key_s) + val.clone(),
+				get(&key_q) + val.clone() * val,
+			),
+		};
+		// Compute the sample variance, like the math::variance function
+		let n = c.to_float();
+		let res = match n {
+			n if n < 1.0 => Value::None,
+			n if n < 2.0 => Value::from(0.0),
+			n => {
+				let var = ((q.to_float() - s.to_float().powi(2) / n) / (n - 1.0)).max(0.0);
+				Value::from(if stddev {
+					var.sqrt()
+				} else {
+					var
+				})
+			}
+		};
+		ops.push((key, Operator::Equal, res));
+		ops.push((key_c, Operator::Equal, c.into()));
+		ops.push((key_s, Operator::Equal, s.into()));
+		ops.push((key_q, Operator::Equal, q.into()));
 	}
 	/// Set the new average value for the field in the foreign table
 	fn mean(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
 		//
-		let mut key_c = Idiom::from(vec![Part::from("__")]);
-		key_c.0.push(Part::from(key.to_hash()));
-		key_c.0.push(Part::from("c"));
+		let key_c = Self::aux(&key, "c");
 		//
 		ops.push((
 			key.clone(),
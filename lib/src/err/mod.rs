@@ -93,6 +93,26 @@ pub enum Error {
 	#[error("Transaction is too large")]
 	TxTooLarge,
 
+	/// The transaction conflicted with another transaction, and can be retried
+	#[error("Failed to commit transaction due to a read or write conflict")]
+	TxConflict,
+
+	/// The encryption key does not match the key the datastore was encrypted with
+	#[error("The datastore was encrypted with a different encryption key")]
+	InvalidEncryptionKey,
+
+	/// The datastore is encrypted, but no encryption key was specified
+	#[error("The datastore is encrypted, but no encryption key was specified")]
+	MissingEncryptionKey,
+
+	/// The datastore already contains data which was stored without encryption
+	#[error("The datastore already contains unencrypted data, and must be encrypted by rotating the key offline")]
+	UnencryptedDatastore,
+
+	/// A value read from the datastore could not be decrypted
+	#[error("A value could not be decrypted with the encryption key")]
+	Decryption,
+
 	/// No namespace has been selected
 	#[error("Specify a namespace to use")]
 	NsEmpty,
@@ -300,6 +320,24 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested model does not exist
+	#[error("The model 'ml::{value}' does not exist")]
+	MlNotFound {
+		value: String,
+	},
+
+	/// The requested model does not have an uploaded model file
+	#[error("The model 'ml::{value}' does not have an uploaded model file")]
+	MfNotFound {
+		value: String,
+	},
+
+	/// The uploaded model file is invalid
+	#[error("The model file is invalid: {message}")]
+	InvalidModel {
+		message: String,
+	},
+
 	/// The requested scope does not exist
 	#[error("The scope '{value}' does not exist")]
 	ScNotFound {
@@ -381,6 +419,21 @@ pub enum Error {
 		db: String,
 	},
 
+	/// The requested namespace role does not exist
+	#[error("The role '{value}' does not exist in the namespace '{ns}'")]
+	RoleNsNotFound {
+		value: String,
+		ns: String,
+	},
+
+	/// The requested database role does not exist
+	#[error("The role '{value}' does not exist in the database '{db}'")]
+	RoleDbNotFound {
+		value: String,
+		ns: String,
+		db: String,
+	},
+
 	/// Unable to perform the realtime query
 	#[error("Unable to perform the realtime query")]
 	RealtimeDisabled,
@@ -459,12 +512,30 @@ pub enum Error {
 		name: String,
 	},
 
+	/// The permissions do not allow this query to be run on this model
+	#[error("You don't have permission to run the ml::{name} model")]
+	ModelPermissions {
+		name: String,
+	},
+
 	/// The specified table can not be written as it is setup as a foreign table view
 	#[error("Unable to write to the `{table}` table while setup as a view")]
 	TableIsView {
 		table: String,
 	},
 
+	/// The specified table can not be rebuilt as it is not setup as a foreign table view
+	#[error("Unable to rebuild the `{table}` table as it is not setup as a view")]
+	TableIsNotView {
+		table: String,
+	},
+
+	/// The aggregate function can not be maintained incrementally in a foreign table view
+	#[error("Found '{name}' in the table view, but this aggregate function can not be maintained incrementally")]
+	InvalidViewAggregate {
+		name: String,
+	},
+
 	/// A database entry for the specified record already exists
 	#[error("Database record `{thing}` already exists")]
 	RecordExists {
@@ -641,6 +712,31 @@ pub enum Error {
 	#[error("Versionstamp in key is corrupted: {0}")]
 	CorruptedVersionstampInKey(#[from] VersionstampError),
 
+	/// The table does not keep the history required to read it at a previous version
+	#[error("Versions are not supported on table '{table}', as neither the table nor its database has a change feed")]
+	TbVersionUnavailable {
+		table: String,
+	},
+
+	/// There is no version of the database at the specified time
+	#[error("There is no version of the database at '{value}'")]
+	VersionNotFound {
+		value: String,
+	},
+
+	/// The changes since the specified time are no longer retained by the change feed
+	#[error("Unable to read table '{table}' at '{value}', as it is older than the change feed retention")]
+	VersionExpired {
+		table: String,
+		value: String,
+	},
+
+	/// The VERSION clause is not supported on graph edges and indexes, as they only reflect the current data
+	#[error("Versions are not supported on '{value}', as graph edges and indexes only reflect the current data")]
+	VersionUnsupported {
+		value: String,
+	},
+
 	/// Invalid level
 	#[error("Invalid level '{0}'")]
 	InvalidLevel(String),
@@ -701,6 +797,12 @@ pub enum Error {
 	#[error("There was a problem with authentication")]
 	InvalidAuth,
 
+	/// The JSON Web Key Set of a token is invalid
+	#[error("The JWKS is invalid: {message}")]
+	InvalidJwks {
+		message: String,
+	},
+
 	/// There was an error with signing up
 	#[error("There was a problem with signing up")]
 	InvalidSignup,
@@ -757,6 +859,7 @@ impl From<tikv::Error> for Error {
 			tikv::Error::DuplicateKeyInsertion => Error::TxKeyAlreadyExists,
 			tikv::Error::KeyError(ke) if ke.abort.contains("KeyTooLarge") => Error::TxKeyTooLarge,
 			tikv::Error::RegionError(re) if re.raft_entry_too_large.is_some() => Error::TxTooLarge,
+			tikv::Error::KeyError(ke) if ke.conflict.is_some() => Error::TxConflict,
 			_ => Error::Tx(e.to_string()),
 		}
 	}
@@ -765,14 +868,20 @@ impl From<tikv::Error> for Error {
 #[cfg(feature = "kv-speedb")]
 impl From<speedb::Error> for Error {
 	fn from(e: speedb::Error) -> Error {
-		Error::Tx(e.to_string())
+		match e.kind() {
+			speedb::ErrorKind::Busy | speedb::ErrorKind::TryAgain => Error::TxConflict,
+			_ => Error::Tx(e.to_string()),
+		}
 	}
 }
 
 #[cfg(feature = "kv-rocksdb")]
 impl From<rocksdb::Error> for Error {
 	fn from(e: rocksdb::Error) -> Error {
-		Error::Tx(e.to_string())
+		match e.kind() {
+			rocksdb::ErrorKind::Busy | rocksdb::ErrorKind::TryAgain => Error::TxConflict,
+			_ => Error::Tx(e.to_string()),
+		}
 	}
 }
 
@@ -1,4 +1,5 @@
 use crate::err::Error;
+use crate::fnc::util::hll::HyperLogLog;
 use crate::sql::array::Array;
 use crate::sql::array::Clump;
 use crate::sql::array::Combine;
@@ -44,6 +45,14 @@ pub fn append((mut array, value): (Array, Value)) -> Result<Value, Error> {
 	Ok(array.into())
 }
 
+pub fn approx_distinct((array,): (Array,)) -> Result<Value, Error> {
+	let mut hll = HyperLogLog::default();
+	for v in array.iter() {
+		hll.insert(v);
+	}
+	Ok(hll.estimate().into())
+}
+
 pub fn at((array, i): (Array, i64)) -> Result<Value, Error> {
 	let mut idx = i as usize;
 	if i < 0 {
@@ -85,6 +85,7 @@ pub fn synchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Va
 		"array::all" => array::all,
 		"array::any" => array::any,
 		"array::append" => array::append,
+		"array::approx_distinct" => array::approx_distinct,
 		"array::at" => array::at,
 		"array::boolean_and" => array::boolean_and,
 		"array::boolean_not" => array::boolean_not,
@@ -240,6 +241,7 @@ pub fn synchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Va
 		"string::trim" => string::trim,
 		"string::uppercase" => string::uppercase,
 		"string::words" => string::words,
+		"string::distance::damerau_levenshtein" => string::distance::damerau_levenshtein,
 		"string::distance::hamming" => string::distance::hamming,
 		"string::distance::levenshtein" => string::distance::levenshtein,
 		"string::is::alphanum" => string::is::alphanum,
@@ -257,6 +259,8 @@ pub fn synchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Va
 		"string::is::uuid" => string::is::uuid,
 		"string::similarity::fuzzy" => string::similarity::fuzzy,
 		"string::similarity::jaro" => string::similarity::jaro,
+		"string::similarity::jaro_winkler" => string::similarity::jaro_winkler,
+		"string::similarity::ngram" => string::similarity::ngram,
 		"string::similarity::smithwaterman" => string::similarity::smithwaterman,
 		//
 		"time::ceil" => time::ceil,
@@ -8,5 +8,7 @@ impl_module_def!(
 	"string::similarity",
 	"fuzzy" => run,
 	"jaro" => run,
+	"jaro_winkler" => run,
+	"ngram" => run,
 	"smithwaterman" => run
 );
@@ -136,18 +136,25 @@ pub fn words((string,): (String,)) -> Result<Value, Error> {
 pub mod distance {
 
 	use crate::err::Error;
+	use crate::fnc::util::string::distance;
 	use crate::sql::Value;
 
-	pub fn hamming((_, _): (String, String)) -> Result<Value, Error> {
-		Err(Error::FeatureNotYetImplemented {
-			feature: "string::distance::hamming() function".to_string(),
-		})
+	pub fn damerau_levenshtein((a, b): (String, String)) -> Result<Value, Error> {
+		Ok(distance::damerau_levenshtein(&a, &b).into())
 	}
 
-	pub fn levenshtein((_, _): (String, String)) -> Result<Value, Error> {
-		Err(Error::FeatureNotYetImplemented {
-			feature: "string::distance::levenshtein() function".to_string(),
-		})
+	pub fn hamming((a, b): (String, String)) -> Result<Value, Error> {
+		match distance::hamming(&a, &b) {
+			Some(v) => Ok(v.into()),
+			None => Err(Error::InvalidArguments {
+				name: String::from("string::distance::hamming"),
+				message: String::from("The two strings must be of the same length."),
+			}),
+		}
+	}
+
+	pub fn levenshtein((a, b): (String, String)) -> Result<Value, Error> {
+		Ok(distance::levenshtein(&a, &b).into())
 	}
 }
 
@@ -226,16 +233,30 @@ pub mod similarity {
 
 	use crate::err::Error;
 	use crate::fnc::util::string::fuzzy::Fuzzy;
+	use crate::fnc::util::string::similarity;
 	use crate::sql::Value;
 
 	pub fn fuzzy((a, b): (String, String)) -> Result<Value, Error> {
 		Ok(a.as_str().fuzzy_score(b.as_str()).into())
 	}
 
-	pub fn jaro((_, _): (String, String)) -> Result<Value, Error> {
-		Err(Error::FeatureNotYetImplemented {
-			feature: "string::similarity::jaro() function".to_string(),
-		})
+	pub fn jaro((a, b): (String, String)) -> Result<Value, Error> {
+		Ok(similarity::jaro(&a, &b).into())
+	}
+
+	pub fn jaro_winkler((a, b): (String, String)) -> Result<Value, Error> {
+		Ok(similarity::jaro_winkler(&a, &b).into())
+	}
+
+	pub fn ngram((a, b, n): (String, String, Option<usize>)) -> Result<Value, Error> {
+		let n = n.unwrap_or(2);
+		if n == 0 {
+			return Err(Error::InvalidArguments {
+				name: String::from("string::similarity::ngram"),
+				message: String::from("The size of the n-grams must be greater than 0."),
+			});
+		}
+		Ok(similarity::ngram(&a, &b, n).into())
 	}
 
 	pub fn smithwaterman((a, b): (String, String)) -> Result<Value, Error> {
@@ -0,0 +1,83 @@
+use crate::sql::value::Value;
+use sha2::{Digest, Sha256};
+
+/// The number of bits of the hash which select a register
+const PRECISION: u32 = 10;
+
+/// The number of registers, which gives a standard error of about 3%
+const REGISTERS: usize = 1 << PRECISION;
+
+/// A HyperLogLog sketch, which estimates the number of distinct values it has seen.
+///
+/// The registers are persisted in the auxiliary state of table views, so the
+/// values are hashed with a hash function which does not change between releases.
+pub struct HyperLogLog(Vec<u8>);
+
+impl Default for HyperLogLog {
+	fn default() -> Self {
+		Self(vec![0; REGISTERS])
+	}
+}
+
+impl From<Vec<u8>> for HyperLogLog {
+	fn from(mut registers: Vec<u8>) -> Self {
+		registers.resize(REGISTERS, 0);
+		Self(registers)
+	}
+}
+
+impl From<HyperLogLog> for Vec<u8> {
+	fn from(hll: HyperLogLog) -> Self {
+		hll.0
+	}
+}
+
+impl HyperLogLog {
+	/// Adds a value to the sketch
+	pub fn insert(&mut self, v: &Value) {
+		let bytes: Vec<u8> = v.into();
+		let hash = Sha256::digest(bytes);
+		let hash = u64::from_be_bytes(hash[..8].try_into().unwrap());
+		// The first bits select the register
+		let index = (hash >> (64 - PRECISION)) as usize;
+		// The remaining bits give the rank of the value
+		let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+		if self.0[index] < rank {
+			self.0[index] = rank;
+		}
+	}
+
+	/// Estimates the number of distinct values
+	pub fn estimate(&self) -> u64 {
+		let m = REGISTERS as f64;
+		let alpha = 0.7213 / (1.0 + 1.079 / m);
+		let sum: f64 = self.0.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+		let raw = alpha * m * m / sum;
+		let zeros = self.0.iter().filter(|r| **r == 0).count();
+		// Small cardinalities are estimated with linear counting
+		if raw <= 2.5 * m && zeros > 0 {
+			(m * (m / zeros as f64).ln()).round() as u64
+		} else {
+			raw.round() as u64
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn estimate_distinct_values() {
+		let mut hll = HyperLogLog::default();
+		assert_eq!(hll.estimate(), 0);
+		for i in 0..10000 {
+			hll.insert(&Value::from(i % 5000));
+		}
+		let est = hll.estimate() as f64;
+		assert!((est - 5000.0).abs() < 5000.0 * 0.1, "{est}");
+		// The registers can be persisted and restored
+		let hll = HyperLogLog::from(Vec::from(hll));
+		assert_eq!(hll.estimate() as f64, est);
+	}
+}
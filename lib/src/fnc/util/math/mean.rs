@@ -4,7 +4,7 @@ pub trait Mean {
 	fn mean(&self) -> f64;
 }
 
-impl Mean for Vec<Number> {
+impl Mean for [Number] {
 	fn mean(&self) -> f64 {
 		let len = self.len() as f64;
 		let sum = self.iter().map(|n| n.to_float()).sum::<f64>();
@@ -106,18 +106,131 @@ pub trait PearsonSimilarity {
 impl PearsonSimilarity for Vec<Number> {
 	fn pearson_similarity(&self, other: &Self) -> Result<Number, Error> {
 		check_same_dimension("vector::similarity::pearson", self, other)?;
-		let m1 = self.mean();
-		let m2 = other.mean();
-		let covar: f64 = self
+		Ok(pearson(self, other).into())
+	}
+}
+
+fn pearson(a: &[Number], b: &[Number]) -> f64 {
+	let m1 = a.mean();
+	let m2 = b.mean();
+	let covar: f64 =
+		a.iter().zip(b.iter()).map(|(x, y)| (x.to_float() - m1) * (y.to_float() - m2)).sum();
+	let covar = covar / a.len() as f64;
+	let std_dev1 = deviation(a, m1, false);
+	let std_dev2 = deviation(b, m2, false);
+	covar / (std_dev1 * std_dev2)
+}
+
+pub trait SpearmanSimilarity {
+	fn spearman_similarity(&self, other: &Self) -> Result<Number, Error>;
+}
+
+impl SpearmanSimilarity for Vec<Number> {
+	fn spearman_similarity(&self, other: &Self) -> Result<Number, Error> {
+		check_same_dimension("vector::similarity::spearman", self, other)?;
+		Ok(pearson(&rank(self), &rank(other)).into())
+	}
+}
+
+/// Ranks the values of a vector, giving tied values the average of their ranks
+fn rank(v: &[Number]) -> Vec<Number> {
+	let mut idx: Vec<usize> = (0..v.len()).collect();
+	idx.sort_by(|&a, &b| v[a].cmp(&v[b]));
+	let mut ranks = vec![Number::from(0.0); v.len()];
+	let mut i = 0;
+	while i < idx.len() {
+		// Find the values which are tied with this one
+		let mut j = i + 1;
+		while j < idx.len() && v[idx[j]] == v[idx[i]] {
+			j += 1;
+		}
+		// Ranks start at 1, so the average of the ranks i+1..=j
+		let r = (i + j + 1) as f64 / 2.0;
+		for k in &idx[i..j] {
+			ranks[*k] = r.into();
+		}
+		i = j;
+	}
+	ranks
+}
+
+pub trait MahalanobisDistance {
+	fn mahalanobis_distance(
+		&self,
+		other: &Self,
+		covariance: &[Vec<Number>],
+	) -> Result<Number, Error>;
+}
+
+impl MahalanobisDistance for Vec<Number> {
+	fn mahalanobis_distance(
+		&self,
+		other: &Self,
+		covariance: &[Vec<Number>],
+	) -> Result<Number, Error> {
+		check_same_dimension("vector::distance::mahalanobis", self, other)?;
+		let err = |message: &str| Error::InvalidArguments {
+			name: String::from("vector::distance::mahalanobis"),
+			message: String::from(message),
+		};
+		let n = self.len();
+		if covariance.len() != n || covariance.iter().any(|r| r.len() != n) {
+			return Err(err(
+				"The covariance matrix must be a square matrix of the same dimension as the vectors.",
+			));
+		}
+		let inv =
+			invert(covariance).ok_or_else(|| err("The covariance matrix must be invertible."))?;
+		let diff: Vec<f64> =
+			self.iter().zip(other.iter()).map(|(a, b)| (a - b).to_float()).collect();
+		let d: f64 = inv
 			.iter()
-			.zip(other.iter())
-			.map(|(x, y)| (x.to_float() - m1) * (y.to_float() - m2))
+			.zip(diff.iter())
+			.map(|(row, x)| x * row.iter().zip(diff.iter()).map(|(m, y)| m * y).sum::<f64>())
 			.sum();
-		let covar = covar / self.len() as f64;
-		let std_dev1 = deviation(self, m1, false);
-		let std_dev2 = deviation(other, m2, false);
-		Ok((covar / (std_dev1 * std_dev2)).into())
+		Ok(d.sqrt().into())
+	}
+}
+
+/// Inverts a square matrix with Gauss-Jordan elimination, returning `None` if it is singular
+fn invert(m: &[Vec<Number>]) -> Option<Vec<Vec<f64>>> {
+	let n = m.len();
+	// The matrix augmented with the identity matrix
+	let mut a: Vec<Vec<f64>> = m
+		.iter()
+		.enumerate()
+		.map(|(i, row)| {
+			let mut r: Vec<f64> = row.iter().map(Number::to_float).collect();
+			r.extend((0..n).map(|j| {
+				if i == j {
+					1.0
+				} else {
+					0.0
+				}
+			}));
+			r
+		})
+		.collect();
+	for c in 0..n {
+		// Use the row with the largest pivot for numerical stability
+		let p = (c..n).max_by(|&x, &y| a[x][c].abs().total_cmp(&a[y][c].abs()))?;
+		if a[p][c].abs() < f64::EPSILON {
+			return None;
+		}
+		a.swap(c, p);
+		let pivot = a[c][c];
+		a[c].iter_mut().for_each(|v| *v /= pivot);
+		for r in 0..n {
+			if r != c {
+				let f = a[r][c];
+				if f != 0.0 {
+					let row = a[c].clone();
+					a[r].iter_mut().zip(row.iter()).for_each(|(v, p)| *v -= f * p);
+				}
+			}
+		}
 	}
+	Some(a.into_iter().map(|r| r[n..].to_vec()).collect())
 }
 
 pub trait ManhattanDistance {
@@ -1,4 +1,5 @@
 pub mod geo;
+pub mod hll;
 pub mod math;
 pub mod string;
 
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+/// Returns the number of positions at which the characters of two strings differ,
+/// or `None` if the strings are not of the same length
+pub fn hamming(a: &str, b: &str) -> Option<usize> {
+	if a.chars().count() != b.chars().count() {
+		return None;
+	}
+	Some(a.chars().zip(b.chars()).filter(|(a, b)| a != b).count())
+}
+
+/// Returns the minimum number of insertions, deletions, and substitutions
+/// of characters required to change one string into the other
+pub fn levenshtein(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	// Only the previous row of the matrix is needed
+	let mut row: Vec<usize> = (0..=b.len()).collect();
+	for (i, ca) in a.chars().enumerate() {
+		let mut prev = row[0];
+		row[0] = i + 1;
+		for (j, cb) in b.iter().enumerate() {
+			let cost = usize::from(ca != *cb);
+			let next = (prev + cost).min(row[j] + 1).min(row[j + 1] + 1);
+			prev = row[j + 1];
+			row[j + 1] = next;
+		}
+	}
+	row[b.len()]
+}
+
+/// Returns the minimum number of insertions, deletions, substitutions, and
+/// transpositions of adjacent characters required to change one string into the other
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+	let (n, m) = (a.len(), b.len());
+	let max = n + m;
+	// The last row in which each character of the first string was seen
+	let mut last: HashMap<char, usize> = HashMap::new();
+	// The matrix has an extra row and column holding the maximum distance
+	let mut d = vec![vec![0; m + 2]; n + 2];
+	d[0][0] = max;
+	for i in 0..=n {
+		d[i + 1][0] = max;
+		d[i + 1][1] = i;
+	}
+	for j in 0..=m {
+		d[0][j + 1] = max;
+		d[1][j + 1] = j;
+	}
+	for i in 1..=n {
+		// The last column in this row in which the characters matched
+		let mut db = 0;
+		for j in 1..=m {
+			let k = last.get(&b[j - 1]).copied().unwrap_or(0);
+			let l = db;
+			let cost = if a[i - 1] == b[j - 1] {
+				db = j;
+				0
+			} else {
+				1
+			};
+			d[i + 1][j + 1] = (d[i][j] + cost)
+				.min(d[i + 1][j] + 1)
+				.min(d[i][j + 1] + 1)
+				.min(d[k][l] + (i - k - 1) + 1 + (j - l - 1));
+		}
+		last.insert(a[i - 1], i);
+	}
+	d[n + 1][m + 1]
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn hamming_distance() {
+		assert_eq!(hamming("karolin", "kathrin"), Some(3));
+		assert_eq!(hamming("", ""), Some(0));
+		assert_eq!(hamming("résumé", "resume"), Some(2));
+		assert_eq!(hamming("abc", "ab"), None);
+	}
+
+	#[test]
+	fn levenshtein_distance() {
+		assert_eq!(levenshtein("kitten", "sitting"), 3);
+		assert_eq!(levenshtein("", "abc"), 3);
+		assert_eq!(levenshtein("abc", ""), 3);
+		assert_eq!(levenshtein("flaw", "lawn"), 2);
+		assert_eq!(levenshtein("ca", "ac"), 2);
+	}
+
+	#[test]
+	fn damerau_levenshtein_distance() {
+		assert_eq!(damerau_levenshtein("kitten", "sitting"), 3);
+		assert_eq!(damerau_levenshtein("", "abc"), 3);
+		assert_eq!(damerau_levenshtein("ca", "ac"), 1);
+		// Unlike the optimal string alignment distance, substrings can be edited more than once
+		assert_eq!(damerau_levenshtein("ca", "abc"), 2);
+	}
+}
@@ -1,2 +1,4 @@
+pub mod distance;
 pub mod fuzzy;
+pub mod similarity;
 pub mod slug;
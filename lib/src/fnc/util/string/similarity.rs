@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+/// Returns the Jaro similarity of two strings, between 0 and 1
+pub fn jaro(a: &str, b: &str) -> f64 {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+	if a.is_empty() && b.is_empty() {
+		return 1.0;
+	}
+	if a.is_empty() || b.is_empty() {
+		return 0.0;
+	}
+	// Characters only match if they are not too far apart
+	let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+	let mut a_matched = vec![false; a.len()];
+	let mut b_matched = vec![false; b.len()];
+	let mut matches = 0;
+	for (i, ca) in a.iter().enumerate() {
+		let beg = i.saturating_sub(window);
+		let end = (i + window + 1).min(b.len());
+		for j in beg..end {
+			if !b_matched[j] && b[j] == *ca {
+				a_matched[i] = true;
+				b_matched[j] = true;
+				matches += 1;
+				break;
+			}
+		}
+	}
+	if matches == 0 {
+		return 0.0;
+	}
+	// Count the matching characters which are not in the same order
+	let mut transpositions = 0;
+	let mut j = 0;
+	for (i, ca) in a.iter().enumerate() {
+		if a_matched[i] {
+			while !b_matched[j] {
+				j += 1;
+			}
+			if *ca != b[j] {
+				transpositions += 1;
+			}
+			j += 1;
+		}
+	}
+	let m = matches as f64;
+	let t = transpositions as f64 / 2.0;
+	(m / a.len() as f64 + m / b.len() as f64 + (m - t) / m) / 3.0
+}
+
+/// Returns the Jaro-Winkler similarity of two strings, between 0 and 1,
+/// which favours strings with a common prefix of up to 4 characters
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+	let sim = jaro(a, b);
+	let prefix = a.chars().zip(b.chars()).take(4).take_while(|(a, b)| a == b).count();
+	sim + prefix as f64 * 0.1 * (1.0 - sim)
+}
+
+/// Returns the Jaccard similarity of the sets of character n-grams of two strings,
+/// between 0 and 1. A string shorter than `n` characters is a single n-gram.
+pub fn ngram(a: &str, b: &str, n: usize) -> f64 {
+	let a = ngrams(a, n);
+	let b = ngrams(b, n);
+	if a.is_empty() && b.is_empty() {
+		return 1.0;
+	}
+	let intersection = a.intersection(&b).count();
+	let union = a.union(&b).count();
+	intersection as f64 / union as f64
+}
+
+fn ngrams(s: &str, n: usize) -> HashSet<Vec<char>> {
+	let chars: Vec<char> = s.chars().collect();
+	if chars.is_empty() {
+		HashSet::new()
+	} else if chars.len() < n {
+		HashSet::from([chars])
+	} else {
+		chars.windows(n).map(<[char]>::to_vec).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn jaro_similarity() {
+		assert_eq!(jaro("", ""), 1.0);
+		assert_eq!(jaro("abc", ""), 0.0);
+		assert_eq!(jaro("abc", "xyz"), 0.0);
+		assert_eq!(jaro("same", "same"), 1.0);
+		assert!((jaro("MARTHA", "MARHTA") - 0.944444).abs() < 1e-6);
+		assert!((jaro("DIXON", "DICKSONX") - 0.766667).abs() < 1e-6);
+	}
+
+	#[test]
+	fn jaro_winkler_similarity() {
+		assert_eq!(jaro_winkler("same", "same"), 1.0);
+		assert!((jaro_winkler("MARTHA", "MARHTA") - 0.961111).abs() < 1e-6);
+		assert!((jaro_winkler("DIXON", "DICKSONX") - 0.813333).abs() < 1e-6);
+	}
+
+	#[test]
+	fn ngram_similarity() {
+		assert_eq!(ngram("", "", 2), 1.0);
+		assert_eq!(ngram("night", "night", 2), 1.0);
+		// ni, ig, gh, ht and na, ac, ch, ht share one bigram out of seven
+		assert_eq!(ngram("night", "nacht", 2), 1.0 / 7.0);
+		assert_eq!(ngram("a", "a", 3), 1.0);
+		assert_eq!(ngram("a", "ab", 3), 0.0);
+	}
+}
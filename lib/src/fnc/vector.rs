@@ -48,9 +48,10 @@ pub mod distance {
 
 	use crate::err::Error;
 	use crate::fnc::util::math::vector::{
-		ChebyshevDistance, EuclideanDistance, HammingDistance, ManhattanDistance, MinkowskiDistance,
+		ChebyshevDistance, EuclideanDistance, HammingDistance, MahalanobisDistance,
+		ManhattanDistance, MinkowskiDistance,
 	};
-	use crate::sql::{Number, Value};
+	use crate::sql::{Array, Number, Value};
 
 	pub fn chebyshev((a, b): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
 		Ok(a.chebyshev_distance(&b)?.into())
@@ -64,10 +65,25 @@ pub mod distance {
 		Ok(a.hamming_distance(&b)?.into())
 	}
 
-	pub fn mahalanobis((_, _): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
-		Err(Error::FeatureNotYetImplemented {
-			feature: "vector::distance::mahalanobis() function".to_string(),
-		})
+	pub fn mahalanobis((a, b, c): (Vec<Number>, Vec<Number>, Array)) -> Result<Value, Error> {
+		let err = || Error::InvalidArguments {
+			name: String::from("vector::distance::mahalanobis"),
+			message: String::from("The covariance matrix must be an array of arrays of numbers."),
+		};
+		let c = c
+			.into_iter()
+			.map(|row| match row {
+				Value::Array(row) => row
+					.into_iter()
+					.map(|v| match v {
+						Value::Number(v) => Ok(v),
+						_ => Err(err()),
+					})
+					.collect(),
+				_ => Err(err()),
+			})
+			.collect::<Result<Vec<Vec<Number>>, Error>>()?;
+		Ok(a.mahalanobis_distance(&b, &c)?.into())
 	}
 
 	pub fn manhattan((a, b): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
@@ -82,7 +98,9 @@ pub mod distance {
 pub mod similarity {
 
 	use crate::err::Error;
-	use crate::fnc::util::math::vector::{CosineSimilarity, JaccardSimilarity, PearsonSimilarity};
+	use crate::fnc::util::math::vector::{
+		CosineSimilarity, JaccardSimilarity, PearsonSimilarity, SpearmanSimilarity,
+	};
 	use crate::sql::{Number, Value};
 
 	pub fn cosine((a, b): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
@@ -97,9 +115,7 @@ pub mod similarity {
 		Ok(a.pearson_similarity(&b)?.into())
 	}
 
-	pub fn spearman((_, _): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
-		Err(Error::FeatureNotYetImplemented {
-			feature: "vector::similarity::spearman() function".to_string(),
-		})
+	pub fn spearman((a, b): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
+		Ok(a.spearman_similarity(&b)?.into())
 	}
 }
@@ -1,8 +1,8 @@
-use crate::sql::statements::{DefineTokenStatement, DefineUserStatement};
+use crate::sql::statements::{DefineRoleStatement, DefineTokenStatement, DefineUserStatement};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 
-use super::{is_allowed, Action, Actor, Error, Level, Resource, Role};
+use super::{is_allowed, Action, Actor, Error, Level, Resource, ResourceKind, Role};
 
 /// Specifies the current authentication for the datastore execution context.
 #[derive(Clone, Default, Debug, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize)]
@@ -85,6 +85,21 @@ impl Auth {
 	pub fn has_role(&self, role: &Role) -> bool {
 		self.actor.has_role(role)
 	}
+
+	/// Checks if the current actor has one of the custom roles
+	pub fn has_custom_roles(&self) -> bool {
+		!self.actor.roles().iter().all(Role::is_builtin)
+	}
+
+	/// Attaches the definitions of the custom roles of the current actor
+	pub fn with_custom_roles(&self, roles: Vec<DefineRoleStatement>) -> Self {
+		Self::new(self.actor.clone().with_custom_roles(roles))
+	}
+
+	/// Checks if the custom roles of the current actor grant an action on a kind of resource, or on a named resource
+	pub fn has_grant(&self, action: &Action, kind: &ResourceKind, name: Option<&str>) -> bool {
+		self.actor.has_grant(action, kind, name)
+	}
 }
 
 impl std::convert::From<(&DefineUserStatement, Level)> for Auth {
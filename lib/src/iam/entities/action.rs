@@ -1,11 +1,14 @@
 use std::str::FromStr;
 
 use cedar_policy::{Entity, EntityId, EntityTypeName, EntityUid};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
 
 use crate::dbs::Statement;
 
-// TODO(sgirones): For now keep it simple. In the future, we will allow for custom roles and policies using a more exhaustive list of actions and resources.
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd)]
+// TODO(sgirones): For now keep it simple. In the future, we will allow for custom policies using a more exhaustive list of actions and resources.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
 pub enum Action {
 	View,
 	Edit,
@@ -7,17 +7,19 @@ use cedar_policy::{Entity, EntityId, EntityTypeName, EntityUid, RestrictedExpres
 use serde::{Deserialize, Serialize};
 
 use super::{Level, Resource, ResourceKind};
-use crate::iam::Role;
-use crate::sql::statements::{DefineTokenStatement, DefineUserStatement};
+use crate::iam::{Action, Role};
+use crate::sql::statements::{DefineRoleStatement, DefineTokenStatement, DefineUserStatement};
 
 //
 // User
 //
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct Actor {
 	res: Resource,
 	roles: Vec<Role>,
+	#[revision(start = 2)]
+	custom_roles: Vec<DefineRoleStatement>,
 }
 
 impl Default for Actor {
@@ -25,6 +27,7 @@ impl Default for Actor {
 		Self {
 			res: ResourceKind::Actor.on_level(Level::No),
 			roles: Vec::new(),
+			custom_roles: Vec::new(),
 		}
 	}
 }
@@ -51,14 +54,43 @@ impl Actor {
 		Self {
 			res: Resource::new(id, super::ResourceKind::Actor, level),
 			roles,
+			custom_roles: Vec::new(),
 		}
 	}
 
+	/// Attaches the definitions of the custom roles of the actor, so that their grants are authorized.
+	pub fn with_custom_roles(mut self, roles: Vec<DefineRoleStatement>) -> Self {
+		self.custom_roles = roles
+			.into_iter()
+			.filter(|r| Role::from_str(&r.name).map_or(false, |role| self.has_role(&role)))
+			.collect();
+		self
+	}
+
 	/// Checks if the actor has the given role.
 	pub fn has_role(&self, role: &Role) -> bool {
 		self.roles.contains(role)
 	}
 
+	/// Returns the roles of the actor.
+	pub fn roles(&self) -> &[Role] {
+		&self.roles
+	}
+
+	/// Returns the definitions of the custom roles of the actor.
+	pub fn custom_roles(&self) -> &[DefineRoleStatement] {
+		&self.custom_roles
+	}
+
+	/// Checks if one of the custom roles of the actor grants the given action on the given kind of resource,
+	/// or only on the resource with the given name.
+	pub fn has_grant(&self, action: &Action, kind: &ResourceKind, name: Option<&str>) -> bool {
+		self.custom_roles
+			.iter()
+			.flat_map(|r| r.grants.iter())
+			.any(|g| &g.action == action && &g.kind == kind && g.applies_to(name))
+	}
+
 	// Cedar policy helpers
 	pub fn cedar_attrs(&self) -> HashMap<String, RestrictedExpression> {
 		[
@@ -1,11 +1,11 @@
-use crate::iam::Error;
+use crate::iam::{Action, Error, ResourceKind};
 use crate::sql::Ident;
 use cedar_policy::{Entity, EntityTypeName, EntityUid, RestrictedExpression};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-// In the future, we will allow for custom roles. For now, provide predefined roles.
+// The predefined roles, along with the custom roles which are defined with DEFINE ROLE
 #[derive(Hash, Clone, Default, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
 #[revisioned(revision = 1)]
 pub enum Role {
@@ -13,6 +13,59 @@ pub enum Role {
 	Viewer,
 	Editor,
 	Owner,
+	Custom(String),
+}
+
+impl Role {
+	/// Checks if this is one of the predefined roles
+	pub fn is_builtin(&self) -> bool {
+		!matches!(self, Self::Custom(_))
+	}
+}
+
+// A permission given by a custom role, to do an action on a kind of resource,
+// either on every resource of that kind, or only on the named resources
+#[derive(Hash, Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[revisioned(revision = 2)]
+pub struct Grant {
+	pub action: Action,
+	pub kind: ResourceKind,
+	#[revision(start = 2)]
+	pub names: Vec<Ident>,
+}
+
+impl Grant {
+	/// Checks if the grant applies to the resource with the given name, if any.
+	/// Without a name, only the grants on every resource of the kind apply.
+	pub fn applies_to(&self, name: Option<&str>) -> bool {
+		self.names.is_empty() || name.is_some_and(|n| self.names.iter().any(|v| v.as_str() == n))
+	}
+}
+
+impl std::fmt::Display for Grant {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let kind = match self.kind {
+			ResourceKind::Parameter => "PARAM".to_owned(),
+			ResourceKind::Actor => "USER".to_owned(),
+			ref kind => kind.to_string().to_uppercase(),
+		};
+		write!(f, "{} ON {}", self.action.to_string().to_uppercase(), kind)?;
+		for (i, name) in self.names.iter().enumerate() {
+			let sep = if i == 0 {
+				" "
+			} else {
+				", "
+			};
+			// The names which start the following clauses are escaped
+			match name.as_str() {
+				v if ["GRANT", "COMMENT"].iter().any(|k| k.eq_ignore_ascii_case(v)) => {
+					write!(f, "{sep}`{v}`")?
+				}
+				_ => write!(f, "{sep}{name}")?,
+			}
+		}
+		Ok(())
+	}
 }
 
 impl std::fmt::Display for Role {
@@ -21,6 +74,7 @@ impl std::fmt::Display for Role {
 			Self::Viewer => write!(f, "Viewer"),
 			Self::Editor => write!(f, "Editor"),
 			Self::Owner => write!(f, "Owner"),
+			Self::Custom(name) => write!(f, "{name}"),
 		}
 	}
 }
@@ -32,6 +86,10 @@ impl FromStr for Role {
 			"viewer" => Ok(Self::Viewer),
 			"editor" => Ok(Self::Editor),
 			"owner" => Ok(Self::Owner),
+			// The names of custom roles are plain identifiers
+			_ if !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+				Ok(Self::Custom(s.to_string()))
+			}
 			_ => Err(Error::InvalidRole(s.to_string())),
 		}
 	}
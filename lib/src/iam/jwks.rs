@@ -0,0 +1,267 @@
+//! jwks is a module to verify tokens with the keys of a JSON Web Key Set.
+//!
+//! The value of a `DEFINE TOKEN ... TYPE JWKS` statement is either an inline key set,
+//! the `file://` path of a key set, or the `http://` or `https://` url of a key set.
+//! Remote key sets are cached, and are fetched again once they expire, or when a token
+//! is signed with a key which is not in the cached key set, as is the case after the
+//! keys of the identity provider have been rotated.
+//!
+//! Only root users can define a key set read from a file on the server, and a key set
+//! is only fetched from a url which the network capabilities of the datastore allow.
+//! The algorithm with which a token is verified is always taken from the key, or from
+//! its type, and never from the header of the token.
+
+use crate::dbs::Capabilities;
+use crate::err::Error;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet, PublicKeyUse};
+use jsonwebtoken::{decode_header, Algorithm, DecodingKey, Validation};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// The number of seconds after which a remote key set is fetched again
+const CACHE_EXPIRATION: i64 = 3600;
+
+/// The minimum number of seconds between two fetches caused by an unknown key
+const REFRESH_COOLDOWN: i64 = 30;
+
+struct CachedJwks {
+	/// The cached key set
+	jwks: JwkSet,
+	/// When the key set was fetched
+	fetched: DateTime<Utc>,
+	/// When the key set was fetched because of an unknown key
+	refreshed: Option<DateTime<Utc>>,
+}
+
+static CACHE: Lazy<RwLock<HashMap<String, CachedJwks>>> = Lazy::new(Default::default);
+
+/// Checks if the value of a token definition is an inline key set
+pub(crate) fn is_inline(code: &str) -> bool {
+	code.trim_start().starts_with('{')
+}
+
+/// Checks if the value of a token definition is the path of a key set on the server
+pub(crate) fn is_file(code: &str) -> bool {
+	code.starts_with("file://")
+}
+
+/// Parses a JSON Web Key Set
+pub(crate) fn parse(code: &str) -> Result<JwkSet, Error> {
+	serde_json::from_str(code).map_err(|e| Error::InvalidJwks {
+		message: e.to_string(),
+	})
+}
+
+/// Returns the key and the validation with which to verify the token
+pub(super) async fn config(
+	code: &str,
+	token: &str,
+	caps: &Capabilities,
+) -> Result<(DecodingKey, Validation), Error> {
+	// Decode the header without verifying, to find the key which signed the token
+	let header = decode_header(token)?;
+	let jwk = find(code, header.kid.as_deref(), caps).await?;
+	// The token must be signed with one of the algorithms of the key
+	let algorithms = algorithms(&jwk);
+	let Some(alg) = algorithms.first() else {
+		trace!("The key '{}' does not have a supported algorithm", header.kid.unwrap_or_default());
+		return Err(Error::InvalidAuth);
+	};
+	let mut validation = Validation::new(*alg);
+	validation.algorithms = algorithms;
+	Ok((DecodingKey::from_jwk(&jwk)?, validation))
+}
+
+/// Returns the algorithms with which a key can verify a token.
+///
+/// A key which does not specify its algorithm can verify the tokens
+/// signed with any algorithm of its key type, and of its curve.
+fn algorithms(jwk: &Jwk) -> Vec<Algorithm> {
+	if let Some(alg) = jwk.common.algorithm {
+		return vec![alg];
+	}
+	match &jwk.algorithm {
+		AlgorithmParameters::OctetKey(_) => {
+			vec![Algorithm::HS256, Algorithm::HS384, Algorithm::HS512]
+		}
+		AlgorithmParameters::RSA(_) => vec![
+			Algorithm::RS256,
+			Algorithm::RS384,
+			Algorithm::RS512,
+			Algorithm::PS256,
+			Algorithm::PS384,
+			Algorithm::PS512,
+		],
+		AlgorithmParameters::EllipticCurve(p) => match p.curve {
+			EllipticCurve::P256 => vec![Algorithm::ES256],
+			EllipticCurve::P384 => vec![Algorithm::ES384],
+			_ => vec![],
+		},
+		AlgorithmParameters::OctetKeyPair(p) => match p.curve {
+			EllipticCurve::Ed25519 => vec![Algorithm::EdDSA],
+			_ => vec![],
+		},
+	}
+}
+
+/// Finds a signing key by its id, from an inline or a cached key set
+async fn find(code: &str, kid: Option<&str>, caps: &Capabilities) -> Result<Jwk, Error> {
+	// Inline key sets are not cached
+	if is_inline(code) {
+		return select(&parse(code)?, kid).cloned().ok_or(Error::InvalidAuth);
+	}
+	let now = Utc::now();
+	// Check the cached key set
+	let refresh = match CACHE.read().unwrap().get(code) {
+		Some(v) if now - v.fetched < Duration::seconds(CACHE_EXPIRATION) => {
+			if let Some(jwk) = select(&v.jwks, kid) {
+				return Ok(jwk.clone());
+			}
+			// The keys may have been rotated, but do not fetch them too often
+			if matches!(v.refreshed, Some(t) if now - t < Duration::seconds(REFRESH_COOLDOWN)) {
+				trace!("The key '{}' was not found in the cached key set", kid.unwrap_or_default());
+				return Err(Error::InvalidAuth);
+			}
+			true
+		}
+		_ => false,
+	};
+	// Fetch the key set
+	let jwks = fetch(code, caps).await.map_err(|e| {
+		warn!("Unable to fetch the key set from '{}': {}", code, e);
+		Error::InvalidAuth
+	})?;
+	let jwk = select(&jwks, kid).cloned();
+	// Cache the key set
+	CACHE.write().unwrap().insert(
+		code.to_owned(),
+		CachedJwks {
+			jwks,
+			fetched: now,
+			refreshed: refresh.then_some(now),
+		},
+	);
+	jwk.ok_or(Error::InvalidAuth)
+}
+
+/// Selects a signing key by its id. Without an id, the key set must contain a single signing key
+fn select<'a>(jwks: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+	let mut keys = jwks
+		.keys
+		.iter()
+		.filter(|k| !matches!(k.common.public_key_use, Some(PublicKeyUse::Encryption)));
+	match kid {
+		Some(kid) => keys.find(|k| k.common.key_id.as_deref() == Some(kid)),
+		None => match (keys.next(), keys.next()) {
+			(Some(k), None) => Some(k),
+			_ => None,
+		},
+	}
+}
+
+/// Fetches a key set from a file or from a url
+async fn fetch(url: &str, caps: &Capabilities) -> Result<JwkSet, Error> {
+	match url.strip_prefix("file://") {
+		Some(path) => fetch_file(path).await,
+		None => fetch_url(url, caps).await,
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn fetch_file(path: &str) -> Result<JwkSet, Error> {
+	let data = tokio::fs::read_to_string(path).await.map_err(|e| Error::InvalidJwks {
+		message: e.to_string(),
+	})?;
+	parse(&data)
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_file(path: &str) -> Result<JwkSet, Error> {
+	Err(Error::InvalidJwks {
+		message: format!("Unable to read '{path}' in WebAssembly"),
+	})
+}
+
+#[cfg(feature = "http")]
+async fn fetch_url(url: &str, caps: &Capabilities) -> Result<JwkSet, Error> {
+	// The url is checked again, as the capabilities may have changed since the key set was defined
+	check_url(url, caps)?;
+	let res = reqwest::Client::builder().build()?.get(url).send().await?;
+	let txt = res.error_for_status()?.text().await?;
+	parse(&txt)
+}
+
+#[cfg(not(feature = "http"))]
+async fn fetch_url(url: &str, _: &Capabilities) -> Result<JwkSet, Error> {
+	Err(Error::InvalidJwks {
+		message: format!("Unable to fetch '{url}' without the http feature"),
+	})
+}
+
+/// Checks that the network capabilities allow a key set to be fetched from a url
+pub(crate) fn check_url(url: &str, caps: &Capabilities) -> Result<(), Error> {
+	use crate::dbs::capabilities::NetTarget;
+	let target = url::Url::parse(url).map_err(|e| Error::InvalidJwks {
+		message: e.to_string(),
+	})?;
+	match target.host() {
+		Some(host)
+			if caps.allows_network_target(&NetTarget::Host(
+				host.to_owned(),
+				target.port_or_known_default(),
+			)) =>
+		{
+			Ok(())
+		}
+		_ => Err(Error::NetTargetNotAllowed(target.to_string())),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const JWKS: &str = r#"{
+		"keys": [
+			{ "kty": "oct", "kid": "one", "alg": "HS256", "k": "b25l" },
+			{ "kty": "oct", "kid": "two", "alg": "HS512", "k": "dHdv" },
+			{ "kty": "oct", "kid": "enc", "use": "enc", "k": "ZW5j" }
+		]
+	}"#;
+
+	#[test]
+	fn select_key() {
+		let jwks = parse(JWKS).unwrap();
+		let jwk = select(&jwks, Some("two")).unwrap();
+		assert_eq!(jwk.common.key_id.as_deref(), Some("two"));
+		assert!(select(&jwks, Some("enc")).is_none());
+		assert!(select(&jwks, Some("three")).is_none());
+		// Without an id, the key is ambiguous
+		assert!(select(&jwks, None).is_none());
+		let jwks = parse(r#"{ "keys": [{ "kty": "oct", "k": "b25l" }] }"#).unwrap();
+		assert!(select(&jwks, None).is_some());
+	}
+
+	#[test]
+	fn key_algorithms() {
+		let jwks = parse(JWKS).unwrap();
+		assert_eq!(algorithms(&jwks.keys[1]), vec![Algorithm::HS512]);
+		// Without an algorithm, the key is only used with the algorithms of its type
+		assert_eq!(
+			algorithms(&jwks.keys[2]),
+			vec![Algorithm::HS256, Algorithm::HS384, Algorithm::HS512]
+		);
+		let jwks = parse(r#"{ "keys": [{ "kty": "EC", "crv": "P-384", "x": "eA", "y": "eQ" }] }"#)
+			.unwrap();
+		assert_eq!(algorithms(&jwks.keys[0]), vec![Algorithm::ES384]);
+	}
+
+	#[test]
+	fn invalid_jwks() {
+		assert!(is_inline(JWKS));
+		assert!(!is_inline("https://example.com/jwks.json"));
+		assert!(matches!(parse(r#"{ "keys": 1 }"#), Err(Error::InvalidJwks { .. })));
+	}
+}
@@ -6,6 +6,7 @@ pub mod auth;
 pub mod base;
 pub mod clear;
 pub mod entities;
+pub(crate) mod jwks;
 pub mod policies;
 pub mod signin;
 pub mod signup;
@@ -40,7 +41,7 @@ pub fn is_allowed(
 	resource: &Resource,
 	ctx: Option<Context>,
 ) -> Result<(), Error> {
-	match policies::is_allowed(actor, action, resource, ctx.unwrap_or(Context::empty())) {
+	match policies::is_allowed(actor, action, resource, ctx.unwrap_or(Context::empty()))? {
 		(allowed, _) if allowed => Ok(()),
 		_ => {
 			let err = Error::NotAllowed {
@@ -1,25 +1,29 @@
-use cedar_policy::{Authorizer, Context, Decision, Entities, Entity, EntityUid, Request, Response};
+use cedar_policy::{
+	Authorizer, Context, Decision, Entities, Entity, EntityUid, PolicySet, Request, Response,
+};
 
 mod policy_set;
 
 use policy_set::*;
 
-use crate::iam::{Action, Actor, Resource};
+use crate::iam::{Action, Actor, Error, Resource};
 
-/// Checks if the actor is allowed to do the action on the resource, given the context and based on the default policy set.
+/// Checks if the actor is allowed to do the action on the resource, given the context and based on the default policy set,
+/// along with the grants of the custom roles of the actor.
 pub fn is_allowed(
 	actor: &Actor,
 	action: &Action,
 	resource: &Resource,
 	context: Context,
-) -> (bool, Response) {
-	_is_allowed(
+) -> Result<(bool, Response), Error> {
+	Ok(_is_allowed(
+		&policy_set(actor)?,
 		Some(actor.into()),
 		Some(action.into()),
 		Some(resource.into()),
 		Entities::from_entities(_get_entities(Some(actor), Some(resource))).unwrap(),
 		context,
-	)
+	))
 }
 
 fn _get_entities(actor: Option<&Actor>, resource: Option<&Resource>) -> Vec<Entity> {
@@ -34,19 +38,18 @@ fn _get_entities(actor: Option<&Actor>, resource: Option<&Resource>) -> Vec<Enti
 }
 
 fn _is_allowed(
+	policy_set: &PolicySet,
 	actor: Option<EntityUid>,
 	action: Option<EntityUid>,
 	resource: Option<EntityUid>,
 	entities: Entities,
 	context: Context,
 ) -> (bool, Response) {
-	let policy_set = POLICY_SET.to_owned();
-
 	let authorizer = Authorizer::new();
 
 	let req = Request::new(actor, action, resource, context);
 
-	let res = authorizer.is_authorized(&req, &policy_set, &entities);
+	let res = authorizer.is_authorized(&req, policy_set, &entities);
 
 	(res.decision() == Decision::Allow, res)
 }
@@ -55,7 +58,9 @@ fn _is_allowed(
 mod tests {
 	use cedar_policy::{ValidationMode, ValidationResult, Validator};
 
-	use crate::iam::{default_schema, entities::Level, ResourceKind, Role};
+	use crate::iam::{default_schema, entities::Level, Grant, ResourceKind, Role};
+	use crate::sql::statements::DefineRoleStatement;
+	use crate::sql::Base;
 
 	use super::*;
 
@@ -79,7 +84,7 @@ mod tests {
 		let actor = Actor::new("test".into(), vec![Role::Viewer], Level::Root);
 		let res = ResourceKind::Namespace.on_root();
 
-		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty());
+		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty()).unwrap();
 		assert!(allowed);
 
 		// Returns false if the actor is not allowed to do the action on the resource
@@ -90,7 +95,90 @@ mod tests {
 		);
 		let res = ResourceKind::Namespace.on_root();
 
-		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty());
+		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty()).unwrap();
+		assert!(!allowed);
+	}
+
+	#[test]
+	fn test_is_allowed_with_custom_role() {
+		let role = DefineRoleStatement {
+			name: "reader".into(),
+			base: Base::Db,
+			grants: vec![
+				Grant {
+					action: Action::View,
+					kind: ResourceKind::Table,
+					names: vec![],
+				},
+				Grant {
+					action: Action::Edit,
+					kind: ResourceKind::Index,
+					names: vec![],
+				},
+			],
+			comment: None,
+		};
+		let actor = Actor::new(
+			"test".into(),
+			vec![Role::Custom("reader".into())],
+			Level::Database("test".into(), "test".into()),
+		)
+		.with_custom_roles(vec![role]);
+
+		// Returns true for the granted actions on the actor's level
+		let res = ResourceKind::Table.on_db("test", "test");
+		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty()).unwrap();
+		assert!(allowed);
+		let res = ResourceKind::Index.on_db("test", "test");
+		let (allowed, _) = is_allowed(&actor, &Action::Edit, &res, Context::empty()).unwrap();
+		assert!(allowed);
+
+		// Returns false for the actions which are not granted
+		let res = ResourceKind::Table.on_db("test", "test");
+		let (allowed, _) = is_allowed(&actor, &Action::Edit, &res, Context::empty()).unwrap();
+		assert!(!allowed);
+		let res = ResourceKind::Function.on_db("test", "test");
+		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty()).unwrap();
+		assert!(!allowed);
+
+		// Returns false for the granted actions on another level
+		let res = ResourceKind::Table.on_db("test", "other");
+		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty()).unwrap();
+		assert!(!allowed);
+	}
+
+	#[test]
+	fn test_is_allowed_with_custom_role_on_named_resources() {
+		let role = DefineRoleStatement {
+			name: "reader".into(),
+			base: Base::Db,
+			grants: vec![Grant {
+				action: Action::View,
+				kind: ResourceKind::Table,
+				names: vec!["person".into(), "order".into()],
+			}],
+			comment: None,
+		};
+		let actor = Actor::new(
+			"test".into(),
+			vec![Role::Custom("reader".into())],
+			Level::Database("test".into(), "test".into()),
+		)
+		.with_custom_roles(vec![role]);
+
+		// Returns true for the named resources
+		for name in ["person", "order"] {
+			let res = Resource::new(name.into(), ResourceKind::Table, ("test", "test").into());
+			let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty()).unwrap();
+			assert!(allowed, "{name}");
+		}
+
+		// Returns false for the other resources of the same kind
+		let res = Resource::new("secret".into(), ResourceKind::Table, ("test", "test").into());
+		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty()).unwrap();
+		assert!(!allowed);
+		let res = ResourceKind::Table.on_db("test", "test");
+		let (allowed, _) = is_allowed(&actor, &Action::View, &res, Context::empty()).unwrap();
 		assert!(!allowed);
 	}
 }
@@ -1,8 +1,12 @@
+use std::borrow::Cow;
 use std::str::FromStr;
 
-use cedar_policy::PolicySet;
+use cedar_policy::{EntityId, EntityTypeName, EntityUid, Policy, PolicySet};
 use once_cell::sync::Lazy;
 
+use crate::iam::{Action, Actor, Error, Role};
+use crate::sql::statements::DefineRoleStatement;
+
 pub static POLICY_SET: Lazy<PolicySet> = Lazy::new(|| {
 	PolicySet::from_str(
     r#"
@@ -24,7 +28,7 @@ pub static POLICY_SET: Lazy<PolicySet> = Lazy::new(|| {
     ) when {
         principal.roles.contains(Role::"Editor") &&
         resource.level in principal.level &&
-        ["Namespace", "Database", "Scope", "Table", "Document", "Option", "Function", "Analyzer", "Parameter", "Event", "Field", "Index"].contains(resource.type)
+        ["Namespace", "Database", "Scope", "Table", "Document", "Option", "Function", "Analyzer", "Parameter", "Event", "Field", "Index", "Model"].contains(resource.type)
     };
 
     // Owner role can edit all resources on the same level hierarchy or below
@@ -38,3 +42,79 @@ pub static POLICY_SET: Lazy<PolicySet> = Lazy::new(|| {
     };
 "#).unwrap()
 });
+
+/// Returns the policy set which applies to the actor: the default policy set,
+/// along with the policies compiled from the grants of its custom roles.
+pub fn policy_set(actor: &Actor) -> Result<Cow<'static, PolicySet>, Error> {
+	if actor.custom_roles().is_empty() {
+		return Ok(Cow::Borrowed(&*POLICY_SET));
+	}
+	let mut policy_set = POLICY_SET.to_owned();
+	for role in actor.custom_roles() {
+		for policy in role_policies(role)? {
+			policy_set.add(policy).map_err(|_| Error::InvalidRole(role.name.to_string()))?;
+		}
+	}
+	Ok(Cow::Owned(policy_set))
+}
+
+/// Compiles the grants of a custom role into one policy per action.
+/// Like the predefined roles, a custom role applies on the same level hierarchy or below.
+fn role_policies(role: &DefineRoleStatement) -> Result<Vec<Policy>, Error> {
+	let uid = EntityUid::from(&Role::from_str(&role.name)?);
+	let mut policies = Vec::new();
+	for action in [Action::View, Action::Edit].iter() {
+		let grants = role.grants.iter().filter(|g| &g.action == action);
+		// The grants on every resource of a kind
+		let kinds = grants
+			.clone()
+			.filter(|g| g.names.is_empty())
+			.map(|g| format!("\"{}\"", g.kind))
+			.collect::<Vec<_>>();
+		let mut conditions = Vec::new();
+		if !kinds.is_empty() {
+			conditions.push(format!("[{}].contains(resource.type)", kinds.join(", ")));
+		}
+		// The grants on named resources
+		for grant in grants.filter(|g| !g.names.is_empty()) {
+			let kind = EntityTypeName::from_str(&grant.kind.to_string())
+				.map_err(|_| Error::InvalidRole(role.name.to_string()))?;
+			let names = grant
+				.names
+				.iter()
+				.map(|name| {
+					let id = EntityId::from_str(name)
+						.map_err(|_| Error::InvalidRole(role.name.to_string()))?;
+					Ok(EntityUid::from_type_name_and_id(kind.clone(), id).to_string())
+				})
+				.collect::<Result<Vec<_>, Error>>()?;
+			conditions.push(format!(
+				"(resource.type == \"{}\" && resource in [{}])",
+				grant.kind,
+				names.join(", ")
+			));
+		}
+		if conditions.is_empty() {
+			continue;
+		}
+		let src = format!(
+			r#"
+    permit(
+        principal,
+        action == {},
+        resource
+    ) when {{
+        principal.roles.contains({uid}) &&
+        resource.level in principal.level &&
+        ({})
+    }};
+"#,
+			EntityUid::from(action),
+			conditions.join(" || ")
+		);
+		let policy = Policy::parse(Some(format!("{uid}::{action}")), src)
+			.map_err(|_| Error::InvalidRole(role.name.to_string()))?;
+		policies.push(policy);
+	}
+	Ok(policies)
+}
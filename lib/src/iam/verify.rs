@@ -1,11 +1,12 @@
-use crate::dbs::Session;
+use crate::dbs::{Capabilities, Session};
 use crate::err::Error;
+use crate::iam::jwks;
 use crate::iam::token::Claims;
 use crate::iam::Auth;
 use crate::iam::{Actor, Level, Role};
 use crate::kvs::Datastore;
 use crate::sql::json;
-use crate::sql::statements::DefineUserStatement;
+use crate::sql::statements::{DefineTokenStatement, DefineUserStatement};
 use crate::sql::Algorithm;
 use crate::sql::Value;
 use argon2::Argon2;
@@ -74,9 +75,31 @@ fn config(algo: Algorithm, code: String) -> Result<(DecodingKey, Validation), Er
 			DecodingKey::from_rsa_pem(code.as_ref())?,
 			Validation::new(jsonwebtoken::Algorithm::RS512),
 		)),
+		// A key set is verified with the key which signed the token
+		Algorithm::Jwks => Err(Error::InvalidAuth),
 	}
 }
 
+async fn token_config(
+	de: &DefineTokenStatement,
+	token: &str,
+	caps: &Capabilities,
+) -> Result<(DecodingKey, Validation), Error> {
+	// Get the key with which to verify the token
+	let (key, mut validation) = match de.kind {
+		Algorithm::Jwks => jwks::config(&de.code, token, caps).await?,
+		_ => config(de.kind.clone(), de.code.clone())?,
+	};
+	// Check the issuer and the audience claims if required
+	if let Some(iss) = &de.issuer {
+		validation.set_issuer(&[iss]);
+	}
+	if let Some(aud) = &de.audience {
+		validation.set_audience(&[aud]);
+	}
+	Ok((key, validation))
+}
+
 static KEY: Lazy<DecodingKey> = Lazy::new(|| DecodingKey::from_secret(&[]));
 
 static DUD: Lazy<Validation> = Lazy::new(|| {
@@ -160,7 +183,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, token: &str) -> Resul
 			};
 			// Get the scope token
 			let de = tx.get_sc_token(&ns, &db, &sc, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = token_config(&de, token, kvs.capabilities()).await?;
 			// Verify the token
 			decode::<Claims>(token, &cf.0, &cf.1)?;
 			// Log the success
@@ -225,7 +248,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, token: &str) -> Resul
 			let mut tx = kvs.transaction(false, false).await?;
 			// Get the database token
 			let de = tx.get_db_token(&ns, &db, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = token_config(&de, token, kvs.capabilities()).await?;
 			// Verify the token
 			decode::<Claims>(token, &cf.0, &cf.1)?;
 			// Parse the roles
@@ -294,7 +317,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, token: &str) -> Resul
 			let mut tx = kvs.transaction(false, false).await?;
 			// Get the namespace token
 			let de = tx.get_ns_token(&ns, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = token_config(&de, token, kvs.capabilities()).await?;
 			// Verify the token
 			decode::<Claims>(token, &cf.0, &cf.1)?;
 			// Parse the roles
@@ -922,6 +945,214 @@ mod tests {
 		}
 	}
 
+	// Creates a key set where the secret of each key is its id
+	fn jwks(kids: &[&str]) -> String {
+		let keys = kids
+			.iter()
+			.map(|kid| {
+				let k = base64_lib::engine::general_purpose::URL_SAFE_NO_PAD.encode(kid);
+				format!(r#"{{ "kty": "oct", "kid": "{kid}", "alg": "HS256", "k": "{k}" }}"#)
+			})
+			.collect::<Vec<_>>();
+		format!(r#"{{ "keys": [{}] }}"#, keys.join(", "))
+	}
+
+	// Creates a database token signed with the key of the given id
+	fn jwks_token(kid: &str, tk: &str, iss: &str, aud: &str) -> String {
+		let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256);
+		header.kid = Some(kid.to_string());
+		let claims = serde_json::json!({
+			"iss": iss,
+			"aud": aud,
+			"iat": Utc::now().timestamp(),
+			"exp": (Utc::now() + Duration::hours(1)).timestamp(),
+			"ns": "test",
+			"db": "test",
+			"tk": tk,
+		});
+		encode(&header, &claims, &EncodingKey::from_secret(kid.as_ref())).unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_token_jwks_inline() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let sess = Session::owner().with_ns("test").with_db("test");
+		let sql = format!(
+			"DEFINE TOKEN idp ON DB TYPE JWKS VALUE '{}' ISSUER 'https://idp' AUDIENCE 'surrealdb'",
+			jwks(&["one", "two"])
+		);
+		ds.execute(&sql, &sess, None).await.unwrap().remove(0).result.unwrap();
+
+		//
+		// Test with a token signed by one of the keys
+		//
+		for kid in ["one", "two"] {
+			let enc = jwks_token(kid, "idp", "https://idp", "surrealdb");
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_ok(), "Failed to signin with token: {:?}", res);
+			assert_eq!(sess.au.id(), "idp");
+			assert!(sess.au.is_db());
+			assert!(sess.au.has_role(&Role::Viewer), "Auth user expected to have Viewer role");
+		}
+
+		//
+		// Test with a token signed by an unknown key
+		//
+		{
+			let enc = jwks_token("three", "idp", "https://idp", "surrealdb");
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_err(), "Unexpected success signing in with token: {:?}", res);
+		}
+
+		//
+		// Test with an invalid issuer or audience
+		//
+		for enc in [
+			jwks_token("one", "idp", "https://other", "surrealdb"),
+			jwks_token("one", "idp", "https://idp", "other"),
+		] {
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_err(), "Unexpected success signing in with token: {:?}", res);
+		}
+
+		//
+		// Test with an invalid inline key set
+		//
+		{
+			let sql = "DEFINE TOKEN invalid ON DB TYPE JWKS VALUE '{ \"keys\": 1 }'";
+			let res = ds.execute(sql, &sess, None).await.unwrap().remove(0).result;
+
+			assert!(matches!(res, Err(Error::InvalidJwks { .. })), "Unexpected result: {:?}", res);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_token_jwks_file_rotation() {
+		let dir = temp_dir::TempDir::new().unwrap();
+		let path = dir.path().join("jwks.json");
+		std::fs::write(&path, jwks(&["one"])).unwrap();
+
+		let ds = Datastore::new("memory").await.unwrap();
+		let sess = Session::owner().with_ns("test").with_db("test");
+		let sql = format!("DEFINE TOKEN idp ON DB TYPE JWKS VALUE 'file://{}'", path.display());
+		ds.execute(&sql, &sess, None).await.unwrap().remove(0).result.unwrap();
+
+		//
+		// Test with a token signed by the current key
+		//
+		{
+			let enc = jwks_token("one", "idp", "https://idp", "surrealdb");
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_ok(), "Failed to signin with token: {:?}", res);
+		}
+
+		//
+		// Test with a token signed by a rotated key
+		//
+		{
+			std::fs::write(&path, jwks(&["two"])).unwrap();
+			let enc = jwks_token("two", "idp", "https://idp", "surrealdb");
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_ok(), "Failed to signin with token: {:?}", res);
+		}
+
+		//
+		// Test with a token signed by another key shortly after the rotation
+		//
+		{
+			std::fs::write(&path, jwks(&["three"])).unwrap();
+			let enc = jwks_token("three", "idp", "https://idp", "surrealdb");
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_err(), "Unexpected success signing in with token: {:?}", res);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_token_jwks_sources() {
+		let ds = Datastore::new("memory").await.unwrap();
+
+		//
+		// Test that a database user can not read a key set from a file
+		//
+		{
+			let sess = Session::for_level(("test", "test").into(), Role::Owner)
+				.with_ns("test")
+				.with_db("test");
+			let sql = "DEFINE TOKEN idp ON DB TYPE JWKS VALUE 'file:///etc/passwd'";
+			let res = ds.execute(sql, &sess, None).await.unwrap().remove(0).result;
+
+			assert!(matches!(res, Err(Error::IamError(_))), "Unexpected result: {:?}", res);
+		}
+
+		//
+		// Test that a key set can not be fetched from a url which is not allowed
+		//
+		{
+			let sess = Session::owner().with_ns("test").with_db("test");
+			let sql = "DEFINE TOKEN idp ON DB TYPE JWKS VALUE 'http://127.0.0.1:8000/jwks.json'";
+			let res = ds.execute(sql, &sess, None).await.unwrap().remove(0).result;
+
+			assert!(
+				matches!(res, Err(Error::NetTargetNotAllowed(_))),
+				"Unexpected result: {:?}",
+				res
+			);
+		}
+	}
+
+	#[cfg(feature = "http")]
+	#[tokio::test]
+	async fn test_token_jwks_http() {
+		use crate::dbs::capabilities::{NetTarget, Targets};
+		use wiremock::{
+			matchers::{method, path},
+			Mock, ResponseTemplate,
+		};
+
+		let server = wiremock::MockServer::start().await;
+		Mock::given(method("GET"))
+			.and(path("/.well-known/jwks.json"))
+			.respond_with(ResponseTemplate::new(200).set_body_string(jwks(&["one"])))
+			.expect(1)
+			.mount(&server)
+			.await;
+
+		let ds = Datastore::new("memory").await.unwrap().with_capabilities(
+			Capabilities::default().with_network_targets(Targets::Some(
+				[NetTarget::from_str(&server.address().to_string()).unwrap()].into(),
+			)),
+		);
+		let sess = Session::owner().with_ns("test").with_db("test");
+		let sql = format!(
+			"DEFINE TOKEN idp ON DB TYPE JWKS VALUE '{}/.well-known/jwks.json'",
+			server.uri()
+		);
+		ds.execute(&sql, &sess, None).await.unwrap().remove(0).result.unwrap();
+
+		// The key set is only fetched once
+		for _ in 0..2 {
+			let enc = jwks_token("one", "idp", "https://idp", "surrealdb");
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_ok(), "Failed to signin with token: {:?}", res);
+		}
+
+		server.verify().await;
+	}
+
 	#[test]
 	fn test_verify_pass() {
 		let salt = SaltString::generate(&mut rand::thread_rng());
@@ -0,0 +1,336 @@
+//! The progress of the indexes which are defined with `DEFINE INDEX ... CONCURRENTLY`,
+//! or which are rebuilt with `REBUILD INDEX`.
+//!
+//! Such an index is built in the background, one batch of records per transaction,
+//! while the records which are written in the meantime keep maintaining the index.
+//! The query planner only uses the index once all the records have been indexed.
+//!
+//! A rebuilt index keeps serving queries from its current entries, while the new
+//! entries are built under a separate storage name. Once they are complete, the
+//! definition of the index is switched to the new storage in a single transaction,
+//! and the previous entries are then removed in the background, batch by batch.
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction, Workable};
+use crate::doc::Document;
+use crate::err::Error;
+use crate::idx::VersionedSerdeState;
+use crate::key::thing;
+use crate::kvs;
+use crate::kvs::Key;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Object, Thing, Value};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The number of records indexed in each transaction
+const BUILD_BATCH_SIZE: u32 = 1000;
+
+/// The number of batches indexed on each tick of the datastore
+pub(crate) const BUILD_BATCHES_PER_TICK: u32 = 10;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 2)]
+pub(crate) struct IndexBuilding {
+	/// The key of the last record which has been indexed
+	pub(crate) cursor: Option<Key>,
+	/// The number of records which have been indexed
+	pub(crate) count: u64,
+	/// The error which stopped the build, if any
+	pub(crate) error: Option<String>,
+	/// The storage of the entries which a rebuild replaced, and which remain to be removed
+	#[revision(start = 2)]
+	pub(crate) purge: Option<String>,
+}
+
+impl VersionedSerdeState for IndexBuilding {}
+
+impl IndexBuilding {
+	/// Retrieves the progress of an index, if it is still being built
+	pub(crate) async fn get(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<Option<Self>, Error> {
+		let key = crate::key::root::ib::new(ns, db, tb, ix);
+		match tx.get(key).await? {
+			Some(val) => Ok(Some(Self::try_from_val(val)?)),
+			None => Ok(None),
+		}
+	}
+
+	/// Persists the progress of an index
+	pub(crate) async fn set(
+		&self,
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<(), Error> {
+		let key = crate::key::root::ib::new(ns, db, tb, ix);
+		tx.set(key, self.try_to_val()?).await
+	}
+
+	/// Stops the build of an index, reporting the error which prevents it from being built
+	pub(crate) async fn fail(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+		error: &Error,
+	) -> Result<(), Error> {
+		if let Some(mut state) = Self::get(tx, ns, db, tb, ix).await? {
+			state.error = Some(error.to_string());
+			state.set(tx, ns, db, tb, ix).await?;
+		}
+		Ok(())
+	}
+
+	/// Retrieves the progress of an index, if its entries are still being built.
+	///
+	/// Once a rebuilt index has been switched to its new entries, its progress
+	/// only tracks the removal of the previous entries, and the index is built.
+	pub(crate) async fn status(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<Option<Self>, Error> {
+		let state = Self::get(tx, ns, db, &ix.what, &ix.name).await?;
+		Ok(state.filter(|state| state.purge.is_none() || ix.rebuild.is_some()))
+	}
+
+	/// Starts rebuilding an index in the background.
+	///
+	/// An index which is complete keeps being used while its entries are rebuilt
+	/// under a separate storage. An index whose first build has not completed is
+	/// not used yet, so its build is restarted instead, like a rebuild which is
+	/// already in progress.
+	pub(crate) async fn rebuild(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let state = Self::get(tx, ns, db, &ix.what, &ix.name).await?;
+		// The entries replaced by a previous rebuild are still removed
+		let purge = state.as_ref().and_then(|state| state.purge.clone());
+		// Unless the first build is in progress, build the entries under a new storage
+		if ix.rebuild.is_none() && (state.is_none() || purge.is_some()) {
+			let rebuild = format!("{}#{}", ix.name.as_str(), Uuid::new_v4().simple());
+			let def = DefineIndexStatement {
+				rebuild: Some(rebuild.into()),
+				..ix.clone()
+			};
+			let key = crate::key::table::ix::new(ns, db, &ix.what, &ix.name);
+			tx.set(key, &def).await?;
+			// Clear the cache
+			let key = crate::key::table::ix::prefix(ns, db, &ix.what);
+			tx.clr(key).await?;
+		}
+		// Build the entries from the first record, without deleting any progress key
+		let state = Self {
+			purge,
+			..Self::default()
+		};
+		state.set(tx, ns, db, &ix.what, &ix.name).await
+	}
+
+	/// Removes all the entries of an index, whichever storage they are in, and stops its build
+	pub(crate) async fn remove(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<(), Error> {
+		let mut storages = vec![ix.to_owned()];
+		let key = crate::key::table::ix::new(ns, db, tb, ix);
+		if let Some(val) = tx.get(key).await? {
+			let def: DefineIndexStatement = val.into();
+			storages.extend(def.storage.map(|v| v.0));
+			storages.extend(def.rebuild.map(|v| v.0));
+		}
+		if let Some(state) = Self::get(tx, ns, db, tb, ix).await? {
+			storages.extend(state.purge);
+		}
+		for storage in storages.iter() {
+			let key = crate::key::index::all::new(ns, db, tb, storage);
+			tx.delp(key, u32::MAX).await?;
+		}
+		let key = crate::key::root::ib::new(ns, db, tb, ix);
+		tx.del(key).await
+	}
+
+	/// Lists the keys of the indexes which are being built, leaving out the builds which failed
+	pub(crate) async fn pending(tx: &mut kvs::Transaction) -> Result<Vec<Key>, Error> {
+		let beg = crate::key::root::ib::prefix();
+		let end = crate::key::root::ib::suffix();
+		let mut res = vec![];
+		for (k, v) in tx.scan(beg..end, u32::MAX).await? {
+			if Self::try_from_val(v)?.error.is_none() {
+				res.push(k);
+			}
+		}
+		Ok(res)
+	}
+
+	/// Checks if an index has been completely built, and can be used by the query planner
+	pub(crate) async fn is_ready(
+		tx: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<bool, Error> {
+		// The current entries of an index are complete while it is rebuilt
+		if !ix.concurrently || ix.rebuild.is_some() {
+			return Ok(true);
+		}
+		Ok(Self::status(tx, ns, db, ix).await?.is_none())
+	}
+
+	/// Indexes the next batch of records of the table, returning true once the index is built.
+	///
+	/// Every record of the batch is written again, so that a concurrent update of
+	/// one of these records conflicts with the batch, instead of leaving stale
+	/// index entries behind. The batch is then retried by the next build.
+	pub(crate) async fn next_batch(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		tb: &str,
+		ix: &str,
+	) -> Result<bool, Error> {
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Check that the index is still being built
+		let mut state = match Self::get(&mut run, opt.ns(), opt.db(), tb, ix).await? {
+			Some(state) if state.error.is_none() => state,
+			_ => return Ok(true),
+		};
+		// Get the current definition of the index
+		let key = crate::key::table::ix::new(opt.ns(), opt.db(), tb, ix);
+		let def: DefineIndexStatement = match run.get(key).await? {
+			Some(val) => val.into(),
+			None => DefineIndexStatement::default(),
+		};
+		// Remove a batch of the entries which a rebuild replaced
+		if let Some(storage) = &state.purge {
+			let beg: Key = crate::key::index::all::new(opt.ns(), opt.db(), tb, storage).into();
+			let mut end = beg.clone();
+			end.push(0xff);
+			let res = run.scan(beg.clone()..end.clone(), BUILD_BATCH_SIZE).await?;
+			for (k, _) in res.into_iter() {
+				run.del(k).await?;
+			}
+			if run.scan(beg..end, 1).await?.is_empty() {
+				state.purge = None;
+			}
+			// The index is done once there is nothing left to rebuild
+			if state.purge.is_none() && def.rebuild.is_none() {
+				let key = crate::key::root::ib::new(opt.ns(), opt.db(), tb, ix);
+				run.del(key).await?;
+				return Ok(true);
+			}
+			state.set(&mut run, opt.ns(), opt.db(), tb, ix).await?;
+			return Ok(false);
+		}
+		// Check that the index is still built in the background
+		if !def.concurrently && def.rebuild.is_none() {
+			let key = crate::key::root::ib::new(opt.ns(), opt.db(), tb, ix);
+			run.del(key).await?;
+			return Ok(true);
+		}
+		// A rebuilt index is built under its new storage
+		let build = def.shadow().unwrap_or_else(|| def.clone());
+		// Get the next batch of records
+		let beg = match state.cursor.take() {
+			Some(mut key) => {
+				key.push(0x00);
+				key
+			}
+			None => thing::prefix(opt.ns(), opt.db(), tb),
+		};
+		let end = thing::suffix(opt.ns(), opt.db(), tb);
+		let res = run.scan(beg..end, BUILD_BATCH_SIZE).await?;
+		// Release the transaction
+		drop(run);
+		// Index the records
+		for (k, v) in res.iter() {
+			let key: thing::Thing = k.into();
+			let val: Value = v.into();
+			let rid = Thing::from((key.tb, key.id));
+			txn.lock().await.set(k.clone(), v.clone()).await?;
+			let doc = Document::new(None, Some(&rid), None, &val, Workable::Normal);
+			doc.build_index(ctx, opt, txn, &build).await?;
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Otherwise store the progress
+		if res.len() == BUILD_BATCH_SIZE as usize {
+			state.count += res.len() as u64;
+			state.cursor = res.last().map(|(k, _)| k.clone());
+			state.set(&mut run, opt.ns(), opt.db(), tb, ix).await?;
+			return Ok(false);
+		}
+		// The index is built once all the records have been indexed
+		let key = crate::key::root::ib::new(opt.ns(), opt.db(), tb, ix);
+		match def.rebuild {
+			// Switch a rebuilt index to its new entries, and remove the previous ones afterwards
+			Some(ref rebuild) => {
+				let state = Self {
+					purge: Some(def.storage().to_owned()),
+					..Self::default()
+				};
+				state.set(&mut run, opt.ns(), opt.db(), tb, ix).await?;
+				let def = DefineIndexStatement {
+					storage: Some(rebuild.clone()),
+					rebuild: None,
+					..def.clone()
+				};
+				let key = crate::key::table::ix::new(opt.ns(), opt.db(), tb, ix);
+				run.set(key, &def).await?;
+				// Clear the cache
+				let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), tb);
+				run.clr(key).await?;
+				Ok(false)
+			}
+			None => {
+				run.del(key).await?;
+				Ok(true)
+			}
+		}
+	}
+}
+
+impl From<Option<IndexBuilding>> for Value {
+	fn from(state: Option<IndexBuilding>) -> Self {
+		let mut res = Object::default();
+		match state {
+			None => {
+				res.insert("status".to_owned(), Value::from("ready"));
+			}
+			Some(IndexBuilding {
+				count,
+				error: Some(error),
+				..
+			}) => {
+				res.insert("status".to_owned(), Value::from("error"));
+				res.insert("count".to_owned(), Value::from(count));
+				res.insert("error".to_owned(), Value::from(error));
+			}
+			Some(IndexBuilding {
+				count,
+				..
+			}) => {
+				res.insert("status".to_owned(), Value::from("indexing"));
+				res.insert("count".to_owned(), Value::from(count));
+			}
+		}
+		Value::from(res)
+	}
+}
@@ -17,6 +17,7 @@ use crate::idx::ft::postings::Postings;
 use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::{TermDocs, TermsDocs};
 use crate::idx::ft::terms::{TermId, Terms};
+use crate::idx::planner::stats::IndexStatistics;
 use crate::idx::trees::btree::BStatistics;
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::{IndexKeyBase, VersionedSerdeState};
@@ -72,6 +73,20 @@ pub(crate) struct FtStatistics {
 	postings: BStatistics,
 }
 
+impl From<&FtStatistics> for IndexStatistics {
+	fn from(stats: &FtStatistics) -> Self {
+		Self {
+			keys_count: stats.doc_ids.keys_count,
+			distinct_count: stats.terms.keys_count,
+			total_size: stats.doc_ids.total_size
+				+ stats.terms.total_size
+				+ stats.doc_lengths.total_size
+				+ stats.postings.total_size,
+			..Default::default()
+		}
+	}
+}
+
 impl From<FtStatistics> for Value {
 	fn from(stats: FtStatistics) -> Self {
 		let mut res = Object::default();
@@ -1,6 +1,8 @@
+pub(crate) mod builder;
 pub(crate) mod docids;
 pub(crate) mod ft;
 pub(crate) mod planner;
+pub(crate) mod spatial;
 pub mod trees;
 
 use crate::dbs::Options;
@@ -20,11 +22,14 @@ use crate::key::index::bs::Bs;
 use crate::key::index::bt::Bt;
 use crate::key::index::bu::Bu;
 use crate::key::index::vm::Vm;
+use crate::key::index::Index;
 use crate::kvs::{Key, Val};
 use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Array, Id};
 use revision::Revisioned;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::ops::Range;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Default)]
@@ -47,7 +52,7 @@ impl IndexKeyBase {
 				ns: opt.ns().to_string(),
 				db: opt.db().to_string(),
 				tb: ix.what.to_string(),
-				ix: ix.name.to_string(),
+				ix: ix.storage().to_string(),
 			}),
 		}
 	}
@@ -184,6 +189,45 @@ impl IndexKeyBase {
 		)
 		.into()
 	}
+
+	fn new_index_key(&self, fd: &Array, id: &Id) -> Key {
+		Index::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			fd,
+			Some(id),
+		)
+		.into()
+	}
+
+	/// The range of the index entries whose leading values are the given values
+	fn new_index_composite_range(&self, fd: &Array) -> Range<Key> {
+		let (ns, db, tb, ix) = self.names();
+		Index::prefix_ids_composite_beg(ns, db, tb, ix, fd)
+			..Index::prefix_ids_composite_end(ns, db, tb, ix, fd)
+	}
+
+	/// The range of the index entries having a string value starting with the given string
+	fn new_index_starts_with_range(&self, fd: &Array) -> Range<Key> {
+		let (ns, db, tb, ix) = self.names();
+		Index::prefix_ids_starts_with_beg(ns, db, tb, ix, fd)
+			..Index::prefix_ids_starts_with_end(ns, db, tb, ix, fd)
+	}
+
+	fn names(&self) -> (&str, &str, &str, &str) {
+		(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+		)
+	}
+
+	fn table(&self) -> &str {
+		self.inner.tb.as_str()
+	}
 }
 
 /// This trait provides `Revision` based default implementations for serialization/deserialization
@@ -0,0 +1,239 @@
+//! The cost model of the query planner.
+//!
+//! Costs are expressed in an arbitrary unit, where reading and filtering
+//! one record during a table scan costs 1. The estimates rely on the
+//! statistics persisted by `ANALYZE INDEX`, so plans are only costed
+//! when every index involved in the plan has been analyzed, and when
+//! the statistics of the standard and unique indexes are not stale. When the
+//! statistics include a histogram of the values, it is used to estimate
+//! the records matched by an equality or a range.
+use crate::idx::planner::plan::{IndexAccess, IndexOperator, RangeValue};
+use crate::idx::planner::tree::{IndexMap, IndexRef};
+use crate::sql::index::Index;
+use crate::sql::{Array, Object, Value};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// The cost of reading and filtering a record during a table scan
+const TABLE_ROW_COST: f64 = 1.0;
+/// The cost of starting an index scan
+const INDEX_SEEK_COST: f64 = 2.0;
+/// The cost of reading an index entry
+const INDEX_ROW_COST: f64 = 0.2;
+/// The cost of fetching and filtering a record found by an index
+const FETCH_ROW_COST: f64 = 1.0;
+/// The fraction of records matched by a range with a single bound
+const RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+/// The fraction of records matched by a range with both bounds
+const BOUNDED_RANGE_SELECTIVITY: f64 = 1.0 / 4.0;
+/// The fraction of documents matched by a full-text search
+const MATCHES_SELECTIVITY: f64 = 0.1;
+/// The fraction of records matched by a spatial search
+const SPATIAL_SELECTIVITY: f64 = 0.1;
+
+/// The estimated number of records returned by a plan, and its estimated cost
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Estimate {
+	pub(super) rows: f64,
+	pub(super) cost: f64,
+}
+
+impl From<&Estimate> for Value {
+	fn from(e: &Estimate) -> Self {
+		Value::from(Object::from(HashMap::from([
+			("rows", Value::from(e.rows.round() as u64)),
+			("cost", Value::from((e.cost * 100.0).round() / 100.0)),
+		])))
+	}
+}
+
+pub(super) struct CostModel<'a> {
+	im: &'a IndexMap,
+	/// The estimated number of records in the table
+	table_rows: f64,
+}
+
+impl<'a> CostModel<'a> {
+	/// Returns a cost model if every given index has been analyzed
+	pub(super) fn new(im: &'a IndexMap, mut irs: impl Iterator<Item = IndexRef>) -> Option<Self> {
+		if !irs.all(|ir| im.statistics.contains_key(&ir)) {
+			return None;
+		}
+		// Every record of the table is in a standard or unique index,
+		// while the other kinds of indexes count documents, terms or cells
+		let table_rows = im
+			.statistics
+			.iter()
+			.filter(|(ir, _)| {
+				matches!(im.definitions.get(ir).map(|ix| &ix.index), Some(Index::Idx | Index::Uniq))
+			})
+			.map(|(_, s)| s.keys_count)
+			.max()? as f64;
+		Some(Self {
+			im,
+			table_rows,
+		})
+	}
+
+	/// Estimates a scan of the whole table
+	pub(super) fn table(&self) -> Estimate {
+		Estimate {
+			rows: self.table_rows,
+			cost: self.table_rows * TABLE_ROW_COST,
+		}
+	}
+
+	/// Estimates the iteration of an index
+	pub(super) fn index(&self, a: &IndexAccess) -> Estimate {
+		let rows = self.rows(a);
+		Estimate {
+			rows,
+			cost: INDEX_SEEK_COST + rows * (INDEX_ROW_COST + FETCH_ROW_COST),
+		}
+	}
+
+	/// Estimates the intersection of the records of several indexes,
+	/// assuming that the expressions are independent
+	pub(super) fn intersection(&self, accesses: &[&IndexAccess]) -> Estimate {
+		let mut rows = self.table_rows;
+		let mut cost = 0.0;
+		for a in accesses {
+			let r = self.rows(a);
+			rows *= r / self.table_rows.max(1.0);
+			cost += INDEX_SEEK_COST + r * INDEX_ROW_COST;
+		}
+		Estimate {
+			rows,
+			cost: cost + rows * FETCH_ROW_COST,
+		}
+	}
+
+	/// Estimates the union of several plans
+	pub(super) fn union(&self, estimates: &[Estimate]) -> Estimate {
+		Estimate {
+			rows: estimates.iter().map(|e| e.rows).sum::<f64>().min(self.table_rows),
+			cost: estimates.iter().map(|e| e.cost).sum(),
+		}
+	}
+
+	/// Checks if an index can be intersected with another index
+	pub(super) fn is_intersectable(&self, a: &IndexAccess) -> bool {
+		let ir = match a {
+			IndexAccess::Single(_, io) => io.ir(),
+			IndexAccess::Range(ir, _) => *ir,
+		};
+		matches!(self.im.definitions.get(&ir).map(|ix| &ix.index), Some(Index::Idx | Index::Uniq))
+	}
+
+	/// Estimates the number of records returned by an index
+	fn rows(&self, a: &IndexAccess) -> f64 {
+		let (ir, rows) = match a {
+			IndexAccess::Single(_, io) => {
+				let ir = io.ir();
+				let rows = match io.op() {
+					IndexOperator::Equality(a) => match self.im.definitions.get(&ir) {
+						Some(ix) if ix.index == Index::Uniq => 1.0,
+						_ => self.equal_rows(ir, a),
+					},
+					IndexOperator::RangePart(_, _) => match io.range() {
+						Some((from, to)) => {
+							self.range_rows(ir, &Array::default(), &from, &to, RANGE_SELECTIVITY)
+						}
+						None => self.keys(ir) * RANGE_SELECTIVITY,
+					},
+					IndexOperator::Matches(_, _) => self.table_rows * MATCHES_SELECTIVITY,
+					IndexOperator::Knn(_, k) => *k as f64,
+					IndexOperator::Spatial(_, _) => self.table_rows * SPATIAL_SELECTIVITY,
+				};
+				(ir, rows)
+			}
+			IndexAccess::Range(ir, rq) => {
+				// The equalities on the leading columns of a composite index narrow the range
+				let selectivity =
+					if rq.prefix.is_empty() && (rq.from.value.is_none() || rq.to.value.is_none()) {
+						RANGE_SELECTIVITY
+					} else {
+						BOUNDED_RANGE_SELECTIVITY
+					};
+				(*ir, self.range_rows(*ir, &rq.prefix, &rq.from, &rq.to, selectivity))
+			}
+		};
+		// The entries of the other kinds of indexes are not kept up to date
+		match self.im.definitions.get(&ir).map(|ix| &ix.index) {
+			Some(Index::Idx | Index::Uniq) => rows.min(self.keys(ir)),
+			_ => rows.min(self.table_rows),
+		}
+	}
+
+	/// Estimates the number of records having a value, using the histogram if any
+	fn equal_rows(&self, ir: IndexRef, fd: &Array) -> f64 {
+		let Some(stats) = self.im.statistics.get(&ir) else {
+			return 0.0;
+		};
+		if stats.histogram.is_empty() {
+			return self.keys(ir) / self.distinct(ir);
+		}
+		// A value which is not in the histogram was not in the index when it was analyzed
+		match stats.histogram.iter().find(|b| b.contains(fd)) {
+			Some(b) => b.count as f64 / b.distinct.max(1) as f64,
+			None => 1.0,
+		}
+	}
+
+	/// Estimates the number of records within a range, using the histogram if any.
+	/// A bucket partially covered by the range is assumed to be half covered.
+	fn range_rows(
+		&self,
+		ir: IndexRef,
+		prefix: &Array,
+		from: &RangeValue,
+		to: &RangeValue,
+		selectivity: f64,
+	) -> f64 {
+		let Some(stats) = self.im.statistics.get(&ir) else {
+			return 0.0;
+		};
+		if stats.histogram.is_empty() {
+			return self.keys(ir) * selectivity;
+		}
+		let mut rows = 0.0;
+		for b in &stats.histogram {
+			let min = Self::position(&b.min, prefix, from, to);
+			let max = Self::position(&b.max, prefix, from, to);
+			if min == Ordering::Equal && max == Ordering::Equal {
+				rows += b.count as f64;
+			} else if min != Ordering::Greater && max != Ordering::Less {
+				rows += b.count as f64 / 2.0;
+			}
+		}
+		rows
+	}
+
+	/// Checks if the indexed values are below (Less), within (Equal) or above (Greater) a range.
+	/// The range starts with the values of the leading columns, followed by the range of the next column.
+	fn position(fd: &Array, prefix: &Array, from: &RangeValue, to: &RangeValue) -> Ordering {
+		let len = prefix.len().min(fd.len());
+		match fd[..len].partial_cmp(&prefix[..]) {
+			Some(Ordering::Equal) | None => {}
+			Some(o) => return o,
+		}
+		let Some(v) = fd.get(len) else {
+			return Ordering::Equal;
+		};
+		if !from.value.is_none() && (v.lt(&from.value) || (!from.inclusive && v.eq(&from.value))) {
+			return Ordering::Less;
+		}
+		if !to.value.is_none() && (v.gt(&to.value) || (!to.inclusive && v.eq(&to.value))) {
+			return Ordering::Greater;
+		}
+		Ordering::Equal
+	}
+
+	fn keys(&self, ir: IndexRef) -> f64 {
+		self.im.statistics.get(&ir).map(|s| s.keys_count).unwrap_or_default() as f64
+	}
+
+	fn distinct(&self, ir: IndexRef) -> f64 {
+		self.im.statistics.get(&ir).map(|s| s.distinct_count).unwrap_or_default().max(1) as f64
+	}
+}
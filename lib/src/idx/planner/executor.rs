@@ -5,13 +5,18 @@ use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::terms::TermId;
 use crate::idx::ft::{FtIndex, MatchRef};
+use crate::idx::planner::cost::Estimate;
 use crate::idx::planner::iterators::{
-	IndexEqualThingIterator, IndexRangeThingIterator, KnnThingIterator, MatchesThingIterator,
-	ThingIterator, UniqueEqualThingIterator, UniqueRangeThingIterator,
+	IndexEqualThingIterator, IndexRangeThingIterator, IntersectionThingIterator, KnnThingIterator,
+	MatchesThingIterator, SpatialKnnThingIterator, SpatialThingIterator, ThingIterator,
+	UniqueEqualThingIterator, UniqueRangeThingIterator,
 };
 use crate::idx::planner::plan::IndexOperator::Matches;
-use crate::idx::planner::plan::{IndexOperator, IndexOption, RangeValue};
+use crate::idx::planner::plan::{
+	IndexAccess, IndexOperator, IndexOption, IndexOrder, RangeQueryBuilder,
+};
 use crate::idx::planner::tree::{IndexMap, IndexRef};
+use crate::idx::spatial::SpatialIndex;
 use crate::idx::trees::mtree::MTreeIndex;
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::IndexKeyBase;
@@ -20,8 +25,9 @@ use crate::kvs::Key;
 use crate::sql::index::Index;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Array, Expression, Object, Table, Thing, Value};
+use geo::Point;
 use roaring::RoaringTreemap;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -33,27 +39,79 @@ pub(crate) struct QueryExecutor {
 	it_entries: Vec<IteratorEntry>,
 	index_definitions: HashMap<IndexRef, DefineIndexStatement>,
 	mt_exp: HashMap<Arc<Expression>, MtEntry>,
+	/// The records nearest to a point, found by a spatial index
+	sp_exp: HashMap<Arc<Expression>, VecDeque<Thing>>,
+	/// The estimates of the iterators, when the indexes have been analyzed
+	estimates: HashMap<IteratorRef, Estimate>,
+	table_estimate: Option<Estimate>,
 }
 
 pub(crate) type IteratorRef = u16;
 
 pub(super) enum IteratorEntry {
 	Single(Arc<Expression>, IndexOption),
-	Range(HashSet<Arc<Expression>>, IndexRef, RangeValue, RangeValue),
+	/// The range is iterated in the order of the ORDER BY clause, if any
+	Range(IndexRef, RangeQueryBuilder, Option<IndexOrder>),
+	Intersection(Vec<IteratorEntry>),
 }
 
 impl IteratorEntry {
-	pub(super) fn explain(&self, e: &mut HashMap<&str, Value>) -> IndexRef {
+	/// Iterates the whole index, in the order of the ORDER BY clause
+	pub(super) fn ordered(ir: IndexRef, order: IndexOrder) -> Self {
+		Self::Range(ir, RangeQueryBuilder::default(), Some(order))
+	}
+
+	/// Makes the iterator return the records in the order of the given index,
+	/// returns false if the records can't be returned in this order
+	pub(super) fn set_order(&mut self, ir: IndexRef, order: IndexOrder) -> bool {
+		match self {
+			// Every record matching an equality has the same value
+			Self::Single(_, io) => io.ir() == ir && matches!(io.op(), IndexOperator::Equality(_)),
+			Self::Range(r, rq, o) if *r == ir && rq.prefix.is_empty() => {
+				*o = Some(order);
+				true
+			}
+			_ => false,
+		}
+	}
+
+	/// Checks if the iterator may return the same record more than once
+	pub(super) fn has_duplicates(&self) -> bool {
+		match self {
+			// A record is found in each of the cells covering its geometries
+			Self::Single(_, io) => matches!(io.op(), IndexOperator::Spatial(..)),
+			Self::Range(..) => false,
+			Self::Intersection(v) => v.iter().any(Self::has_duplicates),
+		}
+	}
+
+	pub(super) fn explain(&self, e: &mut HashMap<&str, Value>) -> Option<IndexRef> {
 		match self {
 			Self::Single(_, io) => {
 				io.explain(e);
-				io.ir()
+				Some(io.ir())
 			}
-			Self::Range(_, ir, from, to) => {
-				e.insert("from", Value::from(from));
-				e.insert("to", Value::from(to));
-				*ir
+			Self::Range(ir, rq, order) => {
+				if !rq.prefix.is_empty() {
+					e.insert("prefix", Value::from(rq.prefix.clone()));
+				}
+				e.insert("from", Value::from(&rq.from));
+				e.insert("to", Value::from(&rq.to));
+				if let Some(o) = order {
+					e.insert("order", Value::from(*o));
+				}
+				Some(*ir)
 			}
+			Self::Intersection(_) => None,
+		}
+	}
+}
+
+impl From<IndexAccess> for IteratorEntry {
+	fn from(a: IndexAccess) -> Self {
+		match a {
+			IndexAccess::Single(exp, io) => Self::Single(exp, io),
+			IndexAccess::Range(ir, rq) => Self::Range(ir, rq, None),
 		}
 	}
 }
@@ -71,6 +129,7 @@ impl QueryExecutor {
 		let mut ft_map = HashMap::default();
 		let mut mt_map: HashMap<IndexRef, MTreeIndex> = HashMap::default();
 		let mut mt_exp = HashMap::default();
+		let mut sp_exp = HashMap::default();
 
 		// Create all the instances of FtIndex
 		// Build the FtEntries and map them to Expressions and MatchRef
@@ -120,6 +179,14 @@ impl QueryExecutor {
 							mt_exp.insert(exp, entry);
 						}
 					}
+					Index::Spatial => {
+						if let IndexOperator::Knn(a, k) = io.op() {
+							if let Some(p) = Self::point(a) {
+								let si = SpatialIndex::new(IndexKeyBase::new(opt, idx_def));
+								sp_exp.insert(exp, si.knn(&mut run, p, *k).await?);
+							}
+						}
+					}
 					_ => {}
 				}
 			}
@@ -133,9 +200,20 @@ impl QueryExecutor {
 			it_entries: Vec::new(),
 			index_definitions: im.definitions,
 			mt_exp,
+			sp_exp,
+			estimates: HashMap::default(),
+			table_estimate: None,
 		})
 	}
 
+	/// Returns the point of a KNN search on a spatial index
+	fn point(a: &Array) -> Option<Point<f64>> {
+		match a.as_slice() {
+			[Value::Number(x), Value::Number(y)] => Some(Point::new(x.to_float(), y.to_float())),
+			_ => None,
+		}
+	}
+
 	pub(crate) async fn knn(
 		&self,
 		_txn: &Transaction,
@@ -148,10 +226,29 @@ impl QueryExecutor {
 		})
 	}
 
-	pub(super) fn add_iterator(&mut self, it_entry: IteratorEntry) -> IteratorRef {
-		let ir = self.it_entries.len();
+	pub(super) fn add_iterator(
+		&mut self,
+		it_entry: IteratorEntry,
+		estimate: Option<Estimate>,
+	) -> IteratorRef {
+		let ir = self.it_entries.len() as IteratorRef;
 		self.it_entries.push(it_entry);
-		ir as IteratorRef
+		if let Some(e) = estimate {
+			self.estimates.insert(ir, e);
+		}
+		ir
+	}
+
+	pub(super) fn set_table_estimate(&mut self, estimate: Option<Estimate>) {
+		self.table_estimate = estimate;
+	}
+
+	pub(crate) fn estimate(&self, ir: IteratorRef) -> Option<Value> {
+		self.estimates.get(&ir).map(Value::from)
+	}
+
+	pub(crate) fn table_estimate(&self) -> Option<Value> {
+		self.table_estimate.as_ref().map(Value::from)
 	}
 
 	pub(crate) fn is_distinct(&self, ir: IteratorRef) -> bool {
@@ -161,25 +258,31 @@ impl QueryExecutor {
 	pub(crate) fn is_iterator_expression(&self, ir: IteratorRef, exp: &Expression) -> bool {
 		match self.it_entries.get(ir as usize) {
 			Some(IteratorEntry::Single(e, ..)) => exp.eq(e.as_ref()),
-			Some(IteratorEntry::Range(es, ..)) => es.contains(exp),
+			Some(IteratorEntry::Range(_, rq, _)) => rq.exps.contains(exp),
 			_ => false,
 		}
 	}
 
 	pub(crate) fn explain(&self, itr: IteratorRef) -> Value {
 		match self.it_entries.get(itr as usize) {
-			Some(ie) => {
-				let mut e = HashMap::default();
-				let ir = ie.explain(&mut e);
-				if let Some(ix) = self.index_definitions.get(&ir) {
-					e.insert("index", Value::from(ix.name.0.to_owned()));
-				}
-				Value::from(Object::from(e))
-			}
+			Some(ie) => self.explain_entry(ie),
 			None => Value::None,
 		}
 	}
 
+	fn explain_entry(&self, ie: &IteratorEntry) -> Value {
+		let mut e = HashMap::default();
+		if let IteratorEntry::Intersection(ies) = ie {
+			let v: Vec<Value> = ies.iter().map(|ie| self.explain_entry(ie)).collect();
+			e.insert("intersection", Value::from(v));
+		} else if let Some(ir) = ie.explain(&mut e) {
+			if let Some(ix) = self.index_definitions.get(&ir) {
+				e.insert("index", Value::from(ix.name.0.to_owned()));
+			}
+		}
+		Value::from(Object::from(e))
+	}
+
 	fn get_match_ref(match_ref: &Value) -> Option<MatchRef> {
 		if let Value::Number(n) = match_ref {
 			let m = n.to_int() as u8;
@@ -205,13 +308,24 @@ impl QueryExecutor {
 								..
 							} => self.new_search_index_iterator(ir, io.clone()).await,
 							Index::MTree(_) => Ok(self.new_mtree_index_knn_iterator(ir)),
+							Index::Spatial => Ok(self.new_spatial_index_iterator(opt, ir, ix, io)),
 						}
 					} else {
 						Ok(None)
 					}
 				}
-				IteratorEntry::Range(_, ir, from, to) => {
-					Ok(self.new_range_iterator(opt, *ir, from, to)?)
+				IteratorEntry::Range(ir, rq, order) => {
+					Ok(self.new_range_iterator(opt, *ir, rq, *order)?)
+				}
+				IteratorEntry::Intersection(ies) => {
+					let mut its = Vec::with_capacity(ies.len());
+					for ie in ies {
+						match self.new_intersected_iterator(opt, ie)? {
+							Some(it) => its.push(it),
+							None => return Ok(None),
+						}
+					}
+					Ok(Some(ThingIterator::Intersection(IntersectionThingIterator::new(its))))
 				}
 			}
 		} else {
@@ -219,6 +333,25 @@ impl QueryExecutor {
 		}
 	}
 
+	/// Intersections are only made of standard and unique indexes
+	fn new_intersected_iterator(
+		&self,
+		opt: &Options,
+		ie: &IteratorEntry,
+	) -> Result<Option<ThingIterator>, Error> {
+		match ie {
+			IteratorEntry::Single(_, io) => match self.index_definitions.get(&io.ir()) {
+				Some(ix) if ix.index == Index::Idx => Self::new_index_iterator(opt, ix, io.clone()),
+				Some(ix) if ix.index == Index::Uniq => {
+					Self::new_unique_index_iterator(opt, ix, io.clone())
+				}
+				_ => Ok(None),
+			},
+			IteratorEntry::Range(ir, rq, order) => self.new_range_iterator(opt, *ir, rq, *order),
+			IteratorEntry::Intersection(_) => Ok(None),
+		}
+	}
+
 	fn new_index_iterator(
 		opt: &Options,
 		ix: &DefineIndexStatement,
@@ -228,7 +361,12 @@ impl QueryExecutor {
 			IndexOperator::Equality(array) => {
 				Ok(Some(ThingIterator::IndexEqual(IndexEqualThingIterator::new(opt, ix, array)?)))
 			}
-			IndexOperator::RangePart(_, _) => Ok(None), // TODO
+			IndexOperator::RangePart(_, _) => Ok(io.range().map(|(from, to)| {
+				let prefix = Array::default();
+				ThingIterator::IndexRange(IndexRangeThingIterator::new(
+					opt, ix, &prefix, &from, &to, false,
+				))
+			})),
 			_ => Ok(None),
 		}
 	}
@@ -237,19 +375,22 @@ impl QueryExecutor {
 		&self,
 		opt: &Options,
 		ir: IndexRef,
-		from: &RangeValue,
-		to: &RangeValue,
+		rq: &RangeQueryBuilder,
+		order: Option<IndexOrder>,
 	) -> Result<Option<ThingIterator>, Error> {
+		let reverse = order.is_some_and(|o| o.is_reverse());
+		let (prefix, from, to) = (&rq.prefix, &rq.from, &rq.to);
 		if let Some(ix) = self.index_definitions.get(&ir) {
 			match ix.index {
-				Index::Idx => {
-					return Ok(Some(ThingIterator::IndexRange(IndexRangeThingIterator::new(
-						opt, ix, from, to,
+				Index::Uniq if ix.cols.len() == 1 => {
+					return Ok(Some(ThingIterator::UniqueRange(UniqueRangeThingIterator::new(
+						opt, ix, from, to, reverse,
 					))))
 				}
-				Index::Uniq => {
-					return Ok(Some(ThingIterator::UniqueRange(UniqueRangeThingIterator::new(
-						opt, ix, from, to,
+				// The keys of a composite unique index are scanned like the keys of a standard index
+				Index::Idx | Index::Uniq => {
+					return Ok(Some(ThingIterator::IndexRange(IndexRangeThingIterator::new(
+						opt, ix, prefix, from, to, reverse,
 					))))
 				}
 				_ => {}
@@ -267,9 +408,11 @@ impl QueryExecutor {
 			IndexOperator::Equality(array) => {
 				Ok(Some(ThingIterator::UniqueEqual(UniqueEqualThingIterator::new(opt, ix, array)?)))
 			}
-			IndexOperator::RangePart(_, _) => {
-				todo!()
-			}
+			IndexOperator::RangePart(_, _) => Ok(io.range().map(|(from, to)| {
+				ThingIterator::UniqueRange(UniqueRangeThingIterator::new(
+					opt, ix, &from, &to, false,
+				))
+			})),
 			_ => Ok(None),
 		}
 	}
@@ -302,6 +445,32 @@ impl QueryExecutor {
 		None
 	}
 
+	fn new_spatial_index_iterator(
+		&self,
+		opt: &Options,
+		ir: IteratorRef,
+		ix: &DefineIndexStatement,
+		io: &IndexOption,
+	) -> Option<ThingIterator> {
+		match io.op() {
+			IndexOperator::Knn(_, _) => {
+				if let Some(IteratorEntry::Single(exp, ..)) = self.it_entries.get(ir as usize) {
+					if let Some(res) = self.sp_exp.get(exp.as_ref()) {
+						let it = SpatialKnnThingIterator::new(res.clone());
+						return Some(ThingIterator::SpatialKnn(it));
+					}
+				}
+				None
+			}
+			IndexOperator::Spatial(_, Value::Geometry(g)) => {
+				let si = SpatialIndex::new(IndexKeyBase::new(opt, ix));
+				let it = SpatialThingIterator::new(&ix.what, si.search_ranges(g));
+				Some(ThingIterator::Spatial(it))
+			}
+			_ => None,
+		}
+	}
+
 	pub(crate) async fn matches(
 		&self,
 		txn: &Transaction,
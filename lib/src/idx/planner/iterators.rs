@@ -8,8 +8,10 @@ use crate::key::index::Index;
 use crate::kvs::Key;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Array, Thing, Value};
+use async_recursion::async_recursion;
 use roaring::RoaringTreemap;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use std::ops::Range;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -20,9 +22,14 @@ pub(crate) enum ThingIterator {
 	UniqueRange(UniqueRangeThingIterator),
 	Matches(MatchesThingIterator),
 	Knn(KnnThingIterator),
+	Intersection(IntersectionThingIterator),
+	Spatial(SpatialThingIterator),
+	SpatialKnn(SpatialKnnThingIterator),
 }
 
 impl ThingIterator {
+	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
 	pub(crate) async fn next_batch(
 		&mut self,
 		tx: &Transaction,
@@ -35,6 +42,9 @@ impl ThingIterator {
 			ThingIterator::UniqueRange(i) => i.next_batch(tx, size).await,
 			ThingIterator::Matches(i) => i.next_batch(tx, size).await,
 			ThingIterator::Knn(i) => i.next_batch(tx, size).await,
+			ThingIterator::Intersection(i) => i.next_batch(tx, size).await,
+			ThingIterator::Spatial(i) => i.next_batch(tx, size).await,
+			ThingIterator::SpatialKnn(i) => Ok(i.next_batch(size)),
 		}
 	}
 }
@@ -46,8 +56,8 @@ pub(crate) struct IndexEqualThingIterator {
 
 impl IndexEqualThingIterator {
 	pub(super) fn new(opt: &Options, ix: &DefineIndexStatement, v: &Array) -> Result<Self, Error> {
-		let beg = Index::prefix_ids_beg(opt.ns(), opt.db(), &ix.what, &ix.name, v);
-		let end = Index::prefix_ids_end(opt.ns(), opt.db(), &ix.what, &ix.name, v);
+		let beg = Index::prefix_ids_beg(opt.ns(), opt.db(), &ix.what, ix.storage(), v);
+		let end = Index::prefix_ids_end(opt.ns(), opt.db(), &ix.what, ix.storage(), v);
 		Ok(Self {
 			beg,
 			end,
@@ -117,43 +127,104 @@ impl RangeScan {
 
 pub(crate) struct IndexRangeThingIterator {
 	r: RangeScan,
+	/// The range is iterated in descending order
+	reverse: bool,
 }
 
 impl IndexRangeThingIterator {
 	pub(super) fn new(
 		opt: &Options,
 		ix: &DefineIndexStatement,
+		prefix: &Array,
 		from: &RangeValue,
 		to: &RangeValue,
+		reverse: bool,
 	) -> Self {
-		let beg = Self::compute_beg(opt, ix, from);
-		let end = Self::compute_end(opt, ix, to);
+		let (beg, end) = if ix.cols.len() > 1 {
+			(
+				Self::compute_composite_beg(opt, ix, prefix, from),
+				Self::compute_composite_end(opt, ix, prefix, to),
+			)
+		} else {
+			(Self::compute_beg(opt, ix, from), Self::compute_end(opt, ix, to))
+		};
 		Self {
 			r: RangeScan::new(beg, from.inclusive, end, to.inclusive),
+			reverse,
+		}
+	}
+
+	/// The values of the leading columns are followed by the lower bound of the next column
+	fn compute_composite_beg(
+		opt: &Options,
+		ix: &DefineIndexStatement,
+		prefix: &Array,
+		from: &RangeValue,
+	) -> Vec<u8> {
+		let mut fd = prefix.clone();
+		if from.value == Value::None {
+			return Index::prefix_ids_composite_beg(
+				opt.ns(),
+				opt.db(),
+				&ix.what,
+				ix.storage(),
+				&fd,
+			);
+		}
+		fd.push(from.value.to_owned());
+		if from.inclusive {
+			Index::prefix_ids_composite_beg(opt.ns(), opt.db(), &ix.what, ix.storage(), &fd)
+		} else {
+			Index::prefix_ids_composite_end(opt.ns(), opt.db(), &ix.what, ix.storage(), &fd)
+		}
+	}
+
+	/// The values of the leading columns are followed by the upper bound of the next column
+	fn compute_composite_end(
+		opt: &Options,
+		ix: &DefineIndexStatement,
+		prefix: &Array,
+		to: &RangeValue,
+	) -> Vec<u8> {
+		let mut fd = prefix.clone();
+		if to.value == Value::None {
+			return Index::prefix_ids_composite_end(
+				opt.ns(),
+				opt.db(),
+				&ix.what,
+				ix.storage(),
+				&fd,
+			);
+		}
+		fd.push(to.value.to_owned());
+		if to.inclusive {
+			Index::prefix_ids_composite_end(opt.ns(), opt.db(), &ix.what, ix.storage(), &fd)
+		} else {
+			Index::prefix_ids_composite_beg(opt.ns(), opt.db(), &ix.what, ix.storage(), &fd)
 		}
 	}
 
 	fn compute_beg(opt: &Options, ix: &DefineIndexStatement, from: &RangeValue) -> Vec<u8> {
 		if from.value == Value::None {
-			return Index::prefix_beg(opt.ns(), opt.db(), &ix.what, &ix.name);
+			return Index::prefix_beg(opt.ns(), opt.db(), &ix.what, ix.storage());
 		}
 		let fd = Array::from(from.value.to_owned());
 		if from.inclusive {
-			Index::prefix_ids_beg(opt.ns(), opt.db(), &ix.what, &ix.name, &fd)
+			Index::prefix_ids_beg(opt.ns(), opt.db(), &ix.what, ix.storage(), &fd)
 		} else {
-			Index::prefix_ids_end(opt.ns(), opt.db(), &ix.what, &ix.name, &fd)
+			Index::prefix_ids_end(opt.ns(), opt.db(), &ix.what, ix.storage(), &fd)
 		}
 	}
 
 	fn compute_end(opt: &Options, ix: &DefineIndexStatement, to: &RangeValue) -> Vec<u8> {
 		if to.value == Value::None {
-			return Index::prefix_end(opt.ns(), opt.db(), &ix.what, &ix.name);
+			return Index::prefix_end(opt.ns(), opt.db(), &ix.what, ix.storage());
 		}
 		let fd = Array::from(to.value.to_owned());
 		if to.inclusive {
-			Index::prefix_ids_end(opt.ns(), opt.db(), &ix.what, &ix.name, &fd)
+			Index::prefix_ids_end(opt.ns(), opt.db(), &ix.what, ix.storage(), &fd)
 		} else {
-			Index::prefix_ids_beg(opt.ns(), opt.db(), &ix.what, &ix.name, &fd)
+			Index::prefix_ids_beg(opt.ns(), opt.db(), &ix.what, ix.storage(), &fd)
 		}
 	}
 
@@ -164,11 +235,20 @@ impl IndexRangeThingIterator {
 	) -> Result<Vec<(Thing, DocId)>, Error> {
 		let min = self.r.beg.clone();
 		let max = self.r.end.clone();
-		let res = txn.lock().await.scan(min..max, limit).await?;
-		if let Some((key, _)) = res.last() {
-			self.r.beg = key.clone();
-			self.r.beg.push(0x00);
-		}
+		let res = if self.reverse {
+			let res = txn.lock().await.scanr(min..max, limit).await?;
+			if let Some((key, _)) = res.last() {
+				self.r.end = key.clone();
+			}
+			res
+		} else {
+			let res = txn.lock().await.scan(min..max, limit).await?;
+			if let Some((key, _)) = res.last() {
+				self.r.beg = key.clone();
+				self.r.beg.push(0x00);
+			}
+			res
+		};
 		let mut r = Vec::with_capacity(res.len());
 		for (k, v) in res {
 			if self.r.matches(&k) {
@@ -185,7 +265,7 @@ pub(crate) struct UniqueEqualThingIterator {
 
 impl UniqueEqualThingIterator {
 	pub(super) fn new(opt: &Options, ix: &DefineIndexStatement, a: &Array) -> Result<Self, Error> {
-		let key = Index::new(opt.ns(), opt.db(), &ix.what, &ix.name, a, None).into();
+		let key = Index::new(opt.ns(), opt.db(), &ix.what, ix.storage(), a, None).into();
 		Ok(Self {
 			key: Some(key),
 		})
@@ -204,6 +284,8 @@ impl UniqueEqualThingIterator {
 pub(crate) struct UniqueRangeThingIterator {
 	r: RangeScan,
 	done: bool,
+	/// The range is iterated in descending order
+	reverse: bool,
 }
 
 impl UniqueRangeThingIterator {
@@ -212,24 +294,26 @@ impl UniqueRangeThingIterator {
 		ix: &DefineIndexStatement,
 		from: &RangeValue,
 		to: &RangeValue,
+		reverse: bool,
 	) -> Self {
 		let beg = Self::compute_beg(opt, ix, from);
 		let end = Self::compute_end(opt, ix, to);
 		Self {
 			r: RangeScan::new(beg, from.inclusive, end, to.inclusive),
 			done: false,
+			reverse,
 		}
 	}
 
 	fn compute_beg(opt: &Options, ix: &DefineIndexStatement, from: &RangeValue) -> Vec<u8> {
 		if from.value == Value::None {
-			return Index::prefix_beg(opt.ns(), opt.db(), &ix.what, &ix.name);
+			return Index::prefix_beg(opt.ns(), opt.db(), &ix.what, ix.storage());
 		}
 		Index::new(
 			opt.ns(),
 			opt.db(),
 			&ix.what,
-			&ix.name,
+			ix.storage(),
 			&Array::from(from.value.to_owned()),
 			None,
 		)
@@ -239,11 +323,18 @@ impl UniqueRangeThingIterator {
 
 	fn compute_end(opt: &Options, ix: &DefineIndexStatement, to: &RangeValue) -> Vec<u8> {
 		if to.value == Value::None {
-			return Index::prefix_end(opt.ns(), opt.db(), &ix.what, &ix.name);
+			return Index::prefix_end(opt.ns(), opt.db(), &ix.what, ix.storage());
 		}
-		Index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &Array::from(to.value.to_owned()), None)
-			.encode()
-			.unwrap()
+		Index::new(
+			opt.ns(),
+			opt.db(),
+			&ix.what,
+			ix.storage(),
+			&Array::from(to.value.to_owned()),
+			None,
+		)
+		.encode()
+		.unwrap()
 	}
 
 	async fn next_batch(
@@ -254,6 +345,9 @@ impl UniqueRangeThingIterator {
 		if self.done {
 			return Ok(vec![]);
 		}
+		if self.reverse {
+			return self.next_batch_reverse(txn, limit).await;
+		}
 		let min = self.r.beg.clone();
 		let max = self.r.end.clone();
 		limit += 1;
@@ -279,6 +373,93 @@ impl UniqueRangeThingIterator {
 		self.done = true;
 		Ok(r)
 	}
+
+	async fn next_batch_reverse(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let mut tx = txn.lock().await;
+		let mut r = Vec::with_capacity(limit as usize);
+		// The end key is part of the range, and comes first
+		let end = self.r.end.clone();
+		if self.r.matches(&end) {
+			if let Some(v) = tx.get(end.clone()).await? {
+				r.push((v.into(), NO_DOC_ID));
+			}
+		}
+		let min = self.r.beg.clone();
+		let res = tx.scanr(min..end, limit).await?;
+		let n = res.len() as u32;
+		let mut last = None;
+		for (k, v) in res {
+			if self.r.matches(&k) {
+				r.push((v.into(), NO_DOC_ID));
+			}
+			last = Some(k);
+		}
+		match last {
+			// The next batch ends with the last returned key, which is excluded
+			Some(k) if n == limit => {
+				self.r.end_excl = Some(k.clone());
+				self.r.end = k;
+			}
+			_ => self.done = true,
+		}
+		Ok(r)
+	}
+}
+
+pub(crate) struct IntersectionThingIterator {
+	/// The first iterator is streamed, the records of the others are collected
+	its: Vec<ThingIterator>,
+	collected: Option<Vec<HashSet<Thing>>>,
+}
+
+impl IntersectionThingIterator {
+	pub(super) fn new(its: Vec<ThingIterator>) -> Self {
+		Self {
+			its,
+			collected: None,
+		}
+	}
+
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let Some((first, others)) = self.its.split_first_mut() else {
+			return Ok(vec![]);
+		};
+		if self.collected.is_none() {
+			let mut collected = Vec::with_capacity(others.len());
+			for it in others {
+				let mut set = HashSet::new();
+				loop {
+					let res = it.next_batch(txn, limit).await?;
+					if res.is_empty() {
+						break;
+					}
+					set.extend(res.into_iter().map(|(t, _)| t));
+				}
+				collected.push(set);
+			}
+			self.collected = Some(collected);
+		}
+		let collected = self.collected.as_ref().unwrap();
+		loop {
+			let res = first.next_batch(txn, limit).await?;
+			if res.is_empty() {
+				return Ok(res);
+			}
+			let res: Vec<_> =
+				res.into_iter().filter(|(t, _)| collected.iter().all(|s| s.contains(t))).collect();
+			if !res.is_empty() {
+				return Ok(res);
+			}
+		}
+	}
 }
 
 pub(crate) struct MatchesThingIterator {
@@ -362,3 +543,66 @@ impl KnnThingIterator {
 		Ok(res)
 	}
 }
+
+/// Scans the cells of a spatial index. A record is returned for each of its geometries
+/// and cells, so the records are deduplicated by the distinct check of the iterator.
+pub(crate) struct SpatialThingIterator {
+	tb: String,
+	ranges: VecDeque<Range<Key>>,
+}
+
+impl SpatialThingIterator {
+	pub(super) fn new(tb: &str, ranges: VecDeque<Range<Key>>) -> Self {
+		Self {
+			tb: tb.to_owned(),
+			ranges,
+		}
+	}
+
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let mut res = vec![];
+		let mut tx = txn.lock().await;
+		while res.is_empty() {
+			let Some(r) = self.ranges.pop_front() else {
+				break;
+			};
+			let batch = tx.scan(r.clone(), limit).await?;
+			// The range is not exhausted yet
+			if batch.len() == limit as usize {
+				if let Some((k, _)) = batch.last() {
+					let mut beg = k.clone();
+					beg.push(0x00);
+					self.ranges.push_front(beg..r.end);
+				}
+			}
+			for (k, _) in batch {
+				if let Some(id) = Index::decode(&k)?.id {
+					res.push((Thing::from((self.tb.clone(), id.into_owned())), NO_DOC_ID));
+				}
+			}
+		}
+		Ok(res)
+	}
+}
+
+/// Returns the records nearest to a point, found by a spatial index
+pub(crate) struct SpatialKnnThingIterator {
+	res: VecDeque<Thing>,
+}
+
+impl SpatialKnnThingIterator {
+	pub(super) fn new(res: VecDeque<Thing>) -> Self {
+		Self {
+			res,
+		}
+	}
+
+	fn next_batch(&mut self, limit: u32) -> Vec<(Thing, DocId)> {
+		let n = self.res.len().min(limit as usize);
+		self.res.drain(..n).map(|t| (t, NO_DOC_ID)).collect()
+	}
+}
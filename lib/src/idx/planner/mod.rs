@@ -1,36 +1,49 @@
+mod cost;
 pub(crate) mod executor;
 pub(crate) mod iterators;
 pub(crate) mod plan;
+pub(crate) mod stats;
 mod tree;
 
 use crate::ctx::Context;
 use crate::dbs::{Iterable, Iterator, Options, Transaction};
 use crate::err::Error;
 use crate::idx::planner::executor::{IteratorEntry, QueryExecutor};
-use crate::idx::planner::plan::{Plan, PlanBuilder};
+use crate::idx::planner::plan::{IndexOrder, Plan, PlanBuilder};
 use crate::idx::planner::tree::Tree;
 use crate::sql::with::With;
-use crate::sql::{Cond, Table};
+use crate::sql::{Cond, Order, Table};
 use std::collections::HashMap;
 
 pub(crate) struct QueryPlanner<'a> {
 	opt: &'a Options,
 	with: &'a Option<With>,
 	cond: &'a Option<Cond>,
+	/// The ORDER BY clause, if an index may return the records in this order until the LIMIT
+	order: Option<&'a Order>,
 	/// There is one executor per table
 	executors: HashMap<String, QueryExecutor>,
 	requires_distinct: bool,
+	/// The records are returned in the order of the ORDER BY clause
+	ordered: bool,
 	fallbacks: Vec<String>,
 }
 
 impl<'a> QueryPlanner<'a> {
-	pub(crate) fn new(opt: &'a Options, with: &'a Option<With>, cond: &'a Option<Cond>) -> Self {
+	pub(crate) fn new(
+		opt: &'a Options,
+		with: &'a Option<With>,
+		cond: &'a Option<Cond>,
+		order: Option<&'a Order>,
+	) -> Self {
 		Self {
 			opt,
 			with,
 			cond,
+			order,
 			executors: HashMap::default(),
 			requires_distinct: false,
+			ordered: false,
 			fallbacks: vec![],
 		}
 	}
@@ -42,41 +55,69 @@ impl<'a> QueryPlanner<'a> {
 		t: Table,
 		it: &mut Iterator,
 	) -> Result<(), Error> {
-		match Tree::build(ctx, self.opt, txn, &t, self.cond, self.with).await? {
-			Some((node, im, with_indexes)) => {
-				let mut exe = QueryExecutor::new(self.opt, txn, &t, im).await?;
-				match PlanBuilder::build(node, self.with, with_indexes)? {
-					Plan::SingleIndex(exp, io) => {
-						let ir = exe.add_iterator(IteratorEntry::Single(exp, io));
-						it.ingest(Iterable::Index(t.clone(), ir));
-						self.executors.insert(t.0.clone(), exe);
-					}
-					Plan::MultiIndex(v) => {
-						for (exp, io) in v {
-							let ir = exe.add_iterator(IteratorEntry::Single(exp, io));
-							it.ingest(Iterable::Index(t.clone(), ir));
-							self.requires_distinct = true;
-						}
-						self.executors.insert(t.0.clone(), exe);
-					}
-					Plan::SingleIndexMultiExpression(ixn, rq) => {
-						let ir =
-							exe.add_iterator(IteratorEntry::Range(rq.exps, ixn, rq.from, rq.to));
-						it.ingest(Iterable::Index(t.clone(), ir));
-						self.executors.insert(t.0.clone(), exe);
-					}
-					Plan::TableIterator(fallback) => {
-						if let Some(fallback) = fallback {
-							self.fallbacks.push(fallback);
-						}
-						self.executors.insert(t.0.clone(), exe);
-						it.ingest(Iterable::Table(t));
-					}
-				}
-			}
+		// A descending order requires a datastore which can scan an index in reverse
+		let order = match self.order {
+			Some(o) if !o.direction && !txn.lock().await.can_scanr() => None,
+			o => o,
+		};
+		let idiom = order.map(|o| &o.order);
+		let tree = match Tree::build(ctx, self.opt, txn, &t, self.cond, self.with, idiom).await? {
+			Some(tree) => tree,
 			None => {
 				it.ingest(Iterable::Table(t));
+				return Ok(());
+			}
+		};
+		let order = tree.order.zip(order).map(|(ir, o)| (ir, IndexOrder::new(o.direction)));
+		let plan = match tree.root {
+			Some(root) => PlanBuilder::build(root, self.with, tree.with_indexes, &tree.index_map)?,
+			// Without any condition, the tree was only built to find the index of the order
+			None => Plan::TableIterator(None, None),
+		};
+		let mut exe = QueryExecutor::new(self.opt, txn, &t, tree.index_map).await?;
+		match plan {
+			Plan::SingleIndex(access, est) => {
+				let mut entry: IteratorEntry = access.into();
+				if let Some((ir, o)) = order {
+					self.ordered = entry.set_order(ir, o);
+				}
+				self.requires_distinct |= entry.has_duplicates();
+				let ir = exe.add_iterator(entry, est);
+				it.ingest(Iterable::Index(t.clone(), ir));
+				self.executors.insert(t.0.clone(), exe);
 			}
+			Plan::MultiIndex(v) => {
+				for (access, est) in v {
+					let ir = exe.add_iterator(access.into(), est);
+					it.ingest(Iterable::Index(t.clone(), ir));
+					self.requires_distinct = true;
+				}
+				self.executors.insert(t.0.clone(), exe);
+			}
+			Plan::Intersection(v, est) => {
+				let entry = IteratorEntry::Intersection(v.into_iter().map(Into::into).collect());
+				self.requires_distinct |= entry.has_duplicates();
+				let ir = exe.add_iterator(entry, Some(est));
+				it.ingest(Iterable::Index(t.clone(), ir));
+				self.executors.insert(t.0.clone(), exe);
+			}
+			Plan::TableIterator(fallback, est) => match order {
+				// The whole index is iterated in order, until the LIMIT is reached
+				Some((ir, o)) => {
+					let ir = exe.add_iterator(IteratorEntry::ordered(ir, o), None);
+					it.ingest(Iterable::Index(t.clone(), ir));
+					self.ordered = true;
+					self.executors.insert(t.0.clone(), exe);
+				}
+				_ => {
+					if let Some(fallback) = fallback {
+						self.fallbacks.push(fallback);
+					}
+					exe.set_table_estimate(est);
+					self.executors.insert(t.0.clone(), exe);
+					it.ingest(Iterable::Table(t));
+				}
+			},
 		}
 		Ok(())
 	}
@@ -93,6 +134,11 @@ impl<'a> QueryPlanner<'a> {
 		self.requires_distinct
 	}
 
+	/// Checks if the records are returned in the order of the ORDER BY clause
+	pub(crate) fn is_ordered(&self) -> bool {
+		self.ordered
+	}
+
 	pub(crate) fn fallbacks(&self) -> &Vec<String> {
 		&self.fallbacks
 	}
@@ -1,17 +1,28 @@
 use crate::err::Error;
 use crate::idx::ft::MatchRef;
-use crate::idx::planner::tree::{IndexRef, Node};
+use crate::idx::planner::cost::{CostModel, Estimate};
+use crate::idx::planner::tree::{IndexMap, IndexRef, Node};
 use crate::sql::with::With;
 use crate::sql::{Array, Object};
 use crate::sql::{Expression, Idiom, Operator, Value};
-use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::Hash;
+use std::mem;
 use std::sync::Arc;
 
+/// Identifies a group of expressions which are all joined with AND
+type GroupRef = usize;
+
 pub(super) struct PlanBuilder {
 	indexes: Vec<(Arc<Expression>, IndexOption)>,
-	range_queries: HashMap<IndexRef, RangeQueryBuilder>,
+	/// The range expressions of each group, merged per index
+	range_queries: BTreeMap<(GroupRef, IndexRef), RangeQueryBuilder>,
+	/// The expressions of each group on the columns of a composite index
+	composites: BTreeMap<(GroupRef, IndexRef), CompositeBuilder>,
+	/// The expressions which can only be resolved by a composite index
+	composite_exps: Vec<Arc<Expression>>,
+	groups: GroupRef,
 	with_indexes: Vec<IndexRef>,
 	all_and: bool,
 	all_exp_with_index: bool,
@@ -22,43 +33,128 @@ impl PlanBuilder {
 		root: Node,
 		with: &Option<With>,
 		with_indexes: Vec<IndexRef>,
+		im: &IndexMap,
 	) -> Result<Plan, Error> {
 		if let Some(With::NoIndex) = with {
-			return Ok(Plan::TableIterator(Some("WITH NOINDEX".to_string())));
+			return Ok(Plan::TableIterator(Some("WITH NOINDEX".to_string()), None));
 		}
 		let mut b = PlanBuilder {
 			indexes: Vec::new(),
-			range_queries: HashMap::new(),
+			range_queries: BTreeMap::new(),
+			composites: BTreeMap::new(),
+			composite_exps: Vec::new(),
+			groups: 0,
 			with_indexes,
 			all_and: true,
 			all_exp_with_index: true,
 		};
 		// Browse the AST and collect information
-		if let Err(e) = b.eval_node(root) {
-			return Ok(Plan::TableIterator(Some(e.to_string())));
+		if let Err(e) = b.eval_node(root, 0) {
+			return Ok(Plan::TableIterator(Some(e.to_string()), None));
 		}
+		// The expressions on the leading columns of composite indexes are merged into range scans
+		b.build_composites(im);
 		// If we didn't found any index, we're done with no index plan
-		if b.indexes.is_empty() {
-			return Ok(Plan::TableIterator(Some("NO INDEX FOUND".to_string())));
+		if b.indexes.is_empty() && b.range_queries.is_empty() {
+			return Ok(Plan::TableIterator(Some("NO INDEX FOUND".to_string()), None));
+		}
+		// If every index has been analyzed, we choose the plan with the lowest estimated cost
+		let irs =
+			b.indexes.iter().map(|(_, io)| io.ir()).chain(b.range_queries.keys().map(|k| k.1));
+		if let Some(cm) = CostModel::new(im, irs) {
+			return Ok(b.cost_based_plan(&cm));
 		}
-
 		// If every boolean operator are AND then we can use the single index plan
 		if b.all_and {
-			// TODO: This is currently pretty arbitrary
 			// We take the "first" range query if one is available
-			if let Some((ir, rq)) = b.range_queries.drain().take(1).next() {
-				return Ok(Plan::SingleIndexMultiExpression(ir, rq));
+			if let Some(((_, ir), rq)) = b.range_queries.into_iter().next() {
+				return Ok(Plan::SingleIndex(IndexAccess::Range(ir, rq), None));
 			}
 			// Otherwise we take the first single index option
 			if let Some((e, i)) = b.indexes.pop() {
-				return Ok(Plan::SingleIndex(e, i));
+				return Ok(Plan::SingleIndex(IndexAccess::Single(e, i), None));
 			}
 		}
 		// If every expression is backed by an index with can use the MultiIndex plan
 		if b.all_exp_with_index {
-			return Ok(Plan::MultiIndex(b.indexes));
+			return Ok(Plan::MultiIndex(
+				b.into_accesses().into_iter().map(|a| (a, None)).collect(),
+			));
+		}
+		Ok(Plan::TableIterator(None, None))
+	}
+
+	fn cost_based_plan(self, cm: &CostModel) -> Plan {
+		let table = cm.table();
+		// The table is not scanned if some indexes have been explicitly requested
+		let scannable = self.with_indexes.is_empty();
+		let (all_and, all_exp_with_index) = (self.all_and, self.all_exp_with_index);
+		let mut candidates: Vec<(IndexAccess, Estimate)> = self
+			.into_accesses()
+			.into_iter()
+			.map(|a| {
+				let e = cm.index(&a);
+				(a, e)
+			})
+			.collect();
+		// A nearest neighbours search can only be resolved by its index
+		let knn = candidates.iter().position(|(a, _)| a.is_knn());
+		if all_and {
+			if let Some(i) = knn {
+				let (a, e) = candidates.swap_remove(i);
+				return Plan::SingleIndex(a, Some(e));
+			}
+			// The cheapest single index
+			let best = candidates
+				.iter()
+				.enumerate()
+				.min_by(|(_, (_, a)), (_, (_, b))| a.cost.total_cmp(&b.cost))
+				.map(|(i, (_, e))| (i, *e));
+			// The intersection of the two most selective standard or unique indexes
+			let mut selective: Vec<usize> =
+				(0..candidates.len()).filter(|i| cm.is_intersectable(&candidates[*i].0)).collect();
+			selective.sort_by(|a, b| candidates[*a].1.rows.total_cmp(&candidates[*b].1.rows));
+			if let [a, b, ..] = selective[..] {
+				let e = cm.intersection(&[&candidates[a].0, &candidates[b].0]);
+				if best.map_or(true, |(_, best)| e.cost < best.cost)
+					&& (!scannable || e.cost < table.cost)
+				{
+					// The least selective index is streamed, the other one is collected
+					let (hi, lo) = (a.max(b), a.min(b));
+					let (x, y) = (candidates.swap_remove(hi).0, candidates.swap_remove(lo).0);
+					let accesses = if hi == b {
+						vec![x, y]
+					} else {
+						vec![y, x]
+					};
+					return Plan::Intersection(accesses, e);
+				}
+			}
+			if let Some((i, e)) = best {
+				if !scannable || e.cost < table.cost {
+					return Plan::SingleIndex(candidates.swap_remove(i).0, Some(e));
+				}
+			}
+		} else if all_exp_with_index {
+			let union = cm.union(&candidates.iter().map(|(_, e)| *e).collect::<Vec<_>>());
+			if knn.is_some() || !scannable || union.cost < table.cost {
+				return Plan::MultiIndex(
+					candidates.into_iter().map(|(a, e)| (a, Some(e))).collect(),
+				);
+			}
+		} else {
+			return Plan::TableIterator(None, Some(table));
 		}
-		Ok(Plan::TableIterator(None))
+		Plan::TableIterator(Some("TABLE SCAN IS CHEAPER".to_string()), Some(table))
+	}
+
+	/// Returns every way to access the records with an index
+	fn into_accesses(self) -> Vec<IndexAccess> {
+		let mut accesses: Vec<IndexAccess> =
+			self.indexes.into_iter().map(|(e, io)| IndexAccess::Single(e, io)).collect();
+		accesses
+			.extend(self.range_queries.into_iter().map(|((_, ir), rq)| IndexAccess::Range(ir, rq)));
+		accesses
 	}
 
 	// Check if we have an explicit list of index we can use
@@ -71,10 +167,11 @@ impl PlanBuilder {
 		io
 	}
 
-	fn eval_node(&mut self, node: Node) -> Result<(), String> {
+	fn eval_node(&mut self, node: Node, group: GroupRef) -> Result<(), String> {
 		match node {
 			Node::Expression {
 				io,
+				composites,
 				left,
 				right,
 				exp,
@@ -83,13 +180,28 @@ impl PlanBuilder {
 					self.all_and = false;
 				}
 				let is_bool = self.check_boolean_operator(exp.operator());
+				let mut with_composite = false;
+				for io in composites.iter() {
+					if let Some(io) = self.filter_index_option(Some(io.clone())) {
+						self.composites.entry((group, io.ir())).or_default().add(exp.clone(), io);
+						with_composite = true;
+					}
+				}
 				if let Some(io) = self.filter_index_option(io) {
-					self.add_index_option(exp, io);
+					self.add_index_option(group, exp.clone(), io);
+				} else if with_composite {
+					self.composite_exps.push(exp.clone());
 				} else if self.all_exp_with_index && !is_bool {
 					self.all_exp_with_index = false;
 				}
-				self.eval_node(*left)?;
-				self.eval_node(*right)?;
+				// Both sides of an OR start a new group of expressions
+				let (lg, rg) = if is_bool && Operator::And.ne(exp.operator()) {
+					(self.new_group(), self.new_group())
+				} else {
+					(group, group)
+				};
+				self.eval_node(*left, lg)?;
+				self.eval_node(*right, rg)?;
 				Ok(())
 			}
 			Node::Unsupported(reason) => Err(reason),
@@ -110,28 +222,71 @@ impl PlanBuilder {
 		}
 	}
 
-	fn add_index_option(&mut self, exp: Arc<Expression>, io: IndexOption) {
+	fn new_group(&mut self) -> GroupRef {
+		self.groups += 1;
+		self.groups
+	}
+
+	/// Turns the expressions on composite indexes into range queries, when they constrain
+	/// the leading columns of the index with equalities, optionally followed by a range
+	fn build_composites(&mut self, im: &IndexMap) {
+		for ((group, ir), cb) in mem::take(&mut self.composites) {
+			if let Some(ix) = im.definitions.get(&ir) {
+				if let Some(rq) = cb.build(&ix.cols) {
+					self.range_queries.insert((group, ir), rq);
+				}
+			}
+		}
+		// An expression which is not part of any range query still requires a table scan
+		let covered =
+			|e: &Arc<Expression>| self.range_queries.values().any(|rq| rq.exps.contains(e));
+		if !self.composite_exps.iter().all(covered) {
+			self.all_exp_with_index = false;
+		}
+	}
+
+	fn add_index_option(&mut self, group: GroupRef, exp: Arc<Expression>, io: IndexOption) {
+		// Range expressions on the same index are merged into a single range scan
 		if let IndexOperator::RangePart(o, v) = io.op() {
-			match self.range_queries.entry(io.ir()) {
+			match self.range_queries.entry((group, io.ir())) {
 				Entry::Occupied(mut e) => {
-					e.get_mut().add(exp.clone(), o, v);
+					e.get_mut().add(exp, o, v);
 				}
 				Entry::Vacant(e) => {
 					let mut b = RangeQueryBuilder::default();
-					b.add(exp.clone(), o, v);
+					b.add(exp, o, v);
 					e.insert(b);
 				}
 			}
+			return;
 		}
 		self.indexes.push((exp, io));
 	}
 }
 
 pub(super) enum Plan {
-	TableIterator(Option<String>),
-	SingleIndex(Arc<Expression>, IndexOption),
-	MultiIndex(Vec<(Arc<Expression>, IndexOption)>),
-	SingleIndexMultiExpression(IndexRef, RangeQueryBuilder),
+	/// Iterates the whole table
+	TableIterator(Option<String>, Option<Estimate>),
+	/// Iterates the records of a single index
+	SingleIndex(IndexAccess, Option<Estimate>),
+	/// Iterates the union of the records of several indexes
+	MultiIndex(Vec<(IndexAccess, Option<Estimate>)>),
+	/// Iterates the intersection of the records of several indexes
+	Intersection(Vec<IndexAccess>, Estimate),
+}
+
+/// A way to access records with an index
+pub(super) enum IndexAccess {
+	/// An index resolving a single expression
+	Single(Arc<Expression>, IndexOption),
+	/// A range of an index resolving one or more expressions
+	Range(IndexRef, RangeQueryBuilder),
+}
+
+impl IndexAccess {
+	fn is_knn(&self) -> bool {
+		matches!(self, Self::Single(_, io) if matches!(io.op(), IndexOperator::Knn(_, _)))
+	}
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -150,6 +305,8 @@ pub(super) enum IndexOperator {
 	RangePart(Operator, Value),
 	Matches(String, Option<MatchRef>),
 	Knn(Array, u32),
+	/// A geometry operator resolved by a spatial index
+	Spatial(Operator, Value),
 }
 
 impl IndexOption {
@@ -173,6 +330,17 @@ impl IndexOption {
 		&self.0.id
 	}
 
+	/// Returns the range covered by a range operator
+	pub(super) fn range(&self) -> Option<(RangeValue, RangeValue)> {
+		if let IndexOperator::RangePart(op, v) = self.op() {
+			let mut rq = RangeQueryBuilder::default();
+			if rq.narrow(op, v) {
+				return Some((rq.from, rq.to));
+			}
+		}
+		None
+	}
+
 	pub(crate) fn explain(&self, e: &mut HashMap<&str, Value>) {
 		match self.op() {
 			IndexOperator::Equality(a) => {
@@ -188,7 +356,7 @@ impl IndexOption {
 				e.insert("operator", Value::from(Operator::Matches(*a).to_string()));
 				e.insert("value", Value::from(qs.to_owned()));
 			}
-			IndexOperator::RangePart(op, v) => {
+			IndexOperator::RangePart(op, v) | IndexOperator::Spatial(op, v) => {
 				e.insert("operator", Value::from(op.to_string()));
 				e.insert("value", v.to_owned());
 			}
@@ -271,23 +439,109 @@ impl From<&RangeValue> for Value {
 	}
 }
 
+/// The direction in which an index is iterated, when it resolves the ORDER BY clause
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum IndexOrder {
+	Ascending,
+	Descending,
+}
+
+impl IndexOrder {
+	pub(super) fn new(ascending: bool) -> Self {
+		if ascending {
+			Self::Ascending
+		} else {
+			Self::Descending
+		}
+	}
+
+	pub(super) fn is_reverse(&self) -> bool {
+		matches!(self, Self::Descending)
+	}
+}
+
+impl From<IndexOrder> for Value {
+	fn from(o: IndexOrder) -> Self {
+		match o {
+			IndexOrder::Ascending => Value::from("ASC"),
+			IndexOrder::Descending => Value::from("DESC"),
+		}
+	}
+}
+
 #[derive(Default, Debug)]
 pub(super) struct RangeQueryBuilder {
 	pub(super) exps: HashSet<Arc<Expression>>,
+	/// The values of the leading columns of a composite index
+	pub(super) prefix: Array,
 	pub(super) from: RangeValue,
 	pub(super) to: RangeValue,
 }
 
 impl RangeQueryBuilder {
 	fn add(&mut self, exp: Arc<Expression>, op: &Operator, v: &Value) {
+		if self.narrow(op, v) {
+			self.exps.insert(exp);
+		}
+	}
+
+	/// Narrows the range with a range operator, returns false for any other operator
+	fn narrow(&mut self, op: &Operator, v: &Value) -> bool {
 		match op {
 			Operator::LessThan => self.to.set_to(v),
 			Operator::LessThanOrEqual => self.to.set_to_inclusive(v),
 			Operator::MoreThan => self.from.set_from(v),
 			Operator::MoreThanOrEqual => self.from.set_from_inclusive(v),
-			_ => return,
+			_ => return false,
+		}
+		true
+	}
+}
+
+/// The expressions of a group on the columns of a composite index
+#[derive(Default)]
+struct CompositeBuilder {
+	/// The equality of each column
+	equalities: HashMap<Idiom, (Arc<Expression>, Value)>,
+	/// The range of each column
+	ranges: HashMap<Idiom, RangeQueryBuilder>,
+}
+
+impl CompositeBuilder {
+	fn add(&mut self, exp: Arc<Expression>, io: IndexOption) {
+		match io.op() {
+			IndexOperator::Equality(a) if a.len() == 1 => {
+				self.equalities.entry(io.id().clone()).or_insert((exp, a[0].clone()));
+			}
+			IndexOperator::RangePart(o, v) => {
+				self.ranges.entry(io.id().clone()).or_default().add(exp, o, v);
+			}
+			_ => {}
+		}
+	}
+
+	/// Builds a range query from the equalities on the leading columns,
+	/// and the range on the following column, if any
+	fn build(mut self, cols: &[Idiom]) -> Option<RangeQueryBuilder> {
+		let mut rq = RangeQueryBuilder::default();
+		for col in cols {
+			if let Some((exp, v)) = self.equalities.remove(col) {
+				rq.exps.insert(exp);
+				rq.prefix.push(v);
+				continue;
+			}
+			if let Some(r) = self.ranges.remove(col) {
+				rq.exps.extend(r.exps);
+				rq.from = r.from;
+				rq.to = r.to;
+			}
+			break;
+		}
+		if rq.exps.is_empty() {
+			None
+		} else {
+			Some(rq)
 		}
-		self.exps.insert(exp);
 	}
 }
 
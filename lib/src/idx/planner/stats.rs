@@ -0,0 +1,295 @@
+//! The statistics of an index, which are persisted by `ANALYZE INDEX`,
+//! and which the query planner uses to estimate the cost of a plan.
+//!
+//! The writes to an analyzed standard or unique index record how many
+//! entries they added or removed under their own keys, which are folded into
+//! the number of entries of the index on the ticks of the datastore. Once it
+//! has drifted too far from the number of entries which were analyzed, the
+//! distribution of the values no longer describes the index, and the
+//! statistics are considered stale.
+use crate::err::Error;
+use crate::idx::VersionedSerdeState;
+use crate::key::index::Index;
+use crate::key::root::sd::Sd;
+use crate::kvs::{Key, Transaction, Val};
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Array, Object, Value};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// The number of index entries read at once when computing the statistics
+const SCAN_BATCH_SIZE: u32 = 1000;
+
+/// The number of changes folded into the statistics at once
+const FOLD_BATCH_SIZE: u32 = 1000;
+
+/// The number of batches of changes folded on each tick of the datastore
+pub(crate) const FOLD_BATCHES_PER_TICK: u32 = 10;
+
+/// The maximum number of buckets of a histogram
+const HISTOGRAM_BUCKETS: u64 = 10;
+
+/// The fraction of the analyzed entries which can be added or removed before the statistics are stale
+const STALE_RATIO: f64 = 0.5;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 3)]
+pub(crate) struct IndexStatistics {
+	/// The number of records in the index
+	pub(crate) keys_count: u64,
+	/// The number of distinct values (or terms) in the index
+	pub(crate) distinct_count: u64,
+	/// The approximate size of the index in bytes
+	#[revision(start = 2)]
+	pub(crate) total_size: u64,
+	/// An equi-depth histogram of the indexed values, ordered by value
+	#[revision(start = 2)]
+	pub(crate) histogram: Vec<HistogramBucket>,
+	/// The number of records in the index when it was analyzed
+	#[revision(start = 3)]
+	pub(crate) analyzed_count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(crate) struct HistogramBucket {
+	/// The lowest value of the bucket
+	pub(crate) min: Array,
+	/// The highest value of the bucket
+	pub(crate) max: Array,
+	/// The number of records in the bucket
+	pub(crate) count: u64,
+	/// The number of distinct values in the bucket
+	pub(crate) distinct: u64,
+}
+
+impl HistogramBucket {
+	fn new(fd: Array) -> Self {
+		Self {
+			min: fd.clone(),
+			max: fd,
+			count: 1,
+			distinct: 1,
+		}
+	}
+
+	/// Checks if a value is within the bounds of the bucket
+	pub(crate) fn contains(&self, fd: &Array) -> bool {
+		self.min.le(fd) && self.max.ge(fd)
+	}
+}
+
+impl VersionedSerdeState for IndexStatistics {}
+
+impl IndexStatistics {
+	/// Computes the statistics of a standard or unique index by scanning its entries
+	pub(crate) async fn compute(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<Self, Error> {
+		let mut stats = Self::default();
+		// The first scan counts the entries and the distinct values
+		let mut scan = IndexScan::new(ns, db, ix);
+		let mut last: Option<Array> = None;
+		loop {
+			let res = scan.next_batch(tx).await?;
+			if res.is_empty() {
+				break;
+			}
+			for (k, v) in &res {
+				stats.total_size += (k.len() + v.len()) as u64;
+				let k = Index::decode(k)?;
+				stats.keys_count += 1;
+				// The entries are ordered by value, so every new value is a distinct value
+				if last.as_ref() != Some(k.fd.as_ref()) {
+					stats.distinct_count += 1;
+					last = Some(k.fd.into_owned());
+				}
+			}
+		}
+		// The second scan builds the histogram, where the records
+		// having the same value always belong to the same bucket
+		let depth = (stats.keys_count + HISTOGRAM_BUCKETS - 1) / HISTOGRAM_BUCKETS;
+		let mut scan = IndexScan::new(ns, db, ix);
+		let mut bucket: Option<HistogramBucket> = None;
+		loop {
+			let res = scan.next_batch(tx).await?;
+			if res.is_empty() {
+				break;
+			}
+			for (k, _) in &res {
+				let k = Index::decode(k)?;
+				match &mut bucket {
+					Some(b) if b.max == *k.fd => {
+						b.count += 1;
+					}
+					Some(b) if b.count < depth => {
+						b.max = k.fd.into_owned();
+						b.count += 1;
+						b.distinct += 1;
+					}
+					_ => {
+						if let Some(b) = bucket.replace(HistogramBucket::new(k.fd.into_owned())) {
+							stats.histogram.push(b);
+						}
+					}
+				}
+			}
+		}
+		stats.histogram.extend(bucket);
+		Ok(stats)
+	}
+
+	/// Checks if too many records have been written since the index was analyzed
+	pub(crate) fn is_stale(&self) -> bool {
+		let drift = self.keys_count.abs_diff(self.analyzed_count);
+		drift as f64 > self.analyzed_count as f64 * STALE_RATIO
+	}
+
+	/// Retrieves the persisted statistics of an index, if it has been analyzed
+	pub(crate) async fn get(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<Option<Self>, Error> {
+		let key = crate::key::index::st::new(ns, db, tb, ix);
+		match tx.get(key).await? {
+			Some(val) => Ok(Some(Self::try_from_val(val)?)),
+			None => Ok(None),
+		}
+	}
+
+	/// Persists the statistics of an index
+	pub(crate) async fn set(
+		&self,
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<(), Error> {
+		let key = crate::key::index::st::new(ns, db, tb, ix);
+		tx.set(key, self.try_to_val()?).await
+	}
+
+	/// Records the entries which a write added to, or removed from, an index, if it has been analyzed
+	pub(crate) async fn record(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+		delta: i64,
+	) -> Result<(), Error> {
+		// The statistics are only read, so that concurrent writes don't conflict
+		if tx.exi(crate::key::index::st::new(ns, db, tb, ix)).await? {
+			let key = crate::key::root::sd::new(ns, db, tb, ix, Uuid::new_v4());
+			tx.set(key, delta.to_be_bytes().to_vec()).await?;
+		}
+		Ok(())
+	}
+
+	/// Folds a batch of the recorded changes into the statistics of their indexes,
+	/// returning true once there are no more changes to fold
+	pub(crate) async fn fold(tx: &mut Transaction) -> Result<bool, Error> {
+		let beg = crate::key::root::sd::prefix();
+		let end = crate::key::root::sd::suffix();
+		let res = tx.scan(beg..end, FOLD_BATCH_SIZE).await?;
+		let done = res.len() < FOLD_BATCH_SIZE as usize;
+		// Sum the changes of each index
+		let mut deltas: BTreeMap<(String, String, String, String), i64> = BTreeMap::new();
+		for (k, v) in res {
+			let sd = Sd::decode(&k)?;
+			let delta = v
+				.try_into()
+				.map(i64::from_be_bytes)
+				.map_err(|_| Error::Internal("Invalid index statistics change".to_string()))?;
+			let ix = (sd.ns.to_owned(), sd.db.to_owned(), sd.tb.to_owned(), sd.ix.to_owned());
+			*deltas.entry(ix).or_default() += delta;
+			tx.del(k).await?;
+		}
+		// The changes of the indexes which are no longer analyzed are discarded
+		for ((ns, db, tb, ix), delta) in deltas {
+			if let Some(mut stats) = Self::get(tx, &ns, &db, &tb, &ix).await? {
+				stats.keys_count = stats.keys_count.saturating_add_signed(delta);
+				stats.set(tx, &ns, &db, &tb, &ix).await?;
+			}
+		}
+		Ok(done)
+	}
+
+	/// Removes the recorded changes of an index, which are counted when it is analyzed
+	pub(crate) async fn clear(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<(), Error> {
+		let beg = crate::key::root::sd::prefix_ix(ns, db, tb, ix);
+		let end = crate::key::root::sd::suffix_ix(ns, db, tb, ix);
+		tx.delr(beg..end, FOLD_BATCH_SIZE).await
+	}
+}
+
+/// Scans the entries of a standard or unique index, batch after batch
+struct IndexScan {
+	beg: Key,
+	end: Key,
+}
+
+impl IndexScan {
+	fn new(ns: &str, db: &str, ix: &DefineIndexStatement) -> Self {
+		Self {
+			beg: Index::prefix_beg(ns, db, &ix.what, ix.storage()),
+			end: Index::prefix_end(ns, db, &ix.what, ix.storage()),
+		}
+	}
+
+	async fn next_batch(&mut self, tx: &mut Transaction) -> Result<Vec<(Key, Val)>, Error> {
+		let res = tx.scan(self.beg.clone()..self.end.clone(), SCAN_BATCH_SIZE).await?;
+		if let Some((key, _)) = res.last() {
+			// Continue after the last key of this batch
+			self.beg = key.clone();
+			self.beg.push(0x00);
+		}
+		Ok(res)
+	}
+}
+
+impl From<IndexStatistics> for Value {
+	fn from(stats: IndexStatistics) -> Self {
+		let mut res = Object::default();
+		res.insert("keys_count".to_owned(), Value::from(stats.keys_count));
+		res.insert("distinct_count".to_owned(), Value::from(stats.distinct_count));
+		res.insert("total_size".to_owned(), Value::from(stats.total_size));
+		let histogram: Vec<Value> = stats.histogram.into_iter().map(Value::from).collect();
+		res.insert("histogram".to_owned(), Value::from(histogram));
+		Value::from(res)
+	}
+}
+
+impl From<HistogramBucket> for Value {
+	fn from(b: HistogramBucket) -> Self {
+		// Values of single field indexes are not wrapped in an array
+		let value = |mut fd: Array| {
+			if fd.len() == 1 {
+				fd.0.remove(0)
+			} else {
+				Value::from(fd)
+			}
+		};
+		let mut res = Object::default();
+		res.insert("min".to_owned(), value(b.min));
+		res.insert("max".to_owned(), value(b.max));
+		res.insert("count".to_owned(), Value::from(b.count));
+		res.insert("distinct".to_owned(), Value::from(b.distinct));
+		Value::from(res)
+	}
+}
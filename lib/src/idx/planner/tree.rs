@@ -1,15 +1,25 @@
 use crate::ctx::Context;
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
+use crate::idx::builder::IndexBuilding;
 use crate::idx::planner::plan::{IndexOperator, IndexOption};
+use crate::idx::planner::stats::IndexStatistics;
 use crate::sql::index::Index;
 use crate::sql::statements::DefineIndexStatement;
-use crate::sql::{Array, Cond, Expression, Idiom, Operator, Subquery, Table, Value, With};
+use crate::sql::{
+	Array, Cond, Expression, Geometry, Idiom, Kind, Operator, Subquery, Table, Value, With,
+};
 use async_recursion::async_recursion;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-pub(super) struct Tree {}
+pub(super) struct Tree {
+	pub(super) root: Option<Node>,
+	pub(super) index_map: IndexMap,
+	pub(super) with_indexes: Vec<IndexRef>,
+	/// The standard or unique index which iterates the records in the ORDER BY order
+	pub(super) order: Option<IndexRef>,
+}
 
 impl Tree {
 	/// Traverse all the conditions and extract every expression
@@ -21,7 +31,8 @@ impl Tree {
 		table: &'a Table,
 		cond: &'a Option<Cond>,
 		with: &'a Option<With>,
-	) -> Result<Option<(Node, IndexMap, Vec<IndexRef>)>, Error> {
+		order: Option<&'a Idiom>,
+	) -> Result<Option<Self>, Error> {
 		let with_indexes = match with {
 			Some(With::Index(ixs)) => Vec::with_capacity(ixs.len()),
 			_ => vec![],
@@ -37,11 +48,23 @@ impl Tree {
 			index_map: IndexMap::default(),
 			with_indexes,
 		};
-		let mut res = None;
+		let mut root = None;
 		if let Some(cond) = cond {
-			res = Some((b.eval_value(&cond.0).await?, b.index_map, b.with_indexes));
+			root = Some(b.eval_value(&cond.0).await?);
+		}
+		let order = match order {
+			Some(i) => b.find_order_index(i).await?,
+			None => None,
+		};
+		if root.is_none() && order.is_none() {
+			return Ok(None);
 		}
-		Ok(res)
+		Ok(Some(Self {
+			root,
+			index_map: b.index_map,
+			with_indexes: b.with_indexes,
+			order,
+		}))
 	}
 }
 
@@ -75,13 +98,39 @@ impl<'a> TreeBuilder<'a> {
 		let mut irs = Vec::new();
 		if let Some(indexes) = &self.indexes {
 			for ix in indexes.as_ref() {
-				if ix.cols.len() == 1 && ix.cols[0].eq(i) {
+				// A composite index may resolve expressions on any of its columns
+				let composite = matches!(ix.index, Index::Idx | Index::Uniq) && ix.cols.contains(i);
+				if (ix.cols.len() == 1 && ix.cols[0].eq(i)) || composite {
+					// An index which is still being built is not used
+					let ready = IndexBuilding::is_ready(
+						&mut *self.txn.lock().await,
+						self.opt.ns(),
+						self.opt.db(),
+						ix,
+					)
+					.await?;
+					if !ready {
+						continue;
+					}
 					let ir = self.index_map.definitions.len() as IndexRef;
 					if let Some(With::Index(ixs)) = self.with {
 						if ixs.contains(&ix.name.0) {
 							self.with_indexes.push(ir);
 						}
 					}
+					// Collect the statistics of the index, if it has been analyzed
+					let stats = IndexStatistics::get(
+						&mut *self.txn.lock().await,
+						self.opt.ns(),
+						self.opt.db(),
+						&self.table.0,
+						&ix.name.0,
+					)
+					.await?;
+					// Stale statistics are ignored, as they would mislead the cost model
+					if let Some(stats) = stats.filter(|s| !s.is_stale()) {
+						self.index_map.statistics.insert(ir, stats);
+					}
 					self.index_map.definitions.insert(ir, ix.clone());
 					irs.push(ir);
 				}
@@ -96,15 +145,56 @@ impl<'a> TreeBuilder<'a> {
 		Ok(irs)
 	}
 
+	/// Finds a standard or unique index on the ORDER BY idiom, which can be used.
+	///
+	/// The entries of an index are ordered by their encoded value, which only matches the
+	/// order of the values when every record holds a single value of the same scalar type.
+	async fn find_order_index(&mut self, i: &Idiom) -> Result<Option<IndexRef>, Error> {
+		if let Some(With::NoIndex) = self.with {
+			return Ok(None);
+		}
+		let fields = self
+			.txn
+			.lock()
+			.await
+			.all_tb_fields(self.opt.ns(), self.opt.db(), &self.table.0)
+			.await?;
+		let scalar = fields.iter().any(|fd| {
+			fd.name.eq(i)
+				&& matches!(fd.kind, Some(Kind::Bool | Kind::Int | Kind::Float | Kind::String))
+		});
+		if !scalar {
+			return Ok(None);
+		}
+		if let Some(irs) = self.find_indexes(i).await? {
+			for ir in irs.iter() {
+				// Only the indexes explicitly requested can be used
+				if let Some(With::Index(_)) = self.with {
+					if !self.with_indexes.contains(ir) {
+						continue;
+					}
+				}
+				if let Some(ix) = self.index_map.definitions.get(ir) {
+					if ix.cols.len() == 1 && matches!(ix.index, Index::Idx | Index::Uniq) {
+						return Ok(Some(*ir));
+					}
+				}
+			}
+		}
+		Ok(None)
+	}
+
 	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
 	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
 	async fn eval_value(&mut self, v: &Value) -> Result<Node, Error> {
 		match v {
 			Value::Expression(e) => self.eval_expression(e).await,
 			Value::Idiom(i) => self.eval_idiom(i).await,
-			Value::Strand(_) | Value::Number(_) | Value::Bool(_) | Value::Thing(_) => {
-				Ok(Node::Scalar(v.to_owned()))
-			}
+			Value::Strand(_)
+			| Value::Number(_)
+			| Value::Bool(_)
+			| Value::Thing(_)
+			| Value::Geometry(_) => Ok(Node::Scalar(v.to_owned())),
 			Value::Array(a) => Ok(self.eval_array(a)),
 			Value::Subquery(s) => self.eval_subquery(s).await,
 			Value::Param(p) => {
@@ -146,9 +236,12 @@ impl<'a> TreeBuilder<'a> {
 			} => {
 				let left = self.eval_value(l).await?;
 				let right = self.eval_value(r).await?;
+				let composites =
+					self.index_map.composites.get(e).cloned().unwrap_or_else(|| Arc::new(vec![]));
 				if let Some(io) = self.index_map.options.get(e) {
 					return Ok(Node::Expression {
 						io: Some(io.clone()),
+						composites,
 						left: Box::new(left),
 						right: Box::new(right),
 						exp: Arc::new(e.clone()),
@@ -156,12 +249,15 @@ impl<'a> TreeBuilder<'a> {
 				}
 				let mut io = None;
 				if let Some((id, irs)) = left.is_indexed_field() {
-					io = self.lookup_index_option(irs.as_slice(), o, id, &right, e);
+					io = self.lookup_index_option(irs.as_slice(), o, id, &right, e, true);
 				} else if let Some((id, irs)) = right.is_indexed_field() {
-					io = self.lookup_index_option(irs.as_slice(), o, id, &left, e);
+					io = self.lookup_index_option(irs.as_slice(), o, id, &left, e, false);
 				};
+				let composites =
+					self.index_map.composites.get(e).cloned().unwrap_or_else(|| Arc::new(vec![]));
 				Ok(Node::Expression {
 					io,
+					composites,
 					left: Box::new(left),
 					right: Box::new(right),
 					exp: Arc::new(e.clone()),
@@ -177,9 +273,22 @@ impl<'a> TreeBuilder<'a> {
 		id: &Idiom,
 		n: &Node,
 		e: &Expression,
+		field_left: bool,
 	) -> Option<IndexOption> {
+		let mut res = None;
+		let mut composites = vec![];
 		for ir in irs {
 			if let Some(ix) = self.index_map.definitions.get(ir) {
+				// The columns of a composite index are matched individually
+				if ix.cols.len() > 1 {
+					if let Some(op) = Self::eval_index_operator(op, n) {
+						composites.push(IndexOption::new(*ir, id.clone(), op));
+					}
+					continue;
+				}
+				if res.is_some() {
+					continue;
+				}
 				let op = match &ix.index {
 					Index::Idx => Self::eval_index_operator(op, n),
 					Index::Uniq => Self::eval_index_operator(op, n),
@@ -207,15 +316,19 @@ impl<'a> TreeBuilder<'a> {
 							None
 						}
 					}
+					Index::Spatial => Self::eval_spatial_operator(op, n, field_left),
 				};
 				if let Some(op) = op {
 					let io = IndexOption::new(*ir, id.clone(), op);
 					self.index_map.options.insert(Arc::new(e.clone()), io.clone());
-					return Some(io);
+					res = Some(io);
 				}
 			}
 		}
-		None
+		if !composites.is_empty() {
+			self.index_map.composites.insert(Arc::new(e.clone()), Arc::new(composites));
+		}
+		res
 	}
 
 	fn eval_index_operator(op: &Operator, n: &Node) -> Option<IndexOperator> {
@@ -233,6 +346,29 @@ impl<'a> TreeBuilder<'a> {
 		}
 	}
 
+	/// A spatial index resolves the geometries inside or intersecting a geometry,
+	/// and the geometries nearest to a point
+	fn eval_spatial_operator(op: &Operator, n: &Node, field_left: bool) -> Option<IndexOperator> {
+		match (op, n) {
+			(Operator::Knn(k), Node::Scalar(Value::Geometry(Geometry::Point(p)))) => {
+				Some(IndexOperator::Knn(Array::from(vec![p.x(), p.y()]), *k))
+			}
+			(Operator::Knn(k), Node::Vector(a)) if a.len() == 2 => {
+				Some(IndexOperator::Knn(a.clone(), *k))
+			}
+			(Operator::Inside, Node::Scalar(v @ Value::Geometry(_))) if field_left => {
+				Some(IndexOperator::Spatial(Operator::Inside, v.clone()))
+			}
+			(Operator::Contain, Node::Scalar(v @ Value::Geometry(_))) if !field_left => {
+				Some(IndexOperator::Spatial(Operator::Inside, v.clone()))
+			}
+			(Operator::Intersects, Node::Scalar(v @ Value::Geometry(_))) => {
+				Some(IndexOperator::Spatial(Operator::Intersects, v.clone()))
+			}
+			_ => None,
+		}
+	}
+
 	async fn eval_subquery(&mut self, s: &Subquery) -> Result<Node, Error> {
 		match s {
 			Subquery::Value(v) => self.eval_value(v).await,
@@ -247,13 +383,17 @@ pub(super) type IndexRef = u16;
 #[derive(Default)]
 pub(super) struct IndexMap {
 	pub(super) options: HashMap<Arc<Expression>, IndexOption>,
+	/// The options of the expressions matching a column of a composite index
+	pub(super) composites: HashMap<Arc<Expression>, Arc<Vec<IndexOption>>>,
 	pub(super) definitions: HashMap<IndexRef, DefineIndexStatement>,
+	pub(super) statistics: HashMap<IndexRef, IndexStatistics>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(super) enum Node {
 	Expression {
 		io: Option<IndexOption>,
+		composites: Arc<Vec<IndexOption>>,
 		left: Box<Node>,
 		right: Box<Node>,
 		exp: Arc<Expression>,
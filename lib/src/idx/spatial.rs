@@ -0,0 +1,431 @@
+use crate::err::Error;
+use crate::idx::IndexKeyBase;
+use crate::key::index::Index;
+use crate::kvs::{Key, Transaction};
+use crate::sql::{Array, Geometry, Id, Thing, Value};
+use geo::{
+	BoundingRect, Closest, ClosestPoint, Coord, HaversineDistance, Intersects, LineString, Point,
+	Rect,
+};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::f64::consts::FRAC_PI_2;
+use std::iter;
+use std::ops::Range;
+
+/// The alphabet of the geohash cells
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// The finest precision of a cell, which is about 38m x 19m
+const MAX_PRECISION: usize = 8;
+
+/// The maximum number of cells covering an indexed geometry
+const MAX_INDEX_CELLS: u64 = 4;
+
+/// The maximum number of cells covering a queried geometry
+const MAX_QUERY_CELLS: u64 = 16;
+
+/// The number of index entries read at once
+const SCAN_BATCH_SIZE: u32 = 1000;
+
+/// The mean radius of the Earth in metres, as used by the haversine distance
+const EARTH_RADIUS: f64 = 6371008.8;
+
+/// A spatial index stores, for each record, the geohash cells covering the bounding
+/// box of its geometries, along with the position of each geometry in the record.
+/// A geometry spanning many cells is stored in fewer, larger cells, so the entries
+/// of a cell are also found by any cell it contains or is part of. The index only
+/// returns candidates: the records still have to be checked, and a record is found
+/// once for each of its geometries and cells.
+pub(crate) struct SpatialIndex {
+	ikb: IndexKeyBase,
+}
+
+impl SpatialIndex {
+	pub(crate) fn new(ikb: IndexKeyBase) -> Self {
+		Self {
+			ikb,
+		}
+	}
+
+	pub(crate) async fn index_document(
+		&self,
+		run: &mut Transaction,
+		rid: &Thing,
+		old: Option<Vec<Value>>,
+		new: Option<Vec<Value>>,
+	) -> Result<(), Error> {
+		// Delete the old index data
+		if let Some(o) = old {
+			for (fd, _) in Self::cells(o) {
+				let key = self.ikb.new_index_key(&fd, &rid.id);
+				run.del(key).await?;
+			}
+		}
+		// Create the new index data
+		if let Some(n) = new {
+			for (fd, g) in Self::cells(n) {
+				let key = self.ikb.new_index_key(&fd, &rid.id);
+				run.set(key, Value::Geometry(g)).await?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Returns the cells covering each geometry of the indexed values,
+	/// along with the position of the geometry in the indexed values
+	fn cells(vals: Vec<Value>) -> Vec<(Array, Geometry)> {
+		let mut geometries = vec![];
+		for v in vals {
+			collect_geometries(v, &mut geometries);
+		}
+		let mut res = vec![];
+		for (i, g) in geometries.into_iter().enumerate() {
+			if let Some(rect) = bounding_rect(&g) {
+				for cell in covering(&rect, MAX_INDEX_CELLS) {
+					let fd = Array::from(vec![Value::from(cell), Value::from(i as i64)]);
+					res.push((fd, g.clone()));
+				}
+			}
+		}
+		res
+	}
+
+	/// Returns the ranges of the index entries which may intersect the given geometry
+	pub(crate) fn search_ranges(&self, g: &Geometry) -> VecDeque<Range<Key>> {
+		match bounding_rect(g) {
+			Some(rect) => self.rect_ranges(&rect),
+			None => VecDeque::new(),
+		}
+	}
+
+	fn rect_ranges(&self, rect: &Rect<f64>) -> VecDeque<Range<Key>> {
+		self.cells_ranges(&covering(rect, MAX_QUERY_CELLS))
+	}
+
+	/// Returns the ranges of the index entries overlapping the given cells
+	fn cells_ranges(&self, cells: &[String]) -> VecDeque<Range<Key>> {
+		// The larger cells containing the given cells
+		let mut parents = BTreeSet::new();
+		for c in cells {
+			for i in 0..c.len() {
+				parents.insert(&c[..i]);
+			}
+		}
+		let mut ranges = VecDeque::with_capacity(parents.len() + cells.len());
+		for p in parents {
+			ranges.push_back(self.ikb.new_index_composite_range(&Array::from(Value::from(p))));
+		}
+		// The given cells, and the smaller cells they contain
+		for c in cells {
+			let fd = Array::from(Value::from(c.as_str()));
+			ranges.push_back(self.ikb.new_index_starts_with_range(&fd));
+		}
+		ranges
+	}
+
+	/// Finds the records whose geometries are the closest to the given point
+	pub(crate) async fn knn(
+		&self,
+		run: &mut Transaction,
+		p: Point<f64>,
+		k: u32,
+	) -> Result<VecDeque<Thing>, Error> {
+		if k == 0 {
+			return Ok(VecDeque::new());
+		}
+		// Look for k records in the cells around the point, from the smallest to the largest
+		let rect = Rect::new(p.0, p.0);
+		let mut precision = MAX_PRECISION;
+		let mut found = loop {
+			let ranges = self.cells_ranges(&covering_at(&rect, precision));
+			let found = self.distances(run, p, ranges).await?;
+			if found.len() >= k as usize || precision == 0 {
+				break found;
+			}
+			precision -= 1;
+		};
+		if found.len() >= k as usize {
+			// The k-th closest record bounds the distance of the k nearest records
+			let mut distances: Vec<f64> = found.values().copied().collect();
+			distances.sort_by(|a, b| a.total_cmp(b));
+			let rect = radius_rect(&p, distances[k as usize - 1]);
+			found = self.distances(run, p, self.rect_ranges(&rect)).await?;
+		}
+		let mut res: Vec<(Id, f64)> = found.into_iter().collect();
+		res.sort_by(|(a, da), (b, db)| match da.total_cmp(db) {
+			Ordering::Equal => a.cmp(b),
+			o => o,
+		});
+		let tb = self.ikb.table();
+		Ok(res
+			.into_iter()
+			.take(k as usize)
+			.map(|(id, _)| Thing::from((tb.to_owned(), id)))
+			.collect())
+	}
+
+	/// Computes the distance between the point and the records found in the given ranges
+	async fn distances(
+		&self,
+		run: &mut Transaction,
+		p: Point<f64>,
+		mut ranges: VecDeque<Range<Key>>,
+	) -> Result<HashMap<Id, f64>, Error> {
+		let mut res: HashMap<Id, f64> = HashMap::new();
+		while let Some(r) = ranges.pop_front() {
+			let batch = run.scan(r.clone(), SCAN_BATCH_SIZE).await?;
+			if batch.len() == SCAN_BATCH_SIZE as usize {
+				if let Some((k, _)) = batch.last() {
+					let mut beg = k.clone();
+					beg.push(0x00);
+					ranges.push_front(beg..r.end);
+				}
+			}
+			for (k, v) in batch {
+				let key = Index::decode(&k)?;
+				if let (Some(id), Value::Geometry(g)) = (key.id, Value::from(v)) {
+					let d = distance(&p, g);
+					let e = res.entry(id.into_owned()).or_insert(d);
+					if d < *e {
+						*e = d;
+					}
+				}
+			}
+		}
+		Ok(res)
+	}
+}
+
+/// Extracts the geometries of an indexed value
+fn collect_geometries(v: Value, geometries: &mut Vec<Geometry>) {
+	match v {
+		Value::Geometry(g) => geometries.push(g),
+		Value::Array(a) => {
+			for v in a {
+				collect_geometries(v, geometries);
+			}
+		}
+		_ => {}
+	}
+}
+
+fn bounding_rect(g: &Geometry) -> Option<Rect<f64>> {
+	geo::Geometry::from(g.clone()).bounding_rect()
+}
+
+/// The haversine distance in metres between a point and a geometry, as computed by
+/// geo::distance. The closest point of each segment of a line is found on the plane.
+fn distance(p: &Point<f64>, g: Geometry) -> f64 {
+	match g {
+		Geometry::Point(v) => p.haversine_distance(&v),
+		Geometry::Line(v) => line_distance(p, &v),
+		Geometry::Polygon(v) => {
+			if v.intersects(p) {
+				return 0.0;
+			}
+			iter::once(v.exterior())
+				.chain(v.interiors())
+				.map(|l| line_distance(p, l))
+				.fold(f64::MAX, f64::min)
+		}
+		Geometry::MultiPoint(v) => {
+			v.into_iter().map(|v| p.haversine_distance(&v)).fold(f64::MAX, f64::min)
+		}
+		Geometry::MultiLine(v) => v.iter().map(|l| line_distance(p, l)).fold(f64::MAX, f64::min),
+		Geometry::MultiPolygon(v) => {
+			v.into_iter().map(|v| distance(p, Geometry::Polygon(v))).fold(f64::MAX, f64::min)
+		}
+		Geometry::Collection(v) => v.into_iter().map(|g| distance(p, g)).fold(f64::MAX, f64::min),
+	}
+}
+
+/// The haversine distance in metres between a point and the closest segment of a line
+fn line_distance(p: &Point<f64>, l: &LineString<f64>) -> f64 {
+	if let [c] = l.0.as_slice() {
+		return p.haversine_distance(&Point::from(*c));
+	}
+	l.lines()
+		.map(|s| match s.closest_point(p) {
+			Closest::Intersection(_) => 0.0,
+			Closest::SinglePoint(c) => p.haversine_distance(&c),
+			Closest::Indeterminate => f64::MAX,
+		})
+		.fold(f64::MAX, f64::min)
+}
+
+/// Returns a rectangle containing every coordinate within a distance in metres of a point
+fn radius_rect(p: &Point<f64>, r: f64) -> Rect<f64> {
+	// The distance as an angle, and the latitudes it spans
+	let d = r / EARTH_RADIUS;
+	let lat = p.y().to_radians();
+	let (lat0, lat1) = (lat - d, lat + d);
+	let (x0, x1) = if lat0 > -FRAC_PI_2 && lat1 < FRAC_PI_2 {
+		let dx = (d.sin() / lat.cos()).asin().to_degrees();
+		(p.x() - dx, p.x() + dx)
+	} else {
+		// Every longitude is within the distance of a pole
+		(-180.0, 180.0)
+	};
+	// The rectangles crossing the antimeridian span every longitude
+	let (x0, x1) = if x0 < -180.0 || x1 > 180.0 {
+		(-180.0, 180.0)
+	} else {
+		(x0, x1)
+	};
+	Rect::new(
+		Coord {
+			x: x0,
+			y: lat0.max(-FRAC_PI_2).to_degrees(),
+		},
+		Coord {
+			x: x1,
+			y: lat1.min(FRAC_PI_2).to_degrees(),
+		},
+	)
+}
+
+/// Returns the cells covering a rectangle, with the finest precision
+/// which does not require more than the given number of cells
+fn covering(rect: &Rect<f64>, max: u64) -> Vec<String> {
+	for precision in (1..=MAX_PRECISION).rev() {
+		let (x0, y0) = cell_xy(rect.min(), precision);
+		let (x1, y1) = cell_xy(rect.max(), precision);
+		if (x1 - x0 + 1) * (y1 - y0 + 1) <= max {
+			return covering_at(rect, precision);
+		}
+	}
+	// The empty cell covers the whole world
+	vec![String::new()]
+}
+
+/// Returns the cells covering a rectangle at the given precision
+fn covering_at(rect: &Rect<f64>, precision: usize) -> Vec<String> {
+	let (x0, y0) = cell_xy(rect.min(), precision);
+	let (x1, y1) = cell_xy(rect.max(), precision);
+	let mut cells = Vec::with_capacity(((x1 - x0 + 1) * (y1 - y0 + 1)) as usize);
+	for x in x0..=x1 {
+		for y in y0..=y1 {
+			cells.push(encode(x, y, precision));
+		}
+	}
+	cells
+}
+
+/// The number of bits of the longitude and the latitude of a cell
+fn bits(precision: usize) -> (u32, u32) {
+	let bits = precision as u32 * 5;
+	((bits + 1) / 2, bits / 2)
+}
+
+/// The column and row of the cell containing the coordinates
+fn cell_xy(c: Coord<f64>, precision: usize) -> (u64, u64) {
+	let (lon_bits, lat_bits) = bits(precision);
+	(cell_index(c.x, -180.0, 180.0, lon_bits), cell_index(c.y, -90.0, 90.0, lat_bits))
+}
+
+fn cell_index(v: f64, min: f64, max: f64, bits: u32) -> u64 {
+	let n = 1u64 << bits;
+	let i = ((v - min) / (max - min) * n as f64).floor();
+	// Coordinates outside of the bounds belong to the cells on the edges
+	if i.is_nan() || i <= 0.0 {
+		0
+	} else {
+		(i as u64).min(n - 1)
+	}
+}
+
+/// Encodes a cell as a geohash, interleaving the bits of the longitude and the latitude
+fn encode(x: u64, y: u64, precision: usize) -> String {
+	let (lon_bits, lat_bits) = bits(precision);
+	let mut hash = String::with_capacity(precision);
+	let mut v = 0;
+	for i in 0..precision as u32 * 5 {
+		let bit = if i % 2 == 0 {
+			(x >> (lon_bits - 1 - i / 2)) & 1
+		} else {
+			(y >> (lat_bits - 1 - i / 2)) & 1
+		};
+		v = (v << 1) | bit as usize;
+		if i % 5 == 4 {
+			hash.push(BASE32[v] as char);
+			v = 0;
+		}
+	}
+	hash
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encode_point() {
+		// The geohash of the Eiffel Tower
+		let rect = Rect::new(
+			Coord {
+				x: 2.2945,
+				y: 48.8584,
+			},
+			Coord {
+				x: 2.2945,
+				y: 48.8584,
+			},
+		);
+		assert_eq!(covering(&rect, MAX_INDEX_CELLS), vec!["u09tunqu"]);
+		assert_eq!(covering_at(&rect, 3), vec!["u09"]);
+		assert_eq!(covering_at(&rect, 0), vec![""]);
+	}
+
+	#[test]
+	fn covering_rect() {
+		let rect = Rect::new(
+			Coord {
+				x: -0.5,
+				y: 51.2,
+			},
+			Coord {
+				x: 0.3,
+				y: 51.7,
+			},
+		);
+		let cells = covering(&rect, MAX_QUERY_CELLS);
+		assert!(cells.len() <= MAX_QUERY_CELLS as usize);
+		// Every cell has the same precision
+		assert!(cells.iter().all(|c| c.len() == cells[0].len()));
+		// The corners are covered
+		for c in [rect.min(), rect.max()] {
+			let p = covering(&Rect::new(c, c), 1);
+			assert!(cells.iter().any(|cell| p[0].starts_with(cell.as_str())), "{cells:?}");
+		}
+	}
+
+	#[test]
+	fn radius_rect_bounds() {
+		// A tenth of a degree of latitude is about 11km
+		let rect = radius_rect(&Point::new(2.0, 60.0), 11_120.0);
+		assert!((rect.min().y - 59.9).abs() < 0.001 && (rect.max().y - 60.1).abs() < 0.001);
+		// A degree of longitude is half as long at 60 degrees of latitude
+		assert!((rect.min().x - 1.8).abs() < 0.001 && (rect.max().x - 2.2).abs() < 0.001);
+		// Across the antimeridian, or around a pole, every longitude is covered
+		for p in [Point::new(179.99, 0.0), Point::new(0.0, 89.99)] {
+			let rect = radius_rect(&p, 11_120.0);
+			assert_eq!((rect.min().x, rect.max().x), (-180.0, 180.0));
+		}
+	}
+
+	#[test]
+	fn covering_world() {
+		let rect = Rect::new(
+			Coord {
+				x: -180.0,
+				y: -90.0,
+			},
+			Coord {
+				x: 180.0,
+				y: 90.0,
+			},
+		);
+		assert_eq!(covering(&rect, MAX_INDEX_CELLS), vec![""]);
+	}
+}
@@ -3,6 +3,7 @@ use crate::fnc::util::math::vector::{
 	CosineSimilarity, EuclideanDistance, HammingDistance, ManhattanDistance, MinkowskiDistance,
 };
 use crate::idx::docids::{DocId, DocIds};
+use crate::idx::planner::stats::IndexStatistics;
 use crate::idx::trees::btree::BStatistics;
 use crate::idx::trees::store::{
 	NodeId, StoredNode, TreeNode, TreeNodeProvider, TreeNodeStore, TreeStoreType,
@@ -153,9 +154,12 @@ impl MTreeIndex {
 	}
 
 	pub(crate) async fn statistics(&self, tx: &mut Transaction) -> Result<MtStatistics, Error> {
-		Ok(MtStatistics {
-			doc_ids: self.doc_ids.read().await.statistics(tx).await?,
-		})
+		let mut stats = {
+			let mut store = self.store.lock().await;
+			self.mtree.read().await.statistics(tx, &mut store).await?
+		};
+		stats.doc_ids = self.doc_ids.read().await.statistics(tx).await?;
+		Ok(stats)
 	}
 
 	pub(crate) async fn finish(self, tx: &mut Transaction) -> Result<(), Error> {
@@ -248,6 +252,87 @@ impl MTree {
 		})
 	}
 
+	/// Traverses the whole tree, collecting statistics and checking the M-Tree invariants:
+	/// - every leaf is at the same depth,
+	/// - no node has more entries than the capacity,
+	/// - every entry is covered by the radius of its routing entry,
+	/// - the distance of every object to its parent matches the stored distance.
+	async fn statistics(
+		&self,
+		tx: &mut Transaction,
+		store: &mut MTreeNodeStore,
+	) -> Result<MtStatistics, Error> {
+		let mut stats = MtStatistics::default();
+		let capacity = self.state.capacity as usize;
+		let mut leaf_depth = None;
+		let mut queue = VecDeque::new();
+		if let Some(root_id) = self.state.root {
+			queue.push_back((root_id, 1u32, None));
+		}
+		while let Some((node_id, depth, parent)) = queue.pop_front() {
+			let node = store.get_node(tx, node_id).await?;
+			stats.nodes_count += 1;
+			stats.total_size += node.size as u64;
+			stats.max_depth = stats.max_depth.max(depth);
+			let len = node.n.len();
+			if len > capacity {
+				stats.inconsistencies.push(format!(
+					"Node {node_id} has {len} entries, more than the capacity ({capacity})"
+				));
+			}
+			match &node.n {
+				MTreeNode::Internal(entries) => {
+					for e in entries {
+						self.check_covering(&mut stats, node_id, &parent, &e.center, e.radius);
+						queue.push_back((e.node, depth + 1, Some((e.center.clone(), e.radius))));
+					}
+				}
+				MTreeNode::Leaf(objects) => {
+					let expected_depth = *leaf_depth.get_or_insert(depth);
+					if depth != expected_depth {
+						stats.inconsistencies.push(format!(
+							"Leaf {node_id} is at depth {depth}, other leaves are at depth {expected_depth}"
+						));
+					}
+					for (o, p) in objects {
+						stats.objects_count += 1;
+						stats.docs_count += p.docs.len();
+						self.check_covering(&mut stats, node_id, &parent, o, 0.0);
+						let parent_dist = parent
+							.as_ref()
+							.map_or(0.0, |(c, _)| self.calculate_distance(c.as_ref(), o.as_ref()));
+						if (parent_dist - p.parent_dist).abs() > f64::EPSILON {
+							stats.inconsistencies.push(format!(
+								"Object {o:?} in leaf {node_id} has a parent distance of {}, expected {parent_dist}",
+								p.parent_dist
+							));
+						}
+					}
+				}
+			}
+			store.set_node(node, false)?;
+		}
+		Ok(stats)
+	}
+
+	fn check_covering(
+		&self,
+		stats: &mut MtStatistics,
+		node_id: NodeId,
+		parent: &Option<(Arc<Vector>, f64)>,
+		center: &Vector,
+		radius: f64,
+	) {
+		if let Some((parent_center, parent_radius)) = parent {
+			let d = self.calculate_distance(parent_center.as_ref(), center) + radius;
+			if d > parent_radius + f64::EPSILON {
+				stats.inconsistencies.push(format!(
+					"Entry {center:?} in node {node_id} is not covered by its parent (distance: {d}, radius: {parent_radius})"
+				));
+			}
+		}
+	}
+
 	fn check_add(k: usize, dist: f64, res: &BTreeMap<PriorityResult, RoaringTreemap>) -> bool {
 		if res.len() < k {
 			true
@@ -534,7 +619,8 @@ impl MTree {
 		parent.as_ref().map_or(0.0, |p| {
 			let mut max_dist = 0f64;
 			for e in node {
-				max_dist = max_dist.max(self.calculate_distance(p.as_ref(), e.center.as_ref()));
+				max_dist =
+					max_dist.max(self.calculate_distance(p.as_ref(), e.center.as_ref()) + e.radius);
 			}
 			max_dist
 		})
@@ -589,11 +675,10 @@ impl MTree {
 		if let Some(root_id) = self.state.root {
 			let node = store.get_node(tx, root_id).await?;
 			match self.delete_at_node(tx, store, node, &None, Arc::new(object), doc_id).await? {
-				DeletionResult::DocRemoved => Ok(true),
-				DeletionResult::CoveringRadius(_) | DeletionResult::NotFound => Ok(false),
+				DeletionResult::NotFound => Ok(false),
+				DeletionResult::DocRemoved | DeletionResult::CoveringRadius(_) => Ok(true),
 				DeletionResult::Underflown(id, key, n) => {
-					let sn = StoredNode::new(n, id, key, 0);
-					store.set_node(sn, true)?;
+					self.delete_underflown_root(tx, store, id, key, n).await?;
 					Ok(true)
 				}
 			}
@@ -628,7 +713,7 @@ impl MTree {
 				.await
 			}
 			MTreeNode::Leaf(n) => {
-				self.delete_node_leaf(store, node.id, node.key, n, parent_center, object, id).await
+				self.delete_node_leaf(store, node.id, node.key, n, parent_center, object, id)
 			}
 		}
 	}
@@ -640,138 +725,253 @@ impl MTree {
 		store: &mut MTreeNodeStore,
 		node_id: NodeId,
 		node_key: Key,
-		mut internal_node: InternalNode,
-		_parent_center: &Option<Arc<Vector>>,
+		mut node: InternalNode,
+		parent_center: &Option<Arc<Vector>>,
 		object: Arc<Vector>,
 		id: DocId,
 	) -> Result<DeletionResult, Error> {
-		let mut node_update = false;
-		let mut child_idx = None;
-		let mut child_radius = 0.0;
-		for (i, e) in internal_node.iter().enumerate() {
-			child_radius = self.calculate_distance(e.center.as_ref(), &object);
-			if child_radius <= e.radius {
-				child_idx = Some(i);
-				break;
-			}
-		}
-		let mut res = DeletionResult::NotFound;
-		if let Some(child_idx) = child_idx {
-			let child_entry = &mut internal_node[child_idx];
-			let child_id = child_entry.node;
-			let child_center = child_entry.center.clone();
-			let child_node = store.get_node(tx, child_id).await?;
+		// The covering regions may overlap, so every child covering the object is a candidate
+		let candidates: Vec<usize> = node
+			.iter()
+			.enumerate()
+			.filter(|(_, e)| self.calculate_distance(e.center.as_ref(), &object) <= e.radius)
+			.map(|(i, _)| i)
+			.collect();
+		let mut doc_removed = false;
+		let mut node_updated = false;
+		for child_idx in candidates {
+			let child_center = node[child_idx].center.clone();
+			let child_node = store.get_node(tx, node[child_idx].node).await?;
 			match self
-				.delete_at_node(tx, store, child_node, &Some(child_center.clone()), object, id)
+				.delete_at_node(tx, store, child_node, &Some(child_center), object.clone(), id)
 				.await?
 			{
-				DeletionResult::NotFound => res = DeletionResult::NotFound,
-				DeletionResult::DocRemoved => res = DeletionResult::DocRemoved,
+				DeletionResult::NotFound => continue,
+				DeletionResult::DocRemoved => {
+					doc_removed = true;
+				}
 				DeletionResult::CoveringRadius(r) => {
-					if r > child_radius {
-						internal_node[child_idx].radius = r;
-						node_update = true;
-					}
+					node[child_idx].radius = r;
+					node_updated = true;
 				}
-				DeletionResult::Underflown(child_node_id, child_node_key, child_node) => {
-					if self
-						.deletion_underflown(
-							tx,
-							store,
-							&mut internal_node,
-							child_center.as_ref(),
-							child_node_id,
-							child_node_key,
-							child_node,
-						)
-						.await?
-					{
-						node_update = true;
-					}
+				DeletionResult::Underflown(child_id, child_key, child_node) => {
+					self.delete_underflown(
+						tx, store, &mut node, child_idx, child_id, child_key, child_node,
+					)
+					.await?;
+					node_updated = true;
 				}
 			}
-			if internal_node.len() < self.minimum {
-				return Ok(DeletionResult::Underflown(
-					node_id,
-					node_key,
-					MTreeNode::Internal(internal_node),
-				));
-			}
+			break;
+		}
+		if !node_updated {
+			let sn = StoredNode::new(MTreeNode::Internal(node), node_id, node_key, 0);
+			store.set_node(sn, false)?;
+			return Ok(if doc_removed {
+				DeletionResult::DocRemoved
+			} else {
+				DeletionResult::NotFound
+			});
+		}
+		if node.len() < self.minimum {
+			return Ok(DeletionResult::Underflown(node_id, node_key, MTreeNode::Internal(node)));
 		}
-		let sn = StoredNode::new(MTreeNode::Internal(internal_node), node_id, node_key, 0);
-		store.set_node(sn, node_update)?;
-		Ok(res)
+		let max_dist = self.compute_internal_max_distance(&node, parent_center);
+		let sn = StoredNode::new(MTreeNode::Internal(node), node_id, node_key, 0);
+		store.set_node(sn, true)?;
+		Ok(DeletionResult::CoveringRadius(max_dist))
 	}
 
-	#[allow(unused_variables, unused_assignments, clippy::too_many_arguments)]
-	async fn deletion_underflown(
+	/// Handles a child node which has less than the minimum number of entries.
+	/// The child is either merged with its closest sibling, if they fit together in a single node,
+	/// or it takes from its closest sibling the entries required to reach the minimum.
+	#[allow(clippy::too_many_arguments)]
+	async fn delete_underflown(
 		&mut self,
 		tx: &mut Transaction,
 		store: &mut MTreeNodeStore,
 		node: &mut InternalNode,
-		other_center: &Vector,
-		underflow_child_id: NodeId,
-		underflow_child_key: Key,
-		underflow_child_node: MTreeNode,
-	) -> Result<bool, Error> {
-		let min = f64::NAN;
-		let mut s_child_idx = None;
-		// Find node entre Onn € N, e <> 0, for which d(On, Onn) is a minimum
+		child_idx: usize,
+		child_id: NodeId,
+		child_key: Key,
+		child_node: MTreeNode,
+	) -> Result<(), Error> {
+		// An empty node is simply removed
+		if child_node.len() == 0 {
+			store.remove_node(child_id, child_key)?;
+			node.remove(child_idx);
+			return Ok(());
+		}
+		let child_center = node[child_idx].center.clone();
+		// Find the sibling which is the closest to the underflown child
+		let mut sibling_idx = None;
+		let mut min_dist = f64::MAX;
 		for (i, e) in node.iter().enumerate() {
-			if e.node != underflow_child_id {
-				let d = self.calculate_distance(other_center, e.center.as_ref());
-				if min.is_nan() || d < min {
-					s_child_idx = Some(i);
+			if i != child_idx {
+				let d = self.calculate_distance(child_center.as_ref(), e.center.as_ref());
+				if sibling_idx.is_none() || d < min_dist {
+					min_dist = d;
+					sibling_idx = Some(i);
 				}
 			}
 		}
-		if let Some(s_child_idx) = s_child_idx {
-			let mut node_updated = false;
-			let s_child_entry = &node[s_child_idx];
-			let s_child_center = s_child_entry.center.clone();
-			let s_child_node = store.get_node(tx, s_child_entry.node).await?;
-			if s_child_node.n.len() + underflow_child_node.len() <= self.state.capacity as usize {
-				node.remove(s_child_idx);
-				node_updated = true;
-				//
-				match underflow_child_node {
-					MTreeNode::Internal(n) => {
-						let mut s_child_node = s_child_node.n.internal()?;
-						for e in n {
-							let parent_dist =
-								self.calculate_distance(e.center.as_ref(), s_child_center.as_ref());
-							s_child_node.push(e);
-						}
-						//TODO
-						return Err(Error::FeatureNotYetImplemented {
-							feature: "MTREE deletions".to_string(),
-						});
-					}
-					MTreeNode::Leaf(n) => {
-						let mut s_child_node = s_child_node.n.leaf()?;
-						for (o, mut p) in n {
-							p.parent_dist =
-								self.calculate_distance(o.as_ref(), s_child_center.as_ref());
-							s_child_node.insert(o, p);
-						}
-						//TODO
-						return Err(Error::FeatureNotYetImplemented {
-							feature: "MTREE deletions".to_string(),
-						});
+		let sibling_idx = if let Some(sibling_idx) = sibling_idx {
+			sibling_idx
+		} else {
+			// Without any sibling, the child is kept as it is, and the parent will take care of it
+			node[child_idx].radius = self.compute_node_radius(&child_node, &child_center);
+			store.set_node(StoredNode::new(child_node, child_id, child_key, 0), true)?;
+			return Ok(());
+		};
+		let sibling_center = node[sibling_idx].center.clone();
+		let sibling = store.get_node(tx, node[sibling_idx].node).await?;
+		if sibling.n.len() + child_node.len() <= self.state.capacity as usize {
+			// Merge the underflown child into its sibling
+			let merged = self.merge_nodes(sibling.n, child_node, &sibling_center)?;
+			node[sibling_idx].radius = self.compute_node_radius(&merged, &sibling_center);
+			store.set_node(StoredNode::new(merged, sibling.id, sibling.key, 0), true)?;
+			store.remove_node(child_id, child_key)?;
+			node.remove(child_idx);
+		} else {
+			// Move the sibling's entries closest to the underflown child
+			let (child_node, sibling_node) =
+				self.redistribute_nodes(child_node, &child_center, sibling.n)?;
+			node[child_idx].radius = self.compute_node_radius(&child_node, &child_center);
+			node[sibling_idx].radius = self.compute_node_radius(&sibling_node, &sibling_center);
+			store.set_node(StoredNode::new(child_node, child_id, child_key, 0), true)?;
+			store.set_node(StoredNode::new(sibling_node, sibling.id, sibling.key, 0), true)?;
+		}
+		Ok(())
+	}
+
+	async fn delete_underflown_root(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut MTreeNodeStore,
+		root_id: NodeId,
+		root_key: Key,
+		root_node: MTreeNode,
+	) -> Result<(), Error> {
+		match root_node {
+			// The tree is empty
+			n if n.len() == 0 => {
+				store.remove_node(root_id, root_key)?;
+				self.state.root = None;
+				self.updated = true;
+			}
+			// The root has a single child, which becomes the new root
+			MTreeNode::Internal(mut n) if n.len() == 1 => {
+				let entry = n.remove(0);
+				store.remove_node(root_id, root_key)?;
+				let mut new_root = store.get_node(tx, entry.node).await?;
+				if let MTreeNode::Leaf(ref mut objects) = new_root.n {
+					for p in objects.values_mut() {
+						p.parent_dist = 0.0;
 					}
 				}
-			} else {
-				todo!()
+				store.set_node(new_root, true)?;
+				self.state.root = Some(entry.node);
+				self.updated = true;
+			}
+			// The root is allowed to have less than the minimum number of entries
+			n => {
+				store.set_node(StoredNode::new(n, root_id, root_key, 0), true)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn merge_nodes(
+		&self,
+		node: MTreeNode,
+		other: MTreeNode,
+		center: &Arc<Vector>,
+	) -> Result<MTreeNode, Error> {
+		match (node, other) {
+			(MTreeNode::Internal(mut n), MTreeNode::Internal(o)) => {
+				n.extend(o);
+				Ok(MTreeNode::Internal(n))
+			}
+			(MTreeNode::Leaf(mut n), MTreeNode::Leaf(o)) => {
+				for (v, p) in o {
+					self.insert_leaf_object(&mut n, center, v, p);
+				}
+				Ok(MTreeNode::Leaf(n))
+			}
+			_ => Err(Error::CorruptedIndex),
+		}
+	}
+
+	fn redistribute_nodes(
+		&self,
+		node: MTreeNode,
+		center: &Arc<Vector>,
+		sibling: MTreeNode,
+	) -> Result<(MTreeNode, MTreeNode), Error> {
+		match (node, sibling) {
+			(MTreeNode::Internal(mut n), MTreeNode::Internal(mut s)) => {
+				while n.len() < self.minimum {
+					let idx = self.closest_index(s.iter().map(|e| e.center.as_ref()), center)?;
+					n.push(s.remove(idx));
+				}
+				Ok((MTreeNode::Internal(n), MTreeNode::Internal(s)))
+			}
+			(MTreeNode::Leaf(mut n), MTreeNode::Leaf(mut s)) => {
+				while n.len() < self.minimum {
+					let idx = self.closest_index(s.keys().map(|v| v.as_ref()), center)?;
+					let (v, p) = s.shift_remove_index(idx).ok_or(Error::Unreachable)?;
+					self.insert_leaf_object(&mut n, center, v, p);
+				}
+				Ok((MTreeNode::Leaf(n), MTreeNode::Leaf(s)))
+			}
+			_ => Err(Error::CorruptedIndex),
+		}
+	}
+
+	fn closest_index<'a, I>(&self, vectors: I, center: &Vector) -> Result<usize, Error>
+	where
+		I: Iterator<Item = &'a Vector>,
+	{
+		let mut closest = None;
+		let mut min_dist = f64::MAX;
+		for (i, v) in vectors.enumerate() {
+			let d = self.calculate_distance(center, v);
+			if closest.is_none() || d < min_dist {
+				min_dist = d;
+				closest = Some(i);
+			}
+		}
+		closest.ok_or(Error::Unreachable)
+	}
+
+	fn insert_leaf_object(
+		&self,
+		node: &mut LeafNode,
+		center: &Arc<Vector>,
+		object: Arc<Vector>,
+		mut p: ObjectProperties,
+	) {
+		match node.entry(object) {
+			Entry::Occupied(mut e) => {
+				e.get_mut().docs |= p.docs;
+			}
+			Entry::Vacant(e) => {
+				p.parent_dist = self.calculate_distance(center.as_ref(), e.key());
+				e.insert(p);
 			}
-			#[allow(unreachable_code)]
-			Ok(node_updated)
-		} else {
-			Ok(false)
+		}
+	}
+
+	fn compute_node_radius(&self, node: &MTreeNode, center: &Arc<Vector>) -> f64 {
+		let center = Some(center.clone());
+		match node {
+			MTreeNode::Internal(n) => self.compute_internal_max_distance(n, &center),
+			MTreeNode::Leaf(n) => self.compute_leaf_max_distance(n, &center),
 		}
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	async fn delete_node_leaf(
+	fn delete_node_leaf(
 		&mut self,
 		store: &mut MTreeNodeStore,
 		node_id: NodeId,
@@ -788,23 +988,31 @@ impl MTree {
 			if p.docs.remove(id) {
 				doc_removed = true;
 				if p.docs.is_empty() {
-					e.remove();
+					e.shift_remove();
 					entry_removed = true;
 				}
 			}
 		}
-		if entry_removed && leaf_node.len() < self.minimum && self.state.root != Some(node_id) {
-			return Ok(DeletionResult::Underflown(node_id, node_key, MTreeNode::Leaf(leaf_node)));
-		}
-		if doc_removed {
+		if entry_removed {
+			if leaf_node.len() < self.minimum {
+				return Ok(DeletionResult::Underflown(
+					node_id,
+					node_key,
+					MTreeNode::Leaf(leaf_node),
+				));
+			}
+			let max_dist = self.compute_leaf_max_distance(&leaf_node, parent_center);
 			let sn = StoredNode::new(MTreeNode::Leaf(leaf_node), node_id, node_key, 0);
 			store.set_node(sn, true)?;
-			return Ok(DeletionResult::DocRemoved);
+			return Ok(DeletionResult::CoveringRadius(max_dist));
 		}
-		let max_dist = self.compute_leaf_max_distance(&leaf_node, parent_center);
 		let sn = StoredNode::new(MTreeNode::Leaf(leaf_node), node_id, node_key, 0);
-		store.set_node(sn, false)?;
-		Ok(DeletionResult::CoveringRadius(max_dist))
+		store.set_node(sn, doc_removed)?;
+		if doc_removed {
+			Ok(DeletionResult::DocRemoved)
+		} else {
+			Ok(DeletionResult::NotFound)
+		}
 	}
 
 	async fn finish(&self, tx: &mut Transaction, key: Key) -> Result<(), Error> {
@@ -882,20 +1090,6 @@ impl MTreeNode {
 			MTreeNode::Leaf(m) => m.len(),
 		}
 	}
-
-	fn internal(self) -> Result<InternalNode, Error> {
-		match self {
-			MTreeNode::Internal(n) => Ok(n),
-			MTreeNode::Leaf(_) => Err(Error::Unreachable),
-		}
-	}
-
-	fn leaf(self) -> Result<LeafNode, Error> {
-		match self {
-			MTreeNode::Internal(_) => Err(Error::Unreachable),
-			MTreeNode::Leaf(n) => Ok(n),
-		}
-	}
 }
 trait NodeVectors: Sized {
 	fn len(&self) -> usize;
@@ -975,16 +1169,13 @@ impl NodeVectors for InternalNode {
 		for (i, r) in self.into_iter().enumerate() {
 			let dist_p1 = distances[i][p1];
 			let dist_p2 = distances[i][p2];
+			// The covering radius must include the covering radius of the routed node
 			if dist_p1 <= dist_p2 {
+				r1 = r1.max(dist_p1 + r.radius);
 				internal1.push(r);
-				if dist_p1 > r1 {
-					r1 = dist_p1;
-				}
 			} else {
+				r2 = r2.max(dist_p2 + r.radius);
 				internal2.push(r);
-				if dist_p2 > r2 {
-					r2 = dist_p2;
-				}
 			}
 		}
 		Ok((internal1, r1, internal2, r2))
@@ -1032,14 +1223,39 @@ impl TreeNode for MTreeNode {
 	}
 }
 
+#[derive(Default)]
 pub(crate) struct MtStatistics {
 	doc_ids: BStatistics,
+	nodes_count: u32,
+	max_depth: u32,
+	objects_count: u64,
+	docs_count: u64,
+	total_size: u64,
+	inconsistencies: Vec<String>,
+}
+
+impl From<&MtStatistics> for IndexStatistics {
+	fn from(stats: &MtStatistics) -> Self {
+		Self {
+			keys_count: stats.docs_count,
+			distinct_count: stats.objects_count,
+			total_size: stats.total_size,
+			..Default::default()
+		}
+	}
 }
 
 impl From<MtStatistics> for Value {
 	fn from(stats: MtStatistics) -> Self {
 		let mut res = Object::default();
 		res.insert("doc_ids".to_owned(), Value::from(stats.doc_ids));
+		res.insert("nodes_count".to_owned(), Value::from(stats.nodes_count));
+		res.insert("max_depth".to_owned(), Value::from(stats.max_depth));
+		res.insert("objects_count".to_owned(), Value::from(stats.objects_count));
+		res.insert("docs_count".to_owned(), Value::from(stats.docs_count));
+		res.insert("total_size".to_owned(), Value::from(stats.total_size));
+		res.insert("consistent".to_owned(), Value::from(stats.inconsistencies.is_empty()));
+		res.insert("inconsistencies".to_owned(), Value::from(stats.inconsistencies));
 		Value::from(res)
 	}
 }
@@ -1360,8 +1576,8 @@ mod tests {
 			// Check Root node (level 1)
 			check_internal(&mut tx, &mut s, 6, |m| {
 				assert_eq!(m.len(), 2);
-				check_routing_vec(m, 0, &vec1, 2, 2.0);
-				check_routing_vec(m, 1, &vec10, 5, 4.0);
+				check_routing_vec(m, 0, &vec1, 2, 3.0);
+				check_routing_vec(m, 1, &vec10, 5, 6.0);
 			})
 			.await;
 			// Check level 2
@@ -1437,7 +1653,6 @@ mod tests {
 	}
 
 	#[test(tokio::test)]
-	#[ignore]
 	async fn test_mtree_deletion_doc_removed_and_none() {
 		let ds = Datastore::new("memory").await.unwrap();
 
@@ -1540,11 +1755,11 @@ mod tests {
 			check_tree_properties(&mut tx, &mut s, &t, 3, 2, Some(2), Some(3)).await;
 		}
 
-		// Remove vec5 / 50 => DeleteResult::UnderflownLeafIndexMap
+		// Remove vec5 / 50 => DeleteResult::Underflown, the leaf is merged and the root collapses
 		{
 			let (s, mut tx) = new_operation(&ds, TreeStoreType::Write).await;
 			let mut s = s.lock().await;
-			assert!(!t.delete(&mut tx, &mut s, vec5.clone(), 50).await.unwrap());
+			assert!(t.delete(&mut tx, &mut s, vec5.clone(), 50).await.unwrap());
 			finish_operation(tx, s, true).await;
 		}
 		{
@@ -1552,7 +1767,17 @@ mod tests {
 			let mut s = s.lock().await;
 			let res = t.knn_search(&mut tx, &mut s, &vec1, 10).await.unwrap();
 			check_knn(&res.objects, vec![vec![10], vec![20], vec![30], vec![40]]);
-			check_tree_properties(&mut tx, &mut s, &t, 3, 2, Some(2), Some(3)).await;
+			check_tree_properties(&mut tx, &mut s, &t, 1, 1, Some(4), Some(4)).await;
+			assert_eq!(t.state.root, Some(0));
+			check_leaf(&mut tx, &mut s, 0, |m| {
+				assert_eq!(m.len(), 4);
+				check_leaf_vec(m, 0, &vec1, 0.0, &[10]);
+				check_leaf_vec(m, 1, &vec2, 0.0, &[20]);
+				check_leaf_vec(m, 2, &vec3, 0.0, &[30]);
+				check_leaf_vec(m, 3, &vec4, 0.0, &[40]);
+			})
+			.await;
+			check_consistency(&mut tx, &mut s, &t).await;
 		}
 	}
 
@@ -1620,13 +1845,171 @@ mod tests {
 			check_tree_properties(&mut tx, &mut s, &t, 8, 3, Some(2), Some(3)).await;
 		}
 
-		// Remove ->
+		// Remove v9 -> the leaf still has the minimum number of objects
 		{
 			let (s, mut tx) = new_operation(&ds, TreeStoreType::Write).await;
 			let mut s = s.lock().await;
 			assert!(t.delete(&mut tx, &mut s, v9.clone(), 90).await.unwrap());
 			finish_operation(tx, s, true).await;
 		}
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Traversal).await;
+			let mut s = s.lock().await;
+			check_tree_properties(&mut tx, &mut s, &t, 8, 3, Some(2), Some(3)).await;
+			check_consistency(&mut tx, &mut s, &t).await;
+		}
+
+		// Remove v10 -> the underflown leaf is merged into its closest sibling
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Write).await;
+			let mut s = s.lock().await;
+			assert!(t.delete(&mut tx, &mut s, v10.clone(), 100).await.unwrap());
+			finish_operation(tx, s, true).await;
+		}
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Traversal).await;
+			let mut s = s.lock().await;
+			check_tree_properties(&mut tx, &mut s, &t, 7, 3, Some(2), Some(3)).await;
+			check_internal(&mut tx, &mut s, 2, |m| {
+				assert_eq!(m.len(), 2);
+				check_routing_vec(m, 0, &v13, 1, 1.0);
+				check_routing_vec(m, 1, &v8, 5, 3.0);
+			})
+			.await;
+			check_leaf(&mut tx, &mut s, 5, |m| {
+				assert_eq!(m.len(), 3);
+				check_leaf_vec(m, 0, &v7, 1.0, &[70]);
+				check_leaf_vec(m, 1, &v8, 0.0, &[80]);
+				check_leaf_vec(m, 2, &v11, 3.0, &[110]);
+			})
+			.await;
+			check_consistency(&mut tx, &mut s, &t).await;
+		}
+
+		// Remove v11 and v12 -> the underflown routing node is merged and the root collapses
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Write).await;
+			let mut s = s.lock().await;
+			assert!(t.delete(&mut tx, &mut s, v11.clone(), 110).await.unwrap());
+			assert!(t.delete(&mut tx, &mut s, v12.clone(), 120).await.unwrap());
+			finish_operation(tx, s, true).await;
+		}
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Traversal).await;
+			let mut s = s.lock().await;
+			check_tree_properties(&mut tx, &mut s, &t, 4, 2, Some(3), Some(3)).await;
+			assert_eq!(t.state.root, Some(6));
+			check_internal(&mut tx, &mut s, 6, |m| {
+				assert_eq!(m.len(), 3);
+				check_routing_vec(m, 0, &v1, 3, 2.0);
+				check_routing_vec(m, 1, &v4, 4, 2.0);
+				check_routing_vec(m, 2, &v8, 5, 5.0);
+			})
+			.await;
+			let res = t.knn_search(&mut tx, &mut s, &v13, 3).await.unwrap();
+			check_knn(&res.objects, vec![vec![130], vec![80], vec![70]]);
+			check_consistency(&mut tx, &mut s, &t).await;
+		}
+
+		// Remove every remaining vector -> the tree is empty
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Write).await;
+			let mut s = s.lock().await;
+			for (v, d) in [(v13, 130), (v1, 10), (v2, 20), (v3, 30), (v4, 40), (v5, 50)] {
+				assert!(t.delete(&mut tx, &mut s, v, d).await.unwrap());
+			}
+			finish_operation(tx, s, true).await;
+		}
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Traversal).await;
+			let mut s = s.lock().await;
+			check_tree_properties(&mut tx, &mut s, &t, 1, 1, Some(3), Some(3)).await;
+			assert_eq!(t.state.root, Some(5));
+			check_leaf(&mut tx, &mut s, 5, |m| {
+				assert_eq!(m.len(), 3);
+				check_leaf_vec(m, 0, &v7, 0.0, &[70]);
+				check_leaf_vec(m, 1, &v8, 0.0, &[80]);
+				check_leaf_vec(m, 2, &v6, 0.0, &[60]);
+			})
+			.await;
+			check_consistency(&mut tx, &mut s, &t).await;
+		}
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Write).await;
+			let mut s = s.lock().await;
+			for (v, d) in [(v6, 60), (v7, 70), (v8.clone(), 80)] {
+				assert!(t.delete(&mut tx, &mut s, v, d).await.unwrap());
+			}
+			assert!(!t.delete(&mut tx, &mut s, v8, 80).await.unwrap());
+			finish_operation(tx, s, true).await;
+		}
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Traversal).await;
+			let mut s = s.lock().await;
+			assert_eq!(t.state.root, None);
+			let res = t.knn_search(&mut tx, &mut s, &v0, 10).await.unwrap();
+			check_knn(&res.objects, vec![]);
+			check_consistency(&mut tx, &mut s, &t).await;
+		}
+	}
+
+	#[test(tokio::test)]
+	async fn test_mtree_deletions_redistribute_leaf_node() {
+		let ds = Datastore::new("memory").await.unwrap();
+
+		let mut t = MTree::new(MState::new(4), Distance::Euclidean);
+
+		let vectors: Vec<Vector> = (1..=7).map(|i| vec![i.into()]).collect();
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Write).await;
+			let mut s = s.lock().await;
+			for (i, v) in vectors.iter().enumerate() {
+				t.insert(&mut tx, &mut s, v.clone(), (i as DocId + 1) * 10).await.unwrap();
+			}
+			finish_operation(tx, s, true).await;
+		}
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Traversal).await;
+			let mut s = s.lock().await;
+			check_tree_properties(&mut tx, &mut s, &t, 3, 2, Some(3), Some(4)).await;
+			check_consistency(&mut tx, &mut s, &t).await;
+		}
+
+		// Remove vec1 and vec2 -> the leaf underflows and takes vec4 from its sibling
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Write).await;
+			let mut s = s.lock().await;
+			assert!(t.delete(&mut tx, &mut s, vectors[0].clone(), 10).await.unwrap());
+			assert!(t.delete(&mut tx, &mut s, vectors[1].clone(), 20).await.unwrap());
+			finish_operation(tx, s, true).await;
+		}
+		{
+			let (s, mut tx) = new_operation(&ds, TreeStoreType::Traversal).await;
+			let mut s = s.lock().await;
+			check_tree_properties(&mut tx, &mut s, &t, 3, 2, Some(2), Some(3)).await;
+			check_internal(&mut tx, &mut s, 2, |m| {
+				assert_eq!(m.len(), 2);
+				check_routing_vec(m, 0, &vectors[0], 0, 3.0);
+				check_routing_vec(m, 1, &vectors[4], 1, 2.0);
+			})
+			.await;
+			check_leaf(&mut tx, &mut s, 0, |m| {
+				assert_eq!(m.len(), 2);
+				check_leaf_vec(m, 0, &vectors[2], 2.0, &[30]);
+				check_leaf_vec(m, 1, &vectors[3], 3.0, &[40]);
+			})
+			.await;
+			check_leaf(&mut tx, &mut s, 1, |m| {
+				assert_eq!(m.len(), 3);
+				check_leaf_vec(m, 0, &vectors[4], 0.0, &[50]);
+				check_leaf_vec(m, 1, &vectors[5], 1.0, &[60]);
+				check_leaf_vec(m, 2, &vectors[6], 2.0, &[70]);
+			})
+			.await;
+			let res = t.knn_search(&mut tx, &mut s, &vectors[0], 10).await.unwrap();
+			check_knn(&res.objects, vec![vec![30], vec![40], vec![50], vec![60], vec![70]]);
+			check_consistency(&mut tx, &mut s, &t).await;
+		}
 	}
 
 	fn check_leaf_vec(
@@ -1707,6 +2090,11 @@ mod tests {
 		.await
 	}
 
+	async fn check_consistency(tx: &mut Transaction, s: &mut MTreeNodeStore, t: &MTree) {
+		let stats = t.statistics(tx, s).await.unwrap();
+		assert!(stats.inconsistencies.is_empty(), "{:?}", stats.inconsistencies);
+	}
+
 	fn check_knn(res: &VecDeque<RoaringTreemap>, expected: Vec<Vec<DocId>>) {
 		assert_eq!(res.len(), expected.len(), "{:?}", res);
 		for (i, (a, b)) in res.iter().zip(expected.iter()).enumerate() {
@@ -221,10 +221,12 @@ where
 		tx: &mut Transaction,
 		node_id: NodeId,
 	) -> Result<StoredNode<N>, Error> {
-		if let Some(n) = self.nodes.pop(&node_id) {
-			return Ok(n);
+		let n = self.nodes.pop(&node_id);
+		tx.metrics().observe_index_cache(n.is_some());
+		match n {
+			Some(n) => Ok(n),
+			None => self.keys.load::<N>(tx, node_id).await,
 		}
-		self.keys.load::<N>(tx, node_id).await
 	}
 
 	fn set_node(&mut self, node: StoredNode<N>) {
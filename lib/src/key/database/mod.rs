@@ -2,8 +2,12 @@ pub mod all;
 pub mod az;
 pub mod fc;
 pub mod lg;
+pub mod mf;
+pub mod ml;
 pub mod pa;
+pub mod rl;
 pub mod sc;
+pub mod sy;
 pub mod tb;
 pub mod ti;
 pub mod tk;
@@ -0,0 +1,69 @@
+//! Stores the sync checkpoint of a peer
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+// Sy stands for Sync checkpoint
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Sy<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub peer: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, peer: &'a str) -> Sy<'a> {
+	Sy::new(ns, db, peer)
+}
+
+/// Returns the prefix for the sync checkpoints of all the peers of a database
+pub fn prefix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = crate::key::database::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b's', b'y']);
+	k
+}
+
+/// Returns the suffix for the sync checkpoints of all the peers of a database
+pub fn suffix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = prefix(ns, db);
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl<'a> Sy<'a> {
+	pub fn new(ns: &'a str, db: &'a str, peer: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b's',
+			_e: b'y',
+			peer,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Sy::new(
+			"testns",
+			"testdb",
+			"testpeer",
+		);
+		let enc = Sy::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\x00*testdb\x00!sytestpeer\x00");
+		let dec = Sy::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
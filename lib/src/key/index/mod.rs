@@ -11,6 +11,7 @@ pub mod bp;
 pub mod bs;
 pub mod bt;
 pub mod bu;
+pub mod st;
 pub mod vm;
 
 use crate::sql::array::Array;
@@ -160,6 +161,52 @@ impl<'a> Index<'a> {
 		beg.extend_from_slice(&[0xff]);
 		beg
 	}
+
+	/// The keys of a composite index starting with the values of the leading columns.
+	/// The byte which terminates the encoded array of values is removed.
+	fn prefix_ids_composite(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> Vec<u8> {
+		let mut beg = Self::prefix_ids(ns, db, tb, ix, fd);
+		beg.pop();
+		beg
+	}
+
+	pub fn prefix_ids_composite_beg(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> Vec<u8> {
+		let mut beg = Self::prefix_ids_composite(ns, db, tb, ix, fd);
+		beg.extend_from_slice(&[0x00]);
+		beg
+	}
+
+	pub fn prefix_ids_composite_end(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> Vec<u8> {
+		let mut beg = Self::prefix_ids_composite(ns, db, tb, ix, fd);
+		beg.extend_from_slice(&[0xff]);
+		beg
+	}
+
+	/// The keys of an index whose single value is a string starting with the given string.
+	/// The bytes which terminate the encoded string and array of values are removed.
+	pub fn prefix_ids_starts_with_beg(
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+		fd: &Array,
+	) -> Vec<u8> {
+		let mut beg = Self::prefix_ids(ns, db, tb, ix, fd);
+		beg.truncate(beg.len() - 2);
+		beg
+	}
+
+	pub fn prefix_ids_starts_with_end(
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+		fd: &Array,
+	) -> Vec<u8> {
+		let mut beg = Self::prefix_ids_starts_with_beg(ns, db, tb, ix, fd);
+		beg.extend_from_slice(&[0xff]);
+		beg
+	}
 }
 
 #[cfg(test)]
@@ -180,4 +227,40 @@ mod tests {
 		let dec = Index::decode(&enc).unwrap();
 		assert_eq!(val, dec);
 	}
+
+	#[test]
+	fn composite_prefix() {
+		use super::*;
+		let fd = vec!["testfd1", "testfd2"].into();
+		let id = "testid".into();
+		let key =
+			Index::new("testns", "testdb", "testtb", "testix", &fd, Some(&id)).encode().unwrap();
+		let prefix = vec!["testfd1"].into();
+		let beg = Index::prefix_ids_composite_beg("testns", "testdb", "testtb", "testix", &prefix);
+		let end = Index::prefix_ids_composite_end("testns", "testdb", "testtb", "testix", &prefix);
+		assert_eq!(beg, b"/*testns\0*testdb\0*testtb\0+testix\0*\0\0\0\x04testfd1\0\0");
+		assert!(beg < key && key < end);
+		let other = vec!["testfd2"].into();
+		let beg = Index::prefix_ids_composite_beg("testns", "testdb", "testtb", "testix", &other);
+		assert!(key < beg);
+	}
+
+	#[test]
+	fn starts_with_prefix() {
+		use super::*;
+		let fd = vec!["u4pruy"].into();
+		let id = "testid".into();
+		let key =
+			Index::new("testns", "testdb", "testtb", "testix", &fd, Some(&id)).encode().unwrap();
+		let prefix = vec!["u4p"].into();
+		let beg =
+			Index::prefix_ids_starts_with_beg("testns", "testdb", "testtb", "testix", &prefix);
+		let end =
+			Index::prefix_ids_starts_with_end("testns", "testdb", "testtb", "testix", &prefix);
+		assert_eq!(beg, b"/*testns\0*testdb\0*testtb\0+testix\0*\0\0\0\x04u4p");
+		assert!(beg < key && key < end);
+		let other = vec!["u4q"].into();
+		let beg = Index::prefix_ids_starts_with_beg("testns", "testdb", "testtb", "testix", &other);
+		assert!(key < beg);
+	}
 }
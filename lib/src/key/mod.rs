@@ -2,25 +2,36 @@
 ///
 /// crate::key::root::all                /
 /// crate::key::root::hb                 /!hb{ts}/{nd}
+/// crate::key::root::ib                 /!ib{ns}{db}{tb}{ix}
+/// crate::key::root::kc                 /!kc
+/// crate::key::root::kr                 /!kr
 /// crate::key::root::nd                 /!nd{nd}
 /// crate::key::root::ni                 /!ni
 /// crate::key::root::ns                 /!ns{ns}
+/// crate::key::root::sd                 /!sd{ns}{db}{tb}{ix}{id}
+/// crate::key::root::vb                 /!vb{ns}{db}{tb}
 ///
 /// crate::key::node::all                /${nd}
 /// crate::key::node::lq                 /${nd}!lq{lq}{ns}{db}
+/// crate::key::node::nt                 /${nd}!nt{ts}{nt}
 ///
 /// crate::key::namespace::all           /*{ns}
 /// crate::key::namespace::db            /*{ns}!db{db}
 /// crate::key::namespace::di            /+{ns id}!di
 /// crate::key::namespace::lg            /*{ns}!lg{lg}
+/// crate::key::namespace::rl            /*{ns}!rl{rl}
 /// crate::key::namespace::tk            /*{ns}!tk{tk}
 ///
 /// crate::key::database::all            /*{ns}*{db}
 /// crate::key::database::az             /*{ns}*{db}!az{az}
 /// crate::key::database::fc             /*{ns}*{db}!fn{fc}
 /// crate::key::database::lg             /*{ns}*{db}!lg{lg}
+/// crate::key::database::mf             /*{ns}*{db}!mf{ml}{vn}
+/// crate::key::database::ml             /*{ns}*{db}!ml{ml}{vn}
 /// crate::key::database::pa             /*{ns}*{db}!pa{pa}
+/// crate::key::database::rl             /*{ns}*{db}!rl{rl}
 /// crate::key::database::sc             /*{ns}*{db}!sc{sc}
+/// crate::key::database::sy             /*{ns}*{db}!sy{sy}
 /// crate::key::database::tb             /*{ns}*{db}!tb{tb}
 /// crate::key::database::ti             /+{ns id}*{db id}!ti
 /// crate::key::database::tk             /*{ns}*{db}!tk{tk}
@@ -49,6 +60,7 @@
 /// crate::key::index::bs                /*{ns}*{db}*{tb}+{ix}!bs
 /// crate::key::index::bt                /*{ns}*{db}*{tb}+{ix}!bt{id}
 /// crate::key::index::bu                /*{ns}*{db}*{tb}+{ix}!bu{id}
+/// crate::key::index::st                /*{ns}*{db}*{tb}+{ix}!st
 /// crate::key::index                    /*{ns}*{db}*{tb}+{ix}*{fd}{id}
 ///
 /// crate::key::change                   /*{ns}*{db}#{ts}
@@ -2,5 +2,6 @@ pub mod all;
 pub mod db;
 pub mod di;
 pub mod lg;
+pub mod rl;
 pub mod tk;
 pub mod us;
@@ -32,14 +32,14 @@ pub fn new<'a>(nd: Uuid, lq: Uuid, ns: &'a str, db: &'a str) -> Lq<'a> {
 pub fn prefix_nd(nd: &Uuid) -> Vec<u8> {
 	let mut k = [b'/', b'$'].to_vec();
 	k.extend_from_slice(nd.as_bytes());
-	k.extend_from_slice(&[0x00]);
+	k.extend_from_slice(&[b'!', b'l', b'q', 0x00]);
 	k
 }
 
 pub fn suffix_nd(nd: &Uuid) -> Vec<u8> {
 	let mut k = [b'/', b'$'].to_vec();
 	k.extend_from_slice(nd.as_bytes());
-	k.extend_from_slice(&[0xff]);
+	k.extend_from_slice(&[b'!', b'l', b'q', 0xff]);
 	k
 }
 
@@ -92,7 +92,10 @@ mod tests {
 			0x0f, 0x10,
 		]);
 		let val = prefix_nd(&nd);
-		assert_eq!(val, b"/$\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x00");
+		assert_eq!(
+			val,
+			b"/$\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10!lq\x00"
+		);
 	}
 
 	#[test]
@@ -100,6 +103,9 @@ mod tests {
 		use super::*;
 		let nd = Uuid::from_bytes([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
 		let val = suffix_nd(&nd);
-		assert_eq!(val, b"/$\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\xff");
+		assert_eq!(
+			val,
+			b"/$\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10!lq\xff"
+		);
 	}
 }
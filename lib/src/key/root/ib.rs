@@ -0,0 +1,140 @@
+//! Stores the progress of an index which is built concurrently
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+/// The Ib key is used to discover the indexes which are still being built in the background
+///
+/// The key is removed once the index is built, so the indexes of a datastore are
+/// complete when there is no Ib key.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ib<'a> {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	_d: u8,
+	pub ns: &'a str,
+	_e: u8,
+	pub db: &'a str,
+	_f: u8,
+	pub tb: &'a str,
+	_g: u8,
+	pub ix: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str) -> Ib<'a> {
+	Ib::new(ns, db, tb, ix)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'i', b'b', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'i', b'b', 0xff]);
+	k
+}
+
+/// Returns the key of the indexes being built in a namespace, database or table
+fn scope(names: &[&str]) -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'i', b'b']);
+	for name in names {
+		k.push(b'*');
+		k.extend_from_slice(name.as_bytes());
+		k.push(0x00);
+	}
+	k
+}
+
+pub fn prefix_ns(ns: &str) -> Vec<u8> {
+	let mut k = scope(&[ns]);
+	k.push(0x00);
+	k
+}
+
+pub fn suffix_ns(ns: &str) -> Vec<u8> {
+	let mut k = scope(&[ns]);
+	k.push(0xff);
+	k
+}
+
+pub fn prefix_db(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = scope(&[ns, db]);
+	k.push(0x00);
+	k
+}
+
+pub fn suffix_db(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = scope(&[ns, db]);
+	k.push(0xff);
+	k
+}
+
+pub fn prefix_tb(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = scope(&[ns, db, tb]);
+	k.push(0x00);
+	k
+}
+
+pub fn suffix_tb(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = scope(&[ns, db, tb]);
+	k.push(0xff);
+	k
+}
+
+impl<'a> Ib<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'i',
+			_c: b'b',
+			_d: b'*',
+			ns,
+			_e: b'*',
+			db,
+			_f: b'*',
+			tb,
+			_g: b'+',
+			ix,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ib::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testix",
+		);
+		let enc = Ib::encode(&val).unwrap();
+		assert_eq!(enc, b"/!ib*testns\0*testdb\0*testtb\0+testix\0");
+
+		let dec = Ib::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn test_prefix() {
+		let val = super::prefix();
+		assert_eq!(val, b"/!ib\0")
+	}
+
+	#[test]
+	fn test_prefix_tb() {
+		let val = super::prefix_tb("testns", "testdb", "testtb");
+		assert_eq!(val, b"/!ib*testns\0*testdb\0*testtb\0\0");
+		let val = super::suffix_tb("testns", "testdb", "testtb");
+		assert_eq!(val, b"/!ib*testns\0*testdb\0*testtb\0\xff");
+	}
+}
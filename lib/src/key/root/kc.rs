@@ -0,0 +1,41 @@
+//! Stores the key-check record, which verifies the encryption key of a datastore
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Kc {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+}
+
+impl Default for Kc {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Kc {
+	pub fn new() -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'k',
+			_c: b'c',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		let val = Kc::new();
+		let enc = Kc::encode(&val).unwrap();
+		assert_eq!(enc, b"/!kc");
+		let dec = Kc::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,41 @@
+//! Stores the progress of a key rotation, which allows an interrupted rotation to be resumed
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Kr {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+}
+
+impl Default for Kr {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Kr {
+	pub fn new() -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'k',
+			_c: b'r',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		let val = Kr::new();
+		let enc = Kr::encode(&val).unwrap();
+		assert_eq!(enc, b"/!kr");
+		let dec = Kr::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -1,6 +1,11 @@
 pub mod all;
 pub mod hb;
+pub mod ib;
+pub mod kc;
+pub mod kr;
 pub mod nd;
 pub mod ni;
 pub mod ns;
+pub mod sd;
 pub mod us;
+pub mod vb;
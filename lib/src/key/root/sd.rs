@@ -0,0 +1,117 @@
+//! Stores a change of the number of entries of an analyzed index
+use derive::Key;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The Sd key records how many entries a write added to, or removed from, an analyzed index
+///
+/// Every write records its change under its own key, so that concurrent writes to a table
+/// never conflict on the statistics of its indexes. The changes are folded into the
+/// statistics of the index on the ticks of the datastore, and when the index is analyzed.
+///
+/// The value is the change of the number of entries, as a big-endian i64
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Sd<'a> {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	_d: u8,
+	pub ns: &'a str,
+	_e: u8,
+	pub db: &'a str,
+	_f: u8,
+	pub tb: &'a str,
+	_g: u8,
+	pub ix: &'a str,
+	#[serde(with = "uuid::serde::compact")]
+	pub id: Uuid,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str, id: Uuid) -> Sd<'a> {
+	Sd::new(ns, db, tb, ix, id)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b's', b'd', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b's', b'd', 0xff]);
+	k
+}
+
+/// Returns the key of the changes of an index
+fn scope(ns: &str, db: &str, tb: &str, ix: &str) -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b's', b'd']);
+	for (sep, name) in [(b'*', ns), (b'*', db), (b'*', tb), (b'+', ix)] {
+		k.push(sep);
+		k.extend_from_slice(name.as_bytes());
+		k.push(0x00);
+	}
+	k
+}
+
+pub fn prefix_ix(ns: &str, db: &str, tb: &str, ix: &str) -> Vec<u8> {
+	let mut k = scope(ns, db, tb, ix);
+	k.push(0x00);
+	k
+}
+
+pub fn suffix_ix(ns: &str, db: &str, tb: &str, ix: &str) -> Vec<u8> {
+	let mut k = scope(ns, db, tb, ix);
+	k.push(0xff);
+	k
+}
+
+impl<'a> Sd<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str, id: Uuid) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b's',
+			_c: b'd',
+			_d: b'*',
+			ns,
+			_e: b'*',
+			db,
+			_f: b'*',
+			tb,
+			_g: b'+',
+			ix,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let id = Uuid::from_bytes([0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10]);
+		let val = Sd::new("testns", "testdb", "testtb", "testix", id);
+		let enc = Sd::encode(&val).unwrap();
+		assert_eq!(
+			enc,
+			b"/!sd*testns\0*testdb\0*testtb\0+testix\0\
+			\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10"
+		);
+
+		let dec = Sd::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn test_prefix_ix() {
+		let val = super::prefix_ix("testns", "testdb", "testtb", "testix");
+		assert_eq!(val, b"/!sd*testns\0*testdb\0*testtb\0+testix\0\0");
+		let val = super::suffix_ix("testns", "testdb", "testtb", "testix");
+		assert_eq!(val, b"/!sd*testns\0*testdb\0*testtb\0+testix\0\xff");
+	}
+}
@@ -0,0 +1,116 @@
+//! Stores the progress of a view which is rebuilt in the background
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+/// The Vb key is used to discover the views which are still being rebuilt in the background
+///
+/// The key is removed once the view is rebuilt, and the records it replaced are removed.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Vb<'a> {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	_d: u8,
+	pub ns: &'a str,
+	_e: u8,
+	pub db: &'a str,
+	_f: u8,
+	pub tb: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str) -> Vb<'a> {
+	Vb::new(ns, db, tb)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'v', b'b', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'v', b'b', 0xff]);
+	k
+}
+
+/// Returns the key of the views being rebuilt in a namespace or database
+fn scope(names: &[&str]) -> Vec<u8> {
+	let mut k = crate::key::root::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'v', b'b']);
+	for name in names {
+		k.push(b'*');
+		k.extend_from_slice(name.as_bytes());
+		k.push(0x00);
+	}
+	k
+}
+
+pub fn prefix_ns(ns: &str) -> Vec<u8> {
+	let mut k = scope(&[ns]);
+	k.push(0x00);
+	k
+}
+
+pub fn suffix_ns(ns: &str) -> Vec<u8> {
+	let mut k = scope(&[ns]);
+	k.push(0xff);
+	k
+}
+
+pub fn prefix_db(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = scope(&[ns, db]);
+	k.push(0x00);
+	k
+}
+
+pub fn suffix_db(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = scope(&[ns, db]);
+	k.push(0xff);
+	k
+}
+
+impl<'a> Vb<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'v',
+			_c: b'b',
+			_d: b'*',
+			ns,
+			_e: b'*',
+			db,
+			_f: b'*',
+			tb,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Vb::new(
+			"testns",
+			"testdb",
+			"testtb",
+		);
+		let enc = Vb::encode(&val).unwrap();
+		assert_eq!(enc, b"/!vb*testns\0*testdb\0*testtb\0");
+
+		let dec = Vb::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn test_prefix_db() {
+		let val = super::prefix_db("testns", "testdb");
+		assert_eq!(val, b"/!vb*testns\0*testdb\0\0");
+		let val = super::suffix_db("testns", "testdb");
+		assert_eq!(val, b"/!vb*testns\0*testdb\0\xff");
+	}
+}
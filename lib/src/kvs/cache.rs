@@ -1,13 +1,16 @@
 use crate::idg::u32::U32;
 use crate::kvs::kv::Key;
+use crate::ml::MlFile;
 use crate::sql::statements::DefineAnalyzerStatement;
 use crate::sql::statements::DefineDatabaseStatement;
 use crate::sql::statements::DefineEventStatement;
 use crate::sql::statements::DefineFieldStatement;
 use crate::sql::statements::DefineFunctionStatement;
 use crate::sql::statements::DefineIndexStatement;
+use crate::sql::statements::DefineModelStatement;
 use crate::sql::statements::DefineNamespaceStatement;
 use crate::sql::statements::DefineParamStatement;
+use crate::sql::statements::DefineRoleStatement;
 use crate::sql::statements::DefineScopeStatement;
 use crate::sql::statements::DefineTableStatement;
 use crate::sql::statements::DefineTokenStatement;
@@ -22,12 +25,15 @@ pub enum Entry {
 	Db(Arc<DefineDatabaseStatement>),
 	Fc(Arc<DefineFunctionStatement>),
 	Ix(Arc<DefineIndexStatement>),
+	Mf(Arc<MlFile>),
+	Ml(Arc<DefineModelStatement>),
 	Ns(Arc<DefineNamespaceStatement>),
 	Pa(Arc<DefineParamStatement>),
 	Tb(Arc<DefineTableStatement>),
 	// Multi definitions
 	Azs(Arc<[DefineAnalyzerStatement]>),
 	Dbs(Arc<[DefineDatabaseStatement]>),
+	Drs(Arc<[DefineRoleStatement]>),
 	Dts(Arc<[DefineTokenStatement]>),
 	Dus(Arc<[DefineUserStatement]>),
 	Evs(Arc<[DefineEventStatement]>),
@@ -36,6 +42,8 @@ pub enum Entry {
 	Fts(Arc<[DefineTableStatement]>),
 	Ixs(Arc<[DefineIndexStatement]>),
 	Lvs(Arc<[LiveStatement]>),
+	Mls(Arc<[DefineModelStatement]>),
+	Nrs(Arc<[DefineRoleStatement]>),
 	Nss(Arc<[DefineNamespaceStatement]>),
 	Nts(Arc<[DefineTokenStatement]>),
 	Nus(Arc<[DefineUserStatement]>),
@@ -0,0 +1,191 @@
+#![cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+
+use crate::err::Error;
+use crate::key::root::kc::Kc;
+use crate::key::root::kr::Kr;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key as AeadKey, Nonce};
+use rand::RngCore;
+
+/// The length of the random nonce which precedes each encrypted value
+const NONCE_LEN: usize = 12;
+
+/// The length of the random salt which precedes the key-check record
+pub(crate) const SALT_LEN: usize = 16;
+
+/// The plaintext of the key-check record
+const KEY_CHECK: &[u8] = b"surrealdb";
+
+/// Encrypts the values stored on disk with ChaCha20-Poly1305.
+///
+/// Each value is encrypted with a random nonce, which is stored in front of
+/// the ciphertext. The key of the value is authenticated alongside it, so a
+/// value which is copied to another key can not be decrypted.
+///
+/// Only the values are encrypted. The keys are stored in plaintext, so that they
+/// remain ordered, and they contain user data: record ids, and the indexed field
+/// values of every index entry.
+///
+/// The 256 bit key is derived from the encryption key with Argon2id, and a random
+/// salt which is stored in front of the key-check record, so that the key-check
+/// record can not be used to quickly guess a weak encryption key.
+pub(crate) struct Cipher {
+	aead: ChaCha20Poly1305,
+	salt: [u8; SALT_LEN],
+}
+
+impl Cipher {
+	/// Creates a cipher, deriving a 256 bit key from the encryption key and the salt
+	pub(crate) fn new(key: &str, salt: [u8; SALT_LEN]) -> Result<Self, Error> {
+		let mut out = [0u8; 32];
+		Argon2::default()
+			.hash_password_into(key.as_bytes(), &salt, &mut out)
+			.map_err(|e| Error::Ds(format!("Unable to derive the encryption key: {e}")))?;
+		Ok(Self {
+			aead: ChaCha20Poly1305::new(AeadKey::from_slice(&out)),
+			salt,
+		})
+	}
+
+	/// Creates a cipher, deriving a 256 bit key from the encryption key and a random salt
+	pub(crate) fn generate(key: &str) -> Result<Self, Error> {
+		Self::new(key, Self::generate_salt())
+	}
+
+	/// Generates a random salt
+	pub(crate) fn generate_salt() -> [u8; SALT_LEN] {
+		let mut salt = [0u8; SALT_LEN];
+		rand::thread_rng().fill_bytes(&mut salt);
+		salt
+	}
+
+	/// Opens the cipher of a datastore, given the encryption key and the key-check record.
+	///
+	/// A datastore which was encrypted can only be opened with the same encryption key,
+	/// and a datastore which already contains unencrypted data can not be encrypted
+	/// without rotating the key offline.
+	pub(crate) fn open(
+		key: Option<&str>,
+		record: Option<&[u8]>,
+		empty: bool,
+	) -> Result<Option<Self>, Error> {
+		match (key, record) {
+			(None, None) => Ok(None),
+			(None, Some(_)) => Err(Error::MissingEncryptionKey),
+			(Some(_), None) if !empty => Err(Error::UnencryptedDatastore),
+			(Some(key), None) => Self::generate(key).map(Some),
+			(Some(key), Some(record)) => {
+				if record.len() < SALT_LEN {
+					return Err(Error::InvalidEncryptionKey);
+				}
+				let (salt, record) = record.split_at(SALT_LEN);
+				let cipher = Self::new(key, salt.try_into().unwrap())?;
+				match cipher.decrypt(&Self::record_key(), record) {
+					Ok(v) if v == KEY_CHECK => Ok(Some(cipher)),
+					_ => Err(Error::InvalidEncryptionKey),
+				}
+			}
+		}
+	}
+
+	/// The key of the key-check record
+	pub(crate) fn record_key() -> Key {
+		Kc::new().into()
+	}
+
+	/// The key of the record which stores the progress of a key rotation
+	pub(crate) fn progress_key() -> Key {
+		Kr::new().into()
+	}
+
+	/// Creates the key-check record, which is decrypted whenever the datastore is opened
+	pub(crate) fn record(&self) -> Result<Val, Error> {
+		let mut out = self.salt.to_vec();
+		out.extend(self.encrypt(&Self::record_key(), KEY_CHECK)?);
+		Ok(out)
+	}
+
+	/// Encrypts the value of a key
+	pub(crate) fn encrypt(&self, key: &[u8], val: &[u8]) -> Result<Val, Error> {
+		let mut nonce = [0u8; NONCE_LEN];
+		rand::thread_rng().fill_bytes(&mut nonce);
+		let payload = Payload {
+			msg: val,
+			aad: key,
+		};
+		let enc = self
+			.aead
+			.encrypt(Nonce::from_slice(&nonce), payload)
+			.map_err(|e| Error::Ds(format!("Unable to encrypt a value: {e}")))?;
+		let mut out = Vec::with_capacity(NONCE_LEN + enc.len());
+		out.extend_from_slice(&nonce);
+		out.extend(enc);
+		Ok(out)
+	}
+
+	/// Decrypts the value of a key, checking that it was not modified
+	pub(crate) fn decrypt(&self, key: &[u8], val: &[u8]) -> Result<Val, Error> {
+		if val.len() < NONCE_LEN {
+			return Err(Error::Decryption);
+		}
+		let (nonce, enc) = val.split_at(NONCE_LEN);
+		let payload = Payload {
+			msg: enc,
+			aad: key,
+		};
+		self.aead.decrypt(Nonce::from_slice(nonce), payload).map_err(|_| Error::Decryption)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encrypt_and_decrypt() {
+		let cipher = Cipher::generate("0123456789abcdef0123456789abcdef").unwrap();
+		let enc = cipher.encrypt(b"key", b"value").unwrap();
+		assert_ne!(&enc[NONCE_LEN..], b"value");
+		assert_eq!(cipher.decrypt(b"key", &enc).unwrap(), b"value");
+		// The value can not be moved to another key
+		assert!(matches!(cipher.decrypt(b"other", &enc), Err(Error::Decryption)));
+	}
+
+	#[test]
+	fn open_with_key_check() {
+		// A new datastore is encrypted with the key
+		let cipher = Cipher::open(Some("0123456789abcdef"), None, true).unwrap().unwrap();
+		let record = cipher.record().unwrap();
+		// The same key opens the datastore
+		assert!(Cipher::open(Some("0123456789abcdef"), Some(&record), false).unwrap().is_some());
+		// A different key fails fast
+		let res = Cipher::open(Some("fedcba9876543210"), Some(&record), false);
+		assert!(matches!(res, Err(Error::InvalidEncryptionKey)));
+		// A missing key fails fast
+		let res = Cipher::open(None, Some(&record), false);
+		assert!(matches!(res, Err(Error::MissingEncryptionKey)));
+		// Existing unencrypted data can not be encrypted in place
+		let res = Cipher::open(Some("0123456789abcdef"), None, false);
+		assert!(matches!(res, Err(Error::UnencryptedDatastore)));
+		// An unencrypted datastore is opened without a key
+		assert!(Cipher::open(None, None, false).unwrap().is_none());
+	}
+
+	#[test]
+	fn derive_key_with_salt() {
+		let cipher = Cipher::generate("0123456789abcdef").unwrap();
+		let enc = cipher.encrypt(b"key", b"value").unwrap();
+		// The same key and salt derive the same key
+		let same = Cipher::new("0123456789abcdef", cipher.salt).unwrap();
+		assert_eq!(same.decrypt(b"key", &enc).unwrap(), b"value");
+		// The same key with another salt derives another key
+		let other = Cipher::new("0123456789abcdef", Cipher::generate_salt()).unwrap();
+		assert!(matches!(other.decrypt(b"key", &enc), Err(Error::Decryption)));
+		// The salt is stored in front of the key-check record
+		let record = cipher.record().unwrap();
+		assert_eq!(&record[..SALT_LEN], &cipher.salt);
+	}
+}
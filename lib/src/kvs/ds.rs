@@ -1,3 +1,4 @@
+use super::options::RocksDbOptions;
 use super::tx::Transaction;
 use crate::cf;
 use crate::ctx::Context;
@@ -10,21 +11,37 @@ use crate::dbs::Options;
 use crate::dbs::Response;
 use crate::dbs::Session;
 use crate::dbs::Variables;
+use crate::doc::rebuild::{ViewBuilding, REBUILD_BATCHES_PER_TICK};
 use crate::err::Error;
 use crate::iam::ResourceKind;
 use crate::iam::{Action, Auth, Error as IamError, Role};
+use crate::idx::builder::{IndexBuilding, BUILD_BATCHES_PER_TICK};
+use crate::idx::planner::stats::{IndexStatistics, FOLD_BATCHES_PER_TICK};
 use crate::key::root::hb::Hb;
+use crate::metrics::Metrics;
+use crate::ml::MlFile;
 use crate::opt::auth::Root;
 use crate::sql;
+use crate::sql::statements::show::ShowSince;
+use crate::sql::statements::DefineModelStatement;
 use crate::sql::statements::DefineUserStatement;
+use crate::sql::statements::{
+	BeginStatement, CommitStatement, DefineStatement, DeleteStatement, UpdateStatement,
+};
 use crate::sql::Base;
+use crate::sql::Data;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Strand;
 use crate::sql::Value;
+use crate::sql::Values;
 use crate::sql::{Query, Uuid};
 use crate::vs::Oracle;
 use channel::Receiver;
 use channel::Sender;
 use futures::lock::Mutex;
 use futures::Future;
+use std::collections::VecDeque;
 use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
@@ -61,6 +78,10 @@ pub struct Datastore {
 	query_timeout: Option<Duration>,
 	// The maximum duration timeout for running multiple statements in a transaction
 	transaction_timeout: Option<Duration>,
+	// The maximum number of bytes which a query can buffer before spilling to disk
+	query_memory_budget: Option<usize>,
+	// The duration above which a statement is logged as a slow query
+	slow_query_threshold: Option<Duration>,
 	// Capabilities for this datastore
 	capabilities: Capabilities,
 	// The versionstamp oracle for this datastore.
@@ -68,6 +89,8 @@ pub struct Datastore {
 	versionstamp_oracle: Arc<Mutex<Oracle>>,
 	// Whether this datastore enables live query notifications to subscribers
 	notification_channel: Option<(Sender<Notification>, Receiver<Notification>)>,
+	// The metrics recorded by this datastore
+	metrics: Arc<Metrics>,
 }
 
 /// We always want to be circulating the live query information
@@ -151,6 +174,58 @@ impl Datastore {
 	/// # }
 	/// ```
 	pub async fn new(path: &str) -> Result<Datastore, Error> {
+		Self::new_with_key(path, None).await
+	}
+
+	/// Creates a new datastore instance, encrypting the values which are stored on disk
+	///
+	/// Only the `file`, `rocksdb`, and `speedb` storage engines support encryption at rest.
+	/// The key is checked against the datastore when it is opened, so a datastore which was
+	/// encrypted can not be opened with a different key, or without a key.
+	///
+	/// Only the values are encrypted. The keys, which include record ids and the indexed
+	/// field values of every index entry, are stored in plaintext.
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::kvs::Datastore;
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let ds = Datastore::new_with_key("file://temp.db", Some("0123456789abcdef")).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn new_with_key(path: &str, key: Option<&str>) -> Result<Datastore, Error> {
+		Self::new_with_options(path, key, &RocksDbOptions::default()).await
+	}
+
+	/// Creates a new datastore instance, opening the `rocksdb` or `speedb` storage engine with the given options
+	///
+	/// The options are ignored by the other storage engines.
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::kvs::{CompactionStyle, Datastore, RocksDbOptions};
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let options = RocksDbOptions::default().with_compaction_style(CompactionStyle::Universal);
+	/// let ds = Datastore::new_with_options("rocksdb://temp.db", None, &options).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[allow(unused_variables)]
+	pub async fn new_with_options(
+		path: &str,
+		key: Option<&str>,
+		options: &RocksDbOptions,
+	) -> Result<Datastore, Error> {
+		// Only the storage engines which store data on disk can be encrypted
+		if key.is_some() && !["file:", "rocksdb:", "speedb:"].iter().any(|p| path.starts_with(p)) {
+			return Err(Error::Ds(
+				"Encryption at rest is only supported by the `rocksdb` and `speedb` storage engines"
+					.to_owned(),
+			));
+		}
 		// Initiate the desired datastore
 		let inner = match path {
 			"memory" => {
@@ -171,7 +246,8 @@ impl Datastore {
 					info!("Starting kvs store at {}", path);
 					let s = s.trim_start_matches("file://");
 					let s = s.trim_start_matches("file:");
-					let v = super::rocksdb::Datastore::new(s).await.map(Inner::RocksDB);
+					let v =
+						super::rocksdb::Datastore::new(s, key, options).await.map(Inner::RocksDB);
 					info!("Started kvs store at {}", path);
 					v
 				}
@@ -185,7 +261,8 @@ impl Datastore {
 					info!("Starting kvs store at {}", path);
 					let s = s.trim_start_matches("rocksdb://");
 					let s = s.trim_start_matches("rocksdb:");
-					let v = super::rocksdb::Datastore::new(s).await.map(Inner::RocksDB);
+					let v =
+						super::rocksdb::Datastore::new(s, key, options).await.map(Inner::RocksDB);
 					info!("Started kvs store at {}", path);
 					v
 				}
@@ -199,7 +276,7 @@ impl Datastore {
 					info!("Starting kvs store at {}", path);
 					let s = s.trim_start_matches("speedb://");
 					let s = s.trim_start_matches("speedb:");
-					let v = super::speedb::Datastore::new(s).await.map(Inner::SpeeDB);
+					let v = super::speedb::Datastore::new(s, key, options).await.map(Inner::SpeeDB);
 					info!("Started kvs store at {}", path);
 					v
 				}
@@ -262,9 +339,12 @@ impl Datastore {
 			auth_enabled: false,
 			query_timeout: None,
 			transaction_timeout: None,
+			query_memory_budget: None,
+			slow_query_threshold: None,
 			notification_channel: None,
 			capabilities: Capabilities::default(),
 			versionstamp_oracle: Arc::new(Mutex::new(Oracle::systime_counter())),
+			metrics: Arc::new(Metrics::default()),
 		})
 	}
 
@@ -280,6 +360,31 @@ impl Datastore {
 		self
 	}
 
+	/// Creates a new datastore instance, with the given node id, which shares the storage
+	/// of this in-memory datastore. This simulates a cluster of nodes within a single process.
+	#[cfg(all(test, feature = "kv-mem"))]
+	pub(crate) fn new_node(&self, id: Uuid) -> Result<Datastore, Error> {
+		let inner = match &self.inner {
+			Inner::Mem(v) => Inner::Mem(v.clone()),
+			#[allow(unreachable_patterns)]
+			_ => return Err(Error::Ds("Only in-memory datastores can be shared".to_owned())),
+		};
+		Ok(Datastore {
+			inner,
+			id,
+			strict: self.strict,
+			auth_enabled: self.auth_enabled,
+			query_timeout: self.query_timeout,
+			transaction_timeout: self.transaction_timeout,
+			query_memory_budget: self.query_memory_budget,
+			slow_query_threshold: self.slow_query_threshold,
+			capabilities: self.capabilities.clone(),
+			versionstamp_oracle: self.versionstamp_oracle.clone(),
+			notification_channel: None,
+			metrics: Arc::new(Metrics::default()),
+		})
+	}
+
 	/// Specify whether this datastore should enable live query notifications
 	pub fn with_notifications(mut self) -> Self {
 		self.notification_channel = Some(channel::bounded(100));
@@ -298,6 +403,26 @@ impl Datastore {
 		self
 	}
 
+	/// Set a memory budget for the results buffered by each query on this Datastore
+	///
+	/// Once a query which sorts, groups, or deduplicates its results exceeds this number
+	/// of bytes, the buffered results are moved to temporary files on disk. Temporary
+	/// files are created within the directory returned by [`std::env::temp_dir`].
+	pub fn with_query_memory_budget(mut self, bytes: Option<usize>) -> Self {
+		self.query_memory_budget = bytes;
+		self
+	}
+
+	/// Set a threshold above which statements are logged as slow queries
+	///
+	/// Each statement which runs for at least this duration is logged as a warning, together
+	/// with the session namespace, database, and user, and the number of keys scanned and
+	/// rows returned by the statement.
+	pub fn with_slow_query_threshold(mut self, duration: Option<Duration>) -> Self {
+		self.slow_query_threshold = duration;
+		self
+	}
+
 	/// Set whether authentication is enabled for this Datastore
 	pub fn with_auth_enabled(mut self, enabled: bool) -> Self {
 		self.auth_enabled = enabled;
@@ -310,11 +435,59 @@ impl Datastore {
 		self
 	}
 
+	/// Rotate the key which encrypts the values stored on disk
+	///
+	/// The datastore must have been opened with the current key, and must not be in use
+	/// by any other process. Without a new key, the values are stored unencrypted.
+	#[allow(unused_variables)]
+	pub async fn rotate_key(&self, key: Option<&str>) -> Result<(), Error> {
+		match &self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.rotate(key).await,
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.rotate(key).await,
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::Ds(
+				"Encryption at rest is only supported by the `rocksdb` and `speedb` storage engines"
+					.to_owned(),
+			)),
+		}
+	}
+
+	/// Read an integer property of the underlying storage engine, if it has one
+	#[cfg(test)]
+	#[allow(unused_variables)]
+	pub(crate) fn engine_property(&self, name: &str) -> Result<Option<u64>, Error> {
+		match &self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.property(name),
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.property(name),
+			#[allow(unreachable_patterns)]
+			_ => Ok(None),
+		}
+	}
+
 	/// Is authentication enabled for this Datastore?
 	pub fn is_auth_enabled(&self) -> bool {
 		self.auth_enabled
 	}
 
+	/// The capabilities of this Datastore
+	pub(crate) fn capabilities(&self) -> &Capabilities {
+		&self.capabilities
+	}
+
+	/// The duration above which statements are logged as slow queries
+	pub(crate) fn slow_query_threshold(&self) -> Option<Duration> {
+		self.slow_query_threshold
+	}
+
+	/// The metrics recorded by this datastore
+	pub(crate) fn metrics(&self) -> &Metrics {
+		&self.metrics
+	}
+
 	/// Setup the initial credentials
 	/// Trigger the `unreachable definition` compilation error, probably due to this issue:
 	/// https://github.com/rust-lang/rust/issues/111370
@@ -470,6 +643,8 @@ impl Datastore {
 			trace!("Deleting node {}", &hb.nd);
 			// TODO should be delr in case of nested entries
 			tx.del_cl(hb.nd).await?;
+			// Notifications can no longer be delivered to the node
+			tx.delr_ndnt(&hb.nd).await?;
 			nodes.push(crate::sql::uuid::Uuid::from(hb.nd));
 		}
 		Ok(nodes)
@@ -566,6 +741,8 @@ impl Datastore {
 				tx.del_tblq(&ndlq.ns, &ndlq.db, &ndlq.tb, tblq.lq.0).await?;
 			}
 		}
+		// Delete node notifications
+		tx.delr_ndnt(&self.id).await?;
 		trace!("Successfully completed nuke");
 		Ok(())
 	}
@@ -667,11 +844,140 @@ impl Datastore {
 	pub async fn tick_at(&self, ts: u64) -> Result<(), Error> {
 		self.save_timestamp_for_versionstamp(ts).await?;
 		self.garbage_collect_stale_change_feeds(ts).await?;
+		self.deliver_remote_notifications().await?;
+		self.build_indexes().await?;
+		self.build_views().await?;
+		self.fold_index_statistics().await?;
 		// TODO Add LQ GC
 		// TODO Add Node GC?
 		Ok(())
 	}
 
+	// build_indexes builds the indexes which are defined concurrently, or rebuilt, one batch of records per transaction.
+	// The indexes take turns, and at most BUILD_BATCHES_PER_TICK batches are indexed, so that a large
+	// table does not hold up the tick. The remaining records are indexed on the following ticks.
+	pub async fn build_indexes(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut queue = VecDeque::from(IndexBuilding::pending(&mut tx).await?);
+		tx.cancel().await?;
+		let mut budget = BUILD_BATCHES_PER_TICK;
+		while budget > 0 {
+			let Some(key) = queue.pop_front() else {
+				break;
+			};
+			budget -= 1;
+			if !self.build_index_batch(&key).await? {
+				queue.push_back(key);
+			}
+		}
+		Ok(())
+	}
+
+	// build_index_batch indexes the next batch of records of an index, returning true once the build is over.
+	async fn build_index_batch(&self, key: &[u8]) -> Result<bool, Error> {
+		let ib = crate::key::root::ib::Ib::decode(key)?;
+		let opt = Options::new()
+			.with_auth(Arc::new(Auth::for_root(Role::Owner)))
+			.with_ns(Some(ib.ns.into()))
+			.with_db(Some(ib.db.into()))
+			.with_strict(self.strict);
+		let mut ctx = Context::default();
+		ctx.add_capabilities(self.capabilities.clone());
+		let txn = self.transaction(true, false).await?.enclose();
+		match IndexBuilding::next_batch(&ctx, &opt, &txn, ib.tb, ib.ix).await {
+			Ok(done) => match txn.lock().await.commit().await {
+				Ok(()) => Ok(done),
+				// The progress is saved with the batch, so the batch is indexed again
+				Err(Error::TxConflict) => Ok(false),
+				Err(e) => Err(e),
+			},
+			Err(e) => {
+				txn.lock().await.cancel().await?;
+				// The error is reported by INFO FOR INDEX
+				let mut tx = self.transaction(true, false).await?;
+				IndexBuilding::fail(&mut tx, ib.ns, ib.db, ib.tb, ib.ix, &e).await?;
+				match tx.commit().await {
+					Ok(()) => Ok(true),
+					Err(Error::TxConflict) => Ok(false),
+					Err(e) => Err(e),
+				}
+			}
+		}
+	}
+
+	// build_views rebuilds the views which are rebuilt with REBUILD TABLE, one batch of records per transaction.
+	// The views take turns, and at most REBUILD_BATCHES_PER_TICK batches are processed on each tick.
+	pub async fn build_views(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut queue = VecDeque::from(ViewBuilding::pending(&mut tx).await?);
+		tx.cancel().await?;
+		let mut budget = REBUILD_BATCHES_PER_TICK;
+		while budget > 0 {
+			let Some(key) = queue.pop_front() else {
+				break;
+			};
+			budget -= 1;
+			if !self.build_view_batch(&key).await? {
+				queue.push_back(key);
+			}
+		}
+		Ok(())
+	}
+
+	// build_view_batch processes the next batch of records of a view, returning true once the rebuild is over.
+	async fn build_view_batch(&self, key: &[u8]) -> Result<bool, Error> {
+		let vb = crate::key::root::vb::Vb::decode(key)?;
+		let opt = Options::new()
+			.with_auth(Arc::new(Auth::for_root(Role::Owner)))
+			.with_ns(Some(vb.ns.into()))
+			.with_db(Some(vb.db.into()))
+			.with_strict(self.strict);
+		let mut ctx = Context::default();
+		ctx.add_capabilities(self.capabilities.clone());
+		let txn = self.transaction(true, false).await?.enclose();
+		match ViewBuilding::next_batch(&ctx, &opt, &txn, vb.tb).await {
+			Ok(done) => match txn.lock().await.commit().await {
+				Ok(()) => Ok(done),
+				// The progress is saved with the batch, so the batch is processed again
+				Err(Error::TxConflict) => Ok(false),
+				Err(e) => Err(e),
+			},
+			Err(e) => {
+				txn.lock().await.cancel().await?;
+				let mut tx = self.transaction(true, false).await?;
+				ViewBuilding::fail(&mut tx, vb.ns, vb.db, vb.tb, &e).await?;
+				match tx.commit().await {
+					Ok(()) => Ok(true),
+					Err(Error::TxConflict) => Ok(false),
+					Err(e) => Err(e),
+				}
+			}
+		}
+	}
+
+	// fold_index_statistics folds the changes recorded by the writes to the analyzed indexes into their statistics.
+	// At most FOLD_BATCHES_PER_TICK batches are folded, and the remaining changes are folded on the following ticks.
+	pub async fn fold_index_statistics(&self) -> Result<(), Error> {
+		for _ in 0..FOLD_BATCHES_PER_TICK {
+			let mut tx = self.transaction(true, false).await?;
+			let done = match IndexStatistics::fold(&mut tx).await {
+				Ok(done) => done,
+				Err(e) => {
+					tx.cancel().await?;
+					return Err(e);
+				}
+			};
+			match tx.commit().await {
+				Ok(()) if done => break,
+				Ok(()) => continue,
+				// The changes are folded again on the next tick
+				Err(Error::TxConflict) => break,
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(())
+	}
+
 	// save_timestamp_for_versionstamp saves the current timestamp for the each database's current versionstamp.
 	pub async fn save_timestamp_for_versionstamp(&self, ts: u64) -> Result<(), Error> {
 		let mut tx = self.transaction(true, false).await?;
@@ -699,6 +1005,52 @@ impl Datastore {
 		Ok(())
 	}
 
+	// deliver_remote_notifications sends the notifications which other nodes queued for the live queries of this node.
+	pub async fn deliver_remote_notifications(&self) -> Result<(), Error> {
+		let Some(channel) = &self.notification_channel else {
+			return Ok(());
+		};
+		loop {
+			let mut tx = self.transaction(true, false).await?;
+			let queued = tx.scan_ndnt(&self.id, 1000).await?;
+			if queued.is_empty() {
+				tx.cancel().await?;
+				return Ok(());
+			}
+			let mut notifications = Vec::with_capacity(queued.len());
+			for (nt, notification) in queued {
+				tx.del_ndnt(nt).await?;
+				notifications.push(notification);
+			}
+			// The notifications are only sent once they are removed from the queue
+			tx.commit().await?;
+			for notification in notifications {
+				channel.0.send(notification).await?;
+			}
+		}
+	}
+
+	/// Counts the live queries registered on this node
+	pub async fn live_query_count(&self) -> Result<u64, Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut beg = crate::key::node::lq::prefix_nd(&self.id);
+		let end = crate::key::node::lq::suffix_nd(&self.id);
+		let mut count = 0;
+		loop {
+			let res = tx.scan(beg..end.clone(), 1000).await?;
+			count += res.len() as u64;
+			match res.last() {
+				Some((k, _)) if res.len() == 1000 => {
+					beg = k.clone();
+					beg.push(0x00);
+				}
+				_ => break,
+			}
+		}
+		tx.cancel().await?;
+		Ok(count)
+	}
+
 	// Creates a heartbeat entry for the member indicating to the cluster
 	// that the node is alive.
 	// This is the preferred way of creating heartbeats inside the database, so try to use this.
@@ -783,6 +1135,8 @@ impl Datastore {
 			cache: super::cache::Cache::default(),
 			cf: cf::Writer::new(),
 			vso: self.versionstamp_oracle.clone(),
+			reads: 0,
+			metrics: self.metrics.clone(),
 		})
 	}
 
@@ -867,6 +1221,10 @@ impl Datastore {
 		if let Some(timeout) = self.query_timeout {
 			ctx.add_timeout(timeout);
 		}
+		// Set the query memory budget
+		if let Some(bytes) = self.query_memory_budget {
+			ctx.add_memory_budget(bytes);
+		}
 		// Setup the notification channel
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
@@ -931,6 +1289,10 @@ impl Datastore {
 		if let Some(timeout) = self.query_timeout {
 			ctx.add_timeout(timeout);
 		}
+		// Set the query memory budget
+		if let Some(bytes) = self.query_memory_budget {
+			ctx.add_memory_budget(bytes);
+		}
 		// Setup the notification channel
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
@@ -941,8 +1303,11 @@ impl Datastore {
 		let ctx = vars.attach(ctx)?;
 		// Start a new transaction
 		let txn = self.transaction(val.writeable(), false).await?.enclose();
-		// Compute the value
-		let res = val.compute(&ctx, &opt, &txn, None).await;
+		// Compute the value with the custom roles of the actor
+		let res = match opt.with_custom_roles(&txn).await {
+			Ok(opt) => val.compute(&ctx, &opt, &txn, None).await,
+			Err(e) => Err(e),
+		};
 		// Store any data
 		match (res.is_ok(), val.writeable()) {
 			// If the compute was successful, then commit if writeable
@@ -1000,6 +1365,10 @@ impl Datastore {
 		if let Some(timeout) = self.query_timeout {
 			ctx.add_timeout(timeout);
 		}
+		// Set the query memory budget
+		if let Some(bytes) = self.query_memory_budget {
+			ctx.add_memory_budget(bytes);
+		}
 		// Setup the notification channel
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
@@ -1010,8 +1379,11 @@ impl Datastore {
 		let ctx = vars.attach(ctx)?;
 		// Start a new transaction
 		let txn = self.transaction(val.writeable(), false).await?.enclose();
-		// Compute the value
-		let res = val.compute(&ctx, &opt, &txn, None).await;
+		// Compute the value with the custom roles of the actor
+		let res = match opt.with_custom_roles(&txn).await {
+			Ok(opt) => val.compute(&ctx, &opt, &txn, None).await,
+			Err(e) => Err(e),
+		};
 		// Store any data
 		match (res.is_ok(), val.writeable()) {
 			// If the compute was successful, then commit if writeable
@@ -1086,4 +1458,204 @@ impl Datastore {
 		// Execute the SQL import
 		self.execute(sql, sess, None).await
 	}
+
+	/// Uploads a machine learning model file, defining the model if it is not yet defined
+	#[instrument(level = "debug", skip(self, sess, data))]
+	pub async fn import_ml(&self, sess: &Session, data: &[u8]) -> Result<(), Error> {
+		// Check that a namespace and database are selected
+		let ns = sess.ns.as_deref().ok_or(Error::NsEmpty)?;
+		let db = sess.db.as_deref().ok_or(Error::DbEmpty)?;
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::Edit, &ResourceKind::Model.on_db(ns, db))?;
+		}
+		// Parse and check the model file
+		let file = MlFile::from_slice(data)?;
+		// Create a new writeable transaction
+		let mut txn = self.transaction(true, false).await?;
+		txn.add_ns(ns, self.strict).await?;
+		txn.add_db(ns, db, self.strict).await?;
+		// Define the model, keeping any existing definition
+		let key = crate::key::database::ml::new(ns, db, &file.name, &file.version);
+		if txn.get(key.clone()).await?.is_none() {
+			let stm = DefineModelStatement {
+				name: file.name.clone().into(),
+				version: file.version.clone(),
+				comment: file.description.clone().map(Strand::from),
+				..Default::default()
+			};
+			txn.set(key, &stm).await?;
+		}
+		// Store the model file
+		let key = crate::key::database::mf::new(ns, db, &file.name, &file.version);
+		txn.set(key, data.to_vec()).await?;
+		txn.commit().await
+	}
+
+	/// Reads the changes of the selected database which a sync peer has not yet applied.
+	///
+	/// The changes are read from the change feed, starting from the given versionstamp,
+	/// or after the versionstamp which the peer last acknowledged with [`Datastore::sync_ack`].
+	#[instrument(level = "debug", skip(self, sess))]
+	pub async fn sync_changes(
+		&self,
+		sess: &Session,
+		peer: Option<&str>,
+		since: Option<u64>,
+		limit: Option<u32>,
+	) -> Result<Vec<cf::ChangeSet>, Error> {
+		// Check that a namespace and database are selected
+		let ns = sess.ns.as_deref().ok_or(Error::NsEmpty)?;
+		let db = sess.db.as_deref().ok_or(Error::DbEmpty)?;
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::View, &ResourceKind::Any.on_db(ns, db))?;
+		}
+		// Create a new readonly transaction
+		let mut txn = self.transaction(false, false).await?;
+		// Start after the checkpoint of the peer if no versionstamp is specified
+		let since = match (since, peer) {
+			(Some(vs), _) => vs,
+			(None, Some(peer)) => {
+				let key = crate::key::database::sy::new(ns, db, peer);
+				match txn.get(key).await? {
+					Some(v) => sync_checkpoint(&v)? + 1,
+					None => 0,
+				}
+			}
+			(None, None) => 0,
+		};
+		// Read the changes from the change feed
+		let res = cf::read(&mut txn, ns, db, None, ShowSince::Versionstamp(since), limit).await?;
+		txn.cancel().await?;
+		Ok(res)
+	}
+
+	/// Acknowledges that a sync peer has applied the changes up to and including the given versionstamp.
+	///
+	/// Storing a checkpoint moves the start of the next pull of the peer, so it requires
+	/// permission to edit the database.
+	#[instrument(level = "debug", skip(self, sess))]
+	pub async fn sync_ack(&self, sess: &Session, peer: &str, vs: u64) -> Result<(), Error> {
+		// Check that a namespace and database are selected
+		let ns = sess.ns.as_deref().ok_or(Error::NsEmpty)?;
+		let db = sess.db.as_deref().ok_or(Error::DbEmpty)?;
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::Edit, &ResourceKind::Any.on_db(ns, db))?;
+		}
+		// Create a new writeable transaction
+		let mut txn = self.transaction(true, false).await?;
+		// Store the checkpoint of the peer
+		let key = crate::key::database::sy::new(ns, db, peer);
+		txn.set(key, vs.to_be_bytes().to_vec()).await?;
+		txn.commit().await
+	}
+
+	/// Applies the changes read from the change feed of a sync peer to the selected database.
+	///
+	/// The changes are applied in a single transaction. Applying a change which is already
+	/// present does not modify the record, so changes are not sent back and forth between peers.
+	/// Returns the versionstamp of the last change set, which is then acknowledged on the peer.
+	#[instrument(level = "debug", skip(self, sess, changes))]
+	pub async fn sync_apply(
+		&self,
+		sess: &Session,
+		changes: Vec<cf::ChangeSet>,
+	) -> Result<Option<u64>, Error> {
+		// Check that a namespace and database are selected
+		let ns = sess.ns.as_deref().ok_or(Error::NsEmpty)?;
+		let db = sess.db.as_deref().ok_or(Error::DbEmpty)?;
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::Edit, &ResourceKind::Any.on_db(ns, db))?;
+		}
+		// Get the versionstamp of the last change set
+		let vs = match changes.last() {
+			Some(cs) => Some(crate::vs::try_to_u64_be(cs.0)?),
+			None => None,
+		};
+		// Create a new readonly transaction
+		let mut txn = self.transaction(false, false).await?;
+		// Convert the changes into statements
+		let mut stm = vec![Statement::Begin(BeginStatement)];
+		for cf::ChangeSet(_, cf::DatabaseMutation(tbs)) in changes {
+			for cf::TableMutations(_, muts) in tbs {
+				for mutation in muts {
+					stm.push(match mutation {
+						cf::TableMutation::Set(id, v) => Statement::Update(UpdateStatement {
+							what: Values(vec![Value::from(id)]),
+							data: Some(Data::ContentExpression(v)),
+							..Default::default()
+						}),
+						cf::TableMutation::Del(id) => Statement::Delete(DeleteStatement {
+							what: Values(vec![Value::from(id)]),
+							..Default::default()
+						}),
+						cf::TableMutation::Def(tb) => match txn.get_tb(ns, db, &tb.name).await {
+							// Skip the table definitions which are already applied
+							Ok(v) if v == tb => continue,
+							Ok(_)
+							| Err(Error::TbNotFound {
+								..
+							}) => Statement::Define(DefineStatement::Table(tb)),
+							Err(e) => return Err(e),
+						},
+					});
+				}
+			}
+		}
+		txn.cancel().await?;
+		stm.push(Statement::Commit(CommitStatement));
+		// Apply the changes, and return the first error
+		for res in self.process(Query(Statements(stm)), sess, None).await? {
+			res.result?;
+		}
+		Ok(vs)
+	}
+
+	/// Computes how many change feed versionstamps each sync peer has not yet acknowledged,
+	/// up to the versionstamp of the latest change in the database.
+	///
+	/// Returns the namespace, database, and id of each peer, along with its lag.
+	pub(crate) async fn sync_lag(&self) -> Result<Vec<(String, String, String, u64)>, Error> {
+		let mut txn = self.transaction(false, false).await?;
+		let mut res = vec![];
+		for ns in txn.all_ns().await?.iter() {
+			for db in txn.all_db(&ns.name).await?.iter() {
+				let (ns, db) = (ns.name.as_str(), db.name.as_str());
+				// Get the versionstamp of the latest change in the database
+				let beg = crate::key::change::prefix(ns, db);
+				let end = crate::key::change::suffix(ns, db);
+				let latest = match txn.scanr(beg..end, 1).await?.pop() {
+					Some((k, _)) => {
+						crate::vs::try_to_u64_be(crate::key::change::Cf::decode(&k)?.vs)?
+					}
+					None => 0,
+				};
+				// Compare it with the checkpoint of each peer
+				let beg = crate::key::database::sy::prefix(ns, db);
+				let end = crate::key::database::sy::suffix(ns, db);
+				for (k, v) in txn.scan(beg..end, u32::MAX).await? {
+					let peer = crate::key::database::sy::Sy::decode(&k)?.peer.to_owned();
+					let lag = latest.saturating_sub(sync_checkpoint(&v)?);
+					res.push((ns.to_owned(), db.to_owned(), peer, lag));
+				}
+			}
+		}
+		txn.cancel().await?;
+		Ok(res)
+	}
+}
+
+// Decodes the versionstamp of a sync checkpoint
+fn sync_checkpoint(v: &[u8]) -> Result<u64, Error> {
+	match v.try_into() {
+		Ok(v) => Ok(u64::from_be_bytes(v)),
+		Err(_) => Err(Error::Internal("the sync checkpoint is not 8 bytes".to_string())),
+	}
 }
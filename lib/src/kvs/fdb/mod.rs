@@ -184,6 +184,8 @@ impl Transaction {
 		};
 		match r {
 			Ok(_r) => {}
+			// The transaction was not committed because of a conflict
+			Err(e) if e.code() == 1020 => return Err(Error::TxConflict),
 			Err(e) => {
 				return Err(Error::Tx(format!("Transaction commit error: {}", e)));
 			}
@@ -512,4 +514,47 @@ impl Transaction {
 		}
 		Ok(res)
 	}
+	/// Retrieve a range of keys from the databases, in descending order
+	pub(crate) async fn scanr<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Scan the keys
+		let begin: Vec<u8> = rng.start;
+		let end: Vec<u8> = rng.end;
+		let opt = foundationdb::RangeOption {
+			limit: Some(limit.try_into().unwrap()),
+			reverse: true,
+			..foundationdb::RangeOption::from((begin.as_slice(), end.as_slice()))
+		};
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		let mut stream = inner.get_ranges_keyvalues(opt, self.snapshot());
+		let mut res: Vec<(Key, Val)> = vec![];
+		loop {
+			let x = stream.try_next().await;
+			match x {
+				Ok(Some(v)) => {
+					let x = (Key::from(v.key()), Val::from(v.value()));
+					res.push(x)
+				}
+				Ok(None) => break,
+				Err(e) => return Err(Error::Tx(format!("GetRanges failed: {}", e))),
+			}
+		}
+		Ok(res)
+	}
 }
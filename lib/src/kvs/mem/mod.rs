@@ -6,9 +6,11 @@ use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
 use std::ops::Range;
+use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct Datastore {
-	db: echodb::Db<Key, Val>,
+	db: Arc<echodb::Db<Key, Val>>,
 }
 
 pub struct Transaction {
@@ -56,7 +58,7 @@ impl Datastore {
 	/// Open a new database
 	pub(crate) async fn new() -> Result<Datastore, Error> {
 		Ok(Datastore {
-			db: echodb::db::new(),
+			db: Arc::new(echodb::db::new()),
 		})
 	}
 	/// Start a new transaction
@@ -13,11 +13,13 @@
 //! - `tikv`: [TiKV](https://github.com/tikv/tikv) a distributed, and transactional key-value database
 //! - `mem`: in-memory database
 mod cache;
+mod cipher;
 mod ds;
 mod fdb;
 mod indxdb;
 mod kv;
 mod mem;
+mod options;
 mod rocksdb;
 mod speedb;
 mod tikv;
@@ -28,4 +30,5 @@ mod tests;
 
 pub use self::ds::*;
 pub use self::kv::*;
+pub use self::options::*;
 pub use self::tx::*;
@@ -0,0 +1,180 @@
+//! The options used to open the `rocksdb` and `speedb` storage engines.
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The compression algorithm used for the files of a level of the LSM tree
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Compression {
+	None,
+	Snappy,
+	Lz4,
+	Zstd,
+}
+
+impl Display for Compression {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::None => f.write_str("none"),
+			Self::Snappy => f.write_str("snappy"),
+			Self::Lz4 => f.write_str("lz4"),
+			Self::Zstd => f.write_str("zstd"),
+		}
+	}
+}
+
+impl FromStr for Compression {
+	type Err = String;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"none" => Ok(Self::None),
+			"snappy" => Ok(Self::Snappy),
+			"lz4" => Ok(Self::Lz4),
+			"zstd" => Ok(Self::Zstd),
+			_ => Err(format!(
+				"Invalid compression '{s}', expected one of 'none', 'snappy', 'lz4', or 'zstd'"
+			)),
+		}
+	}
+}
+
+/// The strategy used to compact the files of the LSM tree
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum CompactionStyle {
+	/// Suited to read-heavy workloads, and the default
+	#[default]
+	Level,
+	/// Suited to write-heavy workloads, at the cost of more disk space
+	Universal,
+}
+
+impl Display for CompactionStyle {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Level => f.write_str("level"),
+			Self::Universal => f.write_str("universal"),
+		}
+	}
+}
+
+impl FromStr for CompactionStyle {
+	type Err = String;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"level" => Ok(Self::Level),
+			"universal" => Ok(Self::Universal),
+			// FIFO compaction deletes the oldest data once the datastore is full
+			"fifo" => Err("FIFO compaction is not supported, as it silently deletes data".into()),
+			_ => Err(format!(
+				"Invalid compaction style '{s}', expected one of 'level' or 'universal'"
+			)),
+		}
+	}
+}
+
+/// The options used to open the `rocksdb` and `speedb` storage engines.
+///
+/// The defaults are suited to a server which has the datastore to itself: a larger
+/// block cache than the engine default, as many background jobs as there are cores,
+/// and cheap compression on the levels which are rewritten most often.
+///
+/// ```rust
+/// use surrealdb::kvs::{Compression, RocksDbOptions};
+///
+/// let options = RocksDbOptions::default()
+/// 	.with_block_cache_size(1 << 30)
+/// 	.with_compression_per_level(vec![Compression::None, Compression::Zstd])
+/// 	.with_sync_writes(true);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RocksDbOptions {
+	/// The size of the cache of uncompressed blocks, in bytes
+	pub block_cache_size: usize,
+	/// The size of a memtable before it is flushed to disk, in bytes
+	pub write_buffer_size: usize,
+	/// The number of concurrent flushes and compactions
+	pub max_background_jobs: i32,
+	/// The compression of each level, where the last one applies to any deeper level
+	pub compression_per_level: Vec<Compression>,
+	/// Whether to sync the write-ahead log to disk when a transaction is committed
+	pub sync_writes: bool,
+	/// The strategy used to compact the files on disk
+	pub compaction_style: CompactionStyle,
+}
+
+impl Default for RocksDbOptions {
+	fn default() -> Self {
+		Self {
+			block_cache_size: 512 << 20,
+			write_buffer_size: 64 << 20,
+			max_background_jobs: std::thread::available_parallelism()
+				.map_or(2, |n| n.get().max(2) as i32),
+			compression_per_level: vec![
+				Compression::None,
+				Compression::None,
+				Compression::Lz4,
+				Compression::Lz4,
+				Compression::Lz4,
+				Compression::Lz4,
+				Compression::Zstd,
+			],
+			sync_writes: false,
+			compaction_style: CompactionStyle::Level,
+		}
+	}
+}
+
+impl RocksDbOptions {
+	/// Set the size of the block cache, in bytes
+	pub fn with_block_cache_size(mut self, bytes: usize) -> Self {
+		self.block_cache_size = bytes;
+		self
+	}
+	/// Set the size of each write buffer, in bytes
+	pub fn with_write_buffer_size(mut self, bytes: usize) -> Self {
+		self.write_buffer_size = bytes;
+		self
+	}
+	/// Set the maximum number of concurrent flushes and compactions
+	pub fn with_max_background_jobs(mut self, jobs: i32) -> Self {
+		self.max_background_jobs = jobs;
+		self
+	}
+	/// Set the compression of each level of the LSM tree
+	pub fn with_compression_per_level(mut self, levels: Vec<Compression>) -> Self {
+		self.compression_per_level = levels;
+		self
+	}
+	/// Set whether commits wait for the write-ahead log to be synced to disk
+	pub fn with_sync_writes(mut self, sync: bool) -> Self {
+		self.sync_writes = sync;
+		self
+	}
+	/// Set the compaction style
+	pub fn with_compaction_style(mut self, style: CompactionStyle) -> Self {
+		self.compaction_style = style;
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_compression() {
+		assert_eq!("LZ4".parse::<Compression>(), Ok(Compression::Lz4));
+		assert_eq!("none".parse::<Compression>(), Ok(Compression::None));
+		assert!("gzip".parse::<Compression>().is_err());
+		assert_eq!(Compression::Zstd.to_string(), "zstd");
+	}
+
+	#[test]
+	fn parse_compaction_style() {
+		assert_eq!("universal".parse::<CompactionStyle>(), Ok(CompactionStyle::Universal));
+		assert!("tiered".parse::<CompactionStyle>().is_err());
+		assert!("fifo".parse::<CompactionStyle>().is_err());
+		assert_eq!(CompactionStyle::Level.to_string(), "level");
+	}
+}
@@ -1,12 +1,17 @@
 #![cfg(feature = "kv-speedb")]
 
 use crate::err::Error;
+use crate::kvs::cipher::{Cipher, SALT_LEN};
 use crate::kvs::Check;
 use crate::kvs::Key;
 use crate::kvs::Val;
+use crate::kvs::{CompactionStyle, Compression, RocksDbOptions};
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
 use futures::lock::Mutex;
-use speedb::{OptimisticTransactionDB, OptimisticTransactionOptions, ReadOptions, WriteOptions};
+use speedb::{
+	BlockBasedOptions, Cache, DBCompactionStyle, DBCompressionType, OptimisticTransactionDB,
+	OptimisticTransactionOptions, Options, ReadOptions, WriteBatchWithTransaction, WriteOptions,
+};
 use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -14,6 +19,9 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct Datastore {
 	db: Pin<Arc<OptimisticTransactionDB>>,
+	cipher: Option<Arc<Cipher>>,
+	/// Whether commits wait for the write-ahead log to be synced to disk
+	sync: bool,
 }
 
 pub struct Transaction {
@@ -27,6 +35,8 @@ pub struct Transaction {
 	inner: Arc<Mutex<Option<speedb::Transaction<'static, OptimisticTransactionDB>>>>,
 	// The read options containing the Snapshot
 	ro: ReadOptions,
+	// The cipher used to encrypt the values, if encryption is enabled
+	cipher: Option<Arc<Cipher>>,
 	// The above, supposedly 'static transaction
 	// actually points here, so we need to ensure
 	// the memory is kept alive. This pointer must
@@ -65,19 +75,136 @@ impl Drop for Transaction {
 }
 
 impl Datastore {
-	/// Open a new database
-	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+	/// Open a new database, encrypting the values with the given encryption key
+	pub(crate) async fn new(
+		path: &str,
+		key: Option<&str>,
+		options: &RocksDbOptions,
+	) -> Result<Datastore, Error> {
+		let db = OptimisticTransactionDB::open(&Self::options(options), path)?;
+		// Check the encryption key against the key-check record
+		let kc = Cipher::record_key();
+		let record = db.get(&kc)?;
+		let empty = {
+			let mut iter = db.raw_iterator();
+			iter.seek_to_first();
+			!iter.valid()
+		};
+		let cipher = Cipher::open(key, record.as_deref(), empty)?;
+		// Create the key-check record of a new encrypted database
+		if let (Some(cipher), None) = (&cipher, &record) {
+			db.put(&kc, cipher.record()?)?;
+		}
 		Ok(Datastore {
-			db: Arc::pin(OptimisticTransactionDB::open_default(path)?),
+			db: Arc::pin(db),
+			cipher: cipher.map(Arc::new),
+			sync: options.sync_writes,
 		})
 	}
+	/// Convert the engine options into the options used to open the database
+	fn options(options: &RocksDbOptions) -> Options {
+		let mut opts = Options::default();
+		opts.create_if_missing(true);
+		opts.set_write_buffer_size(options.write_buffer_size);
+		opts.set_max_background_jobs(options.max_background_jobs);
+		opts.set_compaction_style(match options.compaction_style {
+			CompactionStyle::Level => DBCompactionStyle::Level,
+			CompactionStyle::Universal => DBCompactionStyle::Universal,
+		});
+		let levels: Vec<DBCompressionType> = options
+			.compression_per_level
+			.iter()
+			.map(|c| match c {
+				Compression::None => DBCompressionType::None,
+				Compression::Snappy => DBCompressionType::Snappy,
+				Compression::Lz4 => DBCompressionType::Lz4,
+				Compression::Zstd => DBCompressionType::Zstd,
+			})
+			.collect();
+		// The compression of the deepest level is also the default compression
+		if let Some(last) = levels.last() {
+			opts.set_compression_type(*last);
+		}
+		opts.set_compression_per_level(&levels);
+		let mut block = BlockBasedOptions::default();
+		block.set_block_cache(&Cache::new_lru_cache(options.block_cache_size));
+		opts.set_block_based_table_factory(&block);
+		opts
+	}
+	/// Read an integer property of the database, such as `rocksdb.block-cache-capacity`
+	#[cfg(test)]
+	pub(crate) fn property(&self, name: &str) -> Result<Option<u64>, Error> {
+		Ok(self.db.property_int_value(name)?)
+	}
+	/// Re-encrypt every value with a new encryption key, or decrypt every value without one.
+	///
+	/// This must only be run while the database is not in use. The values are rewritten in
+	/// batches, and each batch also records the last key which it rewrote, along with the salt
+	/// of the new key, so an interrupted rotation is resumed after that key, with the same
+	/// derived key, when it is run again with the same keys. The key-check record is rewritten
+	/// last, once every value has been rewritten.
+	pub(crate) async fn rotate(&self, key: Option<&str>) -> Result<(), Error> {
+		let kc = Cipher::record_key();
+		let kr = Cipher::progress_key();
+		let mut batch = WriteBatchWithTransaction::<true>::default();
+		let mut iter = self.db.raw_iterator();
+		// Resume after the last key rewritten by an interrupted rotation, with the salt of its new key
+		let salt: [u8; SALT_LEN] = match self.db.get(&kr)? {
+			Some(progress) if progress.len() >= SALT_LEN => {
+				let (salt, last) = progress.split_at(SALT_LEN);
+				iter.seek(last);
+				if iter.key() == Some(last) {
+					iter.next();
+				}
+				salt.try_into().unwrap()
+			}
+			Some(_) => return Err(Error::Ds("Invalid key rotation progress".to_string())),
+			None => {
+				iter.seek_to_first();
+				Cipher::generate_salt()
+			}
+		};
+		let new = key.map(|key| Cipher::new(key, salt)).transpose()?;
+		while let (Some(k), Some(v)) = (iter.key(), iter.value()) {
+			if k != kc.as_slice() && k != kr.as_slice() {
+				// Decrypt the value with the current key
+				let val = match &self.cipher {
+					Some(old) => old.decrypt(k, v)?,
+					None => v.to_vec(),
+				};
+				// Encrypt the value with the new key
+				match &new {
+					Some(new) => batch.put(k, new.encrypt(k, &val)?),
+					None => batch.put(k, val),
+				}
+				if batch.len() >= 1000 {
+					// Record the progress along with the rewritten values
+					batch.put(&kr, [&salt[..], k].concat());
+					self.db.write(std::mem::take(&mut batch))?;
+				}
+			}
+			iter.next();
+		}
+		iter.status()?;
+		// Rewrite the key-check record last
+		match &new {
+			Some(new) => batch.put(&kc, new.record()?),
+			None => batch.delete(&kc),
+		}
+		batch.delete(&kr);
+		self.db.write(batch)?;
+		Ok(())
+	}
 	/// Start a new transaction
 	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
 		// Activate the snapshot options
 		let mut to = OptimisticTransactionOptions::default();
 		to.set_snapshot(true);
+		// Wait for the write-ahead log to be synced on commit, if enabled
+		let mut wo = WriteOptions::default();
+		wo.set_sync(self.sync);
 		// Create a new transaction
-		let inner = self.db.transaction_opt(&WriteOptions::default(), &to);
+		let inner = self.db.transaction_opt(&wo, &to);
 		// The database reference must always outlive
 		// the transaction. If it doesn't then this
 		// is undefined behaviour. This unsafe block
@@ -104,6 +231,7 @@ impl Datastore {
 			write,
 			inner: Arc::new(Mutex::new(Some(inner))),
 			ro,
+			cipher: self.cipher.clone(),
 			_db: self.db.clone(),
 		})
 	}
@@ -114,6 +242,20 @@ impl Transaction {
 	pub(crate) fn check_level(&mut self, check: Check) {
 		self.check = check;
 	}
+	/// Encrypt a value before it is written, if encryption is enabled
+	fn encrypt(&self, key: &[u8], val: Val) -> Result<Val, Error> {
+		match &self.cipher {
+			Some(cipher) => cipher.encrypt(key, &val),
+			None => Ok(val),
+		}
+	}
+	/// Decrypt a value after it is read, if encryption is enabled
+	fn decrypt(&self, key: &[u8], val: Val) -> Result<Val, Error> {
+		match &self.cipher {
+			Some(cipher) => cipher.decrypt(key, &val),
+			None => Ok(val),
+		}
+	}
 	/// Check if closed
 	pub(crate) fn closed(&self) -> bool {
 		self.done
@@ -179,9 +321,10 @@ impl Transaction {
 			return Err(Error::TxFinished);
 		}
 		// Get the key
-		let res = self.inner.lock().await.as_ref().unwrap().get_opt(key.into(), &self.ro)?;
+		let key = key.into();
+		let res = self.inner.lock().await.as_ref().unwrap().get_opt(&key, &self.ro)?;
 		// Return result
-		Ok(res)
+		res.map(|v| self.decrypt(&key, v)).transpose()
 	}
 	/// Obtain a new change timestamp for a key
 	/// which is replaced with the current timestamp when the transaction is committed.
@@ -200,7 +343,8 @@ impl Transaction {
 		// Write the timestamp to the "last-write-timestamp" key
 		// to ensure that no other transactions can commit with older timestamps.
 		let k: Key = key.into();
-		let prev = self.inner.lock().await.as_ref().unwrap().get_opt(k.clone(), &self.ro)?;
+		let prev = self.inner.lock().await.as_ref().unwrap().get_opt(&k, &self.ro)?;
+		let prev = prev.map(|v| self.decrypt(&k, v)).transpose()?;
 		let ver = match prev {
 			Some(prev) => {
 				let slice = prev.as_slice();
@@ -217,7 +361,8 @@ impl Transaction {
 
 		let verbytes = u64_to_versionstamp(ver);
 
-		self.inner.lock().await.as_ref().unwrap().put(k, verbytes)?;
+		let val = self.encrypt(&k, verbytes.to_vec())?;
+		self.inner.lock().await.as_ref().unwrap().put(k, val)?;
 		// Return the uint64 representation of the timestamp as the result
 		Ok(verbytes)
 	}
@@ -260,7 +405,9 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Set the key
-		self.inner.lock().await.as_ref().unwrap().put(key.into(), val.into())?;
+		let key = key.into();
+		let val = self.encrypt(&key, val.into())?;
+		self.inner.lock().await.as_ref().unwrap().put(key, val)?;
 		// Return result
 		Ok(())
 	}
@@ -283,7 +430,7 @@ impl Transaction {
 		let inner = inner.as_ref().unwrap();
 		// Get the arguments
 		let key = key.into();
-		let val = val.into();
+		let val = self.encrypt(&key, val.into())?;
 		// Set the key if empty
 		match inner.get_opt(&key, &self.ro)? {
 			None => inner.put(key, val)?,
@@ -311,10 +458,11 @@ impl Transaction {
 		let inner = inner.as_ref().unwrap();
 		// Get the arguments
 		let key = key.into();
-		let val = val.into();
+		let val = self.encrypt(&key, val.into())?;
 		let chk = chk.map(Into::into);
 		// Set the key if valid
-		match (inner.get_opt(&key, &self.ro)?, chk) {
+		let cur = inner.get_opt(&key, &self.ro)?.map(|v| self.decrypt(&key, v)).transpose()?;
+		match (cur, chk) {
 			(Some(v), Some(w)) if v == w => inner.put(key, val)?,
 			(None, None) => inner.put(key, val)?,
 			_ => return Err(Error::TxConditionNotMet),
@@ -361,7 +509,8 @@ impl Transaction {
 		let key = key.into();
 		let chk = chk.map(Into::into);
 		// Delete the key if valid
-		match (inner.get_opt(&key, &self.ro)?, chk) {
+		let cur = inner.get_opt(&key, &self.ro)?.map(|v| self.decrypt(&key, v)).transpose()?;
+		match (cur, chk) {
 			(Some(v), Some(w)) if v == w => inner.delete(key)?,
 			(None, None) => inner.delete(key)?,
 			_ => return Err(Error::TxConditionNotMet),
@@ -411,7 +560,7 @@ impl Transaction {
 				// Check the key and value
 				if let (Some(k), Some(v)) = (k, v) {
 					if k >= beg && k < end {
-						res.push((k.to_vec(), v.to_vec()));
+						res.push((k.to_vec(), self.decrypt(k, v.to_vec())?));
 						iter.next();
 						continue;
 					}
@@ -423,4 +572,61 @@ impl Transaction {
 		// Return result
 		Ok(res)
 	}
+	/// Retrieve a range of keys from the databases, in descending order
+	pub(crate) async fn scanr<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Create result set
+		let mut res = vec![];
+		// Set the key range
+		let beg = rng.start.as_slice();
+		let end = rng.end.as_slice();
+		// Set the ReadOptions with the snapshot
+		let mut ro = ReadOptions::default();
+		ro.set_snapshot(&inner.snapshot());
+		// Create the iterator
+		let mut iter = inner.raw_iterator_opt(ro);
+		// Seek to the last key before the end key
+		iter.seek_for_prev(&rng.end);
+		if iter.key().is_some_and(|k| k >= end) {
+			iter.prev();
+		}
+		// Scan the keys in the iterator
+		while iter.valid() {
+			// Check the scan limit
+			if res.len() < limit as usize {
+				// Get the key and value
+				let (k, v) = (iter.key(), iter.value());
+				// Check the key and value
+				if let (Some(k), Some(v)) = (k, v) {
+					if k >= beg && k < end {
+						res.push((k.to_vec(), self.decrypt(k, v.to_vec())?));
+						iter.prev();
+						continue;
+					}
+				}
+			}
+			// Exit
+			break;
+		}
+		// Return result
+		Ok(res)
+	}
 }
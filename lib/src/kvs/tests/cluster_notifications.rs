@@ -0,0 +1,46 @@
+use crate::sql::test::Parse;
+
+#[tokio::test]
+#[serial]
+async fn notifications_are_delivered_to_the_node_owning_the_live_query() {
+	let node_a = uuid::Uuid::parse_str("5e8f1c2a-7b3d-4a9e-8c6f-1d2e3f4a5b6c").unwrap();
+	let node_b = uuid::Uuid::parse_str("9c8b7a6f-5e4d-4c3b-a2a1-0f9e8d7c6b5a").unwrap();
+	// Both nodes share the same storage
+	let (ds_a, _) = new_ds(node_a).await;
+	let ds_a = ds_a.with_notifications();
+	let ds_b = ds_a.new_node(sql::Uuid::from(node_b)).unwrap().with_notifications();
+	let ses = Session::owner().with_ns("test").with_db("test").with_rt(true);
+
+	// The live query is registered on the first node
+	let mut res = ds_a.execute("LIVE SELECT * FROM person", &ses, None).await.unwrap();
+	let live_id = match res.remove(0).result.unwrap() {
+		Value::Uuid(id) => id,
+		v => panic!("expected uuid, got {v:?}"),
+	};
+
+	// The change is made on the second node
+	let mut res = ds_b.execute("CREATE person:one SET name = 'one'", &ses, None).await.unwrap();
+	assert!(res.remove(0).result.is_ok());
+	// A cancelled change does not queue any notification
+	let sql = "BEGIN; CREATE person:two SET name = 'two'; CANCEL;";
+	ds_b.execute(sql, &ses, None).await.unwrap();
+
+	// The notification is not sent by the second node
+	assert!(ds_b.notifications().unwrap().try_recv().is_err());
+	ds_b.deliver_remote_notifications().await.unwrap();
+	assert!(ds_b.notifications().unwrap().try_recv().is_err());
+	// The notification is queued until the first node delivers it
+	let notifications = ds_a.notifications().unwrap();
+	assert!(notifications.try_recv().is_err());
+	ds_a.deliver_remote_notifications().await.unwrap();
+	let notification = notifications.try_recv().unwrap();
+	assert_eq!(notification.id, live_id);
+	assert_eq!(notification.action, Action::Create);
+	assert_eq!(notification.result, Value::parse("{ id: person:one, name: 'one' }"));
+	assert!(notifications.try_recv().is_err());
+
+	// The queue is empty once delivered
+	let mut tx = ds_a.transaction(false, false).await.unwrap();
+	assert!(tx.scan_ndnt(&node_a, 100).await.unwrap().is_empty());
+	tx.cancel().await.unwrap();
+}
@@ -0,0 +1,37 @@
+#[tokio::test]
+#[serial]
+async fn encryption() {
+	use crate::err::Error;
+	let dir = TempDir::new().unwrap();
+	let path = format!("{ENGINE}:{}", dir.path().to_string_lossy());
+	// Create a new encrypted datastore
+	let ds = Datastore::new_with_key(&path, Some("0123456789abcdef")).await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "some text").await.unwrap();
+	tx.putc("check", "one", None).await.unwrap();
+	tx.putc("check", "two", Some("one")).await.unwrap();
+	tx.commit().await.unwrap();
+	// Check that the values are decrypted
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap().unwrap();
+	assert_eq!(val, b"some text");
+	let val = tx.scan("check".."checl", 10).await.unwrap();
+	assert_eq!(val, vec![(b"check".to_vec(), b"two".to_vec())]);
+	tx.cancel().await.unwrap();
+	drop(ds);
+	// Check that a wrong or missing key fails fast
+	let res = Datastore::new_with_key(&path, Some("fedcba9876543210")).await;
+	assert!(matches!(res, Err(Error::InvalidEncryptionKey)));
+	let res = Datastore::new(&path).await;
+	assert!(matches!(res, Err(Error::MissingEncryptionKey)));
+	// Rotate the encryption key
+	let ds = Datastore::new_with_key(&path, Some("0123456789abcdef")).await.unwrap();
+	ds.rotate_key(Some("fedcba9876543210")).await.unwrap();
+	drop(ds);
+	// Check that the values are decrypted with the new key
+	let ds = Datastore::new_with_key(&path, Some("fedcba9876543210")).await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap().unwrap();
+	assert_eq!(val, b"some text");
+	tx.cancel().await.unwrap();
+}
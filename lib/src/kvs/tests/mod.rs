@@ -41,8 +41,10 @@ mod mem {
 	include!("multireader.rs");
 	include!("timestamp_to_versionstamp.rs");
 	include!("ndlq.rs");
+	include!("ndnt.rs");
 	include!("tblq.rs");
 	include!("tbnt.rs");
+	include!("cluster_notifications.rs");
 }
 
 #[cfg(feature = "kv-rocksdb")]
@@ -54,6 +56,8 @@ mod rocksdb {
 	use serial_test::serial;
 	use temp_dir::TempDir;
 
+	const ENGINE: &str = "rocksdb";
+
 	async fn new_ds(node_id: Uuid) -> (Datastore, Kvs) {
 		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
 		(
@@ -84,8 +88,11 @@ mod rocksdb {
 	include!("multiwriter_same_keys_conflict.rs");
 	include!("timestamp_to_versionstamp.rs");
 	include!("ndlq.rs");
+	include!("ndnt.rs");
 	include!("tblq.rs");
 	include!("tbnt.rs");
+	include!("encryption.rs");
+	include!("options.rs");
 }
 
 #[cfg(feature = "kv-speedb")]
@@ -97,6 +104,8 @@ mod speedb {
 	use serial_test::serial;
 	use temp_dir::TempDir;
 
+	const ENGINE: &str = "speedb";
+
 	async fn new_ds(node_id: Uuid) -> (Datastore, Kvs) {
 		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
 		(
@@ -127,8 +136,11 @@ mod speedb {
 	include!("multiwriter_same_keys_conflict.rs");
 	include!("timestamp_to_versionstamp.rs");
 	include!("ndlq.rs");
+	include!("ndnt.rs");
 	include!("tblq.rs");
 	include!("tbnt.rs");
+	include!("encryption.rs");
+	include!("options.rs");
 }
 
 #[cfg(feature = "kv-tikv")]
@@ -171,6 +183,7 @@ mod tikv {
 	include!("multiwriter_same_keys_conflict.rs");
 	include!("timestamp_to_versionstamp.rs");
 	include!("ndlq.rs");
+	include!("ndnt.rs");
 	include!("tblq.rs");
 	include!("tbnt.rs");
 }
@@ -215,6 +228,7 @@ mod fdb {
 	include!("multiwriter_same_keys_allow.rs");
 	include!("timestamp_to_versionstamp.rs");
 	include!("ndlq.rs");
+	include!("ndnt.rs");
 	include!("tblq.rs");
 	include!("tbnt.rs");
 }
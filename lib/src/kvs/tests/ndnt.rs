@@ -0,0 +1,84 @@
+use crate::dbs::{Action, Notification};
+
+#[tokio::test]
+#[serial]
+async fn write_scan_ndnt() {
+	let nd = uuid::Uuid::parse_str("4f5b4e2c-8e4d-4b8a-9a4f-0b1c3e5d7f91").unwrap();
+	let test = init(nd).await.unwrap();
+
+	// Write some data
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+	let lq =
+		sql::Uuid::from(uuid::Uuid::parse_str("b7b3f0a8-3a44-4f9e-a1c3-5d1a5e0c9a2d").unwrap());
+	let create = Notification {
+		id: lq.clone(),
+		action: Action::Create,
+		result: Value::from("created"),
+	};
+	let delete = Notification {
+		id: lq.clone(),
+		action: Action::Delete,
+		result: Value::from("deleted"),
+	};
+	tx.set_ndnt(nd, create.clone()).await.unwrap();
+	tx.set_ndnt(nd, delete.clone()).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// Verify scan
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+	let res = tx.scan_ndnt(&nd, 100).await.unwrap();
+	assert_eq!(res.len(), 2);
+	assert!(res.iter().all(|(nt, _)| nt.nd == nd));
+	let mut notifications: Vec<Notification> = res.iter().map(|(_, n)| n.clone()).collect();
+	notifications.sort_by_key(|n| n.action.to_string());
+	assert_eq!(notifications, vec![create, delete]);
+	// Delete a single notification
+	tx.del_ndnt(res[0].0.clone()).await.unwrap();
+	tx.commit().await.unwrap();
+
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+	assert_eq!(tx.scan_ndnt(&nd, 100).await.unwrap().len(), 1);
+	// Delete the whole queue
+	tx.delr_ndnt(&nd).await.unwrap();
+	tx.commit().await.unwrap();
+
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+	assert!(tx.scan_ndnt(&nd, 100).await.unwrap().is_empty());
+	// The queue is not mistaken for live queries of the node
+	assert!(tx.scan_ndlq(&nd, 100).await.unwrap().is_empty());
+	tx.commit().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn expired_nodes_get_notifications_removed() {
+	let old_node = uuid::Uuid::parse_str("0a6b5d2e-5c4f-4e27-8c1d-3f9b7a6e2d10").unwrap();
+	let new_node = uuid::Uuid::parse_str("d1e2f3a4-b5c6-4d7e-8f90-a1b2c3d4e5f6").unwrap();
+	let test = init(new_node).await.unwrap();
+
+	// Set up the first node at an early timestamp
+	let old_time = Timestamp {
+		value: 123,
+	};
+	test.bootstrap_at_time(sql::Uuid::from(old_node), old_time).await.unwrap();
+
+	// Queue a notification for the first node
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+	let notification = Notification {
+		id: sql::Uuid::new(),
+		action: Action::Update,
+		result: Value::None,
+	};
+	tx.set_ndnt(old_node, notification).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// Set up the second node at a later timestamp, which expires the first node
+	let new_time = Timestamp {
+		value: 10123,
+	};
+	test.bootstrap_at_time(sql::Uuid::from(new_node), new_time).await.unwrap();
+
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+	assert!(tx.scan_ndnt(&old_node, 100).await.unwrap().is_empty());
+	tx.commit().await.unwrap();
+}
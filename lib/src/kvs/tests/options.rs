@@ -0,0 +1,31 @@
+#[tokio::test]
+#[serial]
+async fn options() {
+	use crate::kvs::{CompactionStyle, Compression, RocksDbOptions};
+	let dir = TempDir::new().unwrap();
+	let path = format!("{ENGINE}:{}", dir.path().to_string_lossy());
+	// Create a datastore with non-default engine options
+	let options = RocksDbOptions::default()
+		.with_block_cache_size(8 << 20)
+		.with_write_buffer_size(4 << 20)
+		.with_max_background_jobs(1)
+		.with_compression_per_level(vec![Compression::None, Compression::Snappy])
+		.with_sync_writes(true)
+		.with_compaction_style(CompactionStyle::Universal);
+	let ds = Datastore::new_with_options(&path, None, &options).await.unwrap();
+	// Check that the options were applied to the engine
+	let capacity = ds.engine_property("rocksdb.block-cache-capacity").unwrap();
+	assert_eq!(capacity, Some(8 << 20));
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "some text").await.unwrap();
+	tx.commit().await.unwrap();
+	drop(ds);
+	// Check that the data can be read back with the default options
+	let ds = Datastore::new(&path).await.unwrap();
+	let capacity = ds.engine_property("rocksdb.block-cache-capacity").unwrap();
+	assert_eq!(capacity, Some(512 << 20));
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap().unwrap();
+	assert_eq!(val, b"some text");
+	tx.cancel().await.unwrap();
+}
@@ -230,3 +230,46 @@ async fn scan() {
 	assert_eq!(val[1].1, b"2");
 	tx.cancel().await.unwrap();
 }
+
+#[tokio::test]
+#[serial]
+async fn scanr() {
+	// Create a new datastore
+	let node_id = Uuid::parse_str("4a0c3f6e-1e34-4a8b-9d57-6f3cb9a02c1d").unwrap();
+	let (ds, _) = new_ds(node_id).await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	assert!(tx.put("test1", "1").await.is_ok());
+	assert!(tx.put("test2", "2").await.is_ok());
+	assert!(tx.put("test3", "3").await.is_ok());
+	assert!(tx.put("test4", "4").await.is_ok());
+	assert!(tx.put("test5", "5").await.is_ok());
+	tx.commit().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scanr("test1".."test9", u32::MAX).await.unwrap();
+	assert_eq!(val.len(), 5);
+	assert_eq!(val[0].0, b"test5");
+	assert_eq!(val[0].1, b"5");
+	assert_eq!(val[4].0, b"test1");
+	assert_eq!(val[4].1, b"1");
+	tx.cancel().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scanr("test2".."test4", u32::MAX).await.unwrap();
+	assert_eq!(val.len(), 2);
+	assert_eq!(val[0].0, b"test3");
+	assert_eq!(val[0].1, b"3");
+	assert_eq!(val[1].0, b"test2");
+	assert_eq!(val[1].1, b"2");
+	tx.cancel().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scanr("test1".."test9", 2).await.unwrap();
+	assert_eq!(val.len(), 2);
+	assert_eq!(val[0].0, b"test5");
+	assert_eq!(val[0].1, b"5");
+	assert_eq!(val[1].0, b"test4");
+	assert_eq!(val[1].1, b"4");
+	tx.cancel().await.unwrap();
+}
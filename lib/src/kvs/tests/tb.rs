@@ -24,6 +24,7 @@ async fn table_definitions_can_be_scanned() {
 		permissions: Default::default(),
 		changefeed: None,
 		comment: None,
+		rebuild: None,
 	};
 	tx.set(&key, &value).await.unwrap();
 
@@ -61,6 +62,7 @@ async fn table_definitions_can_be_deleted() {
 		permissions: Default::default(),
 		changefeed: None,
 		comment: None,
+		rebuild: None,
 	};
 	tx.set(&key, &value).await.unwrap();
 
@@ -5,6 +5,7 @@ use super::Val;
 use crate::cf;
 use crate::dbs::node::ClusterMembership;
 use crate::dbs::node::Timestamp;
+use crate::dbs::Notification;
 use crate::err::Error;
 use crate::idg::u32::U32;
 use crate::key::debug;
@@ -12,6 +13,8 @@ use crate::kvs::cache::Cache;
 use crate::kvs::cache::Entry;
 use crate::kvs::Check;
 use crate::kvs::LqValue;
+use crate::metrics::Metrics;
+use crate::ml::MlFile;
 use crate::sql;
 use crate::sql::paths::EDGE;
 use crate::sql::paths::IN;
@@ -30,14 +33,17 @@ use sql::statements::DefineEventStatement;
 use sql::statements::DefineFieldStatement;
 use sql::statements::DefineFunctionStatement;
 use sql::statements::DefineIndexStatement;
+use sql::statements::DefineModelStatement;
 use sql::statements::DefineNamespaceStatement;
 use sql::statements::DefineParamStatement;
+use sql::statements::DefineRoleStatement;
 use sql::statements::DefineScopeStatement;
 use sql::statements::DefineTableStatement;
 use sql::statements::DefineTokenStatement;
 use sql::statements::DefineUserStatement;
 use sql::statements::LiveStatement;
 use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::fmt;
 use std::fmt::Debug;
 use std::ops::Range;
@@ -55,6 +61,8 @@ pub struct Transaction {
 	pub(super) cache: Cache,
 	pub(super) cf: cf::Writer,
 	pub(super) vso: Arc<Mutex<Oracle>>,
+	pub(super) reads: u64,
+	pub(super) metrics: Arc<Metrics>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -168,6 +176,46 @@ impl Transaction {
 		}
 	}
 
+	/// The number of keys read from the datastore by this transaction.
+	pub fn reads(&self) -> u64 {
+		self.reads
+	}
+
+	/// The metrics of the datastore which created this transaction.
+	pub(crate) fn metrics(&self) -> &Metrics {
+		&self.metrics
+	}
+
+	/// Check if the underlying datastore can scan a range of keys in descending order.
+	///
+	/// The other datastores scan the whole range on every call to [`Transaction::scanr`].
+	pub(crate) fn can_scanr(&self) -> bool {
+		match self {
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-speedb")]
+			Transaction {
+				inner: Inner::SpeeDB(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FoundationDB(_),
+				..
+			} => true,
+			#[allow(unreachable_patterns)]
+			_ => false,
+		}
+	}
+
 	/// Cancel a transaction.
 	///
 	/// This reverses all changes made within the transaction.
@@ -216,7 +264,7 @@ impl Transaction {
 	pub async fn commit(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Commit");
-		match self {
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -249,7 +297,9 @@ impl Transaction {
 			} => v.commit().await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
-		}
+		};
+		self.metrics.observe_commit(&res);
+		res
 	}
 
 	/// Delete a key from the datastore.
@@ -348,7 +398,7 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Get {:?}", key);
-		match self {
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -381,7 +431,11 @@ impl Transaction {
 			} => v.get(key).await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
+		};
+		if let Ok(Some(_)) = &res {
+			self.reads += 1;
 		}
+		res
 	}
 
 	/// Insert or update a key in the datastore.
@@ -625,7 +679,7 @@ impl Transaction {
 			debug::sprint_key(&rng.start.clone().into()),
 			debug::sprint_key(&rng.end.clone().into())
 		);
-		match self {
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -658,7 +712,88 @@ impl Transaction {
 			} => v.scan(rng, limit).await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
+		};
+		if let Ok(v) = &res {
+			self.reads += v.len() as u64;
+		}
+		res
+	}
+
+	/// Retrieve a specific range of keys from the datastore, in descending order.
+	///
+	/// This function fetches the last key-value pairs of the range, in a single request to the underlying datastore.
+	/// Datastores which can not iterate in reverse are scanned forwards, from the start of the range, keeping only
+	/// the last key-value pairs. Iterating such a datastore in reverse, batch after batch, is therefore quadratic,
+	/// which is why the query planner only iterates indexes in reverse when [`Transaction::can_scanr`] is true.
+	#[allow(unused_variables)]
+	pub async fn scanr<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key> + Debug + Clone,
+	{
+		#[cfg(debug_assertions)]
+		trace!(
+			"Scanr {:?} - {:?}",
+			debug::sprint_key(&rng.start.clone().into()),
+			debug::sprint_key(&rng.end.clone().into())
+		);
+		let reads = self.reads;
+		let res = match self {
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-speedb")]
+			Transaction {
+				inner: Inner::SpeeDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FoundationDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[allow(unreachable_patterns)]
+			_ => {
+				if limit == 0 {
+					return Ok(vec![]);
+				}
+				let beg: Key = rng.start.into();
+				let end: Key = rng.end.into();
+				let mut nxt = beg;
+				let mut out: VecDeque<(Key, Val)> =
+					VecDeque::with_capacity(limit.min(1000) as usize);
+				loop {
+					let res = self.scan(nxt.clone()..end.clone(), 1000).await?;
+					let n = res.len();
+					if let Some((k, _)) = res.last() {
+						nxt = k.clone();
+						nxt.push(0x00);
+					}
+					// Only keep the last key-value pairs of the range
+					for kv in res {
+						if out.len() == limit as usize {
+							out.pop_front();
+						}
+						out.push_back(kv);
+					}
+					if n < 1000 {
+						break;
+					}
+				}
+				Ok(out.into_iter().rev().collect())
+			}
+		};
+		// The keys scanned forwards have already been counted
+		if let Ok(v) = &res {
+			self.reads = self.reads.max(reads + v.len() as u64);
 		}
+		res
 	}
 
 	/// Update a key in the datastore if the current value matches a condition.
@@ -1258,6 +1393,48 @@ impl Transaction {
 		self.putc(key, tb, chk).await
 	}
 
+	/// Queue a live query notification for the node which owns the live query
+	pub async fn set_ndnt(&mut self, nd: Uuid, notification: Notification) -> Result<(), Error> {
+		let ts = self.clock();
+		let key = crate::key::node::nt::new(nd, ts.value, Uuid::now_v7());
+		trace!("set_ndnt: key={}", crate::key::debug::sprint_key(&key.encode()?));
+		self.set(key, notification).await
+	}
+
+	/// Scan the notifications queued for a node, in the order they were queued
+	pub async fn scan_ndnt(
+		&mut self,
+		nd: &Uuid,
+		limit: u32,
+	) -> Result<Vec<(crate::key::node::nt::Nt, Notification)>, Error> {
+		let pref = crate::key::node::nt::prefix_nd(nd);
+		let suff = crate::key::node::nt::suffix_nd(nd);
+		trace!(
+			"Scanning range from pref={}, suff={}",
+			crate::key::debug::sprint_key(&pref),
+			crate::key::debug::sprint_key(&suff),
+		);
+		let scanned = self.scan(pref..suff, limit).await?;
+		let mut res = vec![];
+		for (key, value) in scanned {
+			let nt = crate::key::node::nt::Nt::decode(key.as_slice())?;
+			res.push((nt, value.into()));
+		}
+		Ok(res)
+	}
+
+	/// Delete a notification once it has been delivered
+	pub async fn del_ndnt(&mut self, nt: crate::key::node::nt::Nt) -> Result<(), Error> {
+		self.del(nt).await
+	}
+
+	/// Delete all the notifications queued for a node
+	pub async fn delr_ndnt(&mut self, nd: &Uuid) -> Result<(), Error> {
+		let pref = crate::key::node::nt::prefix_nd(nd);
+		let suff = crate::key::node::nt::suffix_nd(nd);
+		self.delr(pref..suff, u32::MAX).await
+	}
+
 	/// Retrieve all ROOT users.
 	pub async fn all_root_users(&mut self) -> Result<Arc<[DefineUserStatement]>, Error> {
 		let beg = crate::key::root::us::prefix();
@@ -1305,6 +1482,25 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve all namespace role definitions for a specific namespace.
+	pub async fn all_ns_roles(&mut self, ns: &str) -> Result<Arc<[DefineRoleStatement]>, Error> {
+		let key = crate::key::namespace::rl::prefix(ns);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Nrs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::namespace::rl::prefix(ns);
+			let end = crate::key::namespace::rl::suffix(ns);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Nrs(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all namespace token definitions for a specific namespace.
 	pub async fn all_ns_tokens(&mut self, ns: &str) -> Result<Arc<[DefineTokenStatement]>, Error> {
 		let key = crate::key::namespace::tk::prefix(ns);
@@ -1366,6 +1562,29 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve all database role definitions for a specific database.
+	pub async fn all_db_roles(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineRoleStatement]>, Error> {
+		let key = crate::key::database::rl::prefix(ns, db);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Drs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::database::rl::prefix(ns, db);
+			let end = crate::key::database::rl::suffix(ns, db);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Drs(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all database token definitions for a specific database.
 	pub async fn all_db_tokens(
 		&mut self,
@@ -1435,6 +1654,29 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve all model definitions for a specific database.
+	pub async fn all_db_models(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineModelStatement]>, Error> {
+		let key = crate::key::database::ml::prefix(ns, db);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Mls(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::database::ml::prefix(ns, db);
+			let end = crate::key::database::ml::suffix(ns, db);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Mls(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all param definitions for a specific database.
 	pub async fn all_db_params(
 		&mut self,
@@ -1703,6 +1945,20 @@ impl Transaction {
 		Ok(val.into())
 	}
 
+	/// Retrieve a specific role definition from a namespace.
+	pub async fn get_ns_role(
+		&mut self,
+		ns: &str,
+		role: &str,
+	) -> Result<DefineRoleStatement, Error> {
+		let key = crate::key::namespace::rl::new(ns, role);
+		let val = self.get(key).await?.ok_or(Error::RoleNsNotFound {
+			value: role.to_owned(),
+			ns: ns.to_owned(),
+		})?;
+		Ok(val.into())
+	}
+
 	/// Retrieve a specific namespace token definition.
 	pub async fn get_ns_token(
 		&mut self,
@@ -1741,6 +1997,22 @@ impl Transaction {
 		Ok(val.into())
 	}
 
+	/// Retrieve a specific role definition from a database.
+	pub async fn get_db_role(
+		&mut self,
+		ns: &str,
+		db: &str,
+		role: &str,
+	) -> Result<DefineRoleStatement, Error> {
+		let key = crate::key::database::rl::new(ns, db, role);
+		let val = self.get(key).await?.ok_or(Error::RoleDbNotFound {
+			value: role.to_owned(),
+			ns: ns.to_owned(),
+			db: db.to_owned(),
+		})?;
+		Ok(val.into())
+	}
+
 	/// Retrieve a specific database token definition.
 	pub async fn get_db_token(
 		&mut self,
@@ -2055,6 +2327,56 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve a specific model definition.
+	pub async fn get_and_cache_db_model(
+		&mut self,
+		ns: &str,
+		db: &str,
+		ml: &str,
+		vn: &str,
+	) -> Result<Arc<DefineModelStatement>, Error> {
+		let key = crate::key::database::ml::new(ns, db, ml, vn).encode()?;
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Ml(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let val = self.get(key.clone()).await?.ok_or(Error::MlNotFound {
+				value: format!("{ml}<{vn}>"),
+			})?;
+			let val: Arc<DefineModelStatement> = Arc::new(val.into());
+			self.cache.set(key, Entry::Ml(Arc::clone(&val)));
+			val
+		})
+	}
+
+	/// Retrieve and parse a specific model file.
+	pub async fn get_and_cache_db_model_file(
+		&mut self,
+		ns: &str,
+		db: &str,
+		ml: &str,
+		vn: &str,
+	) -> Result<Arc<MlFile>, Error> {
+		let key = crate::key::database::mf::new(ns, db, ml, vn).encode()?;
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Mf(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let val = self.get(key.clone()).await?.ok_or(Error::MfNotFound {
+				value: format!("{ml}<{vn}>"),
+			})?;
+			let val = Arc::new(MlFile::from_slice(&val)?);
+			self.cache.set(key, Entry::Mf(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve a specific param definition.
 	pub async fn get_and_cache_db_param(
 		&mut self,
@@ -2228,6 +2550,20 @@ impl Transaction {
 			chn.send(bytes!("OPTION IMPORT;")).await?;
 			chn.send(bytes!("")).await?;
 		}
+		// Output ROLES
+		{
+			let drs = self.all_db_roles(ns, db).await?;
+			if !drs.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- ROLES")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for rl in drs.iter() {
+					chn.send(bytes!(format!("{rl};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
 		// Output USERS
 		{
 			let dus = self.all_db_users(ns, db).await?;
@@ -2284,6 +2620,20 @@ impl Transaction {
 				chn.send(bytes!("")).await?;
 			}
 		}
+		// Output MODELS
+		{
+			let mls = self.all_db_models(ns, db).await?;
+			if !mls.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- MODELS")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for ml in mls.iter() {
+					chn.send(bytes!(format!("{ml};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
 		// Output ANALYZERS
 		{
 			let azs = self.all_db_analyzers(ns, db).await?;
@@ -112,6 +112,7 @@ mod ctx;
 mod doc;
 mod exe;
 mod fnc;
+mod ml;
 mod vs;
 
 pub mod sql;
@@ -134,6 +135,8 @@ pub mod idx;
 pub mod key;
 #[doc(hidden)]
 pub mod kvs;
+#[doc(hidden)]
+pub mod metrics;
 
 #[doc(inline)]
 pub use api::engine;
@@ -160,6 +163,12 @@ pub mod channel {
 	pub use channel::Sender;
 }
 
+#[doc(hidden)]
+/// Change sets for syncing a database between instances
+pub mod sync {
+	pub use crate::cf::ChangeSet;
+}
+
 /// Different error types for embedded and remote databases
 pub mod error {
 	pub use crate::api::err::Error as Api;
@@ -0,0 +1,170 @@
+//! The metrics of the database engine, exported in the Prometheus text format.
+//!
+//! Each datastore records its own counters and histograms as it runs, while
+//! the values which depend on the state of the datastore, such as the number
+//! of live queries, are computed when the metrics are encoded.
+mod types;
+
+pub use types::*;
+
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::kvs::Datastore;
+use std::time::Duration;
+
+/// Histogram buckets in seconds
+pub static DURATION_BUCKETS: &[f64] =
+	&[0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// The metrics recorded by a datastore
+pub struct Metrics {
+	transaction_commits: Counter,
+	transaction_conflicts: Counter,
+	index_cache_hits: Counter,
+	index_cache_misses: Counter,
+	statement_duration: Histogram,
+}
+
+impl Default for Metrics {
+	fn default() -> Self {
+		Self {
+			transaction_commits: Counter::new(
+				"surrealdb_transaction_commits_total",
+				"The number of transactions committed to the datastore.",
+			),
+			transaction_conflicts: Counter::new(
+				"surrealdb_transaction_conflicts_total",
+				"The number of transactions which failed to commit because of a conflict with another transaction.",
+			),
+			index_cache_hits: Counter::new(
+				"surrealdb_index_cache_hits_total",
+				"The number of index nodes found in the cache of the index readers.",
+			),
+			index_cache_misses: Counter::new(
+				"surrealdb_index_cache_misses_total",
+				"The number of index nodes read from the datastore instead of the cache of the index readers.",
+			),
+			statement_duration: Histogram::new(
+				"surrealdb_statement_duration_seconds",
+				"The duration of the statements executed, by type of statement.",
+				DURATION_BUCKETS,
+			),
+		}
+	}
+}
+
+impl Metrics {
+	/// Records the outcome of a transaction commit
+	pub(crate) fn observe_commit<T>(&self, res: &Result<T, Error>) {
+		match res {
+			Ok(_) => self.transaction_commits.inc(),
+			Err(Error::TxConflict) => self.transaction_conflicts.inc(),
+			Err(_) => {}
+		}
+	}
+	/// Records whether an index node was found in the cache of an index reader
+	pub(crate) fn observe_index_cache(&self, hit: bool) {
+		match hit {
+			true => self.index_cache_hits.inc(),
+			false => self.index_cache_misses.inc(),
+		}
+	}
+	/// Records the duration of a statement
+	pub(crate) fn observe_statement(&self, kind: &'static str, time: Duration) {
+		self.statement_duration.observe(&[("statement", kind)], time.as_secs_f64());
+	}
+}
+
+/// Writes the metrics of the datastore in the Prometheus text format.
+///
+/// The metrics describe every namespace and database, so they can only
+/// be read by the users who are allowed to view the root level.
+pub async fn encode(ds: &Datastore, sess: &Session, out: &mut String) -> Result<(), Error> {
+	// Skip auth for Anonymous users if auth is disabled
+	let skip_auth = !ds.is_auth_enabled() && sess.au.is_anon();
+	if !skip_auth {
+		sess.au.is_allowed(Action::View, &ResourceKind::Any.on_root())?;
+	}
+	let metrics = ds.metrics();
+	metrics.transaction_commits.encode(out);
+	metrics.transaction_conflicts.encode(out);
+	metrics.index_cache_hits.encode(out);
+	metrics.index_cache_misses.encode(out);
+	metrics.statement_duration.encode(out);
+	encode_gauge(
+		out,
+		"surrealdb_live_queries",
+		"The number of live queries registered on this node.",
+		&[(vec![], ds.live_query_count().await? as i64)],
+	);
+	let lag: Vec<(Labels, i64)> = ds
+		.sync_lag()
+		.await?
+		.into_iter()
+		.map(|(ns, db, peer, lag)| (vec![("ns", ns), ("db", db), ("peer", peer)], lag as i64))
+		.collect();
+	encode_gauge(
+		out,
+		"surrealdb_changefeed_lag_versionstamps",
+		"The number of change feed versionstamps which a sync peer has not yet acknowledged.",
+		&lag,
+	);
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::iam::Role;
+
+	#[tokio::test]
+	async fn encode_engine_metrics() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		tx.set("test", "ok").await.unwrap();
+		tx.commit().await.unwrap();
+		let mut out = String::new();
+		encode(&ds, &Session::owner(), &mut out).await.unwrap();
+		assert!(out.contains("# TYPE surrealdb_transaction_commits_total counter\n"));
+		assert!(out.contains("\nsurrealdb_transaction_commits_total 1\n"));
+		assert!(out.contains("\nsurrealdb_live_queries 0\n"));
+		// The metrics are recorded separately for each datastore
+		let other = Datastore::new("memory").await.unwrap();
+		assert_eq!(other.metrics().transaction_commits.get(), 0);
+	}
+
+	#[tokio::test]
+	async fn encode_changefeed_lag() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = "
+			DEFINE DATABASE test CHANGEFEED 1h;
+			CREATE person:tobie;
+			CREATE person:jaime;
+		";
+		for res in ds.execute(sql, &ses, None).await.unwrap() {
+			res.result.unwrap();
+		}
+		let changes = ds.sync_changes(&ses, Some("edge"), None, Some(1)).await.unwrap();
+		let vs = crate::vs::try_to_u64_be(changes[0].0).unwrap();
+		ds.sync_ack(&ses, "edge", vs).await.unwrap();
+		let mut out = String::new();
+		encode(&ds, &Session::owner(), &mut out).await.unwrap();
+		assert!(out.contains(
+			"\nsurrealdb_changefeed_lag_versionstamps{ns=\"test\",db=\"test\",peer=\"edge\"} 1\n"
+		));
+	}
+
+	#[tokio::test]
+	async fn encode_requires_root_access() {
+		let ds = Datastore::new("memory").await.unwrap().with_auth_enabled(true);
+		let mut out = String::new();
+		let ses = Session::for_level(("test",).into(), Role::Owner).with_ns("test");
+		let res = encode(&ds, &ses, &mut out).await;
+		assert!(matches!(res, Err(Error::IamError(_))), "{res:?}");
+		let res = encode(&ds, &Session::default(), &mut out).await;
+		assert!(matches!(res, Err(Error::IamError(_))), "{res:?}");
+		encode(&ds, &Session::viewer(), &mut out).await.unwrap();
+	}
+}
@@ -0,0 +1,215 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+/// The labels of a series, as pairs of names and values
+pub type Labels = Vec<(&'static str, String)>;
+
+/// A value which only increases, such as a number of events
+pub struct Counter {
+	name: &'static str,
+	help: &'static str,
+	value: AtomicU64,
+}
+
+impl Counter {
+	pub const fn new(name: &'static str, help: &'static str) -> Self {
+		Self {
+			name,
+			help,
+			value: AtomicU64::new(0),
+		}
+	}
+
+	pub fn inc(&self) {
+		self.value.fetch_add(1, Ordering::Relaxed);
+	}
+
+	pub fn get(&self) -> u64 {
+		self.value.load(Ordering::Relaxed)
+	}
+
+	/// Writes the counter in the Prometheus text format
+	pub fn encode(&self, out: &mut String) {
+		header(out, self.name, self.help, "counter");
+		let _ = writeln!(out, "{} {}", self.name, self.get());
+	}
+}
+
+struct Series {
+	/// The number of observations in each bucket, the last one being +Inf
+	counts: Vec<AtomicU64>,
+	/// The sum of the observations, stored as the bits of a float
+	sum: AtomicU64,
+}
+
+impl Series {
+	fn new(buckets: usize) -> Self {
+		Self {
+			counts: (0..=buckets).map(|_| AtomicU64::new(0)).collect(),
+			sum: AtomicU64::new(0f64.to_bits()),
+		}
+	}
+
+	fn observe(&self, bucket: usize, v: f64) {
+		self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+		let _ = self.sum.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |b| {
+			Some((f64::from_bits(b) + v).to_bits())
+		});
+	}
+}
+
+/// The distribution of observed values, such as durations or sizes,
+/// with a series of cumulative buckets for each set of labels.
+///
+/// The buckets are atomic, so observations only contend on a lock
+/// the first time that a set of labels is observed.
+pub struct Histogram {
+	name: &'static str,
+	help: &'static str,
+	buckets: &'static [f64],
+	series: RwLock<Vec<(Labels, Series)>>,
+}
+
+impl Histogram {
+	pub const fn new(name: &'static str, help: &'static str, buckets: &'static [f64]) -> Self {
+		Self {
+			name,
+			help,
+			buckets,
+			series: RwLock::new(Vec::new()),
+		}
+	}
+
+	pub fn observe(&self, labels: &[(&'static str, &str)], v: f64) {
+		let i = self.buckets.iter().position(|b| v <= *b).unwrap_or(self.buckets.len());
+		let matches =
+			|l: &Labels| l.iter().map(|(k, v)| (*k, v.as_str())).eq(labels.iter().copied());
+		// Record the observation in an existing series
+		{
+			let series = self.series.read().unwrap_or_else(|e| e.into_inner());
+			if let Some((_, s)) = series.iter().find(|(l, _)| matches(l)) {
+				s.observe(i, v);
+				return;
+			}
+		}
+		// Otherwise create the series, unless another thread just did
+		let mut series = self.series.write().unwrap_or_else(|e| e.into_inner());
+		match series.iter().find(|(l, _)| matches(l)) {
+			Some((_, s)) => s.observe(i, v),
+			None => {
+				let s = Series::new(self.buckets.len());
+				s.observe(i, v);
+				let labels = labels.iter().map(|(k, v)| (*k, v.to_string())).collect();
+				series.push((labels, s));
+			}
+		}
+	}
+
+	/// Writes the histogram in the Prometheus text format
+	pub fn encode(&self, out: &mut String) {
+		header(out, self.name, self.help, "histogram");
+		let series = self.series.read().unwrap_or_else(|e| e.into_inner());
+		let mut series: Vec<&(Labels, Series)> = series.iter().collect();
+		series.sort_by(|a, b| a.0.cmp(&b.0));
+		for (labels, s) in series {
+			let mut count = 0;
+			for (i, c) in s.counts.iter().enumerate() {
+				count += c.load(Ordering::Relaxed);
+				let le = match self.buckets.get(i) {
+					Some(b) => b.to_string(),
+					None => "+Inf".to_string(),
+				};
+				let _ = writeln!(
+					out,
+					"{}_bucket{} {count}",
+					self.name,
+					format_labels(labels, Some(&le))
+				);
+			}
+			let sum = f64::from_bits(s.sum.load(Ordering::Relaxed));
+			let _ = writeln!(out, "{}_sum{} {sum}", self.name, format_labels(labels, None));
+			let _ = writeln!(out, "{}_count{} {count}", self.name, format_labels(labels, None));
+		}
+	}
+}
+
+/// Writes a gauge with a value for each set of labels in the Prometheus text format
+pub fn encode_gauge(out: &mut String, name: &str, help: &str, series: &[(Labels, i64)]) {
+	header(out, name, help, "gauge");
+	for (labels, v) in series {
+		let _ = writeln!(out, "{name}{} {v}", format_labels(labels, None));
+	}
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+	let _ = writeln!(out, "# HELP {name} {help}");
+	let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+	let mut pairs: Vec<String> =
+		labels.iter().map(|(k, v)| format!("{k}=\"{}\"", escape(v))).collect();
+	if let Some(le) = le {
+		pairs.push(format!("le=\"{le}\""));
+	}
+	if pairs.is_empty() {
+		String::new()
+	} else {
+		format!("{{{}}}", pairs.join(","))
+	}
+}
+
+/// Escapes a label value, as required by the Prometheus text format
+fn escape(v: &str) -> String {
+	v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encode_counter() {
+		let c = Counter::new("test_total", "A test counter");
+		c.inc();
+		c.inc();
+		let mut out = String::new();
+		c.encode(&mut out);
+		assert_eq!(
+			out,
+			"# HELP test_total A test counter\n# TYPE test_total counter\ntest_total 2\n"
+		);
+	}
+
+	#[test]
+	fn encode_gauge_series() {
+		let mut out = String::new();
+		encode_gauge(&mut out, "test_lag", "A test gauge", &[(vec![("peer", "a".to_owned())], 3)]);
+		assert_eq!(
+			out,
+			"# HELP test_lag A test gauge\n# TYPE test_lag gauge\ntest_lag{peer=\"a\"} 3\n"
+		);
+	}
+
+	#[test]
+	fn encode_histogram() {
+		let h = Histogram::new("test_seconds", "A test histogram", &[0.25, 1.0]);
+		h.observe(&[("kind", "a\"b")], 0.25);
+		h.observe(&[("kind", "a\"b")], 0.5);
+		h.observe(&[("kind", "a\"b")], 4.0);
+		let mut out = String::new();
+		h.encode(&mut out);
+		assert_eq!(
+			out,
+			"# HELP test_seconds A test histogram
+# TYPE test_seconds histogram
+test_seconds_bucket{kind=\"a\\\"b\",le=\"0.25\"} 1
+test_seconds_bucket{kind=\"a\\\"b\",le=\"1\"} 2
+test_seconds_bucket{kind=\"a\\\"b\",le=\"+Inf\"} 3
+test_seconds_sum{kind=\"a\\\"b\"} 4.75
+test_seconds_count{kind=\"a\\\"b\"} 3
+"
+		);
+	}
+}
@@ -0,0 +1,340 @@
+//! ml is a module to store and evaluate machine learning models.
+//!
+//! A model file is a JSON document which describes the model, its name and version, and
+//! the inputs which it expects. Models are uploaded with `Datastore::import_ml`, and are
+//! evaluated inline in queries with `ml::name<version>(...)`.
+//!
+//! ```json
+//! {
+//!     "name": "churn",
+//!     "version": "1.0.0",
+//!     "description": "Likelihood of a customer leaving",
+//!     "inputs": ["age", "spend"],
+//!     "model": {
+//!         "type": "linear",
+//!         "weights": [0.02, -0.5],
+//!         "bias": 0.1,
+//!         "activation": "sigmoid"
+//!     }
+//! }
+//! ```
+
+use crate::err::Error;
+use crate::sql::common::val_char;
+use crate::sql::model::version;
+use crate::sql::Value;
+use serde::{Deserialize, Serialize};
+
+/// A machine learning model file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MlFile {
+	/// The name of the model
+	pub name: String,
+	/// The version of the model, in the form major.minor.patch
+	pub version: String,
+	/// An optional description of the model
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub description: Option<String>,
+	/// The names of the inputs of the model, in order
+	pub inputs: Vec<String>,
+	/// The model itself
+	pub model: Estimator,
+}
+
+/// The supported kinds of models
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Estimator {
+	/// A linear model, optionally followed by an activation function
+	Linear {
+		weights: Vec<f64>,
+		#[serde(default)]
+		bias: f64,
+		#[serde(default)]
+		activation: Activation,
+	},
+	/// A decision tree, where the first node is the root of the tree
+	Tree {
+		nodes: Vec<TreeNode>,
+	},
+}
+
+/// The activation function applied to the output of a linear model
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Activation {
+	#[default]
+	Identity,
+	Sigmoid,
+	Relu,
+	Tanh,
+}
+
+/// A node of a decision tree
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TreeNode {
+	/// Goes to the left node if the input is lower than or equal to the threshold,
+	/// or to the right node otherwise
+	Split {
+		feature: usize,
+		threshold: f64,
+		left: usize,
+		right: usize,
+	},
+	/// Returns the value
+	Leaf {
+		value: f64,
+	},
+}
+
+impl MlFile {
+	/// Parses and validates a model file
+	pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+		let file: Self = serde_json::from_slice(data).map_err(|e| Error::InvalidModel {
+			message: e.to_string(),
+		})?;
+		file.validate()?;
+		Ok(file)
+	}
+
+	fn validate(&self) -> Result<(), Error> {
+		let invalid = |message: String| {
+			Err(Error::InvalidModel {
+				message,
+			})
+		};
+		// Check the name of the model
+		if self.name.split("::").any(|p| p.is_empty() || !p.chars().all(val_char)) {
+			return invalid(format!("'{}' is not a valid model name", self.name));
+		}
+		// Check the version of the model
+		match version(&self.version) {
+			Ok(("", v)) if v == self.version => {}
+			_ => return invalid(format!("'{}' is not a valid model version", self.version)),
+		}
+		// Check the inputs of the model
+		if self.inputs.is_empty() {
+			return invalid("The model must have at least one input".to_owned());
+		}
+		// Check the model itself
+		match &self.model {
+			Estimator::Linear {
+				weights,
+				..
+			} => {
+				if weights.len() != self.inputs.len() {
+					return invalid(format!(
+						"The linear model has {} weights, but {} inputs",
+						weights.len(),
+						self.inputs.len()
+					));
+				}
+			}
+			Estimator::Tree {
+				nodes,
+			} => {
+				if nodes.is_empty() {
+					return invalid("The decision tree must have at least one node".to_owned());
+				}
+				for (i, n) in nodes.iter().enumerate() {
+					if let TreeNode::Split {
+						feature,
+						left,
+						right,
+						..
+					} = n
+					{
+						if *feature >= self.inputs.len() {
+							return invalid(format!("The node {i} refers to an unknown input"));
+						}
+						// Children always follow their parent, so the tree can not loop
+						if *left <= i
+							|| *right <= i || *left >= nodes.len()
+							|| *right >= nodes.len()
+						{
+							return invalid(format!("The node {i} refers to an invalid node"));
+						}
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Evaluates the model with the given arguments.
+	/// The arguments are either an object containing every input by name,
+	/// an array of every input in order, or a single number for a model with one input.
+	/// On failure, the returned error describes what was expected.
+	pub fn evaluate(&self, args: &Value) -> Result<f64, String> {
+		let inputs = self.inputs(args)?;
+		let res = match &self.model {
+			Estimator::Linear {
+				weights,
+				bias,
+				activation,
+			} => {
+				let v = weights.iter().zip(&inputs).map(|(w, x)| w * x).sum::<f64>() + bias;
+				match activation {
+					Activation::Identity => v,
+					Activation::Sigmoid => 1.0 / (1.0 + (-v).exp()),
+					Activation::Relu => v.max(0.0),
+					Activation::Tanh => v.tanh(),
+				}
+			}
+			Estimator::Tree {
+				nodes,
+			} => {
+				let mut i = 0;
+				loop {
+					match &nodes[i] {
+						TreeNode::Split {
+							feature,
+							threshold,
+							left,
+							right,
+						} => {
+							i = if inputs[*feature] <= *threshold {
+								*left
+							} else {
+								*right
+							};
+						}
+						TreeNode::Leaf {
+							value,
+						} => break *value,
+					}
+				}
+			}
+		};
+		Ok(res)
+	}
+
+	fn inputs(&self, args: &Value) -> Result<Vec<f64>, String> {
+		let input = |name: &str, v: Option<&Value>| match v {
+			Some(Value::Number(v)) => Ok(v.to_float()),
+			Some(Value::Bool(v)) => Ok(if *v {
+				1.0
+			} else {
+				0.0
+			}),
+			_ => Err(format!("The model expects a number for the '{name}' input.")),
+		};
+		match args {
+			Value::Object(o) => self.inputs.iter().map(|n| input(n, o.get(n))).collect(),
+			Value::Array(a) if a.len() == self.inputs.len() => {
+				self.inputs.iter().zip(a.iter()).map(|(n, v)| input(n, Some(v))).collect()
+			}
+			v if self.inputs.len() == 1 && !v.is_array() => {
+				Ok(vec![input(&self.inputs[0], Some(v))?])
+			}
+			_ => Err(format!(
+				"The model expects an object with the inputs: {}.",
+				self.inputs.join(", ")
+			)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::test::Parse;
+
+	fn linear() -> &'static str {
+		r#"{
+			"name": "churn",
+			"version": "1.0.0",
+			"inputs": ["age", "spend"],
+			"model": { "type": "linear", "weights": [2.0, -1.0], "bias": 0.5 }
+		}"#
+	}
+
+	fn tree() -> &'static str {
+		r#"{
+			"name": "insurance::prediction",
+			"version": "2.1.0",
+			"inputs": ["age", "purchased_before"],
+			"model": {
+				"type": "tree",
+				"nodes": [
+					{ "feature": 0, "threshold": 30.0, "left": 1, "right": 2 },
+					{ "value": 0.1 },
+					{ "feature": 1, "threshold": 0.5, "left": 3, "right": 4 },
+					{ "value": 0.4 },
+					{ "value": 0.9 }
+				]
+			}
+		}"#
+	}
+
+	#[test]
+	fn evaluate_linear_model() {
+		let file = MlFile::from_slice(linear().as_bytes()).unwrap();
+		let res = file.evaluate(&Value::parse("{ age: 3, spend: 1.5 }")).unwrap();
+		assert_eq!(res, 5.0);
+		let res = file.evaluate(&Value::parse("[1, 2]")).unwrap();
+		assert_eq!(res, 0.5);
+		let res = file.evaluate(&Value::parse("{ age: 3 }"));
+		assert_eq!(res, Err("The model expects a number for the 'spend' input.".to_owned()));
+		let res = file.evaluate(&Value::parse("3"));
+		assert_eq!(res, Err("The model expects an object with the inputs: age, spend.".to_owned()));
+	}
+
+	#[test]
+	fn evaluate_linear_model_with_activation() {
+		let mut file = MlFile::from_slice(linear().as_bytes()).unwrap();
+		let Estimator::Linear {
+			activation,
+			..
+		} = &mut file.model
+		else {
+			unreachable!()
+		};
+		*activation = Activation::Sigmoid;
+		let res = file.evaluate(&Value::parse("{ age: 0, spend: 0.5 }")).unwrap();
+		assert_eq!(res, 0.5);
+		let res = file.evaluate(&Value::parse("{ age: 100, spend: 0 }")).unwrap();
+		assert!(res > 0.99);
+	}
+
+	#[test]
+	fn evaluate_tree_model() {
+		let file = MlFile::from_slice(tree().as_bytes()).unwrap();
+		let res = file.evaluate(&Value::parse("{ age: 18, purchased_before: true }")).unwrap();
+		assert_eq!(res, 0.1);
+		let res = file.evaluate(&Value::parse("{ age: 40, purchased_before: false }")).unwrap();
+		assert_eq!(res, 0.4);
+		let res = file.evaluate(&Value::parse("{ age: 40, purchased_before: true }")).unwrap();
+		assert_eq!(res, 0.9);
+	}
+
+	#[test]
+	fn invalid_model_files() {
+		let check = |data: &str, message: &str| match MlFile::from_slice(data.as_bytes()) {
+			Err(Error::InvalidModel {
+				message: m,
+			}) => assert_eq!(m, message),
+			r => panic!("unexpected result: {r:?}"),
+		};
+		check(&linear().replace("\"1.0.0\"", "\"1.0\""), "'1.0' is not a valid model version");
+		check(
+			&linear().replace("\"churn\"", "\"churn-rate\""),
+			"'churn-rate' is not a valid model name",
+		);
+		check(
+			&linear().replace("[2.0, -1.0]", "[2.0]"),
+			"The linear model has 1 weights, but 2 inputs",
+		);
+		check(
+			&tree().replace("\"left\": 3", "\"left\": 0"),
+			"The node 2 refers to an invalid node",
+		);
+		check(
+			&tree().replace("\"feature\": 1", "\"feature\": 2"),
+			"The node 2 refers to an unknown input",
+		);
+		assert!(MlFile::from_slice(b"\x08\x07onnx").is_err());
+	}
+}
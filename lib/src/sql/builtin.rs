@@ -136,6 +136,7 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 			all => { fn },
 			any => { fn },
 			append => { fn },
+			approx_distinct => { fn },
 			at => { fn },
 			boolean_and => { fn },
 			boolean_not => { fn },
@@ -360,12 +361,15 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 			uppercase => { fn },
 			words => { fn },
 			distance => {
+				damerau_levenshtein => { fn },
 				hamming => { fn },
 				levenshtein => { fn },
 			},
 			similarity => {
 				fuzzy => { fn },
 				jaro => { fn },
+				jaro_winkler => { fn },
+				ngram => { fn },
 				smithwaterman => { fn },
 			},
 			is => {
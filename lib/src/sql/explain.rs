@@ -8,13 +8,22 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
-pub struct Explain(pub bool);
+#[revisioned(revision = 2)]
+pub struct Explain {
+	/// Whether to run the query, and return the number of fetched records and the estimates
+	pub full: bool,
+	/// Whether to run the query, and return the time and rows of each iterator
+	#[revision(start = 2)]
+	pub analyze: bool,
+}
 
 impl fmt::Display for Explain {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.write_str("EXPLAIN")?;
-		if self.0 {
+		if self.analyze {
+			f.write_str(" ANALYZE")?;
+		}
+		if self.full {
 			f.write_str(" FULL")?;
 		}
 		Ok(())
@@ -23,8 +32,15 @@ impl fmt::Display for Explain {
 
 pub fn explain(i: &str) -> IResult<&str, Explain> {
 	let (i, _) = tag_no_case("EXPLAIN")(i)?;
+	let (i, analyze) = opt(tuple((shouldbespace, tag_no_case("ANALYZE"))))(i)?;
 	let (i, full) = opt(tuple((shouldbespace, tag_no_case("FULL"))))(i)?;
-	Ok((i, Explain(full.is_some())))
+	Ok((
+		i,
+		Explain {
+			full: full.is_some(),
+			analyze: analyze.is_some(),
+		},
+	))
 }
 
 #[cfg(test)]
@@ -37,7 +53,7 @@ mod tests {
 		let sql = "EXPLAIN";
 		let res = explain(sql);
 		let out = res.unwrap().1;
-		assert_eq!(out, Explain(false));
+		assert_eq!(out, Explain::default());
 		assert_eq!("EXPLAIN", format!("{}", out));
 	}
 
@@ -46,7 +62,43 @@ mod tests {
 		let sql = "EXPLAIN FULL";
 		let res = explain(sql);
 		let out = res.unwrap().1;
-		assert_eq!(out, Explain(true));
+		assert_eq!(
+			out,
+			Explain {
+				full: true,
+				analyze: false,
+			}
+		);
 		assert_eq!("EXPLAIN FULL", format!("{}", out));
 	}
+
+	#[test]
+	fn explain_analyze_statement() {
+		let sql = "EXPLAIN ANALYZE";
+		let res = explain(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			out,
+			Explain {
+				full: false,
+				analyze: true,
+			}
+		);
+		assert_eq!("EXPLAIN ANALYZE", format!("{}", out));
+	}
+
+	#[test]
+	fn explain_analyze_full_statement() {
+		let sql = "EXPLAIN ANALYZE FULL";
+		let res = explain(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			out,
+			Explain {
+				full: true,
+				analyze: true,
+			}
+		);
+		assert_eq!("EXPLAIN ANALYZE FULL", format!("{}", out));
+	}
 }
@@ -104,11 +104,15 @@ impl Function {
 	/// Check if this function is a rolling function
 	pub fn is_rolling(&self) -> bool {
 		match self {
+			Self::Normal(f, _) if f == "array::approx_distinct" => true,
+			Self::Normal(f, _) if f == "array::distinct" => true,
 			Self::Normal(f, _) if f == "count" => true,
 			Self::Normal(f, _) if f == "math::max" => true,
 			Self::Normal(f, _) if f == "math::mean" => true,
 			Self::Normal(f, _) if f == "math::min" => true,
+			Self::Normal(f, _) if f == "math::stddev" => true,
 			Self::Normal(f, _) if f == "math::sum" => true,
+			Self::Normal(f, _) if f == "math::variance" => true,
 			Self::Normal(f, _) if f == "time::max" => true,
 			Self::Normal(f, _) if f == "time::min" => true,
 			_ => false,
@@ -117,6 +121,7 @@ impl Function {
 	/// Check if this function is a grouping function
 	pub fn is_aggregate(&self) -> bool {
 		match self {
+			Self::Normal(f, _) if f == "array::approx_distinct" => true,
 			Self::Normal(f, _) if f == "array::distinct" => true,
 			Self::Normal(f, _) if f == "array::first" => true,
 			Self::Normal(f, _) if f == "array::group" => true,
@@ -100,7 +100,14 @@ impl Idiom {
 	}
 	/// Convert this Idiom to a JSON Path string
 	pub(crate) fn to_path(&self) -> String {
-		format!("/{self}").replace(']', "").replace(&['.', '['][..], "/")
+		self.0
+			.iter()
+			.map(|p| match p {
+				// Escape field names as RFC 6901 reference tokens
+				Part::Field(v) => format!("/{}", v.0.replace('~', "~0").replace('/', "~1")),
+				p => format!("/{p}").replace(']', "").replace(&['.', '['][..], ""),
+			})
+			.collect()
 	}
 	/// Simplifies this Idiom for use in object keys
 	pub(crate) fn simplify(&self) -> Idiom {
@@ -472,4 +479,11 @@ mod tests {
 			])
 		);
 	}
+
+	#[test]
+	fn idiom_to_path() {
+		let out = Idiom(vec![Part::from("test"), Part::from("a/b~c"), Part::from(2)]);
+		assert_eq!("/test/a~1b~0c/2", out.to_path());
+		assert_eq!(out, Value::from(out.to_path()).jsonpath());
+	}
 }
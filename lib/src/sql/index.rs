@@ -26,6 +26,8 @@ pub enum Index {
 	Search(SearchParams),
 	/// M-Tree index for distance based metrics
 	MTree(MTreeParams),
+	/// Spatial index for geometries
+	Spatial,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
@@ -102,12 +104,13 @@ impl Display for Index {
 					p.dimension, p.distance, p.capacity, p.doc_ids_order
 				)
 			}
+			Self::Spatial => f.write_str("SPATIAL"),
 		}
 	}
 }
 
 pub fn index(i: &str) -> IResult<&str, Index> {
-	alt((unique, search, mtree))(i)
+	alt((unique, search, mtree, spatial))(i)
 }
 
 pub fn unique(i: &str) -> IResult<&str, Index> {
@@ -115,6 +118,11 @@ pub fn unique(i: &str) -> IResult<&str, Index> {
 	Ok((i, Index::Uniq))
 }
 
+pub fn spatial(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("SPATIAL")(i)?;
+	Ok((i, Index::Spatial))
+}
+
 pub fn analyzer(i: &str) -> IResult<&str, Ident> {
 	let (i, _) = mightbespace(i)?;
 	let (i, _) = tag_no_case("ANALYZER")(i)?;
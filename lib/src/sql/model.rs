@@ -15,7 +15,8 @@ use crate::{
 	dbs::{Options, Transaction},
 	doc::CursorDoc,
 	err::Error,
-	sql::{error::IResult, value::Value},
+	iam::Action,
+	sql::{error::IResult, permission::Permission, value::Value},
 };
 
 use super::{
@@ -44,12 +45,57 @@ impl Model {
 	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
 	pub(crate) async fn compute(
 		&self,
-		_ctx: &Context<'_>,
-		_opt: &Options,
-		_txn: &Transaction,
-		_doc: Option<&'async_recursion CursorDoc<'_>>,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&'async_recursion CursorDoc<'_>>,
 	) -> Result<Value, Error> {
-		Err(Error::Unimplemented("ML model evaluation not yet implemented".to_string()))
+		// Check this model is allowed
+		ctx.check_allowed_function(format!("ml::{}", self.name).as_str())?;
+		// Get the model definition and the model file
+		let (val, file) = {
+			// Claim transaction
+			let mut run = txn.lock().await;
+			// Get the model definition
+			let val =
+				run.get_and_cache_db_model(opt.ns(), opt.db(), &self.name, &self.version).await?;
+			// Get the model file
+			let file = run
+				.get_and_cache_db_model_file(opt.ns(), opt.db(), &self.name, &self.version)
+				.await?;
+			(val, file)
+		};
+		// Check permissions
+		if opt.check_perms(Action::View) {
+			match &val.permissions {
+				Permission::Full => (),
+				Permission::None => {
+					return Err(Error::ModelPermissions {
+						name: self.name.to_owned(),
+					})
+				}
+				Permission::Specific(e) => {
+					// Disable permissions
+					let opt = &opt.new_with_perms(false);
+					// Process the PERMISSION clause
+					if !e.compute(ctx, opt, txn, doc).await?.is_truthy() {
+						return Err(Error::ModelPermissions {
+							name: self.name.to_owned(),
+						});
+					}
+				}
+			}
+		}
+		// Compute the model arguments
+		let args = self.parameters.compute(ctx, opt, txn, doc).await?;
+		// Evaluate the model
+		match file.evaluate(&args) {
+			Ok(v) => Ok(v.into()),
+			Err(message) => Err(Error::InvalidArguments {
+				name: format!("ml::{}<{}>", self.name, self.version),
+				message,
+			}),
+		}
 	}
 }
 
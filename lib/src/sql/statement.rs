@@ -25,6 +25,7 @@ use crate::sql::statements::output::{output, OutputStatement};
 use crate::sql::statements::r#break::{r#break, BreakStatement};
 use crate::sql::statements::r#continue::{r#continue, ContinueStatement};
 use crate::sql::statements::r#use::{r#use, UseStatement};
+use crate::sql::statements::rebuild::{rebuild, RebuildStatement};
 use crate::sql::statements::relate::{relate, RelateStatement};
 use crate::sql::statements::remove::{remove, RemoveStatement};
 use crate::sql::statements::select::{select, SelectStatement};
@@ -110,6 +111,7 @@ pub enum Statement {
 	Update(UpdateStatement),
 	Throw(ThrowStatement),
 	Use(UseStatement),
+	Rebuild(RebuildStatement),
 }
 
 impl Statement {
@@ -129,7 +131,7 @@ impl Statement {
 	pub(crate) fn writeable(&self) -> bool {
 		match self {
 			Self::Value(v) => v.writeable(),
-			Self::Analyze(_) => false,
+			Self::Analyze(_) => true,
 			Self::Break(_) => false,
 			Self::Continue(_) => false,
 			Self::Create(v) => v.writeable(),
@@ -145,6 +147,7 @@ impl Statement {
 			Self::Option(_) => false,
 			Self::Relate(v) => v.writeable(),
 			Self::Remove(_) => true,
+			Self::Rebuild(_) => true,
 			Self::Select(v) => v.writeable(),
 			Self::Set(v) => v.writeable(),
 			Self::Show(_) => false,
@@ -155,6 +158,39 @@ impl Statement {
 			_ => unreachable!(),
 		}
 	}
+	/// The type of statement, as reported in the metrics
+	pub(crate) fn kind(&self) -> &'static str {
+		match self {
+			Self::Value(_) => "value",
+			Self::Analyze(_) => "analyze",
+			Self::Begin(_) => "begin",
+			Self::Break(_) => "break",
+			Self::Cancel(_) => "cancel",
+			Self::Commit(_) => "commit",
+			Self::Continue(_) => "continue",
+			Self::Create(_) => "create",
+			Self::Define(_) => "define",
+			Self::Delete(_) => "delete",
+			Self::Foreach(_) => "foreach",
+			Self::Insert(_) => "insert",
+			Self::Ifelse(_) => "ifelse",
+			Self::Info(_) => "info",
+			Self::Kill(_) => "kill",
+			Self::Live(_) => "live",
+			Self::Option(_) => "option",
+			Self::Output(_) => "output",
+			Self::Relate(_) => "relate",
+			Self::Remove(_) => "remove",
+			Self::Rebuild(_) => "rebuild",
+			Self::Select(_) => "select",
+			Self::Set(_) => "set",
+			Self::Show(_) => "show",
+			Self::Sleep(_) => "sleep",
+			Self::Throw(_) => "throw",
+			Self::Update(_) => "update",
+			Self::Use(_) => "use",
+		}
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -179,6 +215,7 @@ impl Statement {
 			Self::Output(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Relate(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Remove(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Rebuild(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Select(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Set(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Show(v) => v.compute(ctx, opt, txn, doc).await,
@@ -219,6 +256,7 @@ impl Display for Statement {
 			Self::Output(v) => write!(Pretty::from(f), "{v}"),
 			Self::Relate(v) => write!(Pretty::from(f), "{v}"),
 			Self::Remove(v) => write!(Pretty::from(f), "{v}"),
+			Self::Rebuild(v) => write!(Pretty::from(f), "{v}"),
 			Self::Select(v) => write!(Pretty::from(f), "{v}"),
 			Self::Set(v) => write!(Pretty::from(f), "{v}"),
 			Self::Show(v) => write!(Pretty::from(f), "{v}"),
@@ -256,6 +294,7 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 				map(output, Statement::Output),
 				map(relate, Statement::Relate),
 				map(remove, Statement::Remove),
+				map(rebuild, Statement::Rebuild),
 				map(select, Statement::Select),
 				map(set, Statement::Set),
 				map(show, Statement::Show),
@@ -5,6 +5,7 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
 use crate::idx::ft::FtIndex;
+use crate::idx::planner::stats::IndexStatistics;
 use crate::idx::trees::mtree::MTreeIndex;
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::IndexKeyBase;
@@ -40,7 +41,7 @@ impl AnalyzeStatement {
 		match self {
 			AnalyzeStatement::Idx(tb, idx) => {
 				// Allowed to run?
-				opt.is_allowed(Action::View, ResourceKind::Index, &Base::Db)?;
+				opt.is_allowed(Action::Edit, ResourceKind::Index, &Base::Db)?;
 				// Claim transaction
 				let mut run = txn.lock().await;
 				// Read the index
@@ -50,24 +51,32 @@ impl AnalyzeStatement {
 				let ikb = IndexKeyBase::new(opt, &ix);
 
 				// Index operation dispatching
-				let value: Value = match &ix.index {
+				let (mut stats, value): (IndexStatistics, Value) = match &ix.index {
 					Index::Search(p) => {
 						let az = run.get_db_analyzer(opt.ns(), opt.db(), p.az.as_str()).await?;
 						let ft =
 							FtIndex::new(&mut run, az, ikb, p, TreeStoreType::Traversal).await?;
-						ft.statistics(&mut run).await?.into()
+						let stats = ft.statistics(&mut run).await?;
+						((&stats).into(), stats.into())
 					}
 					Index::MTree(p) => {
 						let mt =
 							MTreeIndex::new(&mut run, ikb, p, TreeStoreType::Traversal).await?;
-						mt.statistics(&mut run).await?.into()
+						let stats = mt.statistics(&mut run).await?;
+						((&stats).into(), stats.into())
 					}
-					_ => {
-						return Err(Error::FeatureNotYetImplemented {
-							feature: "Statistics on unique and non-unique indexes.".to_string(),
-						})
+					Index::Idx | Index::Uniq | Index::Spatial => {
+						let stats =
+							IndexStatistics::compute(&mut run, opt.ns(), opt.db(), &ix).await?;
+						(stats.clone(), stats.into())
 					}
 				};
+				// Persist the statistics for the query planner, which reads them once they exist
+				stats.analyzed_count = stats.keys_count;
+				stats.set(&mut run, opt.ns(), opt.db(), tb.as_str(), idx.as_str()).await?;
+				// The changes recorded by the previous writes are counted by the statistics
+				IndexStatistics::clear(&mut run, opt.ns(), opt.db(), tb.as_str(), idx.as_str())
+					.await?;
 				// Return the result object
 				Ok(value)
 			}
@@ -5,6 +5,7 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::idx::builder::IndexBuilding;
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::ending;
@@ -30,16 +31,37 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 3)]
 pub struct DefineIndexStatement {
 	pub name: Ident,
 	pub what: Ident,
 	pub cols: Idioms,
 	pub index: Index,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub concurrently: bool,
+	#[revision(start = 3)]
+	pub storage: Option<Ident>,
+	#[revision(start = 3)]
+	pub rebuild: Option<Ident>,
 }
 
 impl DefineIndexStatement {
+	/// The name under which the entries of the index are stored.
+	///
+	/// This is the name of the index, until the index is rebuilt. The entries
+	/// of a rebuilt index are stored under the name of its last rebuild.
+	pub(crate) fn storage(&self) -> &str {
+		self.storage.as_ref().unwrap_or(&self.name)
+	}
+	/// The index whose entries are being rebuilt, while the index is rebuilt
+	pub(crate) fn shadow(&self) -> Option<Self> {
+		self.rebuild.as_ref().map(|rebuild| Self {
+			storage: Some(rebuild.clone()),
+			rebuild: None,
+			..self.clone()
+		})
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -54,18 +76,23 @@ impl DefineIndexStatement {
 		let mut run = txn.lock().await;
 		// Clear the cache
 		run.clear_cache();
+		// Remove the index data, and stop any previous build of the index
+		IndexBuilding::remove(&mut run, opt.ns(), opt.db(), &self.what, &self.name).await?;
 		// Process the statement
 		let key = crate::key::table::ix::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
 		run.set(key, self).await?;
-		// Remove the index data
-		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
-		run.delp(key, u32::MAX).await?;
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
+		// Build the index in the background
+		if self.concurrently {
+			let state = IndexBuilding::default();
+			state.set(&mut run, opt.ns(), opt.db(), &self.what, &self.name).await?;
+			return Ok(Value::None);
+		}
 		// Release the transaction
 		drop(run);
 		// Force queries to run
@@ -96,6 +123,9 @@ impl Display for DefineIndexStatement {
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
+		if self.concurrently {
+			f.write_str(" CONCURRENTLY")?
+		}
 		Ok(())
 	}
 }
@@ -132,6 +162,9 @@ pub fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 			DefineIndexOption::Comment(v) => {
 				res.comment = Some(v);
 			}
+			DefineIndexOption::Concurrently => {
+				res.concurrently = true;
+			}
 		}
 	}
 	// Check necessary options
@@ -146,10 +179,11 @@ enum DefineIndexOption {
 	Index(Index),
 	Columns(Idioms),
 	Comment(Strand),
+	Concurrently,
 }
 
 fn index_opts(i: &str) -> IResult<&str, DefineIndexOption> {
-	alt((index_kind, index_columns, index_comment))(i)
+	alt((index_kind, index_columns, index_comment, index_concurrently))(i)
 }
 
 fn index_kind(i: &str) -> IResult<&str, DefineIndexOption> {
@@ -174,6 +208,12 @@ fn index_comment(i: &str) -> IResult<&str, DefineIndexOption> {
 	Ok((i, DefineIndexOption::Comment(v)))
 }
 
+fn index_concurrently(i: &str) -> IResult<&str, DefineIndexOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("CONCURRENTLY")(i)?;
+	Ok((i, DefineIndexOption::Concurrently))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -198,6 +238,9 @@ mod tests {
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Idx,
 				comment: None,
+				concurrently: false,
+				storage: None,
+				rebuild: None,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col");
@@ -215,6 +258,9 @@ mod tests {
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Uniq,
 				comment: None,
+				concurrently: false,
+				storage: None,
+				rebuild: None,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE");
@@ -243,6 +289,9 @@ mod tests {
 					terms_order: 1000,
 				}),
 				comment: None,
+				concurrently: false,
+				storage: None,
+				rebuild: None,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col SEARCH ANALYZER my_analyzer BM25(1.2,0.75) DOC_IDS_ORDER 1000 DOC_LENGTHS_ORDER 1000 POSTINGS_ORDER 1000 TERMS_ORDER 1000 HIGHLIGHTS");
@@ -268,6 +317,9 @@ mod tests {
 					terms_order: 100,
 				}),
 				comment: None,
+				concurrently: false,
+				storage: None,
+				rebuild: None,
 			}
 		);
 		assert_eq!(
@@ -293,6 +345,9 @@ mod tests {
 					doc_ids_order: 100,
 				}),
 				comment: None,
+				concurrently: false,
+				storage: None,
+				rebuild: None,
 			}
 		);
 		assert_eq!(
@@ -300,4 +355,47 @@ mod tests {
 			"DEFINE INDEX my_index ON my_table FIELDS my_col MTREE DIMENSION 4 DIST EUCLIDEAN CAPACITY 40 DOC_IDS_ORDER 100"
 		);
 	}
+
+	#[test]
+	fn check_create_spatial_index() {
+		let sql = "INDEX my_index ON TABLE my_table COLUMNS location SPATIAL";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("location".to_string()))])]),
+				index: Index::Spatial,
+				comment: None,
+				concurrently: false,
+				storage: None,
+				rebuild: None,
+			}
+		);
+		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS location SPATIAL");
+	}
+
+	#[test]
+	fn check_create_index_concurrently() {
+		let sql = "INDEX my_index ON TABLE my_table COLUMNS my_col UNIQUE CONCURRENTLY";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
+				index: Index::Uniq,
+				comment: None,
+				concurrently: true,
+				storage: None,
+				rebuild: None,
+			}
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE CONCURRENTLY"
+		);
+	}
 }
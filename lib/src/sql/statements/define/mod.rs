@@ -7,6 +7,7 @@ mod index;
 mod model;
 mod namespace;
 mod param;
+mod role;
 mod scope;
 mod table;
 mod token;
@@ -18,10 +19,11 @@ pub use event::{event, DefineEventStatement};
 pub use field::{field, DefineFieldStatement};
 pub use function::{function, DefineFunctionStatement};
 pub use index::{index, DefineIndexStatement};
-pub use model::DefineModelStatement;
+pub use model::{model, DefineModelStatement};
 pub use namespace::{namespace, DefineNamespaceStatement};
 use nom::bytes::complete::tag_no_case;
 pub use param::{param, DefineParamStatement};
+pub use role::{role, DefineRoleStatement};
 pub use scope::{scope, DefineScopeStatement};
 pub use table::{table, DefineTableStatement};
 pub use token::{token, DefineTokenStatement};
@@ -58,6 +60,7 @@ pub enum DefineStatement {
 	Index(DefineIndexStatement),
 	User(DefineUserStatement),
 	MlModel(DefineModelStatement),
+	Role(DefineRoleStatement),
 }
 
 impl DefineStatement {
@@ -87,6 +90,7 @@ impl DefineStatement {
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::User(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::MlModel(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Role(ref v) => v.compute(ctx, opt, txn, doc).await,
 		}
 	}
 }
@@ -107,6 +111,7 @@ impl Display for DefineStatement {
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
 			Self::MlModel(v) => Display::fmt(v, f),
+			Self::Role(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -119,6 +124,7 @@ pub fn define(i: &str) -> IResult<&str, DefineStatement> {
 		map(database, DefineStatement::Database),
 		map(function, DefineStatement::Function),
 		map(user, DefineStatement::User),
+		map(role, DefineStatement::Role),
 		map(token, DefineStatement::Token),
 		map(scope, DefineStatement::Scope),
 		map(param, DefineStatement::Param),
@@ -127,6 +133,7 @@ pub fn define(i: &str) -> IResult<&str, DefineStatement> {
 		map(field, DefineStatement::Field),
 		map(index, DefineStatement::Index),
 		map(analyzer, DefineStatement::Analyzer),
+		map(model, DefineStatement::MlModel),
 	))(i)
 }
 
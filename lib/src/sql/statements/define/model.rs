@@ -1,20 +1,34 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::common::{closechevron, openchevron};
+use crate::sql::ending;
+use crate::sql::error::expected;
+use crate::sql::error::IResult;
 use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
-use crate::sql::permission::Permission;
-use async_recursion::async_recursion;
+use crate::sql::ident;
+use crate::sql::ident::Ident;
+use crate::sql::model::version;
+use crate::sql::permission::{permission, Permission};
+use crate::sql::strand::{strand, Strand};
+use crate::sql::util::expect_delimited;
+use crate::sql::value::Value;
 use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use nom::multi::many0;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
-use std::fmt;
-use std::fmt::Write;
-
-use crate::{
-	ctx::Context,
-	dbs::{Options, Transaction},
-	doc::CursorDoc,
-	err::Error,
-	sql::{Ident, Strand, Value},
-};
+use std::fmt::{self, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
 #[revisioned(revision = 1)]
@@ -25,11 +39,36 @@ pub struct DefineModelStatement {
 	pub permissions: Permission,
 }
 
+impl DefineModelStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Model, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Process the statement
+		let key = crate::key::database::ml::new(opt.ns(), opt.db(), &self.name, &self.version);
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		run.set(key, self).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
 impl fmt::Display for DefineModelStatement {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "DEFINE MODEL ml::{}<{}>", self.name, self.version)?;
-		if let Some(comment) = self.comment.as_ref() {
-			write!(f, "COMMENT {}", comment)?;
+		write!(f, "DEFINE MODEL ml::{}<{}>", self.name.as_str(), self.version)?;
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
 		}
 		if !self.permissions.is_full() {
 			let _indent = if is_pretty() {
@@ -44,16 +83,84 @@ impl fmt::Display for DefineModelStatement {
 	}
 }
 
-impl DefineModelStatement {
-	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
-	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
-	pub(crate) async fn compute(
-		&self,
-		_ctx: &Context<'_>,
-		_opt: &Options,
-		_txn: &Transaction,
-		_doc: Option<&'async_recursion CursorDoc<'_>>,
-	) -> Result<Value, Error> {
-		Err(Error::Unimplemented("Ml model definition not yet implemented".to_string()))
+pub fn model(i: &str) -> IResult<&str, DefineModelStatement> {
+	let (i, _) = tag_no_case("MODEL")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag("ml::")(i)?;
+	let (i, name) = ident::multi(i)?;
+	let (i, version) =
+		expected("a version", expect_delimited(openchevron, version, closechevron))(i)?;
+	let (i, opts) = many0(model_opts)(i)?;
+	let (i, _) = expected("PERMISSIONS or COMMENT", ending::query)(i)?;
+	// Create the base statement
+	let mut res = DefineModelStatement {
+		name,
+		version,
+		..Default::default()
+	};
+	// Assign any defined options
+	for opt in opts {
+		match opt {
+			DefineModelOption::Comment(v) => {
+				res.comment = Some(v);
+			}
+			DefineModelOption::Permissions(v) => {
+				res.permissions = v;
+			}
+		}
+	}
+	// Return the statement
+	Ok((i, res))
+}
+
+enum DefineModelOption {
+	Comment(Strand),
+	Permissions(Permission),
+}
+
+fn model_opts(i: &str) -> IResult<&str, DefineModelOption> {
+	alt((model_comment, model_permissions))(i)
+}
+
+fn model_comment(i: &str) -> IResult<&str, DefineModelOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand)(i)?;
+	Ok((i, DefineModelOption::Comment(v)))
+}
+
+fn model_permissions(i: &str) -> IResult<&str, DefineModelOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("PERMISSIONS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(permission)(i)?;
+	Ok((i, DefineModelOption::Permissions(v)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_model() {
+		let sql = "MODEL ml::insurance::prediction<1.0.0> COMMENT 'test' PERMISSIONS WHERE $auth.admin = true";
+		let res = model(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.name.as_str(), "insurance::prediction");
+		assert_eq!(out.version, "1.0.0");
+		assert_eq!(
+			"DEFINE MODEL ml::insurance::prediction<1.0.0> COMMENT 'test' PERMISSIONS WHERE $auth.admin = true",
+			format!("{}", out)
+		);
+	}
+
+	#[test]
+	fn define_model_without_options() {
+		let sql = "MODEL ml::churn<2.10.3>";
+		let res = model(sql);
+		let out = res.unwrap().1;
+		assert_eq!("DEFINE MODEL ml::churn<2.10.3>", format!("{}", out));
 	}
 }
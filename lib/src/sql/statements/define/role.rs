@@ -0,0 +1,305 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::{Action, Grant, ResourceKind, Role};
+use crate::sql::base::{base, Base};
+use crate::sql::comment::shouldbespace;
+use crate::sql::common::commas;
+use crate::sql::ending;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::expected;
+use crate::sql::error::IResult;
+use crate::sql::error::ParseError as SqlError;
+use crate::sql::fmt::Fmt;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::strand::{strand, Strand};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, not, opt, peek, recognize, value};
+use nom::multi::{many0, separated_list1};
+use nom::sequence::{preceded, tuple};
+use nom::Err::Failure;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct DefineRoleStatement {
+	pub name: Ident,
+	pub base: Base,
+	pub grants: Vec<Grant>,
+	pub comment: Option<Strand>,
+}
+
+impl DefineRoleStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Actor, &self.base)?;
+
+		match self.base {
+			Base::Ns => {
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Clear the cache
+				run.clear_cache();
+				// Process the statement
+				let key = crate::key::namespace::rl::new(opt.ns(), &self.name);
+				run.add_ns(opt.ns(), opt.strict).await?;
+				run.set(key, self).await?;
+				// Ok all good
+				Ok(Value::None)
+			}
+			Base::Db => {
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Clear the cache
+				run.clear_cache();
+				// Process the statement
+				let key = crate::key::database::rl::new(opt.ns(), opt.db(), &self.name);
+				run.add_ns(opt.ns(), opt.strict).await?;
+				run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+				run.set(key, self).await?;
+				// Ok all good
+				Ok(Value::None)
+			}
+			// Other levels are not supported
+			_ => Err(Error::InvalidLevel(self.base.to_string())),
+		}
+	}
+}
+
+impl Display for DefineRoleStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE ROLE {} ON {}", self.name, self.base)?;
+		if !self.grants.is_empty() {
+			write!(f, " GRANT {}", Fmt::comma_separated(&self.grants))?
+		}
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		Ok(())
+	}
+}
+
+pub fn role(i: &str) -> IResult<&str, DefineRoleStatement> {
+	let (i, _) = tag_no_case("ROLE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, (name, base, opts)) = cut(|i| {
+		let (i, name) = ident(i)?;
+		// Verify the role is valid, and not one of the predefined roles
+		if !matches!(Role::from_str(&name), Ok(Role::Custom(_))) {
+			return Err(Failure(SqlError::Role(i, name.to_string())));
+		}
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = expect_tag_no_case("ON")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, base) = base(i)?;
+		let (i, opts) = many0(role_opts)(i)?;
+		let (i, _) = expected("GRANT or COMMENT", ending::query)(i)?;
+		Ok((i, (name, base, opts)))
+	})(i)?;
+	// Create the base statement
+	let mut res = DefineRoleStatement {
+		name,
+		base,
+		..Default::default()
+	};
+	// Assign any defined options
+	for opt in opts {
+		match opt {
+			DefineRoleOption::Grants(v) => {
+				res.grants.extend(v);
+			}
+			DefineRoleOption::Comment(v) => {
+				res.comment = Some(v);
+			}
+		}
+	}
+	// Return the statement
+	Ok((i, res))
+}
+
+enum DefineRoleOption {
+	Grants(Vec<Grant>),
+	Comment(Strand),
+}
+
+fn role_opts(i: &str) -> IResult<&str, DefineRoleOption> {
+	alt((role_grants, role_comment))(i)
+}
+
+fn role_grants(i: &str) -> IResult<&str, DefineRoleOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("GRANT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(separated_list1(commas, grant))(i)?;
+	Ok((i, DefineRoleOption::Grants(v)))
+}
+
+fn role_comment(i: &str) -> IResult<&str, DefineRoleOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand)(i)?;
+	Ok((i, DefineRoleOption::Comment(v)))
+}
+
+fn grant(i: &str) -> IResult<&str, Grant> {
+	let (i, action) = expected(
+		"VIEW or EDIT",
+		alt((value(Action::View, tag_no_case("VIEW")), value(Action::Edit, tag_no_case("EDIT")))),
+	)(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = expect_tag_no_case("ON")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, kind) = expected(
+		"a kind of resource",
+		alt((
+			value(ResourceKind::Any, tag_no_case("ANY")),
+			value(ResourceKind::Namespace, tag_no_case("NAMESPACE")),
+			value(ResourceKind::Database, tag_no_case("DATABASE")),
+			value(ResourceKind::Scope, tag_no_case("SCOPE")),
+			value(ResourceKind::Table, tag_no_case("TABLE")),
+			value(ResourceKind::Document, tag_no_case("DOCUMENT")),
+			value(ResourceKind::Option, tag_no_case("OPTION")),
+			value(ResourceKind::Function, tag_no_case("FUNCTION")),
+			value(ResourceKind::Analyzer, tag_no_case("ANALYZER")),
+			value(ResourceKind::Parameter, tag_no_case("PARAM")),
+			value(ResourceKind::Event, tag_no_case("EVENT")),
+			value(ResourceKind::Field, tag_no_case("FIELD")),
+			value(ResourceKind::Index, tag_no_case("INDEX")),
+			value(ResourceKind::Model, tag_no_case("MODEL")),
+			value(ResourceKind::Actor, tag_no_case("USER")),
+		)),
+	)(i)?;
+	let (i, names) = opt(preceded(shouldbespace, separated_list1(commas, grant_name)))(i)?;
+	Ok((
+		i,
+		Grant {
+			action,
+			kind,
+			names: names.unwrap_or_default(),
+		},
+	))
+}
+
+fn grant_name(i: &str) -> IResult<&str, Ident> {
+	// The following grants and clauses are not names
+	let (i, _) = not(peek(alt((
+		recognize(tuple((
+			alt((tag_no_case("VIEW"), tag_no_case("EDIT"))),
+			shouldbespace,
+			tag_no_case("ON"),
+		))),
+		recognize(tuple((alt((tag_no_case("GRANT"), tag_no_case("COMMENT"))), shouldbespace))),
+	))))(i)?;
+	ident(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_role() {
+		let sql = "ROLE reader ON DATABASE GRANT VIEW ON TABLE, edit on index GRANT VIEW ON PARAM COMMENT 'test'";
+		let res = role(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.name.as_str(), "reader");
+		assert_eq!(out.base, Base::Db);
+		assert_eq!(
+			out.grants,
+			vec![
+				Grant {
+					action: Action::View,
+					kind: ResourceKind::Table,
+					names: vec![],
+				},
+				Grant {
+					action: Action::Edit,
+					kind: ResourceKind::Index,
+					names: vec![],
+				},
+				Grant {
+					action: Action::View,
+					kind: ResourceKind::Parameter,
+					names: vec![],
+				},
+			]
+		);
+		assert_eq!(
+			"DEFINE ROLE reader ON DATABASE GRANT VIEW ON TABLE, EDIT ON INDEX, VIEW ON PARAM COMMENT 'test'",
+			format!("{}", out)
+		);
+	}
+
+	#[test]
+	fn define_role_with_named_resources() {
+		let sql = "ROLE reader ON DATABASE GRANT VIEW ON TABLE person, `order`, EDIT ON TABLE `comment` GRANT VIEW ON TABLE `GRANT` COMMENT 'test'";
+		let res = role(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			out.grants,
+			vec![
+				Grant {
+					action: Action::View,
+					kind: ResourceKind::Table,
+					names: vec![Ident::from("person"), Ident::from("order")],
+				},
+				Grant {
+					action: Action::Edit,
+					kind: ResourceKind::Table,
+					names: vec![Ident::from("comment")],
+				},
+				Grant {
+					action: Action::View,
+					kind: ResourceKind::Table,
+					names: vec![Ident::from("GRANT")],
+				},
+			]
+		);
+		assert_eq!(
+			"DEFINE ROLE reader ON DATABASE GRANT VIEW ON TABLE person, order, EDIT ON TABLE `comment`, VIEW ON TABLE `GRANT` COMMENT 'test'",
+			format!("{}", out)
+		);
+		// The statement is parsed back to the same grants
+		let out2 = role(&format!("{}", out)["DEFINE ".len()..]).unwrap().1;
+		assert_eq!(out2, out);
+	}
+
+	#[test]
+	fn define_role_without_grants() {
+		let sql = "ROLE nobody ON NS";
+		let res = role(sql);
+		let out = res.unwrap().1;
+		assert_eq!("DEFINE ROLE nobody ON NAMESPACE", format!("{}", out));
+	}
+
+	#[test]
+	fn define_role_with_predefined_name() {
+		let sql = "ROLE editor ON DATABASE GRANT VIEW ON TABLE";
+		let res = role(sql);
+		assert!(matches!(res, Err(Failure(SqlError::Role(_, r))) if r == "editor"));
+	}
+
+	#[test]
+	fn define_role_with_invalid_name() {
+		let sql = "ROLE `read-only` ON DATABASE GRANT VIEW ON TABLE";
+		let res = role(sql);
+		assert!(matches!(res, Err(Failure(SqlError::Role(_, r))) if r == "read-only"));
+	}
+}
@@ -1,6 +1,7 @@
 use crate::ctx::Context;
 use crate::dbs::Options;
 use crate::dbs::Transaction;
+use crate::doc::rebuild::ViewBuilding;
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
@@ -29,7 +30,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineTableStatement {
 	pub id: Option<u32>,
 	pub name: Ident,
@@ -39,6 +40,8 @@ pub struct DefineTableStatement {
 	pub permissions: Permissions,
 	pub changefeed: Option<ChangeFeed>,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub rebuild: Option<Ident>,
 }
 
 impl DefineTableStatement {
@@ -51,10 +54,16 @@ impl DefineTableStatement {
 	) -> Result<Value, Error> {
 		// Allowed to run?
 		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
+		// Check that the view can be maintained incrementally
+		if let Some(view) = &self.view {
+			view.check_aggregates()?;
+		}
 		// Claim transaction
 		let mut run = txn.lock().await;
 		// Clear the cache
 		run.clear_cache();
+		// Stop any rebuild of the previous definition
+		ViewBuilding::remove(&mut run, opt.ns(), opt.db(), &self.name).await?;
 		// Process the statement
 		let key = crate::key::database::tb::new(opt.ns(), opt.db(), &self.name);
 		let ns = run.add_ns(opt.ns(), opt.strict).await?;
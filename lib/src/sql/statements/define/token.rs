@@ -3,6 +3,7 @@ use crate::dbs::Options;
 use crate::dbs::Transaction;
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::iam::jwks;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
 use crate::sql::algorithm::{algorithm, Algorithm};
@@ -26,25 +27,41 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineTokenStatement {
 	pub name: Ident,
 	pub base: Base,
 	pub kind: Algorithm,
 	pub code: String,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub issuer: Option<String>,
+	#[revision(start = 2)]
+	pub audience: Option<String>,
 }
 
 impl DefineTokenStatement {
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
-		_ctx: &Context<'_>,
+		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
 		_doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
 		opt.is_allowed(Action::Edit, ResourceKind::Actor, &self.base)?;
+		// Check that the key set can be used
+		if self.kind == Algorithm::Jwks {
+			if jwks::is_inline(&self.code) {
+				jwks::parse(&self.code)?;
+			} else if jwks::is_file(&self.code) {
+				// Only root users can read a file on the server
+				opt.is_allowed(Action::Edit, ResourceKind::Actor, &Base::Root)?;
+			} else {
+				// The key set can only be fetched from an allowed url
+				jwks::check_url(&self.code, &ctx.get_capabilities())?;
+			}
+		}
 
 		match &self.base {
 			Base::Ns => {
@@ -102,6 +119,12 @@ impl Display for DefineTokenStatement {
 			self.kind,
 			quote_str(&self.code)
 		)?;
+		if let Some(ref v) = self.issuer {
+			write!(f, " ISSUER {}", quote_str(v))?
+		}
+		if let Some(ref v) = self.audience {
+			write!(f, " AUDIENCE {}", quote_str(v))?
+		}
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
@@ -119,7 +142,7 @@ pub fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 		let (i, _) = shouldbespace(i)?;
 		let (i, base) = base_or_scope(i)?;
 		let (i, opts) = many0(token_opts)(i)?;
-		let (i, _) = expected("TYPE, VALUE, or COMMENT", ending::query)(i)?;
+		let (i, _) = expected("TYPE, VALUE, ISSUER, AUDIENCE, or COMMENT", ending::query)(i)?;
 		Ok((i, (name, base, opts)))
 	})(i)?;
 	// Create the base statement
@@ -137,6 +160,12 @@ pub fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 			DefineTokenOption::Value(v) => {
 				res.code = v;
 			}
+			DefineTokenOption::Issuer(v) => {
+				res.issuer = Some(v);
+			}
+			DefineTokenOption::Audience(v) => {
+				res.audience = Some(v);
+			}
 			DefineTokenOption::Comment(v) => {
 				res.comment = Some(v);
 			}
@@ -153,11 +182,13 @@ pub fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 enum DefineTokenOption {
 	Type(Algorithm),
 	Value(String),
+	Issuer(String),
+	Audience(String),
 	Comment(Strand),
 }
 
 fn token_opts(i: &str) -> IResult<&str, DefineTokenOption> {
-	alt((token_type, token_value, token_comment))(i)
+	alt((token_type, token_value, token_issuer, token_audience, token_comment))(i)
 }
 
 fn token_type(i: &str) -> IResult<&str, DefineTokenOption> {
@@ -176,6 +207,22 @@ fn token_value(i: &str) -> IResult<&str, DefineTokenOption> {
 	Ok((i, DefineTokenOption::Value(v)))
 }
 
+fn token_issuer(i: &str) -> IResult<&str, DefineTokenOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ISSUER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand_raw)(i)?;
+	Ok((i, DefineTokenOption::Issuer(v)))
+}
+
+fn token_audience(i: &str) -> IResult<&str, DefineTokenOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("AUDIENCE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand_raw)(i)?;
+	Ok((i, DefineTokenOption::Audience(v)))
+}
+
 fn token_comment(i: &str) -> IResult<&str, DefineTokenOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("COMMENT")(i)?;
@@ -183,3 +230,23 @@ fn token_comment(i: &str) -> IResult<&str, DefineTokenOption> {
 	let (i, v) = cut(strand)(i)?;
 	Ok((i, DefineTokenOption::Comment(v)))
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_token_jwks() {
+		let sql = "TOKEN idp ON DATABASE TYPE JWKS VALUE 'https://idp.example.com/.well-known/jwks.json' ISSUER 'https://idp.example.com' AUDIENCE 'surrealdb'";
+		let res = token(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.kind, Algorithm::Jwks);
+		assert_eq!(out.issuer.as_deref(), Some("https://idp.example.com"));
+		assert_eq!(out.audience.as_deref(), Some("surrealdb"));
+		assert_eq!(
+			"DEFINE TOKEN idp ON DATABASE TYPE JWKS VALUE 'https://idp.example.com/.well-known/jwks.json' ISSUER 'https://idp.example.com' AUDIENCE 'surrealdb'",
+			format!("{}", out)
+		);
+	}
+}
@@ -4,6 +4,7 @@ use crate::dbs::Transaction;
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
+use crate::iam::Error as IamError;
 use crate::iam::ResourceKind;
 use crate::iam::Role;
 use crate::sql::base::{base, Base};
@@ -34,6 +35,7 @@ use rand::Rng;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
+use std::str::FromStr;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
 #[revisioned(revision = 1)]
@@ -77,6 +79,8 @@ impl DefineUserStatement {
 	) -> Result<Value, Error> {
 		// Allowed to run?
 		opt.is_allowed(Action::Edit, ResourceKind::Actor, &self.base)?;
+		// Check that the custom roles are defined
+		self.check_roles(opt, txn).await?;
 
 		match self.base {
 			Base::Root => {
@@ -121,6 +125,31 @@ impl DefineUserStatement {
 	}
 }
 
+impl DefineUserStatement {
+	/// Checks that the custom roles of the user are defined on the same level as the user
+	async fn check_roles(&self, opt: &Options, txn: &Transaction) -> Result<(), Error> {
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Check every custom role
+		for role in self.roles.iter() {
+			if Role::from_str(role).map_err(Error::IamError)?.is_builtin() {
+				continue;
+			}
+			match self.base {
+				Base::Ns => {
+					run.get_ns_role(opt.ns(), role).await?;
+				}
+				Base::Db => {
+					run.get_db_role(opt.ns(), opt.db(), role).await?;
+				}
+				// Custom roles can not be defined on other levels
+				_ => return Err(Error::IamError(IamError::InvalidRole(role.to_string()))),
+			}
+		}
+		Ok(())
+	}
+}
+
 impl Display for DefineUserStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(
@@ -130,7 +159,15 @@ impl Display for DefineUserStatement {
 			self.base,
 			quote_str(&self.hash),
 			Fmt::comma_separated(
-				&self.roles.iter().map(|r| r.to_string().to_uppercase()).collect::<Vec<String>>()
+				&self
+					.roles
+					.iter()
+					.map(|r| match Role::from_str(r) {
+						Ok(role) if role.is_builtin() => r.to_string().to_uppercase(),
+						// Custom role names are displayed as they were defined
+						_ => r.to_string(),
+					})
+					.collect::<Vec<String>>()
 			)
 		)?;
 		if let Some(ref v) = self.comment {
@@ -231,7 +268,7 @@ fn user_roles(i: &str) -> IResult<&str, DefineUserOption> {
 	let (i, roles) = separated_list1(commas, |i| {
 		let (i, v) = cut(ident)(i)?;
 		// Verify the role is valid
-		Role::try_from(v.as_str()).map_err(|_| Failure(SqlError::Role(i, v.to_string())))?;
+		Role::from_str(&v).map_err(|_| Failure(SqlError::Role(i, v.to_string())))?;
 
 		Ok((i, v))
 	})(i)?;
@@ -5,8 +5,10 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::idx::builder::IndexBuilding;
 use crate::sql::base::base;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::expect_tag_no_case;
 use crate::sql::error::expected;
 use crate::sql::error::ExplainResultExt;
 use crate::sql::error::IResult;
@@ -19,6 +21,7 @@ use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
+use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -32,6 +35,7 @@ pub enum InfoStatement {
 	Sc(Ident),
 	Tb(Ident),
 	User(Ident, Option<Base>),
+	Ix(Ident, Ident),
 }
 
 impl InfoStatement {
@@ -80,6 +84,12 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("databases".to_owned(), tmp.into());
+				// Process the roles
+				let mut tmp = Object::default();
+				for v in run.all_ns_roles(opt.ns()).await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+				}
+				res.insert("roles".to_owned(), tmp.into());
 				// Process the users
 				let mut tmp = Object::default();
 				for v in run.all_ns_users(opt.ns()).await?.iter() {
@@ -120,12 +130,24 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("functions".to_owned(), tmp.into());
+				// Process the models
+				let mut tmp = Object::default();
+				for v in run.all_db_models(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(format!("{}<{}>", v.name.as_str(), v.version), v.to_string().into());
+				}
+				res.insert("models".to_owned(), tmp.into());
 				// Process the params
 				let mut tmp = Object::default();
 				for v in run.all_db_params(opt.ns(), opt.db()).await?.iter() {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("params".to_owned(), tmp.into());
+				// Process the roles
+				let mut tmp = Object::default();
+				for v in run.all_db_roles(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+				}
+				res.insert("roles".to_owned(), tmp.into());
 				// Process the scopes
 				let mut tmp = Object::default();
 				for v in run.all_sc(opt.ns(), opt.db()).await?.iter() {
@@ -220,6 +242,21 @@ impl InfoStatement {
 				// Ok all good
 				Value::from(res.to_string()).ok()
 			}
+			InfoStatement::Ix(ix, tb) => {
+				// Allowed to run?
+				opt.is_allowed(Action::View, ResourceKind::Any, &Base::Db)?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Check that the index exists
+				let ix = run.get_and_cache_tb_index(opt.ns(), opt.db(), tb, ix).await?;
+				// Create the result set
+				let mut res = Object::default();
+				// Process the build progress
+				let state = IndexBuilding::status(&mut run, opt.ns(), opt.db(), &ix).await?;
+				res.insert("building".to_owned(), state.into());
+				// Ok all good
+				Value::from(res).ok()
+			}
 		}
 	}
 }
@@ -236,6 +273,7 @@ impl fmt::Display for InfoStatement {
 				Some(ref b) => write!(f, "INFO FOR USER {u} ON {b}"),
 				None => write!(f, "INFO FOR USER {u}"),
 			},
+			Self::Ix(ref i, ref t) => write!(f, "INFO FOR INDEX {i} ON {t}"),
 		}
 	}
 }
@@ -246,8 +284,8 @@ pub fn info(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = tag_no_case("FOR")(i)?;
 	let (i, _) = cut(shouldbespace)(i)?;
 	expected(
-		"ROOT, NAMESPACE, DATABASE, SCOPE, TABLE or USER",
-		cut(alt((root, ns, db, sc, tb, user))),
+		"ROOT, NAMESPACE, DATABASE, SCOPE, TABLE, USER or INDEX",
+		cut(alt((root, ns, db, sc, tb, user, ix))),
 	)(i)
 }
 
@@ -300,6 +338,20 @@ fn user(i: &str) -> IResult<&str, InfoStatement> {
 	})(i)
 }
 
+fn ix(i: &str) -> IResult<&str, InfoStatement> {
+	let (i, _) = tag_no_case("INDEX")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, index) = ident(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = expect_tag_no_case("ON")(i)?;
+		let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, table) = ident(i)?;
+		Ok((i, InfoStatement::Ix(index, table)))
+	})(i)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -376,4 +428,13 @@ mod tests {
 		assert_eq!(out, InfoStatement::User(Ident::from("test"), None));
 		assert_eq!("INFO FOR USER test", format!("{}", out));
 	}
+
+	#[test]
+	fn info_query_ix() {
+		let sql = "INFO FOR INDEX test ON TABLE person";
+		let res = info(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::Ix(Ident::from("test"), Ident::from("person")));
+		assert_eq!("INFO FOR INDEX test ON person", format!("{}", out));
+	}
 }
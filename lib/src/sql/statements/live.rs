@@ -105,6 +105,11 @@ impl LiveStatement {
 		Ok(id.into())
 	}
 
+	/// Check if this LIVE query sends JSON Patch operations
+	pub(crate) fn is_diff(&self) -> bool {
+		self.expr.is_empty()
+	}
+
 	pub(crate) fn archive(mut self, node_id: Uuid) -> LiveStatement {
 		self.archived = Some(node_id);
 		self
@@ -113,7 +118,10 @@ impl LiveStatement {
 
 impl fmt::Display for LiveStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "LIVE SELECT {} FROM {}", self.expr, self.what)?;
+		match self.is_diff() {
+			true => write!(f, "LIVE SELECT DIFF FROM {}", self.what)?,
+			false => write!(f, "LIVE SELECT {} FROM {}", self.expr, self.what)?,
+		}
 		if let Some(ref v) = self.cond {
 			write!(f, " {v}")?
 		}
@@ -151,3 +159,27 @@ pub fn live(i: &str) -> IResult<&str, LiveStatement> {
 		))
 	})(i)
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn live_select_fields() {
+		let sql = "LIVE SELECT name, age FROM person WHERE age > 18";
+		let res = live(sql);
+		let out = res.unwrap().1;
+		assert!(!out.is_diff());
+		assert_eq!("LIVE SELECT name, age FROM person WHERE age > 18", format!("{}", out))
+	}
+
+	#[test]
+	fn live_select_diff() {
+		let sql = "LIVE SELECT DIFF FROM person WHERE age > 18";
+		let res = live(sql);
+		let out = res.unwrap().1;
+		assert!(out.is_diff());
+		assert_eq!("LIVE SELECT DIFF FROM person WHERE age > 18", format!("{}", out))
+	}
+}
@@ -15,6 +15,7 @@ pub(crate) mod kill;
 pub(crate) mod live;
 pub(crate) mod option;
 pub(crate) mod output;
+pub(crate) mod rebuild;
 pub(crate) mod relate;
 pub(crate) mod remove;
 pub(crate) mod select;
@@ -53,21 +54,29 @@ pub use self::define::DefineEventStatement;
 pub use self::define::DefineFieldStatement;
 pub use self::define::DefineFunctionStatement;
 pub use self::define::DefineIndexStatement;
+pub use self::define::DefineModelStatement;
 pub use self::define::DefineNamespaceStatement;
 pub use self::define::DefineParamStatement;
+pub use self::define::DefineRoleStatement;
 pub use self::define::DefineScopeStatement;
 pub use self::define::DefineStatement;
 pub use self::define::DefineTableStatement;
 pub use self::define::DefineTokenStatement;
 pub use self::define::DefineUserStatement;
 
+pub use self::rebuild::RebuildIndexStatement;
+pub use self::rebuild::RebuildStatement;
+pub use self::rebuild::RebuildTableStatement;
+
 pub use self::remove::RemoveDatabaseStatement;
 pub use self::remove::RemoveEventStatement;
 pub use self::remove::RemoveFieldStatement;
 pub use self::remove::RemoveFunctionStatement;
 pub use self::remove::RemoveIndexStatement;
+pub use self::remove::RemoveModelStatement;
 pub use self::remove::RemoveNamespaceStatement;
 pub use self::remove::RemoveParamStatement;
+pub use self::remove::RemoveRoleStatement;
 pub use self::remove::RemoveScopeStatement;
 pub use self::remove::RemoveStatement;
 pub use self::remove::RemoveTableStatement;
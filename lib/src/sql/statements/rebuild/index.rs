@@ -0,0 +1,98 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::idx::builder::IndexBuilding;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use nom::combinator::opt;
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RebuildIndexStatement {
+	pub name: Ident,
+	pub what: Ident,
+}
+
+impl RebuildIndexStatement {
+	/// Process this type returning a computed simple Value
+	///
+	/// The entries of the index are rebuilt in the background, one batch of
+	/// records per transaction, while the current entries keep being used.
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Index, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Get the index definition
+		let ix = run.get_and_cache_tb_index(opt.ns(), opt.db(), &self.what, &self.name).await?;
+		// Rebuild the index in the background
+		IndexBuilding::rebuild(&mut run, opt.ns(), opt.db(), &ix).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for RebuildIndexStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REBUILD INDEX {} ON {}", self.name, self.what)
+	}
+}
+
+pub fn index(i: &str) -> IResult<&str, RebuildIndexStatement> {
+	let (i, _) = tag_no_case("INDEX")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = cut(ident)(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = expect_tag_no_case("ON")(i)?;
+	let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = cut(ident)(i)?;
+	Ok((
+		i,
+		RebuildIndexStatement {
+			name,
+			what,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn rebuild_index() {
+		let sql = "INDEX my_index ON TABLE my_table";
+		let res = index(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			out,
+			RebuildIndexStatement {
+				name: Ident::from("my_index"),
+				what: Ident::from("my_table"),
+			}
+		);
+		assert_eq!("REBUILD INDEX my_index ON my_table", format!("{}", out));
+	}
+}
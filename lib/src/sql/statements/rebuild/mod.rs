@@ -0,0 +1,79 @@
+mod index;
+mod table;
+
+pub use index::{index, RebuildIndexStatement};
+pub use table::{table, RebuildTableStatement};
+
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::map;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub enum RebuildStatement {
+	Index(RebuildIndexStatement),
+	Table(RebuildTableStatement),
+}
+
+impl RebuildStatement {
+	/// Check if we require a writeable transaction
+	pub(crate) fn writeable(&self) -> bool {
+		true
+	}
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		match self {
+			Self::Index(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Table(ref v) => v.compute(ctx, opt, txn).await,
+		}
+	}
+}
+
+impl Display for RebuildStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Index(v) => Display::fmt(v, f),
+			Self::Table(v) => Display::fmt(v, f),
+		}
+	}
+}
+
+pub fn rebuild(i: &str) -> IResult<&str, RebuildStatement> {
+	let (i, _) = tag_no_case("REBUILD")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	alt((map(index, RebuildStatement::Index), map(table, RebuildStatement::Table)))(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::Ident;
+
+	#[test]
+	fn check_rebuild_serialize() {
+		let stm = RebuildStatement::Table(RebuildTableStatement {
+			name: Ident::from("test"),
+		});
+		let enc: Vec<u8> = stm.try_into().unwrap();
+		assert_eq!(9, enc.len());
+	}
+}
@@ -0,0 +1,96 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::rebuild::ViewBuilding;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RebuildTableStatement {
+	pub name: Ident,
+}
+
+impl RebuildTableStatement {
+	/// Process this type returning a computed simple Value
+	///
+	/// The view is rebuilt in the background into a separate table, one batch of
+	/// records per transaction, while the current records of the view keep being
+	/// used. The indexes of the view are maintained as its records are replaced.
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Get the defined table
+		let tb = run.get_tb(opt.ns(), opt.db(), &self.name).await?;
+		// Only views can be rebuilt
+		let Some(view) = &tb.view else {
+			return Err(Error::TableIsNotView {
+				table: self.name.to_raw(),
+			});
+		};
+		// Check that the view can be maintained incrementally
+		view.check_aggregates()?;
+		// Rebuild the view in the background
+		ViewBuilding::rebuild(&mut run, opt.ns(), opt.db(), &tb).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for RebuildTableStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REBUILD TABLE {}", self.name)
+	}
+}
+
+pub fn table(i: &str) -> IResult<&str, RebuildTableStatement> {
+	let (i, _) = tag_no_case("TABLE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = cut(ident)(i)?;
+	Ok((
+		i,
+		RebuildTableStatement {
+			name,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn rebuild_table() {
+		let sql = "TABLE my_view";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			out,
+			RebuildTableStatement {
+				name: Ident::from("my_view"),
+			}
+		);
+		assert_eq!("REBUILD TABLE my_view", format!("{}", out));
+	}
+}
@@ -3,6 +3,7 @@ use crate::dbs::Options;
 use crate::dbs::Transaction;
 use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
+use crate::idx::builder::IndexBuilding;
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::expect_tag_no_case;
@@ -39,12 +40,11 @@ impl RemoveIndexStatement {
 		let mut run = txn.lock().await;
 		// Clear the cache
 		run.clear_cache();
+		// Remove the index data, and stop any build of the index
+		IndexBuilding::remove(&mut run, opt.ns(), opt.db(), &self.what, &self.name).await?;
 		// Delete the definition
 		let key = crate::key::table::ix::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.del(key).await?;
-		// Remove the index data
-		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
-		run.delp(key, u32::MAX).await?;
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
@@ -4,8 +4,10 @@ mod event;
 mod field;
 mod function;
 mod index;
+mod model;
 mod namespace;
 mod param;
+mod role;
 mod scope;
 mod table;
 mod token;
@@ -17,9 +19,11 @@ pub use event::{event, RemoveEventStatement};
 pub use field::{field, RemoveFieldStatement};
 pub use function::{function, RemoveFunctionStatement};
 pub use index::{index, RemoveIndexStatement};
+pub use model::{model, RemoveModelStatement};
 pub use namespace::{namespace, RemoveNamespaceStatement};
 use nom::bytes::complete::tag_no_case;
 pub use param::{param, RemoveParamStatement};
+pub use role::{role, RemoveRoleStatement};
 pub use scope::{scope, RemoveScopeStatement};
 pub use table::{table, RemoveTableStatement};
 pub use token::{token, RemoveTokenStatement};
@@ -55,6 +59,8 @@ pub enum RemoveStatement {
 	Field(RemoveFieldStatement),
 	Index(RemoveIndexStatement),
 	User(RemoveUserStatement),
+	Model(RemoveModelStatement),
+	Role(RemoveRoleStatement),
 }
 
 impl RemoveStatement {
@@ -83,6 +89,8 @@ impl RemoveStatement {
 			Self::Index(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn).await,
 			Self::User(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Model(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Role(ref v) => v.compute(ctx, opt, txn).await,
 		}
 	}
 }
@@ -102,6 +110,8 @@ impl Display for RemoveStatement {
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
 			Self::User(v) => Display::fmt(v, f),
+			Self::Model(v) => Display::fmt(v, f),
+			Self::Role(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -122,6 +132,8 @@ pub fn remove(i: &str) -> IResult<&str, RemoveStatement> {
 		map(index, RemoveStatement::Index),
 		map(analyzer, RemoveStatement::Analyzer),
 		map(user, RemoveStatement::User),
+		map(role, RemoveStatement::Role),
+		map(model, RemoveStatement::Model),
 	))(i)
 }
 
@@ -0,0 +1,89 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::common::{closechevron, openchevron};
+use crate::sql::error::expected;
+use crate::sql::error::IResult;
+use crate::sql::ident;
+use crate::sql::ident::Ident;
+use crate::sql::model::version;
+use crate::sql::util::expect_delimited;
+use crate::sql::value::Value;
+use derive::Store;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::tag_no_case;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RemoveModelStatement {
+	pub name: Ident,
+	pub version: String,
+}
+
+impl RemoveModelStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Model, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Delete the definition
+		let key = crate::key::database::ml::new(opt.ns(), opt.db(), &self.name, &self.version);
+		run.del(key).await?;
+		// Delete the model file
+		let key = crate::key::database::mf::new(opt.ns(), opt.db(), &self.name, &self.version);
+		run.del(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for RemoveModelStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "REMOVE MODEL ml::{}<{}>", self.name.as_str(), self.version)
+	}
+}
+
+pub fn model(i: &str) -> IResult<&str, RemoveModelStatement> {
+	let (i, _) = tag_no_case("MODEL")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag("ml::")(i)?;
+	let (i, name) = ident::multi(i)?;
+	let (i, version) =
+		expected("a version", expect_delimited(openchevron, version, closechevron))(i)?;
+	Ok((
+		i,
+		RemoveModelStatement {
+			name,
+			version,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn remove_model() {
+		let sql = "MODEL ml::insurance::prediction<1.0.0>";
+		let res = model(sql);
+		let out = res.unwrap().1;
+		assert_eq!("REMOVE MODEL ml::insurance::prediction<1.0.0>", format!("{}", out));
+	}
+}
@@ -42,6 +42,14 @@ impl RemoveNamespaceStatement {
 		// Delete the resource data
 		let key = crate::key::namespace::all::new(&self.name);
 		run.delp(key, u32::MAX).await?;
+		// Stop the indexes being built in the background
+		let beg = crate::key::root::ib::prefix_ns(&self.name);
+		let end = crate::key::root::ib::suffix_ns(&self.name);
+		run.delr(beg..end, u32::MAX).await?;
+		// Stop the views being rebuilt in the background
+		let beg = crate::key::root::vb::prefix_ns(&self.name);
+		let end = crate::key::root::vb::suffix_ns(&self.name);
+		run.delr(beg..end, u32::MAX).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
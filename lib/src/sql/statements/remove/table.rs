@@ -1,6 +1,7 @@
 use crate::ctx::Context;
 use crate::dbs::Options;
 use crate::dbs::Transaction;
+use crate::doc::rebuild::ViewBuilding;
 use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
 use crate::sql::base::Base;
@@ -43,6 +44,12 @@ impl RemoveTableStatement {
 		// Remove the resource data
 		let key = crate::key::table::all::new(opt.ns(), opt.db(), &self.name);
 		run.delp(key, u32::MAX).await?;
+		// Stop the indexes being built in the background
+		let beg = crate::key::root::ib::prefix_tb(opt.ns(), opt.db(), &self.name);
+		let end = crate::key::root::ib::suffix_tb(opt.ns(), opt.db(), &self.name);
+		run.delr(beg..end, u32::MAX).await?;
+		// Stop the view being rebuilt in the background
+		ViewBuilding::remove(&mut run, opt.ns(), opt.db(), &self.name).await?;
 		// Check if this is a foreign table
 		if let Some(view) = &tb.view {
 			// Process each foreign table
@@ -19,7 +19,7 @@ use crate::sql::group::{group, Groups};
 use crate::sql::idiom::Idioms;
 use crate::sql::limit::{limit, Limit};
 use crate::sql::omit::omit;
-use crate::sql::order::{order, Orders};
+use crate::sql::order::{order, Order, Orders};
 use crate::sql::special::check_group_by_fields;
 use crate::sql::special::check_order_by_fields;
 use crate::sql::special::check_split_on_fields;
@@ -79,6 +79,50 @@ impl SelectStatement {
 		self.cond.as_ref().map_or(false, |v| v.writeable())
 	}
 
+	/// Returns the ORDER BY clause, if the records could be returned in this order by an index.
+	///
+	/// The order is only resolved by an index when the iteration can stop at the LIMIT,
+	/// as sorting the records in memory is otherwise cheaper than fetching them one by one.
+	fn index_order(&self) -> Option<&Order> {
+		// The results must not be reordered by other clauses
+		if self.limit.is_none()
+			|| self.group.is_some()
+			|| self.split.is_some()
+			|| self.parallel
+			|| self.what.len() != 1
+		{
+			return None;
+		}
+		// Only a single field can be ordered with an index
+		let order = match self.order.as_ref().map(|o| o.0.as_slice()) {
+			Some([o]) if !o.random && !o.collate && !o.numeric => o,
+			_ => return None,
+		};
+		// The field must be output unchanged from the record
+		if self.expr.single().is_some()
+			|| self.omit.as_ref().is_some_and(|i| i.contains(&order.order))
+		{
+			return None;
+		}
+		let mut output = self.expr.is_all();
+		for field in self.expr.other() {
+			if let Field::Single {
+				expr,
+				alias,
+			} = field
+			{
+				let name = alias.clone().unwrap_or_else(|| expr.to_idiom());
+				if name.eq(&order.order) {
+					if !matches!(expr, Value::Idiom(i) if i.eq(&order.order)) {
+						return None;
+					}
+					output = true;
+				}
+			}
+		}
+		output.then_some(order)
+	}
+
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -89,16 +133,24 @@ impl SelectStatement {
 	) -> Result<Value, Error> {
 		// Valid options?
 		opt.valid_for_db()?;
+		// Indexes only reflect the current data
+		if let (Some(_), Some(with @ With::Index(_))) = (&self.version, &self.with) {
+			return Err(Error::VersionUnsupported {
+				value: with.to_string(),
+			});
+		}
 		// Create a new iterator
 		let mut i = Iterator::new();
 		// Ensure futures are stored
 		let opt = &opt.new_with_futures(false).with_projections(true);
 		// Get a query planner
-		let mut planner = QueryPlanner::new(opt, &self.with, &self.cond);
+		let mut planner = QueryPlanner::new(opt, &self.with, &self.cond, self.index_order());
 		// Loop over the select targets
 		for w in self.what.0.iter() {
 			let v = w.compute(ctx, opt, txn, doc).await?;
 			match v {
+				// Indexes only reflect the current data
+				Value::Table(t) if self.version.is_some() => i.ingest(Iterable::Table(t)),
 				Value::Table(t) => {
 					planner.add_iterables(ctx, txn, t, &mut i).await?;
 				}
@@ -113,6 +165,9 @@ impl SelectStatement {
 				Value::Array(v) => {
 					for v in v {
 						match v {
+							Value::Table(t) if self.version.is_some() => {
+								i.ingest(Iterable::Table(t))
+							}
 							Value::Table(t) => {
 								planner.add_iterables(ctx, txn, t, &mut i).await?;
 							}
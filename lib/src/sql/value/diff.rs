@@ -4,7 +4,18 @@ use crate::sql::value::Value;
 use std::cmp::min;
 
 impl Value {
+	/// Compute the operations which turn this value into another,
+	/// using text patches for any strings which have changed
 	pub(crate) fn diff(&self, val: &Value, path: Idiom) -> Vec<Operation> {
+		self.compare(val, path, true)
+	}
+	/// Compute the RFC 6902 JSON Patch operations which turn this
+	/// value into another, replacing any strings which have changed
+	pub(crate) fn json_patch(&self, val: &Value, path: Idiom) -> Vec<Operation> {
+		self.compare(val, path, false)
+	}
+
+	fn compare(&self, val: &Value, path: Idiom, text: bool) -> Vec<Operation> {
 		let mut ops: Vec<Operation> = vec![];
 		match (self, val) {
 			(Value::Object(a), Value::Object(b)) if a != b => {
@@ -25,7 +36,7 @@ impl Value {
 						}),
 						Some(old) => {
 							let path = path.clone().push(key.clone().into());
-							ops.append(&mut old.diff(val, path))
+							ops.append(&mut old.compare(val, path, text))
 						}
 					}
 				}
@@ -34,7 +45,7 @@ impl Value {
 				let mut n = 0;
 				while n < min(a.len(), b.len()) {
 					let path = path.clone().push(n.into());
-					ops.append(&mut a[n].diff(&b[n], path));
+					ops.append(&mut a[n].compare(&b[n], path, text));
 					n += 1;
 				}
 				while n < b.len() {
@@ -46,16 +57,15 @@ impl Value {
 					}
 					n += 1;
 				}
-				while n < a.len() {
-					if n >= b.len() {
-						ops.push(Operation::Remove {
-							path: path.clone().push(n.into()),
-						})
-					}
-					n += 1;
+				// Remove from the end, so that the indexes
+				// remain valid when applied one after another
+				for n in (b.len()..a.len()).rev() {
+					ops.push(Operation::Remove {
+						path: path.clone().push(n.into()),
+					})
 				}
 			}
-			(Value::Strand(a), Value::Strand(b)) if a != b => ops.push(Operation::Change {
+			(Value::Strand(a), Value::Strand(b)) if text && a != b => ops.push(Operation::Change {
 				path,
 				value: {
 					let dmp = dmp::new();
@@ -120,6 +130,23 @@ mod tests {
 		assert_eq!(res.to_operations().unwrap(), old.diff(&now, Idiom::default()));
 	}
 
+	#[test]
+	fn diff_remove_array() {
+		let old = Value::parse("{ test: [1,2,3,4] }");
+		let now = Value::parse("{ test: [1,2] }");
+		let res =
+			Value::parse("[{ op: 'remove', path: '/test/3' }, { op: 'remove', path: '/test/2' }]");
+		assert_eq!(res.to_operations().unwrap(), old.diff(&now, Idiom::default()));
+	}
+
+	#[test]
+	fn json_patch_replace_text() {
+		let old = Value::parse("{ test: { other: 'test' } }");
+		let now = Value::parse("{ test: { other: 'text' } }");
+		let res = Value::parse("[{ op: 'replace', path: '/test/other', value: 'text' }]");
+		assert_eq!(res.to_operations().unwrap(), old.json_patch(&now, Idiom::default()));
+	}
+
 	#[test]
 	fn diff_change_text() {
 		let old = Value::parse("{ test: { other: 'test' } }");
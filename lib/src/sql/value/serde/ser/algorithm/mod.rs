@@ -41,6 +41,7 @@ impl ser::Serializer for Serializer {
 			"Rs256" => Ok(Algorithm::Rs256),
 			"Rs384" => Ok(Algorithm::Rs384),
 			"Rs512" => Ok(Algorithm::Rs512),
+			"Jwks" => Ok(Algorithm::Jwks),
 			variant => Err(Error::custom(format!("unknown variant `{name}::{variant}`"))),
 		}
 	}
@@ -142,4 +143,11 @@ mod tests {
 		let serialized = algo.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(algo, serialized);
 	}
+
+	#[test]
+	fn jwks() {
+		let algo = Algorithm::Jwks;
+		let serialized = algo.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(algo, serialized);
+	}
 }
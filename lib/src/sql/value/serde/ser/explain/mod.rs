@@ -1 +1,93 @@
 pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Explain;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Explain;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Explain, Error>;
+	type SerializeTuple = Impossible<Explain, Error>;
+	type SerializeTupleStruct = Impossible<Explain, Error>;
+	type SerializeTupleVariant = Impossible<Explain, Error>;
+	type SerializeMap = Impossible<Explain, Error>;
+	type SerializeStruct = SerializeExplain;
+	type SerializeStructVariant = Impossible<Explain, Error>;
+
+	const EXPECTED: &'static str = "a struct `Explain`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeExplain::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeExplain {
+	full: bool,
+	analyze: bool,
+}
+
+impl serde::ser::SerializeStruct for SerializeExplain {
+	type Ok = Explain;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"full" => {
+				self.full = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"analyze" => {
+				self.analyze = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Explain::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(Explain {
+			full: self.full,
+			analyze: self.analyze,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let explain = Explain::default();
+		let value: Explain = explain.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, explain);
+	}
+
+	#[test]
+	fn analyze() {
+		let explain = Explain {
+			full: true,
+			analyze: true,
+		};
+		let value: Explain = explain.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, explain);
+	}
+}
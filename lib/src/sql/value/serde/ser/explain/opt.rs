@@ -30,19 +30,7 @@ impl ser::Serializer for Serializer {
 	where
 		T: ?Sized + Serialize,
 	{
-		value.serialize(self.wrap())
-	}
-
-	#[inline]
-	fn serialize_newtype_struct<T>(
-		self,
-		_name: &'static str,
-		value: &T,
-	) -> Result<Self::Ok, Self::Error>
-	where
-		T: ?Sized + Serialize,
-	{
-		Ok(Some(Explain(value.serialize(ser::primitive::bool::Serializer.wrap())?)))
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
 	}
 }
 
@@ -67,7 +55,10 @@ mod tests {
 
 	#[test]
 	fn some_full() {
-		let option = Some(Explain(true));
+		let option = Some(Explain {
+			full: true,
+			analyze: false,
+		});
 		let serialized = option.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(option, serialized);
 	}
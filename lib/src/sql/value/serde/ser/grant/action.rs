@@ -0,0 +1,57 @@
+use crate::err::Error;
+use crate::iam::Action;
+use crate::sql::value::serde::ser;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Action;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Action, Error>;
+	type SerializeTuple = Impossible<Action, Error>;
+	type SerializeTupleStruct = Impossible<Action, Error>;
+	type SerializeTupleVariant = Impossible<Action, Error>;
+	type SerializeMap = Impossible<Action, Error>;
+	type SerializeStruct = Impossible<Action, Error>;
+	type SerializeStructVariant = Impossible<Action, Error>;
+
+	const EXPECTED: &'static str = "an enum `Action`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"View" => Ok(Action::View),
+			"Edit" => Ok(Action::Edit),
+			variant => Err(Error::custom(format!("unknown variant `{name}::{variant}`"))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+	use serde::Serialize;
+
+	#[test]
+	fn view() {
+		let action = Action::View;
+		let serialized = action.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(action, serialized);
+	}
+
+	#[test]
+	fn edit() {
+		let action = Action::Edit;
+		let serialized = action.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(action, serialized);
+	}
+}
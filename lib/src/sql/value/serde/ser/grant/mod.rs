@@ -0,0 +1,109 @@
+mod action;
+mod kind;
+pub(super) mod vec;
+
+use crate::err::Error;
+use crate::iam::{Action, Grant, ResourceKind};
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Grant;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Grant, Error>;
+	type SerializeTuple = Impossible<Grant, Error>;
+	type SerializeTupleStruct = Impossible<Grant, Error>;
+	type SerializeTupleVariant = Impossible<Grant, Error>;
+	type SerializeMap = Impossible<Grant, Error>;
+	type SerializeStruct = SerializeGrant;
+	type SerializeStructVariant = Impossible<Grant, Error>;
+
+	const EXPECTED: &'static str = "a struct `Grant`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeGrant::default())
+	}
+}
+
+#[derive(Default)]
+pub(super) struct SerializeGrant {
+	action: Option<Action>,
+	kind: Option<ResourceKind>,
+	names: Vec<Ident>,
+}
+
+impl serde::ser::SerializeStruct for SerializeGrant {
+	type Ok = Grant;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"action" => {
+				self.action = Some(value.serialize(action::Serializer.wrap())?);
+			}
+			"kind" => {
+				self.kind = Some(value.serialize(kind::Serializer.wrap())?);
+			}
+			"names" => {
+				self.names = value.serialize(ser::ident::vec::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Grant::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		match (self.action, self.kind) {
+			(Some(action), Some(kind)) => Ok(Grant {
+				action,
+				kind,
+				names: self.names,
+			}),
+			_ => Err(Error::custom("`Grant` missing required field(s)")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn grant() {
+		let grant = Grant {
+			action: Action::Edit,
+			kind: ResourceKind::Index,
+			names: vec![],
+		};
+		let serialized = grant.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(grant, serialized);
+	}
+
+	#[test]
+	fn grant_with_names() {
+		let grant = Grant {
+			action: Action::View,
+			kind: ResourceKind::Table,
+			names: vec![Ident::from("person")],
+		};
+		let serialized = grant.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(grant, serialized);
+	}
+}
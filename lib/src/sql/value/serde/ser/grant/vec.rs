@@ -0,0 +1,70 @@
+use crate::err::Error;
+use crate::iam::Grant;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Vec<Grant>;
+	type Error = Error;
+
+	type SerializeSeq = SerializeGrantVec;
+	type SerializeTuple = Impossible<Vec<Grant>, Error>;
+	type SerializeTupleStruct = Impossible<Vec<Grant>, Error>;
+	type SerializeTupleVariant = Impossible<Vec<Grant>, Error>;
+	type SerializeMap = Impossible<Vec<Grant>, Error>;
+	type SerializeStruct = Impossible<Vec<Grant>, Error>;
+	type SerializeStructVariant = Impossible<Vec<Grant>, Error>;
+
+	const EXPECTED: &'static str = "a `Vec<Grant>`";
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+		Ok(SerializeGrantVec(Vec::with_capacity(len.unwrap_or_default())))
+	}
+}
+
+pub struct SerializeGrantVec(Vec<Grant>);
+
+impl serde::ser::SerializeSeq for SerializeGrantVec {
+	type Ok = Vec<Grant>;
+	type Error = Error;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		self.0.push(value.serialize(super::Serializer.wrap())?);
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::iam::{Action, ResourceKind};
+
+	#[test]
+	fn empty() {
+		let vec: Vec<Grant> = Vec::new();
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+
+	#[test]
+	fn vec() {
+		let vec = vec![Grant {
+			action: Action::View,
+			kind: ResourceKind::Table,
+			names: vec![],
+		}];
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+}
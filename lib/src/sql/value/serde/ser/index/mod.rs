@@ -34,6 +34,7 @@ impl ser::Serializer for Serializer {
 		match variant {
 			"Idx" => Ok(Index::Idx),
 			"Uniq" => Ok(Index::Uniq),
+			"Spatial" => Ok(Index::Spatial),
 			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
 		}
 	}
@@ -80,6 +81,13 @@ mod tests {
 		assert_eq!(idx, serialized);
 	}
 
+	#[test]
+	fn spatial() {
+		let idx = Index::Spatial;
+		let serialized = idx.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(idx, serialized);
+	}
+
 	#[test]
 	fn search() {
 		let idx = Index::Search(SearchParams {
@@ -21,6 +21,7 @@ mod fields;
 mod filter;
 mod function;
 mod geometry;
+mod grant;
 mod graph;
 mod group;
 mod id;
@@ -43,6 +43,9 @@ pub struct SerializeDefineIndexStatement {
 	cols: Idioms,
 	index: Index,
 	comment: Option<Strand>,
+	concurrently: bool,
+	storage: Option<Ident>,
+	rebuild: Option<Ident>,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
@@ -69,6 +72,15 @@ impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"concurrently" => {
+				self.concurrently = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"storage" => {
+				self.storage = value.serialize(ser::string::opt::Serializer.wrap())?.map(Ident);
+			}
+			"rebuild" => {
+				self.rebuild = value.serialize(ser::string::opt::Serializer.wrap())?.map(Ident);
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineIndexStatement::{key}`"
@@ -85,6 +97,9 @@ impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
 			cols: self.cols,
 			index: self.index,
 			comment: self.comment,
+			concurrently: self.concurrently,
+			storage: self.storage,
+			rebuild: self.rebuild,
 		})
 	}
 }
@@ -99,4 +114,25 @@ mod tests {
 		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn concurrently() {
+		let stmt = DefineIndexStatement {
+			concurrently: true,
+			..Default::default()
+		};
+		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn rebuild() {
+		let stmt = DefineIndexStatement {
+			storage: Some("test#1".into()),
+			rebuild: Some("test#2".into()),
+			..Default::default()
+		};
+		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
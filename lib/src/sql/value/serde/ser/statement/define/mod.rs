@@ -4,8 +4,10 @@ mod event;
 mod field;
 mod function;
 mod index;
+mod model;
 mod namespace;
 mod param;
+mod role;
 mod scope;
 mod table;
 mod token;
@@ -66,6 +68,8 @@ impl ser::Serializer for Serializer {
 			"Field" => Ok(DefineStatement::Field(value.serialize(field::Serializer.wrap())?)),
 			"Index" => Ok(DefineStatement::Index(value.serialize(index::Serializer.wrap())?)),
 			"User" => Ok(DefineStatement::User(value.serialize(user::Serializer.wrap())?)),
+			"Role" => Ok(DefineStatement::Role(value.serialize(role::Serializer.wrap())?)),
+			"MlModel" => Ok(DefineStatement::MlModel(value.serialize(model::Serializer.wrap())?)),
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -161,4 +165,18 @@ mod tests {
 		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(stmt, serialized);
 	}
+
+	#[test]
+	fn model() {
+		let stmt = DefineStatement::MlModel(Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
+	#[test]
+	fn role() {
+		let stmt = DefineStatement::Role(Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
 }
@@ -0,0 +1,106 @@
+use crate::err::Error;
+use crate::sql::statements::DefineModelStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use crate::sql::Permission;
+use crate::sql::Strand;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = DefineModelStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<DefineModelStatement, Error>;
+	type SerializeTuple = Impossible<DefineModelStatement, Error>;
+	type SerializeTupleStruct = Impossible<DefineModelStatement, Error>;
+	type SerializeTupleVariant = Impossible<DefineModelStatement, Error>;
+	type SerializeMap = Impossible<DefineModelStatement, Error>;
+	type SerializeStruct = SerializeDefineModelStatement;
+	type SerializeStructVariant = Impossible<DefineModelStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `DefineModelStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeDefineModelStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeDefineModelStatement {
+	name: Ident,
+	version: String,
+	comment: Option<Strand>,
+	permissions: Permission,
+}
+
+impl serde::ser::SerializeStruct for SerializeDefineModelStatement {
+	type Ok = DefineModelStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"version" => {
+				self.version = value.serialize(ser::string::Serializer.wrap())?;
+			}
+			"comment" => {
+				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
+			}
+			"permissions" => {
+				self.permissions = value.serialize(ser::permission::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `DefineModelStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(DefineModelStatement {
+			name: self.name,
+			version: self.version,
+			comment: self.comment,
+			permissions: self.permissions,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = DefineModelStatement::default();
+		let value: DefineModelStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_comment() {
+		let stmt = DefineModelStatement {
+			comment: Some(Default::default()),
+			..Default::default()
+		};
+		let value: DefineModelStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
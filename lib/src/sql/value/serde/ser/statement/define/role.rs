@@ -0,0 +1,111 @@
+use crate::err::Error;
+use crate::iam::Grant;
+use crate::sql::statements::DefineRoleStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Base;
+use crate::sql::Ident;
+use crate::sql::Strand;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = DefineRoleStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<DefineRoleStatement, Error>;
+	type SerializeTuple = Impossible<DefineRoleStatement, Error>;
+	type SerializeTupleStruct = Impossible<DefineRoleStatement, Error>;
+	type SerializeTupleVariant = Impossible<DefineRoleStatement, Error>;
+	type SerializeMap = Impossible<DefineRoleStatement, Error>;
+	type SerializeStruct = SerializeDefineRoleStatement;
+	type SerializeStructVariant = Impossible<DefineRoleStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `DefineRoleStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeDefineRoleStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeDefineRoleStatement {
+	name: Ident,
+	base: Base,
+	grants: Vec<Grant>,
+	comment: Option<Strand>,
+}
+
+impl serde::ser::SerializeStruct for SerializeDefineRoleStatement {
+	type Ok = DefineRoleStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"base" => {
+				self.base = value.serialize(ser::base::Serializer.wrap())?;
+			}
+			"grants" => {
+				self.grants = value.serialize(ser::grant::vec::Serializer.wrap())?;
+			}
+			"comment" => {
+				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `DefineRoleStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(DefineRoleStatement {
+			name: self.name,
+			base: self.base,
+			grants: self.grants,
+			comment: self.comment,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = DefineRoleStatement::default();
+		let value: DefineRoleStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_grants() {
+		let stmt = DefineRoleStatement {
+			grants: vec![Grant {
+				action: crate::iam::Action::View,
+				kind: crate::iam::ResourceKind::Table,
+				names: vec![],
+			}],
+			..Default::default()
+		};
+		let value: DefineRoleStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -43,6 +43,8 @@ pub struct SerializeDefineTokenStatement {
 	kind: Algorithm,
 	code: String,
 	comment: Option<Strand>,
+	issuer: Option<String>,
+	audience: Option<String>,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineTokenStatement {
@@ -69,6 +71,12 @@ impl serde::ser::SerializeStruct for SerializeDefineTokenStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"issuer" => {
+				self.issuer = value.serialize(ser::string::opt::Serializer.wrap())?;
+			}
+			"audience" => {
+				self.audience = value.serialize(ser::string::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineTokenStatement::{key}`"
@@ -85,6 +93,8 @@ impl serde::ser::SerializeStruct for SerializeDefineTokenStatement {
 			kind: self.kind,
 			code: self.code,
 			comment: self.comment,
+			issuer: self.issuer,
+			audience: self.audience,
 		})
 	}
 }
@@ -99,4 +109,16 @@ mod tests {
 		let value: DefineTokenStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_claims() {
+		let stmt = DefineTokenStatement {
+			kind: Algorithm::Jwks,
+			issuer: Some("https://idp.example.com".to_owned()),
+			audience: Some("surrealdb".to_owned()),
+			..Default::default()
+		};
+		let value: DefineTokenStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
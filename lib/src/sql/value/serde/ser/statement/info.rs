@@ -68,7 +68,10 @@ impl ser::Serializer for Serializer {
 		_len: usize,
 	) -> Result<Self::SerializeTupleVariant, Self::Error> {
 		match variant {
-			"User" => Ok(SerializeInfoStatement::default()),
+			"User" | "Ix" => Ok(SerializeInfoStatement {
+				variant,
+				..Default::default()
+			}),
 			variant => Err(Error::custom(format!("unexpected tuple variant `{name}::{variant}`"))),
 		}
 	}
@@ -76,8 +79,9 @@ impl ser::Serializer for Serializer {
 
 #[derive(Default)]
 pub(super) struct SerializeInfoStatement {
+	variant: &'static str,
 	index: usize,
-	tuple: (Option<Ident>, Option<Base>),
+	tuple: (Option<Ident>, Option<Base>, Option<Ident>),
 }
 
 impl serde::ser::SerializeTupleVariant for SerializeInfoStatement {
@@ -88,16 +92,19 @@ impl serde::ser::SerializeTupleVariant for SerializeInfoStatement {
 	where
 		T: Serialize + ?Sized,
 	{
-		match self.index {
-			0 => {
+		match (self.variant, self.index) {
+			(_, 0) => {
 				self.tuple.0 = Some(Ident(value.serialize(ser::string::Serializer.wrap())?));
 			}
-			1 => {
+			("User", 1) => {
 				self.tuple.1 = value.serialize(ser::base::opt::Serializer.wrap())?;
 			}
-			index => {
+			("Ix", 1) => {
+				self.tuple.2 = Some(Ident(value.serialize(ser::string::Serializer.wrap())?));
+			}
+			(variant, index) => {
 				return Err(Error::custom(format!(
-					"unexpected `InfoStatement::User` index `{index}`"
+					"unexpected `InfoStatement::{variant}` index `{index}`"
 				)));
 			}
 		}
@@ -106,9 +113,12 @@ impl serde::ser::SerializeTupleVariant for SerializeInfoStatement {
 	}
 
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		match self.tuple.0 {
-			Some(ident) => Ok(InfoStatement::User(ident, self.tuple.1)),
-			None => Err(Error::custom("`InfoStatement::User` missing required value(s)")),
+		match (self.variant, self.tuple) {
+			("User", (Some(ident), base, _)) => Ok(InfoStatement::User(ident, base)),
+			("Ix", (Some(ix), _, Some(tb))) => Ok(InfoStatement::Ix(ix, tb)),
+			(variant, _) => {
+				Err(Error::custom(format!("`InfoStatement::{variant}` missing required value(s)")))
+			}
 		}
 	}
 }
@@ -158,4 +168,11 @@ mod tests {
 		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(stmt, serialized);
 	}
+
+	#[test]
+	fn ix() {
+		let stmt = InfoStatement::Ix(Default::default(), Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
 }
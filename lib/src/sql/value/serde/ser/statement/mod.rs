@@ -14,6 +14,7 @@ pub mod kill;
 pub mod live;
 pub mod option;
 pub mod output;
+pub mod rebuild;
 pub mod relate;
 pub mod remove;
 pub mod select;
@@ -78,6 +79,7 @@ impl ser::Serializer for Serializer {
 			"Output" => Ok(Statement::Output(value.serialize(output::Serializer.wrap())?)),
 			"Relate" => Ok(Statement::Relate(value.serialize(relate::Serializer.wrap())?)),
 			"Remove" => Ok(Statement::Remove(value.serialize(remove::Serializer.wrap())?)),
+			"Rebuild" => Ok(Statement::Rebuild(value.serialize(rebuild::Serializer.wrap())?)),
 			"Select" => Ok(Statement::Select(value.serialize(select::Serializer.wrap())?)),
 			"Set" => Ok(Statement::Set(value.serialize(set::Serializer.wrap())?)),
 			"Show" => Ok(Statement::Show(value.serialize(show::Serializer.wrap())?)),
@@ -98,6 +100,7 @@ mod tests {
 	use crate::sql::statements::analyze::AnalyzeStatement;
 	use crate::sql::statements::DefineStatement;
 	use crate::sql::statements::InfoStatement;
+	use crate::sql::statements::RebuildStatement;
 	use crate::sql::statements::RemoveStatement;
 	use ser::Serializer as _;
 	use serde::Serialize;
@@ -215,6 +218,13 @@ mod tests {
 		assert_eq!(statement, serialized);
 	}
 
+	#[test]
+	fn rebuild() {
+		let statement = Statement::Rebuild(RebuildStatement::Index(Default::default()));
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
 	#[test]
 	fn select() {
 		let statement = Statement::Select(Default::default());
@@ -0,0 +1,79 @@
+use crate::err::Error;
+use crate::sql::statements::RebuildTableStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = RebuildTableStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<RebuildTableStatement, Error>;
+	type SerializeTuple = Impossible<RebuildTableStatement, Error>;
+	type SerializeTupleStruct = Impossible<RebuildTableStatement, Error>;
+	type SerializeTupleVariant = Impossible<RebuildTableStatement, Error>;
+	type SerializeMap = Impossible<RebuildTableStatement, Error>;
+	type SerializeStruct = SerializeRebuildTableStatement;
+	type SerializeStructVariant = Impossible<RebuildTableStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `RebuildTableStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRebuildTableStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRebuildTableStatement {
+	name: Ident,
+}
+
+impl serde::ser::SerializeStruct for SerializeRebuildTableStatement {
+	type Ok = RebuildTableStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `RebuildTableStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(RebuildTableStatement {
+			name: self.name,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = RebuildTableStatement::default();
+		let value: RebuildTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
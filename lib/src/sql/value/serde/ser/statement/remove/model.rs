@@ -0,0 +1,84 @@
+use crate::err::Error;
+use crate::sql::statements::RemoveModelStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = RemoveModelStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<RemoveModelStatement, Error>;
+	type SerializeTuple = Impossible<RemoveModelStatement, Error>;
+	type SerializeTupleStruct = Impossible<RemoveModelStatement, Error>;
+	type SerializeTupleVariant = Impossible<RemoveModelStatement, Error>;
+	type SerializeMap = Impossible<RemoveModelStatement, Error>;
+	type SerializeStruct = SerializeRemoveModelStatement;
+	type SerializeStructVariant = Impossible<RemoveModelStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `RemoveModelStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRemoveModelStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRemoveModelStatement {
+	name: Ident,
+	version: String,
+}
+
+impl serde::ser::SerializeStruct for SerializeRemoveModelStatement {
+	type Ok = RemoveModelStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"version" => {
+				self.version = value.serialize(ser::string::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `RemoveModelStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(RemoveModelStatement {
+			name: self.name,
+			version: self.version,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = RemoveModelStatement::default();
+		let value: RemoveModelStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -0,0 +1,85 @@
+use crate::err::Error;
+use crate::sql::statements::RemoveRoleStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Base;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = RemoveRoleStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<RemoveRoleStatement, Error>;
+	type SerializeTuple = Impossible<RemoveRoleStatement, Error>;
+	type SerializeTupleStruct = Impossible<RemoveRoleStatement, Error>;
+	type SerializeTupleVariant = Impossible<RemoveRoleStatement, Error>;
+	type SerializeMap = Impossible<RemoveRoleStatement, Error>;
+	type SerializeStruct = SerializeRemoveRoleStatement;
+	type SerializeStructVariant = Impossible<RemoveRoleStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `RemoveRoleStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRemoveRoleStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRemoveRoleStatement {
+	name: Ident,
+	base: Base,
+}
+
+impl serde::ser::SerializeStruct for SerializeRemoveRoleStatement {
+	type Ok = RemoveRoleStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"base" => {
+				self.base = value.serialize(ser::base::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `RemoveRoleStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(RemoveRoleStatement {
+			name: self.name,
+			base: self.base,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = RemoveRoleStatement::default();
+		let value: RemoveRoleStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
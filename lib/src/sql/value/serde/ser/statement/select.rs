@@ -269,7 +269,10 @@ mod tests {
 	#[test]
 	fn with_explain_full() {
 		let stmt = SelectStatement {
-			explain: Some(Explain(true)),
+			explain: Some(Explain {
+				full: true,
+				analyze: false,
+			}),
 			..Default::default()
 		};
 		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
@@ -2295,7 +2295,7 @@ impl Value {
 			.as_str()
 			.trim_start_matches('/')
 			.split(&['.', '/'][..])
-			.map(Part::from)
+			.map(|v| Part::from(v.replace("~1", "/").replace("~0", "~").as_str()))
 			.collect::<Vec<Part>>()
 			.into()
 	}
@@ -1,9 +1,12 @@
+use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
 use crate::sql::error::IResult;
-use crate::sql::field::{fields, Fields};
+use crate::sql::expression::Expression;
+use crate::sql::field::{fields, Field, Fields};
 use crate::sql::group::{group, Groups};
 use crate::sql::table::{tables, Tables};
+use crate::sql::value::Value;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case};
 use nom::combinator::{cut, opt};
@@ -23,6 +26,62 @@ pub struct View {
 	pub group: Option<Groups>,
 }
 
+impl View {
+	/// Checks that the aggregate functions of a grouped view can be maintained incrementally
+	pub(crate) fn check_aggregates(&self) -> Result<(), Error> {
+		if self.group.is_none() {
+			return Ok(());
+		}
+		for field in self.expr.other() {
+			if let Field::Single {
+				expr,
+				..
+			} = field
+			{
+				match expr {
+					// A rolling function is maintained from the values of its arguments
+					Value::Function(f) if f.is_rolling() => {
+						for arg in f.args() {
+							Self::check_nested(arg)?;
+						}
+					}
+					// Any other aggregate function needs all the values of the group
+					expr => Self::check_nested(expr)?,
+				}
+			}
+		}
+		Ok(())
+	}
+	/// Checks that a value does not contain an aggregate function, which can only
+	/// be maintained incrementally when it is the whole expression of a field
+	fn check_nested(v: &Value) -> Result<(), Error> {
+		match v {
+			Value::Function(f) if f.is_aggregate() => Err(Error::InvalidViewAggregate {
+				name: f.to_string(),
+			}),
+			Value::Function(f) => f.args().iter().try_for_each(Self::check_nested),
+			Value::Expression(e) => match e.as_ref() {
+				Expression::Unary {
+					v,
+					..
+				} => Self::check_nested(v),
+				Expression::Binary {
+					l,
+					r,
+					..
+				} => {
+					Self::check_nested(l)?;
+					Self::check_nested(r)
+				}
+			},
+			Value::Array(v) => v.iter().try_for_each(Self::check_nested),
+			Value::Object(v) => v.values().try_for_each(Self::check_nested),
+			Value::Cast(v) => Self::check_nested(&v.1),
+			_ => Ok(()),
+		}
+	}
+}
+
 impl fmt::Display for View {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "AS SELECT {} FROM {}", self.expr, self.what)?;
@@ -1,6 +1,7 @@
 #[allow(unused_imports, dead_code)]
 mod api_integration {
 	use chrono::DateTime;
+	use futures::StreamExt;
 	use once_cell::sync::Lazy;
 	use serde::Deserialize;
 	use serde::Serialize;
@@ -19,6 +20,7 @@ mod api_integration {
 	use surrealdb::opt::auth::Root;
 	use surrealdb::opt::auth::Scope;
 	use surrealdb::opt::Config;
+	use surrealdb::opt::Cursor;
 	use surrealdb::opt::PatchOp;
 	use surrealdb::opt::Resource;
 	use surrealdb::sql::statements::BeginStatement;
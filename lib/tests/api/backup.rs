@@ -22,3 +22,23 @@ async fn export_import() {
 	db.import(&file).await.unwrap();
 	remove_file(file).await.unwrap();
 }
+
+#[tokio::test]
+async fn import_ml() {
+	let db = new_db().await;
+	let db_name = Ulid::new().to_string();
+	db.use_ns(NS).use_db(&db_name).await.unwrap();
+	let file = format!("{db_name}.json");
+	let model = r#"{
+		"name": "churn",
+		"version": "1.0.0",
+		"inputs": ["age", "spend"],
+		"model": { "type": "linear", "weights": [2.0, -1.0], "bias": 0.5 }
+	}"#;
+	tokio::fs::write(&file, model).await.unwrap();
+	db.import(&file).ml().await.unwrap();
+	remove_file(file).await.unwrap();
+	let mut response = db.query("RETURN ml::churn<1.0.0>({ age: 3, spend: 1.5 })").await.unwrap();
+	let value: Option<f64> = response.take(0).unwrap();
+	assert_eq!(value, Some(5.0));
+}
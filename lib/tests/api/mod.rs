@@ -381,6 +381,45 @@ async fn select_record_ranges() {
 	assert_eq!(convert(users), vec!["john"]);
 }
 
+#[tokio::test]
+async fn select_record_pages() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let table = "user";
+	for name in ["amos", "jane", "john", "zoey", "zack"] {
+		let _: Option<RecordId> = db.create((table, name)).await.unwrap();
+	}
+	let convert = |users: Vec<RecordId>| -> Vec<String> {
+		users.into_iter().map(|user| user.id.id.to_string()).collect()
+	};
+	let mut pages = db.select(table).pages(2);
+	let page: Vec<RecordId> = pages.next().await.unwrap().unwrap();
+	assert_eq!(convert(page), vec!["amos", "jane"]);
+	// Records created before the cursor do not shift the next page
+	let _: Option<RecordId> = db.create((table, "anna")).await.unwrap();
+	let page: Vec<RecordId> = pages.next().await.unwrap().unwrap();
+	assert_eq!(convert(page), vec!["john", "zack"]);
+	let cursor: Cursor = pages.cursor().unwrap().to_string().parse().unwrap();
+	let page: Vec<RecordId> = pages.next().await.unwrap().unwrap();
+	assert_eq!(convert(page), vec!["zoey"]);
+	assert!(pages.next().await.is_none());
+	// The cursor can be used to resume the selection
+	let users: Vec<RecordId> = db.select(table).cursor(cursor.clone()).await.unwrap();
+	assert_eq!(convert(users), vec!["zoey"]);
+	// A cursor only continues the pages of its own table
+	let res: surrealdb::Result<Vec<RecordId>> = db.select("other").cursor(cursor.clone()).await;
+	assert!(res.is_err());
+	let mut pages = db.select(table).cursor(cursor).pages(1);
+	let page: Vec<RecordId> = pages.next().await.unwrap().unwrap();
+	assert_eq!(convert(page), vec!["zoey"]);
+	// An empty page is not returned after a full one
+	assert!(pages.next().await.is_none());
+	let mut pages = db.select("nobody").pages(2);
+	let page: Vec<RecordId> = pages.next().await.unwrap().unwrap();
+	assert!(page.is_empty());
+	assert!(pages.next().await.is_none());
+}
+
 #[tokio::test]
 async fn update_table() {
 	let db = new_db().await;
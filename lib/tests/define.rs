@@ -56,6 +56,7 @@ async fn define_statement_database() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			databases: { test: 'DEFINE DATABASE test' },
+			roles: {},
 			tokens: {},
 			users: {},
 		}",
@@ -87,6 +88,8 @@ async fn define_statement_function() -> Result<(), Error> {
 			analyzers: {},
 			tokens: {},
 			functions: { test: 'DEFINE FUNCTION fn::test($first: string, $last: string) { RETURN $first + $last; }' },
+			models: {},
+			roles: {},
 			params: {},
 			scopes: {},
 			params: {},
@@ -120,6 +123,8 @@ async fn define_statement_table_drop() -> Result<(), Error> {
 			analyzers: {},
 			tokens: {},
 			functions: {},
+			models: {},
+			roles: {},
 			params: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test DROP SCHEMALESS' },
@@ -151,6 +156,8 @@ async fn define_statement_table_schemaless() -> Result<(), Error> {
 			analyzers: {},
 			tokens: {},
 			functions: {},
+			models: {},
+			roles: {},
 			params: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test SCHEMALESS' },
@@ -186,6 +193,8 @@ async fn define_statement_table_schemafull() -> Result<(), Error> {
 			analyzers: {},
 			tokens: {},
 			functions: {},
+			models: {},
+			roles: {},
 			params: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test SCHEMAFULL' },
@@ -217,6 +226,8 @@ async fn define_statement_table_schemaful() -> Result<(), Error> {
 			analyzers: {},
 			tokens: {},
 			functions: {},
+			models: {},
+			roles: {},
 			params: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test SCHEMAFULL' },
@@ -256,6 +267,8 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 			analyzers: {},
 			tokens: {},
 			functions: {},
+			models: {},
+			roles: {},
 			params: {},
 			scopes: {},
 			tables: {
@@ -288,6 +301,8 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 			analyzers: {},
 			tokens: {},
 			functions: {},
+			models: {},
+			roles: {},
 			params: {},
 			scopes: {},
 			tables: {
@@ -1172,6 +1187,8 @@ async fn define_statement_analyzer() -> Result<(), Error> {
 			},
 			tokens: {},
 			functions: {},
+			models: {},
+			roles: {},
 			params: {},
 			scopes: {},
 			tables: {},
@@ -1244,6 +1261,221 @@ async fn define_statement_search_index() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_analyze_index() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE INDEX user_age ON user FIELDS age;
+		DEFINE INDEX user_code ON user FIELDS code UNIQUE;
+		FOR $i IN [1, 2, 3, 4, 5, 6, 7, 8, 9] {
+			CREATE type::thing('user', $i) SET age = 20, code = $i;
+		};
+		CREATE user:10 SET age = 30, code = 10;
+		CREATE user:11 SET age = 40, code = 11;
+		CREATE user:12 SET age = 50, code = 12;
+		ANALYZE INDEX user_age ON user;
+		ANALYZE INDEX user_code ON user;
+	"#;
+
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for i in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok(), "{}", i);
+	}
+	// The values of a skewed index are grouped in buckets of about the same size
+	let tmp = res.remove(0).result?;
+	check_path(&tmp, &["keys_count"], |v| assert_eq!(v, Value::from(12)));
+	check_path(&tmp, &["distinct_count"], |v| assert_eq!(v, Value::from(4)));
+	check_path(&tmp, &["total_size"], |v| assert!(v.gt(&Value::from(0))));
+	check_path(&tmp, &["histogram"], |v| {
+		assert_eq!(
+			v,
+			Value::parse(
+				"[
+					{ count: 9, distinct: 1, max: 20, min: 20 },
+					{ count: 2, distinct: 2, max: 40, min: 30 },
+					{ count: 1, distinct: 1, max: 50, min: 50 }
+				]"
+			)
+		)
+	});
+	//
+	let tmp = res.remove(0).result?;
+	check_path(&tmp, &["keys_count"], |v| assert_eq!(v, Value::from(12)));
+	check_path(&tmp, &["distinct_count"], |v| assert_eq!(v, Value::from(12)));
+	check_path(&tmp, &["histogram"], |v| {
+		assert_eq!(
+			v,
+			Value::parse(
+				"[
+					{ count: 2, distinct: 2, max: 2, min: 1 },
+					{ count: 2, distinct: 2, max: 4, min: 3 },
+					{ count: 2, distinct: 2, max: 6, min: 5 },
+					{ count: 2, distinct: 2, max: 8, min: 7 },
+					{ count: 2, distinct: 2, max: 10, min: 9 },
+					{ count: 2, distinct: 2, max: 12, min: 11 }
+				]"
+			)
+		)
+	});
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_index_concurrently() -> Result<(), Error> {
+	let sql = "
+		CREATE user:1 SET email = 'one@surrealdb.com';
+		CREATE user:2 SET email = 'two@surrealdb.com';
+		DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY;
+		INFO FOR INDEX test ON user;
+		CREATE user:3 SET email = 'three@surrealdb.com';
+		SELECT * FROM user WHERE email = 'one@surrealdb.com' EXPLAIN;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for i in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok(), "{}", i);
+	}
+	// The index is built in the background
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'indexing', count: 0 } }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// The index is not used until it is built
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					table: 'user',
+				},
+				operation: 'Iterate Table'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Build the index
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR INDEX test ON user;
+		INFO FOR TABLE user;
+		SELECT * FROM user WHERE email = 'one@surrealdb.com' EXPLAIN;
+		SELECT * FROM user WHERE email = 'three@surrealdb.com';
+		CREATE user:4 SET email = 'one@surrealdb.com';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'ready' } }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	check_path(&tmp, &["indexes", "test"], |v| {
+		assert_eq!(v, Value::from("DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY"))
+	});
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'test',
+						operator: '=',
+						value: 'one@surrealdb.com'
+					},
+					table: 'user',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The records written during the build are indexed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:3, email: 'three@surrealdb.com' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database index `test` already contains 'one@surrealdb.com', with record `user:1`"#
+	));
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_index_concurrently_in_steps() -> Result<(), Error> {
+	let sql = "
+		CREATE |user:10001| SET email = rand::uuid();
+		DEFINE INDEX test ON user FIELDS email CONCURRENTLY;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	for _ in 0..2 {
+		res.remove(0).result?;
+	}
+	// A build only indexes a limited number of batches
+	dbs.build_indexes().await?;
+	let res = &mut dbs.execute("INFO FOR INDEX test ON user", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'indexing', count: 10000 } }");
+	assert_eq!(tmp, val);
+	// The next build indexes the remaining records
+	dbs.build_indexes().await?;
+	let res = &mut dbs.execute("INFO FOR INDEX test ON user", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'ready' } }");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_index_concurrently_error() -> Result<(), Error> {
+	let sql = "
+		CREATE user:1 SET email = 'test@surrealdb.com';
+		CREATE user:2 SET email = 'test@surrealdb.com';
+		DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for _ in 0..3 {
+		res.remove(0).result?;
+	}
+	// The build stops on the duplicate value
+	dbs.build_indexes().await?;
+	//
+	let res = &mut dbs.execute("INFO FOR INDEX test ON user", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		r#"{
+			building: {
+				status: 'error',
+				count: 0,
+				error: "Database index `test` already contains 'test@surrealdb.com', with record `user:1`"
+			}
+		}"#,
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_user_root() -> Result<(), Error> {
 	let sql = "
@@ -1449,8 +1681,8 @@ async fn permissions_checks_define_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ databases: { DB: 'DEFINE DATABASE DB' }, tokens: {  }, users: {  } }"],
-		vec!["{ databases: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ databases: { DB: 'DEFINE DATABASE DB' }, roles: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ databases: {  }, roles: {  }, tokens: {  }, users: {  } }"],
 	];
 
 	let test_cases = [
@@ -1491,8 +1723,8 @@ async fn permissions_checks_define_function() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1533,8 +1765,8 @@ async fn permissions_checks_define_analyzer() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1575,8 +1807,8 @@ async fn permissions_checks_define_token_ns() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ databases: {  }, tokens: { token: \"DEFINE TOKEN token ON NAMESPACE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
-		vec!["{ databases: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ databases: {  }, roles: {  }, tokens: { token: \"DEFINE TOKEN token ON NAMESPACE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
+		vec!["{ databases: {  }, roles: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1617,8 +1849,8 @@ async fn permissions_checks_define_token_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1701,8 +1933,8 @@ async fn permissions_checks_define_user_ns() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ databases: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON NAMESPACE PASSHASH 'secret' ROLES VIEWER\" } }"],
-		vec!["{ databases: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ databases: {  }, roles: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON NAMESPACE PASSHASH 'secret' ROLES VIEWER\" } }"],
+		vec!["{ databases: {  }, roles: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1743,8 +1975,8 @@ async fn permissions_checks_define_user_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
+		vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1785,8 +2017,8 @@ async fn permissions_checks_define_scope() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1827,8 +2059,8 @@ async fn permissions_checks_define_param() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1866,8 +2098,8 @@ async fn permissions_checks_define_table() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -2023,3 +2255,154 @@ async fn permissions_checks_define_index() {
 	let res = iam_check_cases(test_cases.iter(), &scenario, check_results).await;
 	assert!(res.is_ok(), "{}", res.unwrap_err());
 }
+
+#[tokio::test]
+async fn permissions_checks_analyze_index() {
+	let scenario = HashMap::from([
+		("prepare", "DEFINE INDEX index ON TABLE TB FIELDS field; CREATE TB:1 SET field = 1; CREATE TB:2 SET field = 2"),
+		("test", "ANALYZE INDEX index ON TB"),
+		("check", "SELECT * FROM TB WHERE field = 1 EXPLAIN"),
+	]);
+
+	// Define the expected results for the check statement when the test statement succeeded and when it failed
+	let check_results = [
+		vec!["[{ detail: { table: 'TB' }, operation: 'Iterate Table' }, { detail: { reason: 'TABLE SCAN IS CHEAPER' }, operation: 'Fallback' }]"],
+		vec!["[{ detail: { plan: { index: 'index', operator: '=', value: 1 }, table: 'TB' }, operation: 'Iterate Index' }]"],
+	];
+
+	let test_cases = [
+		// Root level
+		((().into(), Role::Owner), ("NS", "DB"), true),
+		((().into(), Role::Editor), ("NS", "DB"), true),
+		((().into(), Role::Viewer), ("NS", "DB"), false),
+		// Namespace level
+		((("NS",).into(), Role::Owner), ("NS", "DB"), true),
+		((("NS",).into(), Role::Owner), ("OTHER_NS", "DB"), false),
+		((("NS",).into(), Role::Editor), ("NS", "DB"), true),
+		((("NS",).into(), Role::Editor), ("OTHER_NS", "DB"), false),
+		((("NS",).into(), Role::Viewer), ("NS", "DB"), false),
+		((("NS",).into(), Role::Viewer), ("OTHER_NS", "DB"), false),
+		// Database level
+		((("NS", "DB").into(), Role::Owner), ("NS", "DB"), true),
+		((("NS", "DB").into(), Role::Owner), ("NS", "OTHER_DB"), false),
+		((("NS", "DB").into(), Role::Owner), ("OTHER_NS", "DB"), false),
+		((("NS", "DB").into(), Role::Editor), ("NS", "DB"), true),
+		((("NS", "DB").into(), Role::Editor), ("NS", "OTHER_DB"), false),
+		((("NS", "DB").into(), Role::Editor), ("OTHER_NS", "DB"), false),
+		((("NS", "DB").into(), Role::Viewer), ("NS", "DB"), false),
+		((("NS", "DB").into(), Role::Viewer), ("NS", "OTHER_DB"), false),
+		((("NS", "DB").into(), Role::Viewer), ("OTHER_NS", "DB"), false),
+	];
+
+	let res = iam_check_cases(test_cases.iter(), &scenario, check_results).await;
+	assert!(res.is_ok(), "{}", res.unwrap_err());
+}
+
+#[tokio::test]
+async fn define_statement_role_permissions() -> Result<(), Error> {
+	let ds = new_ds().await?.with_auth_enabled(true);
+	let owner = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE ROLE reader ON DB GRANT VIEW ON TABLE, EDIT ON INDEX;
+		DEFINE USER alice ON DB PASSWORD 'secret' ROLES reader;
+		DEFINE TABLE person;
+		CREATE person:tobie;
+	";
+	for res in ds.execute(sql, &owner, None).await? {
+		res.result?;
+	}
+	// Sign in as the user with the custom role
+	let mut ses = Session::default();
+	surrealdb::iam::signin::db(
+		&ds,
+		&mut ses,
+		"test".to_owned(),
+		"test".to_owned(),
+		"alice".to_owned(),
+		"secret".to_owned(),
+	)
+	.await?;
+	let sql = "
+		SELECT * FROM person;
+		DEFINE INDEX idx ON person FIELDS name;
+		DEFINE FUNCTION fn::test() { RETURN 1; };
+	";
+	let res = &mut ds.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok(), "{tmp:?}");
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IamError(_))), "{tmp:?}");
+	// The grants of a role which is removed no longer apply to the signed in user
+	ds.execute("REMOVE ROLE reader ON DB", &owner, None).await?.remove(0).result?;
+	let sql = "
+		SELECT * FROM person;
+		DEFINE INDEX other ON person FIELDS name;
+	";
+	let res = &mut ds.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IamError(_))), "{tmp:?}");
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_role_permissions_on_named_tables() -> Result<(), Error> {
+	let ds = new_ds().await?.with_auth_enabled(true);
+	let owner = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE ROLE reader ON DB GRANT VIEW ON TABLE person;
+		DEFINE USER alice ON DB PASSWORD 'secret' ROLES reader;
+		DEFINE TABLE person;
+		DEFINE TABLE secret;
+		CREATE person:tobie;
+		CREATE secret:tobie;
+	";
+	for res in ds.execute(sql, &owner, None).await? {
+		res.result?;
+	}
+	// Sign in as the user with the custom role
+	let mut ses = Session::default();
+	surrealdb::iam::signin::db(
+		&ds,
+		&mut ses,
+		"test".to_owned(),
+		"test".to_owned(),
+		"alice".to_owned(),
+		"secret".to_owned(),
+	)
+	.await?;
+	let sql = "
+		SELECT * FROM person;
+		SELECT * FROM secret;
+		UPDATE person:tobie SET name = 'Tobie';
+	";
+	let res = &mut ds.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	// The role can read the named table
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	// The role is denied the other tables
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	// The role can't edit the named table
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
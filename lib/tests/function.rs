@@ -201,6 +201,28 @@ async fn function_array_append() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_array_approx_distinct() -> Result<(), Error> {
+	let sql = r#"
+		RETURN array::approx_distinct([]);
+		RETURN array::approx_distinct([1, 2, 2, 3, 'a', 'a', [1, 2]]);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(0);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(5);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_array_at() -> Result<(), Error> {
 	let sql = r#"
@@ -3270,6 +3292,57 @@ async fn function_string_contains() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_string_distance_damerau_levenshtein() -> Result<(), Error> {
+	test_queries(
+		r#"
+		RETURN string::distance::damerau_levenshtein("", "");
+		RETURN string::distance::damerau_levenshtein("kitten", "sitting");
+		RETURN string::distance::damerau_levenshtein("ca", "ac");
+		RETURN string::distance::damerau_levenshtein("ca", "abc");
+	"#,
+		&["0", "3", "1", "2"],
+	)
+	.await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_string_distance_hamming() -> Result<(), Error> {
+	test_queries(
+		r#"
+		RETURN string::distance::hamming("", "");
+		RETURN string::distance::hamming("karolin", "kathrin");
+		RETURN string::distance::hamming("résumé", "resume");
+	"#,
+		&["0", "3", "2"],
+	)
+	.await?;
+	check_test_is_error(
+		r#"RETURN string::distance::hamming("abc", "ab");"#,
+		&[
+			"Incorrect arguments for function string::distance::hamming(). The two strings must be of the same length.",
+		],
+	)
+	.await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_string_distance_levenshtein() -> Result<(), Error> {
+	test_queries(
+		r#"
+		RETURN string::distance::levenshtein("", "");
+		RETURN string::distance::levenshtein("kitten", "sitting");
+		RETURN string::distance::levenshtein("", "abc");
+		RETURN string::distance::levenshtein("ca", "ac");
+	"#,
+		&["0", "3", "3", "2"],
+	)
+	.await?;
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_string_ends_with() -> Result<(), Error> {
 	let sql = r#"
@@ -3777,6 +3850,59 @@ async fn function_string_similarity_fuzzy() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_string_similarity_jaro() -> Result<(), Error> {
+	test_queries(
+		r#"
+		RETURN string::similarity::jaro("", "");
+		RETURN string::similarity::jaro("some", "text");
+		RETURN string::similarity::jaro("MARTHA", "MARHTA");
+		RETURN string::similarity::jaro("DIXON", "DICKSONX");
+	"#,
+		&["1.0", "0.0", "0.9444444444444445", "0.7666666666666666"],
+	)
+	.await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_string_similarity_jaro_winkler() -> Result<(), Error> {
+	test_queries(
+		r#"
+		RETURN string::similarity::jaro_winkler("TEXT", "TEXT");
+		RETURN string::similarity::jaro_winkler("MARTHA", "MARHTA");
+		RETURN string::similarity::jaro_winkler("DIXON", "DICKSONX");
+		RETURN string::similarity::jaro_winkler("hello", "hallo");
+	"#,
+		&["1.0", "0.9611111111111111", "0.8133333333333332", "0.88"],
+	)
+	.await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_string_similarity_ngram() -> Result<(), Error> {
+	test_queries(
+		r#"
+		RETURN string::similarity::ngram("", "");
+		RETURN string::similarity::ngram("night", "night");
+		RETURN string::similarity::ngram("night", "nacht");
+		RETURN string::similarity::ngram("night", "nacht", 3);
+		RETURN string::similarity::ngram("a", "a", 3);
+	"#,
+		&["1.0", "1.0", "0.14285714285714285", "0.0", "1.0"],
+	)
+	.await?;
+	check_test_is_error(
+		r#"RETURN string::similarity::ngram("night", "nacht", 0);"#,
+		&[
+			"Incorrect arguments for function string::similarity::ngram(). The size of the n-grams must be greater than 0.",
+		],
+	)
+	.await?;
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_string_similarity_smithwaterman() -> Result<(), Error> {
 	let sql = r#"
@@ -5627,6 +5753,26 @@ async fn function_vector_similarity_pearson() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_vector_similarity_spearman() -> Result<(), Error> {
+	test_queries(
+		r#"
+		RETURN vector::similarity::spearman([1, 2, 3], [10, 20, 30]);
+		RETURN vector::similarity::spearman([1, 2, 3], [3, 2, 1]);
+		RETURN vector::similarity::spearman([1, 2, 3, 4, 5], [5, 6, 7, 8, 7]);
+	"#,
+		&["1.0", "-1.0", "0.8207826816681233"],
+	)
+	.await?;
+
+	check_test_is_error(
+		r"RETURN vector::similarity::spearman([1, 2, 3], [4, 5]);",
+		&[
+			"Incorrect arguments for function vector::similarity::spearman(). The two vectors must be of the same dimension.",
+		]).await?;
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_vector_distance_euclidean() -> Result<(), Error> {
 	test_queries(
@@ -5698,6 +5844,32 @@ async fn function_vector_distance_hamming() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_vector_distance_mahalanobis() -> Result<(), Error> {
+	test_queries(
+		r#"
+		RETURN vector::distance::mahalanobis([1, 2], [1, 2], [[1, 0], [0, 1]]);
+		RETURN vector::distance::mahalanobis([0, 0], [2, 3], [[4, 0], [0, 1]]);
+		RETURN vector::distance::mahalanobis([1, 2], [3, 4], [[2, 0], [0, 2]]);
+	"#,
+		&["0.0", "3.1622776601683795", "2.0"],
+	)
+	.await?;
+
+	check_test_is_error(
+		r"RETURN vector::distance::mahalanobis([1, 2], [3], [[1, 0], [0, 1]]);
+		RETURN vector::distance::mahalanobis([1, 2], [3, 4], [[1, 0]]);
+		RETURN vector::distance::mahalanobis([1, 2], [3, 4], [[1, 2], [2, 4]]);
+		RETURN vector::distance::mahalanobis([1, 2], [3, 4], [[1, 'a'], [0, 1]]);",
+		&[
+			"Incorrect arguments for function vector::distance::mahalanobis(). The two vectors must be of the same dimension.",
+			"Incorrect arguments for function vector::distance::mahalanobis(). The covariance matrix must be a square matrix of the same dimension as the vectors.",
+			"Incorrect arguments for function vector::distance::mahalanobis(). The covariance matrix must be invertible.",
+			"Incorrect arguments for function vector::distance::mahalanobis(). The covariance matrix must be an array of arrays of numbers.",
+		]).await?;
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_vector_distance_minkowski() -> Result<(), Error> {
 	test_queries(
@@ -38,6 +38,7 @@ async fn info_for_root() {
 async fn info_for_ns() {
 	let sql = r#"
         DEFINE DATABASE DB;
+        DEFINE ROLE reader ON NS GRANT VIEW ON TABLE;
         DEFINE USER user ON NS PASSWORD 'pass';
         DEFINE TOKEN token ON NS TYPE HS512 VALUE 'secret';
         INFO FOR NS
@@ -46,13 +47,13 @@ async fn info_for_ns() {
 	let ses = Session::owner().with_ns("ns");
 
 	let mut res = dbs.execute(sql, &ses, None).await.unwrap();
-	assert_eq!(res.len(), 4);
+	assert_eq!(res.len(), 5);
 
 	let out = res.pop().unwrap().output();
 	assert!(out.is_ok(), "Unexpected error: {:?}", out);
 
 	let output_regex = Regex::new(
-		r"\{ databases: \{ DB: .* \}, tokens: \{ token: .* \}, users: \{ user: .* \} \}",
+		r"\{ databases: \{ DB: .* \}, roles: \{ reader: .* \}, tokens: \{ token: .* \}, users: \{ user: .* \} \}",
 	)
 	.unwrap();
 	let out_str = out.unwrap().to_string();
@@ -69,6 +70,7 @@ async fn info_for_db() {
 	let sql = r#"
         DEFINE TABLE TB;
         DEFINE SCOPE account SESSION 24h;
+        DEFINE ROLE reader ON DB GRANT VIEW ON TABLE;
         DEFINE USER user ON DB PASSWORD 'pass';
         DEFINE TOKEN token ON DB TYPE HS512 VALUE 'secret';
         DEFINE FUNCTION fn::greet() {RETURN "Hello";};
@@ -80,12 +82,12 @@ async fn info_for_db() {
 	let ses = Session::owner().with_ns("ns").with_db("db");
 
 	let mut res = dbs.execute(sql, &ses, None).await.unwrap();
-	assert_eq!(res.len(), 8);
+	assert_eq!(res.len(), 9);
 
 	let out = res.pop().unwrap().output();
 	assert!(out.is_ok(), "Unexpected error: {:?}", out);
 
-	let output_regex = Regex::new(r"\{ analyzers: \{ analyzer: .* \}, functions: \{ greet: .* \}, params: \{ param: .* \}, scopes: \{ account: .* \}, tables: \{ TB: .* \}, tokens: \{ token: .* \}, users: \{ user: .* \} \}").unwrap();
+	let output_regex = Regex::new(r"\{ analyzers: \{ analyzer: .* \}, functions: \{ greet: .* \}, models: \{  \}, params: \{ param: .* \}, roles: \{ reader: .* \}, scopes: \{ account: .* \}, tables: \{ TB: .* \}, tokens: \{ token: .* \}, users: \{ user: .* \} \}").unwrap();
 	let out_str = out.unwrap().to_string();
 	assert!(
 		output_regex.is_match(&out_str),
@@ -273,8 +275,8 @@ async fn permissions_checks_info_ns() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ databases: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ databases: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ databases: {  }, roles: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ databases: {  }, roles: {  }, tokens: {  }, users: {  } }"],
 	];
 
 	let test_cases = [
@@ -312,8 +314,8 @@ async fn permissions_checks_info_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, functions: {  }, models: {  }, params: {  }, roles: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
     ];
 
 	let test_cases = [
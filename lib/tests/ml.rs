@@ -0,0 +1,175 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Thing;
+use surrealdb::sql::Value;
+
+const CHURN: &str = r#"{
+	"name": "churn",
+	"version": "1.0.0",
+	"description": "Likelihood of a customer leaving",
+	"inputs": ["age", "spend"],
+	"model": { "type": "linear", "weights": [2.0, -1.0], "bias": 0.5 }
+}"#;
+
+#[tokio::test]
+async fn ml_model_import_and_evaluate() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	dbs.import_ml(&ses, CHURN.as_bytes()).await?;
+	let sql = "
+		CREATE customer:one SET age = 3, spend = 1.5;
+		CREATE customer:two SET age = 1, spend = 2;
+		SELECT id, ml::churn<1.0.0>({ age: age, spend: spend }) AS churn FROM customer;
+		RETURN ml::churn<1.0.0>([1, 1]);
+		RETURN ml::churn<1.0.0>({ age: 1 });
+		RETURN ml::churn<2.0.0>({ age: 1, spend: 1 });
+		INFO FOR DB;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: customer:one,
+				churn: 5.0,
+			},
+			{
+				id: customer:two,
+				churn: 0.5,
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("1.5");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Incorrect arguments for function ml::churn<1.0.0>(). The model expects a number for the 'spend' input."
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The model 'ml::churn<2.0.0>' does not exist"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = tmp.pick(&["models".into(), "churn<1.0.0>".into()]);
+	assert_eq!(
+		val,
+		Value::from("DEFINE MODEL ml::churn<1.0.0> COMMENT 'Likelihood of a customer leaving'")
+	);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn ml_model_define_and_remove() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE MODEL ml::churn<1.0.0> COMMENT 'defined before upload';
+		RETURN ml::churn<1.0.0>({ age: 1, spend: 1 });
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The model 'ml::churn<1.0.0>' does not have an uploaded model file"
+	));
+	// Uploading the file keeps the existing definition
+	dbs.import_ml(&ses, CHURN.as_bytes()).await?;
+	let sql = "
+		RETURN ml::churn<1.0.0>({ age: 1, spend: 1 });
+		INFO FOR DB;
+		REMOVE MODEL ml::churn<1.0.0>;
+		RETURN ml::churn<1.0.0>({ age: 1, spend: 1 });
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("1.5");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = tmp.pick(&["models".into(), "churn<1.0.0>".into()]);
+	assert_eq!(val, Value::from("DEFINE MODEL ml::churn<1.0.0> COMMENT 'defined before upload'"));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The model 'ml::churn<1.0.0>' does not exist"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn ml_model_permissions() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	dbs.import_ml(&ses, CHURN.as_bytes()).await?;
+	let sql = "
+		DEFINE MODEL ml::churn<1.0.0> PERMISSIONS WHERE $auth.id = user:allowed;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let sql = "RETURN ml::churn<1.0.0>({ age: 1, spend: 1 });";
+	let ses = Session::for_scope("test", "test", "test", Thing::from(("user", "allowed")).into());
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("1.5"));
+	//
+	let ses = Session::for_scope("test", "test", "test", Thing::from(("user", "denied")).into());
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "You don't have permission to run the ml::churn model"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn ml_model_import_invalid() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = dbs.import_ml(&ses, CHURN.replace("[2.0, -1.0]", "[2.0]").as_bytes()).await;
+	assert!(matches!(
+		res,
+		Err(e) if e.to_string() == "The model file is invalid: The linear model has 1 weights, but 2 inputs"
+	));
+	let res = dbs.import_ml(&Session::owner(), CHURN.as_bytes()).await;
+	assert!(matches!(res, Err(Error::NsEmpty)));
+	Ok(())
+}
@@ -29,6 +29,8 @@ async fn define_global_param() -> Result<(), Error> {
 			analyzers: {},
 			tokens: {},
 			functions: {},
+			models: {},
+			roles: {},
 			params: { test: 'DEFINE PARAM $test VALUE 12345' },
 			scopes: {},
 			tables: {},
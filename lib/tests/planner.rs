@@ -846,3 +846,608 @@ async fn select_index_single_range_operator_more_or_equal() -> Result<(), Error>
 async fn select_unique_single_range_operator_more_or_equal() -> Result<(), Error> {
 	select_single_range_operator(true, ">=", EXPLAIN_MORE_OR_EQUAL, RESULT_MORE_OR_EQUAL).await
 }
+
+fn multi_range_test(unique: bool) -> String {
+	format!(
+		"DEFINE INDEX year ON TABLE test COLUMNS year {};
+		CREATE test:0 SET year = 2000;
+		CREATE test:10 SET year = 2010;
+		CREATE test:15 SET year = 2015;
+		CREATE test:20 SET year = 2020;
+		SELECT id FROM test WHERE (year > 2000 AND year < 2012) OR year >= 2020 EXPLAIN;
+		SELECT id FROM test WHERE (year > 2000 AND year < 2012) OR year >= 2020;",
+		if unique {
+			"UNIQUE"
+		} else {
+			""
+		},
+	)
+}
+
+async fn select_multi_range(unique: bool) -> Result<(), Error> {
+	let mut res = execute_test(&multi_range_test(unique), 7, 5).await?;
+	check_result(&mut res, EXPLAIN_MULTI_RANGE)?;
+	check_result(&mut res, "[{ id: test:10 }, { id: test:20 }]")?;
+	Ok(())
+}
+
+const EXPLAIN_MULTI_RANGE: &str = r"[
+			{
+				detail: {
+					plan: {
+						from: {
+							inclusive: false,
+							value: 2000
+						},
+						index: 'year',
+						to: {
+							inclusive: false,
+							value: 2012
+						}
+					},
+					table: 'test'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					plan: {
+						from: {
+							inclusive: true,
+							value: 2020
+						},
+						index: 'year',
+						to: {
+							inclusive: false,
+							value: None
+						}
+					},
+					table: 'test'
+				},
+				operation: 'Iterate Index'
+			}
+		]";
+
+#[tokio::test]
+async fn select_index_multi_range() -> Result<(), Error> {
+	select_multi_range(false).await
+}
+
+#[tokio::test]
+async fn select_unique_multi_range() -> Result<(), Error> {
+	select_multi_range(true).await
+}
+
+#[tokio::test]
+async fn select_with_index_statistics() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX idx_a ON person FIELDS a;
+		DEFINE INDEX idx_b ON person FIELDS b;
+		DEFINE INDEX idx_active ON person FIELDS active;
+		FOR $i IN [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20] {
+			CREATE type::thing('person', $i) SET a = $i <= 10, b = $i INSIDE [2, 4, 6, 8, 10, 12, 14, 16, 18, 20], active = true;
+		};
+		ANALYZE INDEX idx_b ON person;
+		ANALYZE INDEX idx_active ON person;
+		ANALYZE INDEX idx_a ON person;
+		SELECT id FROM person WHERE a = true AND b = true EXPLAIN FULL;
+		SELECT id FROM person WHERE a = true AND b = true;
+		SELECT id FROM person WHERE a = true EXPLAIN FULL;
+		SELECT id FROM person WHERE active = true EXPLAIN FULL;
+	";
+	let mut res = execute_test(sql, 11, 6).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(
+		tmp.pick(&["histogram".into()]),
+		Value::parse(
+			"[{ count: 10, distinct: 1, max: false, min: false }, { count: 10, distinct: 1, max: true, min: true }]"
+		)
+	);
+	// The intersection of two indexes is cheaper than a single index
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						cost: 13.0,
+						rows: 5
+					},
+					plan: {
+						intersection: [
+							{
+								index: 'idx_b',
+								operator: '=',
+								value: true
+							},
+							{
+								index: 'idx_a',
+								operator: '=',
+								value: true
+							}
+						]
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					count: 5
+				},
+				operation: 'Fetch'
+			}
+		]",
+	)?;
+	check_result(
+		&mut res,
+		"[{ id: person:2 }, { id: person:4 }, { id: person:6 }, { id: person:8 }, { id: person:10 }]",
+	)?;
+	// A single index is cheaper than the table
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						cost: 14.0,
+						rows: 10
+					},
+					plan: {
+						index: 'idx_a',
+						operator: '=',
+						value: true
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					count: 10
+				},
+				operation: 'Fetch'
+			}
+		]",
+	)?;
+	// The index does not filter anything, the table is cheaper
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						cost: 20.0,
+						rows: 20
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					reason: 'TABLE SCAN IS CHEAPER'
+				},
+				operation: 'Fallback'
+			},
+			{
+				detail: {
+					count: 20
+				},
+				operation: 'Fetch'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_stale_index_statistics() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE INDEX idx_b ON person FIELDS b;
+		CREATE person:1 SET b = 1;
+		CREATE person:2 SET b = 2;
+		ANALYZE INDEX idx_b ON person;
+		SELECT id FROM person WHERE b = 1 EXPLAIN;
+		CREATE |person:100| SET b = 3;
+		SELECT id FROM person WHERE b = 1 EXPLAIN;
+	";
+	let mut res = dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	// The table only has two records when the index is analyzed
+	let table_scan = "[
+		{
+			detail: {
+				table: 'person'
+			},
+			operation: 'Iterate Table'
+		},
+		{
+			detail: {
+				reason: 'TABLE SCAN IS CHEAPER'
+			},
+			operation: 'Fallback'
+		}
+	]";
+	check_result(&mut res, table_scan)?;
+	let _ = res.remove(0).result?;
+	// The writes are not counted until they are folded into the statistics
+	check_result(&mut res, table_scan)?;
+	dbs.tick().await?;
+	// The statistics no longer describe the index once the table has grown
+	let mut res = dbs.execute("SELECT id FROM person WHERE b = 1 EXPLAIN", &ses, None).await?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_b',
+						operator: '=',
+						value: 1
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+fn order_test(unique: bool) -> String {
+	let unique = if unique {
+		"UNIQUE"
+	} else {
+		""
+	};
+	format!(
+		"DEFINE FIELD age ON TABLE person TYPE int;
+		DEFINE INDEX idx_age ON TABLE person COLUMNS age {unique};
+		CREATE person:1 SET age = 30;
+		CREATE person:2 SET age = 10;
+		CREATE person:3 SET age = 50;
+		CREATE person:4 SET age = 20;
+		CREATE person:5 SET age = 40;
+		DEFINE INDEX idx_score ON TABLE item COLUMNS score {unique};
+		CREATE item:1 SET score = 2;
+		CREATE item:2 SET score = 1.5;
+		CREATE item:3 SET score = 1;
+		SELECT id, age FROM person ORDER BY age LIMIT 2 START 1 EXPLAIN;
+		SELECT id, age FROM person ORDER BY age LIMIT 2 START 1;
+		SELECT * FROM person ORDER BY age DESC LIMIT 2;
+		SELECT id, age FROM person WHERE age > 10 AND age <= 40 ORDER BY age LIMIT 2 EXPLAIN;
+		SELECT id, age FROM person WHERE age > 10 AND age <= 40 ORDER BY age LIMIT 2;
+		SELECT id, age FROM person ORDER BY age DESC LIMIT 2 EXPLAIN;
+		SELECT id, age * 2 AS age FROM person ORDER BY age DESC LIMIT 2 EXPLAIN;
+		SELECT id, score FROM item ORDER BY score LIMIT 2 EXPLAIN;
+		SELECT id, score FROM item ORDER BY score LIMIT 2;"
+	)
+}
+
+async fn select_order(unique: bool) -> Result<(), Error> {
+	let mut res = execute_test(&order_test(unique), 20, 11).await?;
+	// The whole index is iterated in order, until the LIMIT is reached
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						from: {
+							inclusive: false,
+							value: NONE
+						},
+						index: 'idx_age',
+						order: 'ASC',
+						to: {
+							inclusive: false,
+							value: NONE
+						}
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: person:4, age: 20 }, { id: person:1, age: 30 }]")?;
+	check_result(&mut res, "[{ id: person:3, age: 50 }, { id: person:5, age: 40 }]")?;
+	// The range is iterated in order
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						from: {
+							inclusive: false,
+							value: 10
+						},
+						index: 'idx_age',
+						order: 'ASC',
+						to: {
+							inclusive: true,
+							value: 40
+						}
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: person:4, age: 20 }, { id: person:1, age: 30 }]")?;
+	// The memory datastore can not scan an index in reverse, so the results are sorted
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			}
+		]",
+	)?;
+	// The ordered field is computed, so the results are sorted
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			}
+		]",
+	)?;
+	// The field has no type, so the integers and the floats are not ordered by the index
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'item'
+				},
+				operation: 'Iterate Table'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: item:3, score: 1 }, { id: item:2, score: 1.5 }]")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_index_order() -> Result<(), Error> {
+	select_order(false).await
+}
+
+#[tokio::test]
+async fn select_unique_order() -> Result<(), Error> {
+	select_order(true).await
+}
+
+fn composite_test(unique: bool) -> String {
+	format!(
+		"DEFINE INDEX idx_tenant ON TABLE event COLUMNS tenant, created {};
+		CREATE event:1 SET tenant = 'a', created = 1;
+		CREATE event:2 SET tenant = 'a', created = 2;
+		CREATE event:3 SET tenant = 'a', created = 3;
+		CREATE event:4 SET tenant = 'b', created = 2;
+		CREATE event:5 SET tenant = 'b', created = 4;
+		SELECT id FROM event WHERE tenant = 'a' AND created > 1 EXPLAIN;
+		SELECT id FROM event WHERE tenant = 'a' AND created > 1;
+		SELECT id FROM event WHERE tenant = 'b' EXPLAIN;
+		SELECT id FROM event WHERE tenant = 'b';
+		SELECT id FROM event WHERE created <= 2 AND tenant = 'b';
+		SELECT id FROM event WHERE tenant = 'a' AND created = 3;
+		SELECT id FROM event WHERE created > 1 EXPLAIN;",
+		if unique {
+			"UNIQUE"
+		} else {
+			""
+		}
+	)
+}
+
+async fn select_composite(unique: bool) -> Result<(), Error> {
+	let mut res = execute_test(&composite_test(unique), 13, 6).await?;
+	// An equality on the leading column is followed by a range on the next column
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						from: {
+							inclusive: false,
+							value: 1
+						},
+						index: 'idx_tenant',
+						prefix: ['a'],
+						to: {
+							inclusive: false,
+							value: NONE
+						}
+					},
+					table: 'event'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: event:2 }, { id: event:3 }]")?;
+	// An equality on the leading column only
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						from: {
+							inclusive: false,
+							value: NONE
+						},
+						index: 'idx_tenant',
+						prefix: ['b'],
+						to: {
+							inclusive: false,
+							value: NONE
+						}
+					},
+					table: 'event'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: event:4 }, { id: event:5 }]")?;
+	check_result(&mut res, "[{ id: event:4 }]")?;
+	check_result(&mut res, "[{ id: event:3 }]")?;
+	// The leading column is not constrained
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'event'
+				},
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					reason: 'NO INDEX FOUND'
+				},
+				operation: 'Fallback'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_index_composite() -> Result<(), Error> {
+	select_composite(false).await
+}
+
+#[tokio::test]
+async fn select_unique_composite() -> Result<(), Error> {
+	select_composite(true).await
+}
+
+const SPATIAL_TEST: &str = "
+	DEFINE INDEX idx_location ON TABLE city COLUMNS location SPATIAL;
+	CREATE city:paris SET location = (2.3522, 48.8566);
+	CREATE city:london SET location = (-0.1276, 51.5072);
+	CREATE city:brussels SET location = (4.3517, 50.8503);
+	CREATE city:berlin SET location = (13.405, 52.52);
+	CREATE city:madrid SET location = (-3.7038, 40.4168);
+	LET $zone = {
+		type: 'Polygon',
+		coordinates: [[[-1.0, 48.0], [5.0, 48.0], [5.0, 52.0], [-1.0, 52.0], [-1.0, 48.0]]]
+	};
+	SELECT id FROM city WHERE location INSIDE $zone EXPLAIN;
+	SELECT id FROM city WHERE location INSIDE $zone ORDER BY id;
+	SELECT id FROM city WHERE $zone CONTAINS location ORDER BY id;
+	SELECT id FROM city WHERE location INTERSECTS {
+		type: 'Polygon',
+		coordinates: [[[12.0, 52.0], [14.0, 52.0], [14.0, 53.0], [12.0, 53.0], [12.0, 52.0]]]
+	};
+	SELECT id FROM city WHERE location <2> (2.0, 49.0) EXPLAIN;
+	SELECT id FROM city WHERE location <2> (2.0, 49.0);
+	UPDATE city:madrid SET location = (2.1, 49.1);
+	SELECT id FROM city WHERE location <2> (2.0, 49.0);
+	SELECT id FROM city WHERE location INSIDE $zone ORDER BY id;
+";
+
+#[tokio::test]
+async fn select_spatial_index() -> Result<(), Error> {
+	let mut res = execute_test(SPATIAL_TEST, 16, 7).await?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_location',
+						operator: 'INSIDE',
+						value: {
+							type: 'Polygon',
+							coordinates: [[[-1.0, 48.0], [5.0, 48.0], [5.0, 52.0], [-1.0, 52.0], [-1.0, 48.0]]]
+						}
+					},
+					table: 'city'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: city:brussels }, { id: city:london }, { id: city:paris }]")?;
+	check_result(&mut res, "[{ id: city:brussels }, { id: city:london }, { id: city:paris }]")?;
+	check_result(&mut res, "[{ id: city:berlin }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_location',
+						operator: '<2>',
+						value: [2.0, 49.0]
+					},
+					table: 'city'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: city:paris }, { id: city:brussels }]")?;
+	// The index follows the updated records
+	let _ = res.remove(0).result?;
+	check_result(&mut res, "[{ id: city:madrid }, { id: city:paris }]")?;
+	check_result(
+		&mut res,
+		"[{ id: city:brussels }, { id: city:london }, { id: city:madrid }, { id: city:paris }]",
+	)?;
+	Ok(())
+}
+
+const SPATIAL_KNN_TEST: &str = "
+	DEFINE INDEX idx_stops ON TABLE route COLUMNS stops SPATIAL;
+	CREATE route:north SET stops = [(0.0, 70.3)];
+	CREATE route:east SET stops = [(0.5, 70.0)];
+	CREATE route:near SET stops = [(10.0, 60.0), (10.0001, 60.0001)];
+	CREATE route:far SET stops = [(10.00005, 60.0)];
+	CREATE route:area SET stops = {
+		type: 'Polygon',
+		coordinates: [[[20.5, 59.5], [21.5, 59.5], [21.5, 60.5], [20.5, 60.5], [20.5, 59.5]]]
+	};
+	SELECT id FROM route WHERE stops <1> (0.0, 70.0);
+	SELECT id FROM route WHERE stops <1> (10.0, 60.0);
+	UPDATE route:near SET stops = [(10.0001, 60.0001)];
+	SELECT id FROM route WHERE stops <1> (10.0, 60.0);
+	SELECT id FROM route WHERE stops INTERSECTS {
+		type: 'Polygon',
+		coordinates: [[[20.0, 59.0], [22.0, 59.0], [22.0, 61.0], [20.0, 61.0], [20.0, 59.0]]]
+	};
+";
+
+#[tokio::test]
+async fn select_spatial_index_knn() -> Result<(), Error> {
+	let mut res = execute_test(SPATIAL_KNN_TEST, 11, 6).await?;
+	// Half a degree of longitude is closer than a third of a degree of latitude this far north
+	check_result(&mut res, "[{ id: route:east }]")?;
+	// Each geometry of a record is indexed, even within the same cell
+	check_result(&mut res, "[{ id: route:near }]")?;
+	let _ = res.remove(0).result?;
+	check_result(&mut res, "[{ id: route:far }]")?;
+	// A geometry covering several cells is returned once
+	check_result(&mut res, "[{ id: route:area }]")?;
+	Ok(())
+}
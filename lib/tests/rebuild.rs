@@ -0,0 +1,344 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn rebuild_statement_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX uniq_email ON TABLE user FIELDS email UNIQUE;
+		CREATE user:1 SET email = 'one@example.com';
+		CREATE user:2 SET email = 'two@example.com';
+		REBUILD INDEX uniq_email ON TABLE user;
+		SELECT id FROM user WHERE email = 'two@example.com';
+		CREATE user:3 SET email = 'one@example.com';
+		REBUILD INDEX missing ON user;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IndexExists { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IxNotFound { value }) if value == "missing"));
+	// The rebuilt entries replace the current ones once they are complete
+	dbs.build_indexes().await?;
+	let sql = "
+		INFO FOR INDEX uniq_email ON user;
+		SELECT id FROM user WHERE email = 'two@example.com';
+		CREATE user:3 SET email = 'one@example.com';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'ready' } }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IndexExists { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_statement_index_in_background() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX idx_email ON TABLE user FIELDS email;
+		CREATE user:1 SET email = 'one@example.com';
+		CREATE user:2 SET email = 'two@example.com';
+		REBUILD INDEX idx_email ON TABLE user;
+		UPDATE user:1 SET email = 'three@example.com';
+		INFO FOR INDEX idx_email ON user;
+		SELECT id FROM user WHERE email = 'three@example.com' EXPLAIN;
+		SELECT id FROM user WHERE email = 'three@example.com';
+		SELECT id FROM user WHERE email = 'one@example.com';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..5 {
+		res.remove(0).result?;
+	}
+	// The rebuild has not indexed any record yet
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'indexing', count: 0 } }");
+	assert_eq!(tmp, val);
+	// The current entries keep being used and maintained in the meantime
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_email',
+						operator: '=',
+						value: 'three@example.com'
+					},
+					table: 'user',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	// The rebuilt entries include the writes which happened during the rebuild
+	dbs.build_indexes().await?;
+	let sql = "
+		INFO FOR INDEX idx_email ON user;
+		SELECT id FROM user WHERE email = 'three@example.com';
+		SELECT id FROM user WHERE email = 'one@example.com';
+		SELECT id FROM user WHERE email = 'two@example.com';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'ready' } }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:2 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_statement_index_during_concurrent_build() -> Result<(), Error> {
+	let sql = "
+		CREATE |user:10001| SET email = 'test@example.com';
+		DEFINE INDEX idx_email ON user FIELDS email CONCURRENTLY;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	for _ in 0..2 {
+		res.remove(0).result?;
+	}
+	// The concurrent build is interrupted by a rebuild
+	dbs.build_indexes().await?;
+	let sql = "
+		REBUILD INDEX idx_email ON user;
+		INFO FOR INDEX idx_email ON user;
+		SELECT id FROM user WHERE email = 'test@example.com' EXPLAIN;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	res.remove(0).result?;
+	// The build is restarted, rather than stopped
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'indexing', count: 0 } }");
+	assert_eq!(tmp, val);
+	// The incomplete index is still not used
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					table: 'user',
+				},
+				operation: 'Iterate Table'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The build then completes all the records
+	dbs.build_indexes().await?;
+	dbs.build_indexes().await?;
+	let sql = "
+		INFO FOR INDEX idx_email ON user;
+		SELECT id FROM user WHERE email = 'test@example.com' EXPLAIN;
+		SELECT count() FROM user WHERE email = 'test@example.com' GROUP ALL;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'ready' } }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_email',
+						operator: '=',
+						value: 'test@example.com'
+					},
+					table: 'user',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 10001 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_statement_table() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_age AS SELECT count(), age FROM person GROUP BY age;
+		DEFINE TABLE person_by_name AS SELECT count(), name FROM person GROUP BY name;
+		CREATE person:one SET age = 39, name = 'Tobie';
+		CREATE person:two SET age = 39, name = 'Jaime';
+		UPDATE person_by_age:[39] SET count = 5;
+		CREATE person_by_age:[40] SET age = 40, count = 1;
+		REBUILD TABLE person_by_age;
+		SELECT * FROM person_by_age;
+		REBUILD TABLE person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..8 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The current records are used until the view is rebuilt
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ age: 39, count: 5, id: person_by_age:[39] },
+			{ age: 40, count: 1, id: person_by_age:[40] }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::TableIsNotView { table }) if table == "person"));
+	// The view is rebuilt in the background
+	dbs.build_views().await?;
+	let sql = "
+		SELECT * FROM person_by_age;
+		SELECT * FROM person_by_name;
+		INFO FOR DB;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ age: 39, count: 2, id: person_by_age:[39] }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ count: 1, id: person_by_name:['Jaime'], name: 'Jaime' },
+			{ count: 1, id: person_by_name:['Tobie'], name: 'Tobie' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The table into which the view was rebuilt is removed
+	let tmp = res.remove(0).result?;
+	let Value::Object(tables) = tmp.pick(&["tables".into()]) else {
+		panic!("{tmp}");
+	};
+	assert_eq!(tables.keys().collect::<Vec<_>>(), ["person", "person_by_age", "person_by_name"]);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_statement_table_during_writes() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_age AS SELECT count(), age FROM person GROUP BY age;
+		CREATE |person:1..10001| SET age = 39;
+		UPDATE person_by_age:[39] SET count = 0;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for _ in 0..4 {
+		res.remove(0).result?;
+	}
+	// The rebuild processes part of the records
+	let res = &mut dbs.execute("REBUILD TABLE person_by_age", &ses, None).await?;
+	res.remove(0).result?;
+	dbs.build_views().await?;
+	// The records are written both before and after the position of the rebuild
+	let sql = "
+		UPDATE person:1 SET age = 40;
+		UPDATE person:10001 SET age = 40;
+		SELECT * FROM person_by_age;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for _ in 0..2 {
+		res.remove(0).result?;
+	}
+	// The current records of the view keep being maintained
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ age: 39, count: -2, id: person_by_age:[39] },
+			{ age: 40, count: 2, id: person_by_age:[40] }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The rebuilt records include every write, exactly once
+	dbs.build_views().await?;
+	let res = &mut dbs.execute("SELECT * FROM person_by_age", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ age: 39, count: 9999, id: person_by_age:[39] },
+			{ age: 40, count: 2, id: person_by_age:[40] }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -5,7 +5,7 @@ use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
 use surrealdb::iam::Role;
-use surrealdb::sql::Value;
+use surrealdb::sql::{Datetime, Value};
 
 #[tokio::test]
 async fn select_field_value() -> Result<(), Error> {
@@ -774,6 +774,64 @@ async fn select_where_explain() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn select_where_explain_analyze() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		CREATE software:surreal SET name = 'SurrealDB';
+		SELECT * FROM person,software EXPLAIN ANALYZE;";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let _ = res.remove(0).result?;
+	let _ = res.remove(0).result?;
+	let _ = res.remove(0).result?;
+	//
+	let mut tmp = res.remove(0).result?;
+	// The time taken and the keys read by each iterator vary
+	if let Value::Array(items) = &mut tmp {
+		for item in items.iter_mut().take(2) {
+			if let Value::Object(item) = item {
+				if let Some(Value::Object(detail)) = item.get_mut("detail") {
+					assert!(matches!(detail.remove("time"), Some(Value::Duration(_))));
+					let scanned = detail.remove("scanned").unwrap_or_default();
+					assert!(scanned >= detail.get("rows").cloned().unwrap_or_default());
+				}
+			}
+		}
+	}
+	let val = Value::parse(
+		"[
+				{
+					detail: {
+						rows: 2,
+						table: 'person',
+					},
+					operation: 'Iterate Table'
+				},
+				{
+					detail: {
+						rows: 1,
+						table: 'software',
+					},
+					operation: 'Iterate Table'
+				},
+				{
+					detail: {
+						count: 3,
+					},
+					operation: 'Fetch'
+				},
+			]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn select_with_function_field() -> Result<(), Error> {
 	let sql = "SELECT *, function() { return this.a } AS b FROM [{ a: 1 }];";
@@ -790,6 +848,218 @@ async fn select_with_function_field() -> Result<(), Error> {
 // Permissions
 //
 
+#[tokio::test]
+async fn select_with_version() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let now = chrono::Utc::now().timestamp() as u64;
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for r in res.drain(..) {
+		assert!(r.result.is_ok());
+	}
+	dbs.tick_at(now).await?;
+	let sql = "
+		UPDATE person:tobie SET name = 'Tobias';
+		DELETE person:jaime;
+		CREATE person:lizzie SET name = 'Lizzie';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for r in res.drain(..) {
+		assert!(r.result.is_ok());
+	}
+	dbs.tick_at(now + 1).await?;
+	let at = Datetime::from(chrono::DateTime::from_timestamp(now as i64, 0).unwrap());
+	let sql = format!(
+		"
+		SELECT * FROM person VERSION {at};
+		SELECT * FROM person:jaime VERSION {at};
+		SELECT * FROM person:lizzie VERSION {at};
+		SELECT * FROM person:aaa..k VERSION {at};
+		SELECT * FROM person;
+	"
+	);
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:jaime,
+				name: 'Jaime',
+			},
+			{
+				id: person:tobie,
+				name: 'Tobie',
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:jaime,
+				name: 'Jaime',
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:jaime,
+				name: 'Jaime',
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:lizzie,
+				name: 'Lizzie',
+			},
+			{
+				id: person:tobie,
+				name: 'Tobias',
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_version_errors() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let now = chrono::Utc::now().timestamp();
+	let at = Datetime::from(chrono::DateTime::from_timestamp(now, 0).unwrap());
+	let sql = format!(
+		"
+		DEFINE TABLE person;
+		DEFINE TABLE animal CHANGEFEED 1h;
+		SELECT * FROM person VERSION '2020-01-01T00:00:00Z';
+		SELECT * FROM animal VERSION '2020-01-01T00:00:00Z';
+		SELECT * FROM animal VERSION {at};
+		SELECT * FROM plant VERSION {at};
+		SELECT * FROM animal WITH INDEX idx VERSION {at};
+		SELECT * FROM animal:dog->eats->plant VERSION {at};
+	"
+	);
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Versions are not supported on table 'person', as neither the table nor its database has a change feed"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Unable to read table 'animal' at '2020-01-01T00:00:00Z', as it is older than the change feed retention"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp.err(), Some(Error::VersionNotFound { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Versions are not supported on table 'plant', as neither the table nor its database has a change feed"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Versions are not supported on 'WITH INDEX idx', as graph edges and indexes only reflect the current data"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp.err(), Some(Error::VersionUnsupported { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_version_in_batches() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let now = chrono::Utc::now().timestamp() as u64;
+	let ids = (1..=120).map(|i| i.to_string()).collect::<Vec<_>>().join(", ");
+	let sql = format!(
+		"
+		DEFINE DATABASE test CHANGEFEED 1h;
+		FOR $i IN [{ids}] {{
+			CREATE type::thing('person', $i) SET num = $i;
+		}};
+		CREATE animal:dog;
+	"
+	);
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for r in res.drain(..) {
+		assert!(r.result.is_ok());
+	}
+	dbs.tick_at(now).await?;
+	let sql = "
+		UPDATE person:60 SET num = 0;
+		DELETE person:1, person:50, person:51, person:120;
+		CREATE person:0, person:121;
+		DELETE animal:dog;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for r in res.drain(..) {
+		assert!(r.result.is_ok());
+	}
+	dbs.tick_at(now + 1).await?;
+	let at = Datetime::from(chrono::DateTime::from_timestamp(now as i64, 0).unwrap());
+	let sql = format!(
+		"
+		SELECT VALUE num FROM person VERSION {at};
+		SELECT VALUE num FROM person:45..55 VERSION {at};
+	"
+	);
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from((1..=120i64).map(Value::from).collect::<Vec<_>>());
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from((45..55i64).map(Value::from).collect::<Vec<_>>());
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 async fn common_permissions_checks(auth_enabled: bool) {
 	let tests = vec![
 		// Root level
@@ -1008,3 +1278,72 @@ async fn check_permissions_auth_disabled() {
 		);
 	}
 }
+
+#[tokio::test]
+async fn select_with_memory_budget() -> Result<(), Error> {
+	let people = (1..=300).map(|i| format!("{{ id: {i}, age: {} }}", i % 3)).collect::<Vec<_>>();
+	let sql = format!(
+		"
+		INSERT INTO person [{}];
+		SELECT id, age FROM person ORDER BY age DESC, id LIMIT 4 START 1;
+		SELECT age, count() AS total FROM person GROUP BY age;
+		SELECT id FROM person ORDER BY id DESC EXPLAIN FULL;
+	",
+		people.join(", ")
+	);
+	// With an empty memory budget, the results are moved to disk in runs of a few results
+	let dbs = new_ds().await?.with_query_memory_budget(Some(0));
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let _ = res.remove(0).result?;
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:5, age: 2 },
+			{ id: person:8, age: 2 },
+			{ id: person:11, age: 2 },
+			{ id: person:14, age: 2 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ age: 0, total: 100 },
+			{ age: 1, total: 100 },
+			{ age: 2, total: 100 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					table: 'person',
+				},
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					count: 300,
+				},
+				operation: 'Fetch'
+			},
+			{
+				detail: {
+					runs: 4,
+					source: 'results',
+				},
+				operation: 'Spill'
+			},
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
@@ -0,0 +1,172 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn sync_changes_between_instances() -> Result<(), Error> {
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let central = new_ds().await?;
+	let edge = new_ds().await?;
+	for dbs in [&central, &edge] {
+		let res = &mut dbs.execute("DEFINE DATABASE test CHANGEFEED 1h", &ses, None).await?;
+		res.remove(0).result?;
+	}
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		DELETE person:jaime;
+	";
+	let res = &mut central.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for r in res.drain(..) {
+		r.result?;
+	}
+	// Pull the changes from the central instance and apply them on the edge
+	let changes = central.sync_changes(&ses, Some("edge"), None, None).await?;
+	assert_eq!(changes.len(), 3);
+	let vs = edge.sync_apply(&ses, changes).await?;
+	assert!(vs.is_some());
+	central.sync_ack(&ses, "edge", vs.unwrap()).await?;
+	// The acknowledged changes are not pulled again
+	let changes = central.sync_changes(&ses, Some("edge"), None, None).await?;
+	assert!(changes.is_empty());
+	// A versionstamp takes precedence over the checkpoint
+	let changes = central.sync_changes(&ses, Some("edge"), Some(0), Some(1)).await?;
+	assert_eq!(changes.len(), 1);
+	//
+	let res = &mut edge.execute("SELECT * FROM person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:tobie,
+				name: 'Tobie'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn sync_changes_are_not_sent_back() -> Result<(), Error> {
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let central = new_ds().await?;
+	let edge = new_ds().await?;
+	for dbs in [&central, &edge] {
+		let res = &mut dbs.execute("DEFINE DATABASE test CHANGEFEED 1h", &ses, None).await?;
+		res.remove(0).result?;
+	}
+	let res = &mut edge.execute("CREATE person:tobie SET name = 'Tobie'", &ses, None).await?;
+	res.remove(0).result?;
+	// Push the changes of the edge to the central instance
+	let changes = edge.sync_changes(&ses, Some("central"), None, None).await?;
+	let vs = central.sync_apply(&ses, changes).await?;
+	edge.sync_ack(&ses, "central", vs.unwrap()).await?;
+	// Pull the changes from the central instance back to the edge
+	let changes = central.sync_changes(&ses, Some("edge"), None, None).await?;
+	assert_eq!(changes.len(), 1);
+	let vs = edge.sync_apply(&ses, changes).await?;
+	central.sync_ack(&ses, "edge", vs.unwrap()).await?;
+	// Applying an existing change does not create a new change
+	let changes = edge.sync_changes(&ses, Some("central"), None, None).await?;
+	assert!(changes.is_empty());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn sync_changes_do_not_ping_pong() -> Result<(), Error> {
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let central = new_ds().await?;
+	let edge = new_ds().await?;
+	for dbs in [&central, &edge] {
+		let res = &mut dbs.execute("DEFINE DATABASE test CHANGEFEED 1h", &ses, None).await?;
+		res.remove(0).result?;
+	}
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	for r in edge.execute(sql, &ses, None).await? {
+		r.result?;
+	}
+	for r in central.execute("CREATE person:lizzie SET name = 'Lizzie'", &ses, None).await? {
+		r.result?;
+	}
+	let sql = "
+		UPDATE person:tobie SET name = 'Tobie Morgan Hitchcock';
+		DELETE person:jaime;
+	";
+	// Sync in both directions, until no changes are left to send
+	let mut rounds = 0;
+	loop {
+		let mut sent = 0;
+		for (from, to, peer) in [(&edge, &central, "central"), (&central, &edge, "edge")] {
+			let changes = from.sync_changes(&ses, Some(peer), None, None).await?;
+			sent += changes.len();
+			if let Some(vs) = to.sync_apply(&ses, changes).await? {
+				from.sync_ack(&ses, peer, vs).await?;
+			}
+		}
+		if sent == 0 {
+			break;
+		}
+		rounds += 1;
+		assert!(rounds <= 2, "the changes are sent back and forth between the peers");
+		// Make further changes on the edge after the first round
+		if rounds == 1 {
+			for r in edge.execute(sql, &ses, None).await? {
+				r.result?;
+			}
+		}
+	}
+	// Both peers hold the same records
+	let val = Value::parse(
+		"[
+			{
+				id: person:lizzie,
+				name: 'Lizzie'
+			},
+			{
+				id: person:tobie,
+				name: 'Tobie Morgan Hitchcock'
+			}
+		]",
+	);
+	for dbs in [&central, &edge] {
+		let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+		assert_eq!(res.remove(0).result?, val);
+	}
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn sync_changes_permissions() -> Result<(), Error> {
+	let dbs = new_ds().await?.with_auth_enabled(true);
+	let ses = Session::viewer().with_ns("test").with_db("test");
+	// A viewer can pull changes
+	let changes = dbs.sync_changes(&ses, Some("edge"), None, None).await?;
+	assert!(changes.is_empty());
+	// A viewer can not acknowledge changes
+	let res = dbs.sync_ack(&ses, "edge", 1).await;
+	assert!(matches!(res, Err(Error::IamError(_))));
+	// A viewer can not apply changes
+	let res = dbs.sync_apply(&ses, changes).await;
+	assert!(matches!(res, Err(Error::IamError(_))));
+	// An editor can acknowledge changes
+	let ses = Session::editor().with_ns("test").with_db("test");
+	dbs.sync_ack(&ses, "edge", 1).await?;
+	// A namespace and database are required
+	let res = dbs.sync_changes(&Session::owner(), None, None, None).await;
+	assert!(matches!(res, Err(Error::NsEmpty)));
+	//
+	Ok(())
+}
@@ -106,3 +106,228 @@ async fn define_foreign_table() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn define_foreign_table_aggregates() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_team AS
+			SELECT
+				count(),
+				count(age > 18) AS adults,
+				math::min(age) AS youngest,
+				math::max(age) AS oldest,
+				math::variance(age) AS variance,
+				math::stddev(age) AS stddev,
+				array::distinct(country) AS countries,
+				array::approx_distinct(country) AS approx,
+				team
+			FROM person
+			GROUP BY team
+		;
+		CREATE person:one SET team = 'a', age = 10, country = 'uk';
+		CREATE person:two SET team = 'a', age = 20, country = 'us';
+		SELECT * FROM person_by_team;
+		DELETE person:two;
+		SELECT * FROM person_by_team;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				adults: 1,
+				approx: 2,
+				count: 2,
+				countries: ['uk', 'us'],
+				id: person_by_team:['a'],
+				oldest: 20,
+				stddev: 7.0710678118654755,
+				team: 'a',
+				variance: 50.0,
+				youngest: 10
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				adults: 0,
+				approx: 2,
+				count: 1,
+				countries: ['uk'],
+				id: person_by_team:['a'],
+				oldest: 10,
+				stddev: 0.0,
+				team: 'a',
+				variance: 0.0,
+				youngest: 10
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_condition() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_team AS
+			SELECT count(), math::min(age) AS youngest, team
+			FROM person
+			WHERE age > 18
+			GROUP BY team
+		;
+		CREATE person:one SET team = 'a', age = 30;
+		CREATE person:two SET team = 'a', age = 20;
+		UPDATE person:two SET age = 10;
+		SELECT * FROM person_by_team;
+		UPDATE person:two SET age = 25;
+		SELECT * FROM person_by_team;
+		DELETE person:two;
+		SELECT * FROM person_by_team;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 1, id: person_by_team:['a'], team: 'a', youngest: 30 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 2, id: person_by_team:['a'], team: 'a', youngest: 25 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 1, id: person_by_team:['a'], team: 'a', youngest: 30 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_unsupported_aggregate() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person_by_age AS
+			SELECT math::median(score) AS median, age
+			FROM person
+			GROUP BY age
+		;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp,
+		Err(Error::InvalidViewAggregate { name }) if name == "math::median(score)"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_extremes() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_team AS
+			SELECT math::min(age) AS youngest, math::max(age) AS oldest, team
+			FROM person
+			GROUP BY team
+		;
+		CREATE person:one SET team = 'a', age = 10;
+		CREATE person:two SET team = 'a', age = 10;
+		CREATE person:three SET team = 'a', age = 20;
+		CREATE person:four SET team = 'b', age = 5;
+		DELETE person:one;
+		SELECT * FROM person_by_team:['a'];
+		DELETE person:two;
+		SELECT * FROM person_by_team:['a'];
+		UPDATE person:three SET age = 15;
+		SELECT * FROM person_by_team:['a'];
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 12);
+	//
+	for _ in 0..7 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Another record still holds the minimum
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person_by_team:['a'], oldest: 20, team: 'a', youngest: 10 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// The minimum is found again from the records of the group
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person_by_team:['a'], oldest: 20, team: 'a', youngest: 20 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person_by_team:['a'], oldest: 15, team: 'a', youngest: 15 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_nested_aggregate() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person_by_age AS
+			SELECT math::median(score) + 1 AS median, age
+			FROM person
+			GROUP BY age
+		;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp,
+		Err(Error::InvalidViewAggregate { name }) if name == "math::median(score)"
+	));
+	//
+	Ok(())
+}
@@ -2,10 +2,10 @@ mod parse;
 use parse::Parse;
 mod helpers;
 use helpers::new_ds;
-use surrealdb::dbs::Session;
+use surrealdb::dbs::{Action, Notification, Session};
 use surrealdb::err::Error;
 use surrealdb::iam::Role;
-use surrealdb::sql::Value;
+use surrealdb::sql::{Uuid, Value};
 
 #[tokio::test]
 async fn update_merge_and_content() -> Result<(), Error> {
@@ -681,3 +681,58 @@ async fn check_permissions_auth_disabled() {
 		);
 	}
 }
+
+#[tokio::test]
+async fn update_live_diff_notification() -> Result<(), Error> {
+	let dbs = new_ds().await?.with_notifications();
+	let ses = Session::owner().with_ns("test").with_db("test").with_rt(true);
+	let res = &mut dbs.execute("LIVE SELECT DIFF FROM person", &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	let live_id: Uuid = match res.remove(0).result? {
+		Value::Uuid(id) => id,
+		_ => panic!("expected uuid"),
+	};
+	let sql = "
+		CREATE person:test CONTENT { name: 'Tobie', tags: ['a', 'b', 'c'], 'a/b': 1 };
+		UPDATE person:test CONTENT { name: 'Jaime', tags: ['a'], 'a/b': 2 };
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	let notifications = dbs.notifications().expect("expected notifications");
+	// A new record replaces the whole document
+	let not = notifications.try_recv().unwrap();
+	assert_eq!(
+		not,
+		Notification {
+			id: live_id,
+			action: Action::Create,
+			result: Value::parse(
+				"[
+					{
+						op: 'replace',
+						path: '',
+						value: { id: person:test, name: 'Tobie', tags: ['a', 'b', 'c'], 'a/b': 1 },
+					}
+				]"
+			),
+		}
+	);
+	// An updated record only sends the changes
+	let not = notifications.try_recv().unwrap();
+	assert_eq!(
+		not,
+		Notification {
+			id: live_id,
+			action: Action::Update,
+			result: Value::parse(
+				"[
+					{ op: 'replace', path: '/a~1b', value: 2 },
+					{ op: 'replace', path: '/name', value: 'Jaime' },
+					{ op: 'remove', path: '/tags/2' },
+					{ op: 'remove', path: '/tags/1' },
+				]"
+			),
+		}
+	);
+	Ok(())
+}
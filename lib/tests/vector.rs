@@ -4,7 +4,7 @@ use crate::helpers::new_ds;
 use parse::Parse;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
-use surrealdb::sql::Value;
+use surrealdb::sql::{Part, Value};
 
 #[tokio::test]
 async fn select_where_mtree_knn() -> Result<(), Error> {
@@ -58,3 +58,47 @@ async fn select_where_mtree_knn() -> Result<(), Error> {
 	assert_eq!(format!("{:#}", tmp), format!("{:#}", val));
 	Ok(())
 }
+
+#[tokio::test]
+async fn delete_update_mtree_index() -> Result<(), Error> {
+	let sql = r"
+		CREATE pts:1 SET point = [1,2,3,4];
+		CREATE pts:2 SET point = [4,5,6,7];
+		CREATE pts:3 SET point = [8,9,10,11];
+		CREATE pts:4 SET point = [12,13,14,15];
+		CREATE pts:5 SET point = [16,17,18,19];
+		DEFINE INDEX mt_pts ON pts FIELDS point MTREE DIMENSION 4 CAPACITY 2;
+		DELETE pts:2;
+		UPDATE pts:3 SET point = [20,21,22,23];
+		DELETE pts:5;
+		LET $pt = [2,3,4,5];
+		SELECT id FROM pts WHERE point <2> $pt;
+		ANALYZE INDEX mt_pts ON pts;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 12);
+	//
+	for _ in 0..10 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: pts:1
+			},
+			{
+				id: pts:4
+			}
+		]",
+	);
+	assert_eq!(format!("{:#}", tmp), format!("{:#}", val));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&[Part::from("objects_count")]), Value::from(3));
+	assert_eq!(tmp.pick(&[Part::from("docs_count")]), Value::from(3));
+	assert_eq!(tmp.pick(&[Part::from("consistent")]), Value::from(true));
+	assert_eq!(tmp.pick(&[Part::from("inconsistencies")]), Value::parse("[]"));
+	Ok(())
+}
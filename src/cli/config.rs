@@ -1,6 +1,8 @@
 #[cfg(feature = "has-storage")]
 use crate::net::client_ip::ClientIp;
 #[cfg(feature = "has-storage")]
+use ipnet::IpNet;
+#[cfg(feature = "has-storage")]
 use std::sync::OnceLock;
 use std::{net::SocketAddr, path::PathBuf};
 
@@ -15,8 +17,12 @@ pub struct Config {
 	pub path: String,
 	#[cfg(feature = "has-storage")]
 	pub client_ip: ClientIp,
+	#[cfg(feature = "has-storage")]
+	pub trusted_proxies: Vec<IpNet>,
 	pub user: Option<String>,
 	pub pass: Option<String>,
+	#[cfg(feature = "has-storage")]
+	pub encryption_key: Option<String>,
 	pub crt: Option<PathBuf>,
 	pub key: Option<PathBuf>,
 	pub tick_interval: Duration,
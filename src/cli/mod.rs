@@ -4,6 +4,8 @@ mod config;
 mod export;
 mod import;
 mod isready;
+#[cfg(feature = "has-storage")]
+mod rotate_key;
 mod sql;
 #[cfg(feature = "has-storage")]
 mod start;
@@ -20,6 +22,8 @@ pub use config::CF;
 use export::ExportCommandArguments;
 use import::ImportCommandArguments;
 use isready::IsReadyCommandArguments;
+#[cfg(feature = "has-storage")]
+use rotate_key::RotateKeyCommandArguments;
 use sql::SqlCommandArguments;
 #[cfg(feature = "has-storage")]
 use start::StartCommandArguments;
@@ -75,6 +79,11 @@ enum Commands {
 	IsReady(IsReadyCommandArguments),
 	#[command(about = "Validate SurrealQL query files")]
 	Validate(ValidateCommandArguments),
+	#[cfg(feature = "has-storage")]
+	#[command(
+		about = "Re-encrypt an on-disk datastore with a new encryption key, while the server is stopped"
+	)]
+	RotateKey(RotateKeyCommandArguments),
 }
 
 pub async fn init() -> ExitCode {
@@ -90,6 +99,8 @@ pub async fn init() -> ExitCode {
 		Commands::Sql(args) => sql::init(args).await,
 		Commands::IsReady(args) => isready::init(args).await,
 		Commands::Validate(args) => validate::init(args).await,
+		#[cfg(feature = "has-storage")]
+		Commands::RotateKey(args) => rotate_key::init(args).await,
 	};
 	if let Err(e) = output {
 		error!("{}", e);
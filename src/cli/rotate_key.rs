@@ -0,0 +1,37 @@
+use crate::err::Error;
+use clap::Args;
+use surrealdb::kvs::Datastore;
+
+#[derive(Args, Debug)]
+pub struct RotateKeyCommandArguments {
+	#[arg(help = "Database path of the datastore to re-encrypt")]
+	#[arg(env = "SURREAL_PATH", index = 1)]
+	#[arg(value_parser = super::validator::path_valid)]
+	path: String,
+	#[arg(help = "The encryption key which the datastore is currently encrypted with")]
+	#[arg(env = "SURREAL_KEY", short = 'k', long = "key")]
+	#[arg(value_parser = super::validator::key_valid)]
+	key: Option<String>,
+	#[arg(help = "The encryption key to re-encrypt the datastore with, or none to decrypt it")]
+	#[arg(env = "SURREAL_NEW_KEY", long = "new-key")]
+	#[arg(value_parser = super::validator::key_valid)]
+	new_key: Option<String>,
+}
+
+pub async fn init(
+	RotateKeyCommandArguments {
+		path,
+		key,
+		new_key,
+	}: RotateKeyCommandArguments,
+) -> Result<(), Error> {
+	// Initialize opentelemetry and logging
+	crate::telemetry::builder().with_log_level("info").init();
+	// Open the datastore with the current key
+	let ds = Datastore::new_with_key(&path, key.as_deref()).await?;
+	// Re-encrypt every value with the new key
+	ds.rotate_key(new_key.as_deref()).await?;
+	info!("The encryption key of the datastore was rotated successfully");
+	// All ok
+	Ok(())
+}
@@ -10,6 +10,7 @@ use crate::err::Error;
 use crate::net::{self, client_ip::ClientIp};
 use crate::node;
 use clap::Args;
+use ipnet::IpNet;
 use opentelemetry::Context as TelemetryContext;
 use std::net::SocketAddr;
 use std::path::PathBuf;
@@ -35,7 +36,6 @@ pub struct StartCommandArguments {
 	#[arg(help = "Encryption key to use for on-disk encryption")]
 	#[arg(env = "SURREAL_KEY", short = 'k', long = "key")]
 	#[arg(value_parser = super::validator::key_valid)]
-	#[arg(hide = true)] // Not currently in use
 	key: Option<String>,
 
 	#[arg(
@@ -91,6 +91,11 @@ pub struct StartCommandArguments {
 	#[arg(env = "SURREAL_CLIENT_IP", long)]
 	#[arg(default_value = "socket", value_enum)]
 	client_ip: ClientIp,
+	#[arg(
+		help = "The comma-separated list of proxy networks, in CIDR notation, whose client IP headers are trusted. Without any, the Forwarded and X-Forwarded-For headers are ignored"
+	)]
+	#[arg(env = "SURREAL_TRUSTED_PROXIES", long = "trusted-proxies", value_delimiter = ',')]
+	trusted_proxies: Vec<IpNet>,
 	#[arg(help = "The hostname or ip address to listen for connections on")]
 	#[arg(env = "SURREAL_BIND", short = 'b', long = "bind")]
 	#[arg(default_value = "0.0.0.0:8000")]
@@ -135,12 +140,14 @@ pub async fn init(
 		username: user,
 		password: pass,
 		client_ip,
+		trusted_proxies,
 		listen_addresses,
 		dbs,
 		web,
 		log,
 		tick_interval,
 		no_banner,
+		key: encryption_key,
 		..
 	}: StartCommandArguments,
 ) -> Result<(), Error> {
@@ -159,9 +166,11 @@ pub async fn init(
 	let _ = config::CF.set(Config {
 		bind: listen_addresses.first().cloned().unwrap(),
 		client_ip,
+		trusted_proxies,
 		path,
 		user,
 		pass,
+		encryption_key,
 		tick_interval,
 		crt: web.as_ref().and_then(|x| x.web_crt.clone()),
 		key: web.as_ref().and_then(|x| x.web_key.clone()),
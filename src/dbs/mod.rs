@@ -4,7 +4,7 @@ use clap::Args;
 use std::sync::OnceLock;
 use std::time::Duration;
 use surrealdb::dbs::capabilities::{Capabilities, FuncTarget, NetTarget, Targets};
-use surrealdb::kvs::Datastore;
+use surrealdb::kvs::{CompactionStyle, Compression, Datastore, RocksDbOptions};
 use surrealdb::opt::auth::Root;
 
 pub static DB: OnceLock<Datastore> = OnceLock::new();
@@ -23,6 +23,13 @@ pub struct StartCommandDbsOptions {
 	#[arg(env = "SURREAL_TRANSACTION_TIMEOUT", long)]
 	#[arg(value_parser = super::cli::validator::duration)]
 	transaction_timeout: Option<Duration>,
+	#[arg(help = "The maximum number of bytes that a query can buffer before spilling to disk")]
+	#[arg(env = "SURREAL_QUERY_MEMORY_BUDGET", long)]
+	query_memory_budget: Option<usize>,
+	#[arg(help = "The duration above which a statement is logged as a slow query")]
+	#[arg(env = "SURREAL_SLOW_QUERY_THRESHOLD", long)]
+	#[arg(value_parser = super::cli::validator::duration)]
+	slow_query_threshold: Option<Duration>,
 	#[arg(help = "Whether to enable authentication", help_heading = "Authentication")]
 	#[arg(env = "SURREAL_AUTH", long = "auth")]
 	#[arg(default_value_t = false)]
@@ -30,6 +37,69 @@ pub struct StartCommandDbsOptions {
 	#[command(flatten)]
 	#[command(next_help_heading = "Capabilities")]
 	caps: DbsCapabilities,
+	#[command(flatten)]
+	#[command(next_help_heading = "Storage engine")]
+	rocksdb: DbsRocksDbOptions,
+}
+
+#[derive(Args, Debug)]
+struct DbsRocksDbOptions {
+	#[arg(
+		help = "The size of the block cache of the rocksdb and speedb storage engines, in bytes"
+	)]
+	#[arg(env = "SURREAL_ROCKSDB_BLOCK_CACHE_SIZE", long)]
+	rocksdb_block_cache_size: Option<usize>,
+	#[arg(
+		help = "The size of each write buffer of the rocksdb and speedb storage engines, in bytes"
+	)]
+	#[arg(env = "SURREAL_ROCKSDB_WRITE_BUFFER_SIZE", long)]
+	rocksdb_write_buffer_size: Option<usize>,
+	#[arg(
+		help = "The maximum number of concurrent flushes and compactions, which defaults to the number of cores"
+	)]
+	#[arg(env = "SURREAL_ROCKSDB_MAX_BACKGROUND_JOBS", long)]
+	rocksdb_max_background_jobs: Option<i32>,
+	#[arg(
+		help = "The comma-separated compression of each level of the LSM tree, among none, snappy, lz4, and zstd",
+		long_help = r#"The comma-separated compression of each level of the LSM tree, among none, snappy, lz4, and zstd.
+The last compression applies to any deeper level. For example:
+ - 'none,none,lz4,lz4,lz4,lz4,zstd' -> The default, leaving the levels which are rewritten most often uncompressed
+ - 'zstd' -> Compress every level with zstd
+"#
+	)]
+	#[arg(env = "SURREAL_ROCKSDB_COMPRESSION_PER_LEVEL", long, value_delimiter = ',')]
+	rocksdb_compression_per_level: Option<Vec<Compression>>,
+	#[arg(help = "Whether commits wait for the write-ahead log to be synced to disk")]
+	#[arg(env = "SURREAL_ROCKSDB_SYNC_WRITES", long)]
+	#[arg(default_value_t = false)]
+	rocksdb_sync_writes: bool,
+	#[arg(
+		help = "The compaction style of the rocksdb and speedb storage engines, either level or universal"
+	)]
+	#[arg(env = "SURREAL_ROCKSDB_COMPACTION_STYLE", long)]
+	rocksdb_compaction_style: Option<CompactionStyle>,
+}
+
+impl From<DbsRocksDbOptions> for RocksDbOptions {
+	fn from(opts: DbsRocksDbOptions) -> Self {
+		let mut res = RocksDbOptions::default().with_sync_writes(opts.rocksdb_sync_writes);
+		if let Some(v) = opts.rocksdb_block_cache_size {
+			res = res.with_block_cache_size(v);
+		}
+		if let Some(v) = opts.rocksdb_write_buffer_size {
+			res = res.with_write_buffer_size(v);
+		}
+		if let Some(v) = opts.rocksdb_max_background_jobs {
+			res = res.with_max_background_jobs(v);
+		}
+		if let Some(v) = opts.rocksdb_compression_per_level {
+			res = res.with_compression_per_level(v);
+		}
+		if let Some(v) = opts.rocksdb_compaction_style {
+			res = res.with_compaction_style(v);
+		}
+		res
+	}
 }
 
 #[derive(Args, Debug)]
@@ -203,8 +273,11 @@ pub async fn init(
 		strict_mode,
 		query_timeout,
 		transaction_timeout,
+		query_memory_budget,
+		slow_query_threshold,
 		auth_enabled,
 		caps,
+		rocksdb,
 	}: StartCommandDbsOptions,
 ) -> Result<(), Error> {
 	// Get local copy of options
@@ -219,6 +292,14 @@ pub async fn init(
 	if let Some(v) = transaction_timeout {
 		debug!("Maximum transaction processing timeout is {v:?}");
 	}
+	// Log specified query memory budget
+	if let Some(v) = query_memory_budget {
+		debug!("Maximum query memory budget is {v} bytes");
+	}
+	// Log specified slow query threshold
+	if let Some(v) = slow_query_threshold {
+		debug!("Slow query threshold is {v:?}");
+	}
 	// Log whether authentication is enabled
 	if auth_enabled {
 		info!("✅🔒 Authentication is enabled 🔒✅");
@@ -229,13 +310,18 @@ pub async fn init(
 	let caps = caps.into();
 	debug!("Server capabilities: {caps}");
 
+	let rocksdb = RocksDbOptions::from(rocksdb);
+	debug!("Storage engine options: {rocksdb:?}");
+
 	// Parse and setup the desired kv datastore
-	let dbs = Datastore::new(&opt.path)
+	let dbs = Datastore::new_with_options(&opt.path, opt.encryption_key.as_deref(), &rocksdb)
 		.await?
 		.with_notifications()
 		.with_strict_mode(strict_mode)
 		.with_query_timeout(query_timeout)
 		.with_transaction_timeout(transaction_timeout)
+		.with_query_memory_budget(query_memory_budget)
+		.with_slow_query_threshold(slow_query_threshold)
 		.with_auth_enabled(auth_enabled)
 		.with_capabilities(caps);
 
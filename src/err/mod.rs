@@ -2,6 +2,7 @@ use axum::response::{IntoResponse, Response};
 use axum::Json;
 use base64::DecodeError as Base64Error;
 use http::StatusCode;
+use opentelemetry::metrics::MetricsError;
 use reqwest::Error as ReqwestError;
 use serde::Serialize;
 use serde_cbor::error::Error as CborError;
@@ -37,6 +38,9 @@ pub enum Error {
 	#[error("The operation is unsupported")]
 	OperationUnsupported,
 
+	#[error("The specified cursor is invalid")]
+	InvalidCursor,
+
 	#[error("There was a problem with the database: {0}")]
 	Db(#[from] SurrealError),
 
@@ -57,6 +61,9 @@ pub enum Error {
 
 	#[error("There was an error with the node agent")]
 	NodeAgent,
+
+	#[error("There was an error collecting the metrics: {0}")]
+	Metrics(#[from] MetricsError),
 }
 
 impl From<Error> for String {
@@ -8,14 +8,14 @@ use axum::Extension;
 use axum::RequestPartsExt;
 use clap::ValueEnum;
 use http::request::Parts;
+use http::HeaderMap;
 use http::Request;
 use http::StatusCode;
-use std::net::SocketAddr;
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
 
 use super::AppState;
 
-// TODO: Support Forwarded, X-Forwarded-For headers.
-// Get inspiration from https://github.com/imbolc/axum-client-ip or simply use it
 #[derive(ValueEnum, Clone, Copy, Debug)]
 pub enum ClientIp {
 	/// Don't use client IP
@@ -37,6 +37,9 @@ pub enum ClientIp {
 	/// Industry standard header used by many proxies
 	#[clap(name = "X-Forwarded-For")]
 	XForwardedFor,
+	/// Standard header defined by RFC 7239
+	#[clap(name = "Forwarded")]
+	Forwarded,
 }
 
 impl std::fmt::Display for ClientIp {
@@ -49,6 +52,7 @@ impl std::fmt::Display for ClientIp {
 			ClientIp::TrueClientIP => write!(f, "True-Client-IP"),
 			ClientIp::XRealIp => write!(f, "X-Real-IP"),
 			ClientIp::XForwardedFor => write!(f, "X-Forwarded-For"),
+			ClientIp::Forwarded => write!(f, "Forwarded"),
 		}
 	}
 }
@@ -63,8 +67,88 @@ impl ClientIp {
 			ClientIp::TrueClientIP => true,
 			ClientIp::XRealIp => true,
 			ClientIp::XForwardedFor => true,
+			ClientIp::Forwarded => true,
 		}
 	}
+
+	/// Checks if the header lists every proxy hop, which any client can prepend hops to
+	fn is_chain(&self) -> bool {
+		matches!(self, ClientIp::XForwardedFor | ClientIp::Forwarded)
+	}
+
+	/// Returns the addresses of the hops found in the headers, from the client to the
+	/// last proxy. Headers with a single address only contain the client address.
+	fn hops(&self, headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+		let values = headers.get_all(self.to_string()).iter().filter_map(|v| match v.to_str() {
+			Ok(v) => Some(v),
+			Err(err) => {
+				debug!("Invalid header value for {}: {}", self, err);
+				None
+			}
+		});
+		match self {
+			ClientIp::XForwardedFor => {
+				values.flat_map(|v| v.split(',')).map(|v| parse_node(v.trim())).collect()
+			}
+			ClientIp::Forwarded => values.flat_map(|v| v.split(',')).map(parse_forwarded).collect(),
+			_ => values.map(|v| parse_node(v.trim())).collect(),
+		}
+	}
+}
+
+/// Extracts the node of the `for` parameter of a `Forwarded` element (RFC 7239)
+fn parse_forwarded(element: &str) -> Option<IpAddr> {
+	element.split(';').find_map(|pair| {
+		let (name, value) = pair.split_once('=')?;
+		if name.trim().eq_ignore_ascii_case("for") {
+			parse_node(value.trim().trim_matches('"'))
+		} else {
+			None
+		}
+	})
+}
+
+/// Parses an IP address, with an optional port. IPv6 addresses with
+/// a port are enclosed in brackets. Obfuscated or unknown nodes are
+/// not IP addresses, so they can't be parsed.
+fn parse_node(node: &str) -> Option<IpAddr> {
+	if let Ok(ip) = node.parse::<IpAddr>() {
+		return Some(ip);
+	}
+	if let Ok(addr) = node.parse::<SocketAddr>() {
+		return Some(addr.ip());
+	}
+	node.strip_prefix('[')?.split(']').next()?.parse().ok()
+}
+
+/// Finds the client address from the peer address and the addresses of the hops
+/// in the headers. The hops are only trusted while they are trusted proxies, so
+/// the client is the last address, from the peer, which is not a trusted proxy.
+/// Without any trusted proxy, the hops of a chain are written by the client, so
+/// the peer is the client, while the headers with a single address are trusted.
+fn resolve(
+	peer: Option<IpAddr>,
+	hops: Vec<Option<IpAddr>>,
+	trusted: &[IpNet],
+	chain: bool,
+) -> Option<IpAddr> {
+	if trusted.is_empty() {
+		return if chain {
+			peer
+		} else {
+			hops.into_iter().next().flatten()
+		};
+	}
+	let is_trusted = |ip: &IpAddr| trusted.iter().any(|net| net.contains(ip));
+	let mut client = peer?;
+	for hop in hops.into_iter().rev() {
+		if !is_trusted(&client) {
+			break;
+		}
+		// An unknown hop hides the addresses which precede it
+		client = hop?;
+	}
+	Some(client)
 }
 
 pub(super) struct ExtractClientIP(pub Option<String>);
@@ -93,16 +177,13 @@ where
 			}
 			// Get the IP from the corresponding header
 			var if var.is_header() => {
-				if let Some(ip) = parts.headers.get(var.to_string()) {
-					ip.to_str().map(|s| ExtractClientIP(Some(s.to_string()))).unwrap_or_else(
-						|err| {
-							debug!("Invalid header value for {}: {}", var, err);
-							ExtractClientIP(None)
-						},
-					)
-				} else {
-					ExtractClientIP(None)
-				}
+				let peer = ConnectInfo::<SocketAddr>::from_request_parts(parts, state)
+					.await
+					.ok()
+					.map(|ConnectInfo(addr)| addr.ip());
+				let hops = var.hops(&parts.headers);
+				let ip = resolve(peer, hops, &app_state.trusted_proxies, var.is_chain());
+				ExtractClientIP(ip.map(|ip| ip.to_string()))
 			}
 			_ => {
 				warn!("Unexpected ClientIp variant: {:?}", app_state.client_ip);
@@ -133,3 +214,79 @@ where
 
 	Ok(next.run(Request::from_parts(parts, body)).await)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use http::HeaderValue;
+
+	fn ip(s: &str) -> IpAddr {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn parse_forwarded_elements() {
+		assert_eq!(
+			parse_forwarded("for=192.0.2.60;proto=http;by=203.0.113.43"),
+			Some(ip("192.0.2.60"))
+		);
+		assert_eq!(
+			parse_forwarded(" For=\"[2001:db8:cafe::17]:4711\""),
+			Some(ip("2001:db8:cafe::17"))
+		);
+		assert_eq!(parse_forwarded("for=192.0.2.43:47011"), Some(ip("192.0.2.43")));
+		assert_eq!(parse_forwarded("for=_hidden"), None);
+		assert_eq!(parse_forwarded("for=unknown"), None);
+		assert_eq!(parse_forwarded("proto=https"), None);
+	}
+
+	#[test]
+	fn forwarded_hops() {
+		let mut headers = HeaderMap::new();
+		headers.append("Forwarded", HeaderValue::from_static("for=192.0.2.43, for=198.51.100.17"));
+		headers.append("Forwarded", HeaderValue::from_static("for=\"[2001:db8::1]\";proto=https"));
+		assert_eq!(
+			ClientIp::Forwarded.hops(&headers),
+			vec![Some(ip("192.0.2.43")), Some(ip("198.51.100.17")), Some(ip("2001:db8::1"))]
+		);
+	}
+
+	#[test]
+	fn x_forwarded_for_hops() {
+		let mut headers = HeaderMap::new();
+		headers.append("X-Forwarded-For", HeaderValue::from_static("203.0.113.195, 70.41.3.18"));
+		headers.append("X-Forwarded-For", HeaderValue::from_static("150.172.238.178"));
+		assert_eq!(
+			ClientIp::XForwardedFor.hops(&headers),
+			vec![Some(ip("203.0.113.195")), Some(ip("70.41.3.18")), Some(ip("150.172.238.178"))]
+		);
+	}
+
+	#[test]
+	fn resolve_with_trusted_proxies() {
+		let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+		let hops = || vec![Some(ip("1.1.1.1")), Some(ip("2.2.2.2")), Some(ip("10.0.0.2"))];
+		// The chain of trusted proxies ends with the client
+		assert_eq!(resolve(Some(ip("10.0.0.1")), hops(), &trusted, true), Some(ip("2.2.2.2")));
+		// The headers of an untrusted peer are ignored
+		assert_eq!(resolve(Some(ip("3.3.3.3")), hops(), &trusted, true), Some(ip("3.3.3.3")));
+		// Every hop is a trusted proxy
+		let hops = vec![Some(ip("10.0.0.3")), Some(ip("10.0.0.2"))];
+		assert_eq!(resolve(Some(ip("10.0.0.1")), hops, &trusted, true), Some(ip("10.0.0.3")));
+		// An unknown hop hides the client
+		let hops = vec![Some(ip("1.1.1.1")), None];
+		assert_eq!(resolve(Some(ip("10.0.0.1")), hops, &trusted, true), None);
+	}
+
+	#[test]
+	fn resolve_without_trusted_proxies() {
+		// The hops of a chain can be spoofed by the client
+		let hops = vec![Some(ip("1.1.1.1")), Some(ip("2.2.2.2"))];
+		assert_eq!(resolve(Some(ip("3.3.3.3")), hops, &[], true), Some(ip("3.3.3.3")));
+		assert_eq!(resolve(Some(ip("3.3.3.3")), vec![], &[], true), Some(ip("3.3.3.3")));
+		// The headers with a single address are trusted
+		let hops = vec![Some(ip("1.1.1.1"))];
+		assert_eq!(resolve(Some(ip("3.3.3.3")), hops, &[], false), Some(ip("1.1.1.1")));
+		assert_eq!(resolve(Some(ip("3.3.3.3")), vec![], &[], false), None);
+	}
+}
@@ -10,6 +10,7 @@ use tower_http::set_header::SetResponseHeaderLayer;
 pub(super) const ID: &str = "ID";
 pub(super) const NS: &str = "NS";
 pub(super) const DB: &str = "DB";
+pub(super) const CURSOR: &str = "surreal-cursor";
 const SERVER: &str = "server";
 const VERSION: &str = "version";
 
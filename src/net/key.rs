@@ -9,14 +9,17 @@ use axum::routing::options;
 use axum::{Extension, Router, TypedHeader};
 use axum_extra::extract::Query;
 use bytes::Bytes;
+use http::{HeaderMap, HeaderName, HeaderValue};
 use http_body::Body as HttpBody;
 use serde::Deserialize;
+use std::ops::Bound;
 use std::str;
 use surrealdb::dbs::Session;
-use surrealdb::sql::Value;
+use surrealdb::opt::Cursor;
+use surrealdb::sql::{Range, Table, Value};
 use tower_http::limit::RequestBodyLimitLayer;
 
-use super::headers::Accept;
+use super::headers::{Accept, CURSOR};
 
 const MAX: usize = 1024 * 16; // 16 KiB
 
@@ -25,6 +28,7 @@ struct QueryOptions {
 	pub limit: Option<i64>,
 	pub start: Option<i64>,
 	pub fields: Option<Vec<String>>,
+	pub cursor: Option<String>,
 }
 
 pub(super) fn router<S, B>() -> Router<S, B>
@@ -74,30 +78,82 @@ async fn select_all(
 ) -> Result<impl IntoResponse, impl IntoResponse> {
 	// Get the datastore reference
 	let db = DB.get().unwrap();
-	// Specify the request statement
+	// Parse the continuation cursor
+	let cursor = match query.cursor {
+		Some(cursor) => Some(cursor.parse::<Cursor>().map_err(|_| Error::InvalidCursor)?),
+		None => None,
+	};
+	// Continue after the last record of the previous page
+	let what = match cursor {
+		// A cursor only continues the pages of its own table
+		Some(cursor) if cursor.table() != table => return Err(Error::InvalidCursor),
+		Some(cursor) => Value::from(Range {
+			tb: table,
+			beg: Bound::Excluded(cursor.into_id()),
+			end: Bound::Unbounded,
+		}),
+		None => Value::Table(Table::from(table)),
+	};
+	// Specify the request statement. The records of the page are scanned first, so
+	// that the cursor to the next page does not depend on the fields which are returned.
 	let sql = match query.fields {
-		None => "SELECT * FROM type::table($table) LIMIT $limit START $start",
-		_ => "SELECT type::fields($fields) FROM type::table($table) LIMIT $limit START $start",
+		None => {
+			"LET $page = SELECT VALUE id FROM $what LIMIT $limit START $start;
+			SELECT * FROM $page;
+			RETURN $page;"
+		}
+		_ => {
+			"LET $page = SELECT VALUE id FROM $what LIMIT $limit START $start;
+			SELECT type::fields($fields) FROM $page;
+			RETURN $page;"
+		}
 	};
 	// Specify the request variables
+	let limit = query.limit.unwrap_or(100);
 	let vars = map! {
-		String::from("table") => Value::from(table),
+		String::from("what") => what,
 		String::from("start") => Value::from(query.start.unwrap_or(0)),
-		String::from("limit") => Value::from(query.limit.unwrap_or(100)),
+		String::from("limit") => Value::from(limit),
 		String::from("fields") => Value::from(query.fields.unwrap_or_default()),
 	};
 	// Execute the query and return the result
 	match db.execute(sql, &session, Some(vars)).await {
-		Ok(ref res) => match maybe_output.as_deref() {
-			// Simple serialization
-			Some(Accept::ApplicationJson) => Ok(output::json(&output::simplify(res))),
-			Some(Accept::ApplicationCbor) => Ok(output::cbor(&output::simplify(res))),
-			Some(Accept::ApplicationPack) => Ok(output::pack(&output::simplify(res))),
-			// Internal serialization
-			Some(Accept::Surrealdb) => Ok(output::full(&res)),
-			// An incorrect content-type was requested
-			_ => Err(Error::InvalidType),
-		},
+		Ok(res) => {
+			let mut res = res.into_iter();
+			let (scan, records, page) = (res.next(), res.next(), res.next());
+			// Only the records of the page are returned, or the error of the scan
+			let res: Vec<_> = match scan {
+				Some(scan) if scan.result.is_err() => vec![scan],
+				_ => records.into_iter().collect(),
+			};
+			// Return a cursor to the next page, if this page is full
+			let mut headers = HeaderMap::new();
+			if let Some(Ok(Value::Array(ids))) = page.map(|r| r.result) {
+				if ids.len() as i64 == limit {
+					if let Some(cursor) = ids.last().and_then(Cursor::from_record) {
+						if let Ok(val) = HeaderValue::from_str(&cursor.to_string()) {
+							headers.insert(HeaderName::from_static(CURSOR), val);
+						}
+					}
+				}
+			}
+			match maybe_output.as_deref() {
+				// Simple serialization
+				Some(Accept::ApplicationJson) => {
+					Ok((headers, output::json(&output::simplify(res))))
+				}
+				Some(Accept::ApplicationCbor) => {
+					Ok((headers, output::cbor(&output::simplify(res))))
+				}
+				Some(Accept::ApplicationPack) => {
+					Ok((headers, output::pack(&output::simplify(res))))
+				}
+				// Internal serialization
+				Some(Accept::Surrealdb) => Ok((headers, output::full(&res))),
+				// An incorrect content-type was requested
+				_ => Err(Error::InvalidType),
+			}
+		}
 		// There was an error when executing the query
 		Err(err) => Err(Error::from(err)),
 	}
@@ -485,3 +541,66 @@ async fn delete_one(
 		Err(err) => Err(Error::from(err)),
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::body::Body;
+	use http::{header, Request, StatusCode};
+	use serde_json::json;
+	use surrealdb::kvs::Datastore;
+	use test_log::test;
+	use tower::ServiceExt;
+
+	/// Selects the records of a table, returning the status, the cursor header, and the records
+	async fn get(uri: &str) -> (StatusCode, Option<String>, serde_json::Value) {
+		let session = Session::owner().with_ns("test").with_db("test");
+		let app = router::<(), Body>().layer(Extension(session));
+		let req = Request::get(uri)
+			.header(header::ACCEPT, "application/json")
+			.body(Body::empty())
+			.unwrap();
+		let res = app.oneshot(req).await.unwrap();
+		let status = res.status();
+		let cursor = res.headers().get(CURSOR).map(|v| v.to_str().unwrap().to_owned());
+		let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+		let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		(status, cursor, body[0]["result"].clone())
+	}
+
+	#[test(tokio::test)]
+	async fn select_all_with_cursor() {
+		let _ = DB.set(Datastore::new("memory").await.unwrap());
+		let session = Session::owner().with_ns("test").with_db("test");
+		let sql = "
+			CREATE person:1 SET name = 'a';
+			CREATE person:2 SET name = 'b';
+			CREATE person:3 SET name = 'c';
+			CREATE person:4 SET name = 'd';
+			CREATE person:5 SET name = 'e';
+		";
+		DB.get().unwrap().execute(sql, &session, None).await.unwrap();
+		// The cursor does not depend on the fields which are returned
+		let (status, cursor, res) = get("/key/person?limit=2&fields=name").await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(res, json!([{ "name": "a" }, { "name": "b" }]));
+		let cursor = cursor.unwrap();
+		// Records created before the cursor do not shift the next page
+		DB.get().unwrap().execute("CREATE person:0", &session, None).await.unwrap();
+		let (status, next, res) =
+			get(&format!("/key/person?limit=2&fields=name&cursor={cursor}")).await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(res, json!([{ "name": "c" }, { "name": "d" }]));
+		// The last page is not full, so there is no cursor to a next page
+		let (status, last, res) =
+			get(&format!("/key/person?limit=2&cursor={}", next.unwrap())).await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(res, json!([{ "id": "person:5", "name": "e" }]));
+		assert_eq!(last, None);
+		// A cursor only continues the pages of its own table
+		let (status, ..) = get(&format!("/key/animal?limit=2&cursor={cursor}")).await;
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+		let (status, ..) = get("/key/person?limit=2&cursor=!").await;
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+	}
+}
@@ -0,0 +1,32 @@
+use crate::dbs::DB;
+use crate::err::Error;
+use crate::telemetry;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Extension, Router};
+use http::header::CONTENT_TYPE;
+use http_body::Body as HttpBody;
+use surrealdb::dbs::Session;
+
+/// The content type of the Prometheus text format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+pub(super) fn router<S, B>() -> Router<S, B>
+where
+	B: HttpBody + Send + 'static,
+	S: Clone + Send + Sync + 'static,
+{
+	Router::new().route("/metrics", get(handler))
+}
+
+async fn handler(Extension(session): Extension<Session>) -> Result<impl IntoResponse, Error> {
+	// Get the datastore reference
+	let db = DB.get().unwrap();
+	// Encode the engine metrics, if the user is allowed to view them
+	let mut out = String::new();
+	surrealdb::metrics::encode(db, &session, &mut out).await?;
+	// Encode the server metrics
+	telemetry::metrics::encode(&mut out)?;
+	// Return the metrics
+	Ok(([(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], out))
+}
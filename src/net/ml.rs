@@ -0,0 +1,41 @@
+use crate::dbs::DB;
+use crate::err::Error;
+use crate::net::output;
+use axum::extract::DefaultBodyLimit;
+use axum::response::IntoResponse;
+use axum::routing::post;
+use axum::Extension;
+use axum::Router;
+use bytes::Bytes;
+use http_body::Body as HttpBody;
+use surrealdb::dbs::Session;
+use tower_http::limit::RequestBodyLimitLayer;
+
+const MAX: usize = 1024 * 1024 * 1024 * 4; // 4 GiB
+
+pub(super) fn router<S, B>() -> Router<S, B>
+where
+	B: HttpBody + Send + 'static,
+	B::Data: Send,
+	B::Error: std::error::Error + Send + Sync + 'static,
+	S: Clone + Send + Sync + 'static,
+{
+	Router::new()
+		.route("/ml/import", post(import))
+		.route_layer(DefaultBodyLimit::disable())
+		.layer(RequestBodyLimitLayer::new(MAX))
+}
+
+async fn import(
+	Extension(session): Extension<Session>,
+	data: Bytes,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get the datastore reference
+	let db = DB.get().unwrap();
+	// Store the model file in the database
+	match db.import_ml(&session, &data).await {
+		Ok(_) => Ok(output::none()),
+		// There was an error when storing the model
+		Err(err) => Err(Error::from(err)),
+	}
+}
@@ -6,6 +6,8 @@ mod health;
 mod import;
 mod input;
 mod key;
+mod metrics;
+mod ml;
 mod output;
 mod params;
 mod rpc;
@@ -22,6 +24,7 @@ use axum::routing::get;
 use axum::{middleware, Router};
 use axum_server::Handle;
 use http::header;
+use ipnet::IpNet;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -52,6 +55,7 @@ const LOG: &str = "surrealdb::net";
 #[derive(Clone)]
 struct AppState {
 	client_ip: client_ip::ClientIp,
+	trusted_proxies: Arc<[IpNet]>,
 }
 
 pub async fn init(ct: CancellationToken) -> Result<(), Error> {
@@ -60,6 +64,7 @@ pub async fn init(ct: CancellationToken) -> Result<(), Error> {
 
 	let app_state = AppState {
 		client_ip: opt.client_ip,
+		trusted_proxies: opt.trusted_proxies.clone().into(),
 	};
 
 	// Specify headers to be obfuscated from all requests/responses
@@ -119,8 +124,10 @@ pub async fn init(ct: CancellationToken) -> Result<(), Error> {
 		.route("/", get(|| async { Redirect::temporary(cnf::APP_ENDPOINT) }))
 		.route("/status", get(|| async {}))
 		.merge(health::router())
+		.merge(metrics::router())
 		.merge(export::router())
 		.merge(import::router())
+		.merge(ml::router())
 		.merge(rpc::router())
 		.merge(version::router())
 		.merge(sync::router())
@@ -1,20 +1,125 @@
+use crate::dbs::DB;
+use crate::err::Error;
+use crate::net::output;
+use axum::extract::DefaultBodyLimit;
 use axum::response::IntoResponse;
-use axum::routing::get;
-use axum::Router;
+use axum::routing::{get, post};
+use axum::{Extension, Router, TypedHeader};
+use axum_extra::extract::Query;
+use bytes::Bytes;
 use http_body::Body as HttpBody;
+use serde::Deserialize;
+use surrealdb::dbs::Session;
+use surrealdb::sql::{Array, Value};
+use surrealdb::sync::ChangeSet;
+use tower_http::limit::RequestBodyLimitLayer;
+
+use super::headers::Accept;
+
+const MAX: usize = 1024 * 1024 * 64; // 64 MiB
+
+#[derive(Default, Deserialize, Debug, Clone)]
+struct PullOptions {
+	/// The id of the peer pulling the changes
+	pub peer: Option<String>,
+	/// The versionstamp from which to pull the changes
+	pub since: Option<u64>,
+	/// The maximum number of change sets to pull
+	pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct AckOptions {
+	/// The id of the peer acknowledging the changes
+	pub peer: String,
+	/// The versionstamp of the last change set applied by the peer
+	pub versionstamp: u64,
+}
 
 pub(super) fn router<S, B>() -> Router<S, B>
 where
 	B: HttpBody + Send + 'static,
+	B::Data: Send,
+	B::Error: std::error::Error + Send + Sync + 'static,
 	S: Clone + Send + Sync + 'static,
 {
-	Router::new().route("/sync", get(save).post(load))
+	Router::new()
+		.route("/sync", get(pull).post(apply))
+		.route("/sync/ack", post(ack))
+		.route_layer(DefaultBodyLimit::disable())
+		.layer(RequestBodyLimitLayer::new(MAX))
+}
+
+/// Returns the changes from the change feed of the database, which the peer has not yet acknowledged
+async fn pull(
+	Extension(session): Extension<Session>,
+	maybe_output: Option<TypedHeader<Accept>>,
+	Query(query): Query<PullOptions>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get the datastore reference
+	let db = DB.get().unwrap();
+	// Read the changes since the versionstamp or the checkpoint of the peer
+	match db.sync_changes(&session, query.peer.as_deref(), query.since, query.limit).await {
+		Ok(res) => match maybe_output.as_deref() {
+			// Simple serialization
+			Some(Accept::ApplicationJson) => Ok(output::json(&output::simplify(changes(res)))),
+			Some(Accept::ApplicationCbor) => Ok(output::cbor(&output::simplify(changes(res)))),
+			Some(Accept::ApplicationPack) => Ok(output::pack(&output::simplify(changes(res)))),
+			// Internal serialization, which is needed to apply the changes on a peer
+			Some(Accept::Surrealdb) => Ok(output::full(&res)),
+			// An incorrect content-type was requested
+			_ => Err(Error::InvalidType),
+		},
+		// There was an error when reading the changes
+		Err(err) => Err(Error::from(err)),
+	}
+}
+
+/// Applies the changes pulled from a peer, using the internal serialization,
+/// and returns the versionstamp to acknowledge on the peer
+async fn apply(
+	Extension(session): Extension<Session>,
+	maybe_output: Option<TypedHeader<Accept>>,
+	body: Bytes,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get the datastore reference
+	let db = DB.get().unwrap();
+	// Decode the received changes
+	let changes: Vec<ChangeSet> =
+		surrealdb::sql::serde::deserialize(&body).map_err(|_| Error::Request)?;
+	// Apply the changes to the database
+	match db.sync_apply(&session, changes).await {
+		Ok(vs) => match maybe_output.as_deref() {
+			// Simple serialization
+			Some(Accept::ApplicationJson) => Ok(output::json(&vs)),
+			Some(Accept::ApplicationCbor) => Ok(output::cbor(&vs)),
+			Some(Accept::ApplicationPack) => Ok(output::pack(&vs)),
+			// Internal serialization
+			Some(Accept::Surrealdb) => Ok(output::full(&vs)),
+			// An incorrect content-type was requested
+			_ => Err(Error::InvalidType),
+		},
+		// There was an error when applying the changes
+		Err(err) => Err(Error::from(err)),
+	}
 }
 
-async fn load() -> impl IntoResponse {
-	"Load"
+/// Stores the checkpoint of a peer, from which its next pull starts
+async fn ack(
+	Extension(session): Extension<Session>,
+	Query(query): Query<AckOptions>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get the datastore reference
+	let db = DB.get().unwrap();
+	// Store the checkpoint of the peer
+	match db.sync_ack(&session, &query.peer, query.versionstamp).await {
+		Ok(_) => Ok(output::none()),
+		// There was an error when storing the checkpoint
+		Err(err) => Err(Error::from(err)),
+	}
 }
 
-async fn save() -> impl IntoResponse {
-	"Save"
+/// Converts the change sets into a readable value
+fn changes(res: Vec<ChangeSet>) -> Value {
+	Value::Array(Array::from(res.into_iter().map(ChangeSet::into_value).collect::<Vec<_>>()))
 }
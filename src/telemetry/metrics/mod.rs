@@ -1,4 +1,5 @@
 pub mod http;
+mod prometheus;
 pub mod ws;
 
 use std::time::Duration;
@@ -74,6 +75,12 @@ static METER_PROVIDER_SIZE: Lazy<BasicController> =
 static METER_DURATION: Lazy<Meter> = Lazy::new(|| METER_PROVIDER_DURATION.meter("duration"));
 static METER_SIZE: Lazy<Meter> = Lazy::new(|| METER_PROVIDER_SIZE.meter("size"));
 
+/// Writes the HTTP and RPC server metrics in the Prometheus text format
+pub fn encode(out: &mut String) -> Result<(), MetricsError> {
+	prometheus::encode(&METER_PROVIDER_DURATION, out)?;
+	prometheus::encode(&METER_PROVIDER_SIZE, out)
+}
+
 /// Initialize the metrics subsystem
 pub fn init(cx: &TelemetryContext) -> Result<(), MetricsError> {
 	METER_PROVIDER_DURATION.start(cx, runtime::Tokio)?;
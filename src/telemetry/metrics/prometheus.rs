@@ -0,0 +1,104 @@
+//! Converts the checkpoints of the OpenTelemetry metrics into the Prometheus text format.
+//!
+//! The checkpoints are those collected for the OTLP exporter, so the
+//! values which are encoded are at most one collection period old.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use opentelemetry::attributes::AttributeSet;
+use opentelemetry::metrics::MetricsError;
+use opentelemetry::sdk::export::metrics::aggregation::{self, Count, Histogram, Sum};
+use opentelemetry::sdk::export::metrics::InstrumentationLibraryReader;
+use opentelemetry::sdk::metrics::aggregators::{HistogramAggregator, SumAggregator};
+use opentelemetry::sdk::metrics::controllers::BasicController;
+
+/// The lines of a metric, along with its description and type
+type Family = (String, &'static str, Vec<String>);
+
+/// Writes the metrics of a controller in the Prometheus text format
+pub(super) fn encode(controller: &BasicController, out: &mut String) -> Result<(), MetricsError> {
+	let temporality = aggregation::cumulative_temporality_selector();
+	// Group the series by metric, as each metric is only described once
+	let mut families: BTreeMap<String, Family> = BTreeMap::new();
+	controller.try_for_each(&mut |_, reader| {
+		reader.try_for_each(&temporality, &mut |record| {
+			let Some(agg) = record.aggregator() else {
+				return Ok(());
+			};
+			let desc = record.descriptor();
+			let kind = desc.number_kind();
+			let name = sanitize(desc.name());
+			let help = desc.description().cloned().unwrap_or_default();
+			let attrs = record.attributes();
+			if let Some(h) = agg.as_any().downcast_ref::<HistogramAggregator>() {
+				let buckets = h.histogram()?;
+				let (_, _, lines) =
+					families.entry(name.clone()).or_insert_with(|| (help, "histogram", vec![]));
+				let mut count = 0.0;
+				for (i, c) in buckets.counts().iter().enumerate() {
+					count += c;
+					let le = match buckets.boundaries().get(i) {
+						Some(b) => b.to_string(),
+						None => "+Inf".to_string(),
+					};
+					lines.push(format!("{name}_bucket{} {count}", labels(attrs, Some(&le))));
+				}
+				lines.push(format!("{name}_sum{} {}", labels(attrs, None), h.sum()?.to_f64(kind)));
+				lines.push(format!("{name}_count{} {}", labels(attrs, None), h.count()?));
+			} else if let Some(s) = agg.as_any().downcast_ref::<SumAggregator>() {
+				let kind_name = match desc.instrument_kind().monotonic() {
+					true => "counter",
+					false => "gauge",
+				};
+				let (_, _, lines) =
+					families.entry(name.clone()).or_insert_with(|| (help, kind_name, vec![]));
+				lines.push(format!("{name}{} {}", labels(attrs, None), s.sum()?.to_f64(kind)));
+			}
+			Ok(())
+		})
+	})?;
+	for (name, (help, kind, lines)) in families {
+		let _ = writeln!(out, "# HELP {name} {help}");
+		let _ = writeln!(out, "# TYPE {name} {kind}");
+		for line in lines {
+			let _ = writeln!(out, "{line}");
+		}
+	}
+	Ok(())
+}
+
+/// Converts an OpenTelemetry name, such as `http.server.duration`, into a Prometheus name
+fn sanitize(name: &str) -> String {
+	name.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+}
+
+fn labels(attrs: &AttributeSet, le: Option<&str>) -> String {
+	let mut pairs: Vec<String> = attrs
+		.iter()
+		.map(|(k, v)| format!("{}=\"{}\"", sanitize(k.as_str()), escape(&v.as_str())))
+		.collect();
+	if let Some(le) = le {
+		pairs.push(format!("le=\"{le}\""));
+	}
+	if pairs.is_empty() {
+		String::new()
+	} else {
+		format!("{{{}}}", pairs.join(","))
+	}
+}
+
+/// Escapes a label value, as required by the Prometheus text format
+fn escape(v: &str) -> String {
+	v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sanitize_names() {
+		assert_eq!(sanitize("http.server.duration"), "http_server_duration");
+		assert_eq!(sanitize("rpc.server.active_connections"), "rpc_server_active_connections");
+	}
+}